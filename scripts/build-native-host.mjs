@@ -0,0 +1,56 @@
+/**
+ * 构建浏览器原生消息宿主并放到 Tauri sidecar 目录
+ *
+ * Tauri 打包时要求 src-tauri/binaries 下存在带目标三元组后缀的
+ * taiasst-native-host，打包后会与主程序位于同一目录
+ */
+import { execFileSync } from "node:child_process";
+import { copyFileSync, mkdirSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const tauriDir = join(
+  dirname(fileURLToPath(import.meta.url)),
+  "..",
+  "src-tauri",
+);
+const binary = "taiasst-native-host";
+
+function hostTriple() {
+  const output = execFileSync("rustc", ["-vV"], { encoding: "utf8" });
+  const match = output.match(/^host: (\S+)$/m);
+  if (!match) {
+    throw new Error("无法获取 Rust 目标三元组");
+  }
+  return match[1];
+}
+
+// tauri build --target 时由 Tauri CLI 传入
+const target = process.env.TAURI_ENV_TARGET_TRIPLE || hostTriple();
+const release = process.env.TAURI_ENV_DEBUG !== "true";
+const extension = target.includes("windows") ? ".exe" : "";
+
+const args = [
+  "build",
+  "-p",
+  "taiasst-bridge",
+  "--bin",
+  binary,
+  "--target",
+  target,
+];
+if (release) {
+  args.push("--release");
+}
+execFileSync("cargo", args, { cwd: tauriDir, stdio: "inherit" });
+
+const built = join(
+  tauriDir,
+  "target",
+  target,
+  release ? "release" : "debug",
+  `${binary}${extension}`,
+);
+const sidecarDir = join(tauriDir, "binaries");
+mkdirSync(sidecarDir, { recursive: true });
+copyFileSync(built, join(sidecarDir, `${binary}-${target}${extension}`));
@@ -0,0 +1,497 @@
+/**
+ * 浏览器桥接协议
+ *
+ * 应用与原生消息宿主（taiasst-native-host）之间通过本机回环 TCP 连接通信，
+ * 每条消息为 长度(4字节大端) || JSON。
+ *
+ * 配对时宿主发送自己的 RSA 公钥，用户在应用中确认后应用返回自己的公钥，双方
+ * 各自保存对方公钥。之后每次连接先交换随机数并用 RSA 私钥签名，宿主同时用应用
+ * 公钥加密一个随机会话密钥。双向验证通过后，后续消息均以会话密钥派生的
+ * AES-256-GCM 密钥加密，随机数由方向和递增序号组成，不在消息中传输。
+ *
+ * 该 crate 同时提供原生消息宿主程序（taiasst-native-host），由应用以 sidecar 方式打包
+ */
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::traits::PublicKeyParts;
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 应用写入监听端口的文件名
+pub const BRIDGE_INFO_FILE: &str = "browser-bridge.json";
+/// 单条消息长度上限（字节）
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
+/// RSA 密钥长度
+pub const RSA_KEY_BITS: usize = 2048;
+/// 随机数长度（字节）
+const NONCE_LEN: usize = 32;
+/// 会话密钥长度（字节）
+const SESSION_KEY_LEN: usize = 32;
+/// 加密消息的认证标签长度（字节）
+const TAG_LEN: usize = 16;
+
+/**
+ * 应用监听信息
+ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BridgeInfo {
+    /// 回环地址上的端口
+    pub port: u16,
+    /// 应用进程ID
+    pub pid: u32,
+}
+
+/**
+ * 匹配到的条目，不包含密码
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BridgeEntry {
+    /// 条目ID
+    pub id: String,
+    /// 标题
+    pub title: String,
+    /// 用户名
+    pub username: String,
+    /// 网址
+    pub url: Option<String>,
+}
+
+/**
+ * 桥接消息
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeMessage {
+    /// 宿主请求配对
+    Pair { public_key: String },
+    /// 配对成功，返回应用公钥
+    Paired { app_public_key: String },
+    /// 宿主发起认证
+    Hello {
+        key_fingerprint: String,
+        nonce: String,
+    },
+    /// 应用返回随机数和签名
+    Challenge { nonce: String, signature: String },
+    /// 宿主返回签名和以应用公钥加密的会话密钥
+    Auth {
+        signature: String,
+        session_key: String,
+    },
+    /// 认证完成
+    Ready,
+    /// 按网址查找条目
+    Find { url: String },
+    /// 查找结果
+    Matches { entries: Vec<BridgeEntry> },
+    /// 请求条目的登录信息
+    Get { entry_id: String, url: String },
+    /// 登录信息
+    Credentials {
+        username: String,
+        password: String,
+        totp: Option<String>,
+    },
+    /// 错误
+    Error { message: String },
+}
+
+/**
+ * 编码一条消息
+ *
+ * @param message 消息
+ * @return 带长度前缀的字节
+ */
+pub fn encode_frame(message: &BridgeMessage) -> Result<Vec<u8>, String> {
+    let body = serde_json::to_vec(message).map_err(|e| format!("序列化消息失败: {}", e))?;
+    if body.len() > MAX_FRAME_LEN {
+        return Err("消息过长".to_string());
+    }
+    let mut frame = (body.len() as u32).to_be_bytes().to_vec();
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/**
+ * 解析长度前缀
+ *
+ * @param header 长度前缀
+ * @return 消息长度
+ */
+pub fn frame_len(header: [u8; 4]) -> Result<usize, String> {
+    let len = u32::from_be_bytes(header) as usize;
+    if len == 0 || len > MAX_FRAME_LEN + TAG_LEN {
+        return Err(format!("消息长度无效: {}", len));
+    }
+    Ok(len)
+}
+
+/**
+ * 解析消息内容
+ *
+ * @param body 不含长度前缀的消息
+ * @return 消息
+ */
+pub fn decode_frame(body: &[u8]) -> Result<BridgeMessage, String> {
+    serde_json::from_slice(body).map_err(|e| format!("解析消息失败: {}", e))
+}
+
+/**
+ * 生成 Base64 编码的随机数
+ */
+pub fn random_nonce() -> String {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    STANDARD.encode(nonce)
+}
+
+/**
+ * 计算公钥指纹
+ *
+ * @param key 公钥
+ * @return SubjectPublicKeyInfo 的 SHA-256 十六进制摘要
+ */
+pub fn key_fingerprint(key: &RsaPublicKey) -> Result<String, String> {
+    let der = key
+        .to_public_key_der()
+        .map_err(|e| format!("编码公钥失败: {}", e))?;
+    Ok(hex::encode(Sha256::digest(der.as_bytes())))
+}
+
+/**
+ * 公钥编码为 PEM
+ */
+pub fn public_key_to_pem(key: &RsaPublicKey) -> Result<String, String> {
+    key.to_public_key_pem(LineEnding::LF)
+        .map_err(|e| format!("编码公钥失败: {}", e))
+}
+
+/**
+ * 从 PEM 解析公钥
+ */
+pub fn public_key_from_pem(pem: &str) -> Result<RsaPublicKey, String> {
+    let key =
+        RsaPublicKey::from_public_key_pem(pem.trim()).map_err(|e| format!("公钥无效: {}", e))?;
+    if key.size() * 8 < RSA_KEY_BITS {
+        return Err("公钥长度不足".to_string());
+    }
+    Ok(key)
+}
+
+/// 应用签名的内容：宿主随机数与应用随机数
+pub fn app_proof(host_nonce: &str, app_nonce: &str) -> Vec<u8> {
+    format!("taiasst-bridge-app\n{}\n{}", host_nonce, app_nonce).into_bytes()
+}
+
+/// 宿主签名的内容：应用随机数、宿主随机数与加密的会话密钥
+pub fn host_proof(app_nonce: &str, host_nonce: &str, session_key: &str) -> Vec<u8> {
+    format!(
+        "taiasst-bridge-host\n{}\n{}\n{}",
+        app_nonce, host_nonce, session_key
+    )
+    .into_bytes()
+}
+
+/**
+ * 使用 RSA PKCS#1 v1.5 / SHA-256 签名
+ *
+ * @param key 私钥
+ * @param data 待签名数据
+ * @return Base64 编码的签名
+ */
+pub fn sign(key: &RsaPrivateKey, data: &[u8]) -> Result<String, String> {
+    let signature = SigningKey::<Sha256>::new(key.clone())
+        .try_sign(data)
+        .map_err(|e| format!("签名失败: {}", e))?;
+    Ok(STANDARD.encode(signature.to_bytes()))
+}
+
+/**
+ * 验证签名
+ *
+ * @param key 公钥
+ * @param data 被签名的数据
+ * @param signature Base64 编码的签名
+ * @return 验证结果
+ */
+pub fn verify(key: &RsaPublicKey, data: &[u8], signature: &str) -> Result<(), String> {
+    let signature = STANDARD
+        .decode(signature.trim())
+        .map_err(|_| "签名格式无效".to_string())?;
+    let signature =
+        Signature::try_from(signature.as_slice()).map_err(|_| "签名格式无效".to_string())?;
+    VerifyingKey::<Sha256>::new(key.clone())
+        .verify(data, &signature)
+        .map_err(|_| "签名验证失败".to_string())
+}
+
+/**
+ * 生成会话密钥并用应用公钥加密
+ *
+ * @param app_key 应用公钥
+ * @return 会话密钥和 Base64 编码的 RSA-OAEP 密文
+ */
+pub fn wrap_session_key(app_key: &RsaPublicKey) -> Result<(Vec<u8>, String), String> {
+    let mut secret = vec![0u8; SESSION_KEY_LEN];
+    OsRng.fill_bytes(&mut secret);
+    let wrapped = app_key
+        .encrypt(&mut OsRng, Oaep::new::<Sha256>(), &secret)
+        .map_err(|e| format!("加密会话密钥失败: {}", e))?;
+    Ok((secret, STANDARD.encode(wrapped)))
+}
+
+/**
+ * 用应用私钥解密会话密钥
+ *
+ * @param key 应用私钥
+ * @param wrapped Base64 编码的 RSA-OAEP 密文
+ * @return 会话密钥
+ */
+pub fn unwrap_session_key(key: &RsaPrivateKey, wrapped: &str) -> Result<Vec<u8>, String> {
+    let wrapped = STANDARD
+        .decode(wrapped.trim())
+        .map_err(|_| "会话密钥格式无效".to_string())?;
+    let secret = key
+        .decrypt(Oaep::new::<Sha256>(), &wrapped)
+        .map_err(|_| "会话密钥解密失败".to_string())?;
+    if secret.len() != SESSION_KEY_LEN {
+        return Err("会话密钥长度无效".to_string());
+    }
+    Ok(secret)
+}
+
+/**
+ * 连接中的一方
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// 原生消息宿主
+    Host,
+    /// 应用
+    App,
+}
+
+impl Role {
+    /// 该方发出消息的方向标识
+    fn label(self) -> [u8; 4] {
+        match self {
+            Role::Host => *b"host",
+            Role::App => *b"app\0",
+        }
+    }
+
+    /// 对端
+    fn peer(self) -> Role {
+        match self {
+            Role::Host => Role::App,
+            Role::App => Role::Host,
+        }
+    }
+}
+
+/**
+ * 认证完成后的加密通道
+ *
+ * 每个方向各自维护递增序号作为 AES-GCM 随机数，重放、乱序或篡改的消息都会解密失败
+ */
+pub struct SecureChannel {
+    cipher: Aes256Gcm,
+    role: Role,
+    sent: u64,
+    received: u64,
+}
+
+impl SecureChannel {
+    /**
+     * 由会话密钥和双方随机数派生通道密钥
+     *
+     * @param secret 会话密钥
+     * @param host_nonce 宿主随机数
+     * @param app_nonce 应用随机数
+     * @param role 当前一方
+     * @return 加密通道
+     */
+    pub fn new(
+        secret: &[u8],
+        host_nonce: &str,
+        app_nonce: &str,
+        role: Role,
+    ) -> Result<Self, String> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"taiasst-bridge-session\n");
+        hasher.update(host_nonce.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(app_nonce.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(secret);
+        let cipher = Aes256Gcm::new_from_slice(&hasher.finalize())
+            .map_err(|e| format!("会话密钥无效: {}", e))?;
+        Ok(Self {
+            cipher,
+            role,
+            sent: 0,
+            received: 0,
+        })
+    }
+
+    /// 由方向标识和序号组成的随机数
+    fn nonce(role: Role, counter: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&role.label());
+        nonce[4..].copy_from_slice(&counter.to_be_bytes());
+        nonce
+    }
+
+    /**
+     * 加密并编码一条消息
+     *
+     * @param message 消息
+     * @return 带长度前缀的密文
+     */
+    pub fn seal(&mut self, message: &BridgeMessage) -> Result<Vec<u8>, String> {
+        let body = serde_json::to_vec(message).map_err(|e| format!("序列化消息失败: {}", e))?;
+        if body.len() > MAX_FRAME_LEN {
+            return Err("消息过长".to_string());
+        }
+        let nonce = Self::nonce(self.role, self.sent);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &body,
+                    aad: b"taiasst-bridge",
+                },
+            )
+            .map_err(|_| "加密消息失败".to_string())?;
+        self.sent = self
+            .sent
+            .checked_add(1)
+            .ok_or_else(|| "消息序号溢出".to_string())?;
+
+        let mut frame = (ciphertext.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(&ciphertext);
+        Ok(frame)
+    }
+
+    /**
+     * 解密一条消息
+     *
+     * @param body 不含长度前缀的密文
+     * @return 消息
+     */
+    pub fn open(&mut self, body: &[u8]) -> Result<BridgeMessage, String> {
+        let nonce = Self::nonce(self.role.peer(), self.received);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: body,
+                    aad: b"taiasst-bridge",
+                },
+            )
+            .map_err(|_| "消息解密失败".to_string())?;
+        self.received = self
+            .received
+            .checked_add(1)
+            .ok_or_else(|| "消息序号溢出".to_string())?;
+        decode_frame(&plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels() -> (SecureChannel, SecureChannel) {
+        let secret = vec![7u8; SESSION_KEY_LEN];
+        (
+            SecureChannel::new(&secret, "h", "a", Role::Host).unwrap(),
+            SecureChannel::new(&secret, "h", "a", Role::App).unwrap(),
+        )
+    }
+
+    fn body(frame: &[u8]) -> &[u8] {
+        let len = frame_len(frame[..4].try_into().unwrap()).unwrap();
+        assert_eq!(len, frame.len() - 4);
+        &frame[4..]
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let frame = encode_frame(&BridgeMessage::Find {
+            url: "https://example.com".to_string(),
+        })
+        .unwrap();
+        match decode_frame(body(&frame)).unwrap() {
+            BridgeMessage::Find { url } => assert_eq!(url, "https://example.com"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(frame_len([0, 0, 0, 0]).is_err());
+        assert!(frame_len((MAX_FRAME_LEN as u32 + 1024).to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn session_key_unwraps_with_app_key() {
+        let key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        let (secret, wrapped) = wrap_session_key(&key.to_public_key()).unwrap();
+        assert_eq!(unwrap_session_key(&key, &wrapped).unwrap(), secret);
+
+        let other = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        assert!(unwrap_session_key(&other, &wrapped).is_err());
+    }
+
+    #[test]
+    fn channel_round_trips_in_both_directions() {
+        let (mut host, mut app) = channels();
+        for _ in 0..3 {
+            let frame = host.seal(&BridgeMessage::Ready).unwrap();
+            assert!(matches!(app.open(body(&frame)), Ok(BridgeMessage::Ready)));
+        }
+        let frame = app
+            .seal(&BridgeMessage::Error {
+                message: "x".to_string(),
+            })
+            .unwrap();
+        assert!(matches!(
+            host.open(body(&frame)),
+            Ok(BridgeMessage::Error { .. })
+        ));
+    }
+
+    #[test]
+    fn channel_rejects_replay_reflection_and_tampering() {
+        let (mut host, mut app) = channels();
+        let first = host.seal(&BridgeMessage::Ready).unwrap();
+        app.open(body(&first)).unwrap();
+        // 重放
+        assert!(app.open(body(&first)).is_err());
+
+        // 反射回发送方
+        let (mut host, _) = channels();
+        let frame = host.seal(&BridgeMessage::Ready).unwrap();
+        assert!(host.open(body(&frame)).is_err());
+
+        // 篡改
+        let (mut host, mut app) = channels();
+        let mut frame = host.seal(&BridgeMessage::Ready).unwrap();
+        let last = frame.len() - 1;
+        frame[last] ^= 1;
+        assert!(app.open(body(&frame)).is_err());
+
+        // 随机数不同则密钥不同
+        let secret = vec![7u8; SESSION_KEY_LEN];
+        let mut host = SecureChannel::new(&secret, "h", "a", Role::Host).unwrap();
+        let mut app = SecureChannel::new(&secret, "h", "b", Role::App).unwrap();
+        let frame = host.seal(&BridgeMessage::Ready).unwrap();
+        assert!(app.open(body(&frame)).is_err());
+    }
+}
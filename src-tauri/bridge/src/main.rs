@@ -0,0 +1,415 @@
+/**
+ * TaiASST 原生消息宿主
+ *
+ * 由浏览器按原生消息协议启动，通过标准输入输出与扩展交换消息
+ * （长度(4字节本机字节序) || JSON），并将请求转发给正在运行的应用：
+ * - status: 返回宿主公钥指纹和配对状态
+ * - pair: 请求与应用配对，需用户在应用中确认
+ * - find: 按网址查找登录条目
+ * - get: 获取条目的登录信息，需用户在应用中确认
+ *
+ * 宿主私钥与应用加密存储的设备级条目相同，以设备密钥加密后保存在应用数据目录中；
+ * 应用公钥和私钥文件仅当前用户可读。认证通过后与应用之间的消息均经加密通道传输
+ */
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::Deserialize;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use taiasst_bridge::{
+    app_proof, decode_frame, encode_frame, frame_len, host_proof, key_fingerprint,
+    public_key_from_pem, public_key_to_pem, random_nonce, sign, verify, wrap_session_key,
+    BridgeInfo, BridgeMessage, Role, SecureChannel, BRIDGE_INFO_FILE, MAX_FRAME_LEN, RSA_KEY_BITS,
+};
+
+/// 应用标识，与 tauri.conf.json 中的 identifier 一致
+const APP_IDENTIFIER: &str = "com.teexue.taiasst";
+/// 宿主私钥文件名
+const HOST_KEY_FILE: &str = "browser-host.key";
+/// 应用加密存储使用的设备密钥文件名
+const DEVICE_KEY_FILE: &str = "device.key";
+/// 宿主私钥在加密存储中的键名，用作附加认证数据
+const HOST_KEY_SECRET: &str = "browser.host_private_key";
+/// 设备密钥长度（字节）
+const DEVICE_KEY_LEN: usize = 32;
+/// AES-GCM 随机数长度（字节）
+const GCM_NONCE_LEN: usize = 12;
+/// 已配对的应用公钥文件名
+const APP_PUBLIC_KEY_FILE: &str = "browser-host-app.pub";
+/// 等待应用响应的最长时间（秒），需覆盖用户确认的时间
+const RESPONSE_TIMEOUT_SECS: u64 = 90;
+
+/**
+ * 扩展发来的请求
+ */
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ExtensionRequest {
+    Status,
+    Pair,
+    Find { url: String },
+    Get { entry_id: String, url: String },
+}
+
+/**
+ * 获取应用数据目录
+ *
+ * 与 Tauri 的 app_data_dir 保持一致
+ */
+fn data_dir() -> Result<PathBuf, String> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| home().map(|h| h.join(".local").join("share")))
+    };
+    base.map(|b| b.join(APP_IDENTIFIER))
+        .ok_or_else(|| "无法获取应用数据目录".to_string())
+}
+
+/**
+ * 写入仅当前用户可读的文件
+ */
+fn write_private_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content))
+        .map_err(|e| format!("写入文件失败 ({}): {}", path.display(), e))
+}
+
+/**
+ * 创建设备密钥的加密器
+ *
+ * 设备密钥由应用首次启动时生成，宿主只读取不创建
+ */
+fn device_cipher(dir: &Path) -> Result<Aes256Gcm, String> {
+    let key = std::fs::read(dir.join(DEVICE_KEY_FILE))
+        .map_err(|_| "未找到设备密钥，请先启动 TaiASST".to_string())?;
+    if key.len() != DEVICE_KEY_LEN {
+        return Err("设备密钥文件已损坏".to_string());
+    }
+    Aes256Gcm::new_from_slice(&key).map_err(|e| format!("设备密钥无效: {}", e))
+}
+
+/// 与应用加密存储设备级条目一致的附加认证数据
+fn host_key_aad() -> Vec<u8> {
+    format!("encrypted_storage:device:{}", HOST_KEY_SECRET).into_bytes()
+}
+
+/**
+ * 以设备密钥加密保存宿主私钥
+ */
+fn save_host_key(dir: &Path, key: &RsaPrivateKey) -> Result<(), String> {
+    let pem = key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| format!("编码宿主私钥失败: {}", e))?;
+    let mut nonce = vec![0u8; GCM_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = device_cipher(dir)?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: pem.as_bytes(),
+                aad: &host_key_aad(),
+            },
+        )
+        .map_err(|_| "加密宿主私钥失败".to_string())?;
+    nonce.extend_from_slice(&ciphertext);
+    write_private_file(&dir.join(HOST_KEY_FILE), STANDARD.encode(nonce).as_bytes())
+}
+
+/**
+ * 读取或生成宿主私钥
+ *
+ * 早期版本以明文 PEM 保存的私钥会在读取后改为加密保存
+ */
+fn host_key(dir: &Path) -> Result<RsaPrivateKey, String> {
+    let path = dir.join(HOST_KEY_FILE);
+    if path.exists() {
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| format!("读取宿主私钥失败: {}", e))?;
+        if content.trim_start().starts_with("-----BEGIN") {
+            let key = RsaPrivateKey::from_pkcs8_pem(&content)
+                .map_err(|e| format!("宿主私钥无效: {}", e))?;
+            save_host_key(dir, &key)?;
+            return Ok(key);
+        }
+
+        let data = STANDARD
+            .decode(content.trim())
+            .map_err(|_| "宿主私钥文件已损坏".to_string())?;
+        if data.len() <= GCM_NONCE_LEN {
+            return Err("宿主私钥文件已损坏".to_string());
+        }
+        let (nonce, ciphertext) = data.split_at(GCM_NONCE_LEN);
+        let pem = device_cipher(dir)?
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &host_key_aad(),
+                },
+            )
+            .map_err(|_| "宿主私钥解密失败，请删除后重新配对".to_string())?;
+        let pem = String::from_utf8(pem).map_err(|_| "宿主私钥无效".to_string())?;
+        return RsaPrivateKey::from_pkcs8_pem(&pem).map_err(|e| format!("宿主私钥无效: {}", e));
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+    let key = RsaPrivateKey::new(&mut OsRng, RSA_KEY_BITS)
+        .map_err(|e| format!("生成宿主密钥失败: {}", e))?;
+    save_host_key(dir, &key)?;
+    Ok(key)
+}
+
+/**
+ * 读取已配对的应用公钥
+ */
+fn app_public_key(dir: &Path) -> Result<Option<RsaPublicKey>, String> {
+    let path = dir.join(APP_PUBLIC_KEY_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let pem = std::fs::read_to_string(&path).map_err(|e| format!("读取应用公钥失败: {}", e))?;
+    public_key_from_pem(&pem).map(Some)
+}
+
+/**
+ * 连接正在运行的应用
+ */
+fn connect(dir: &Path) -> Result<TcpStream, String> {
+    let info =
+        std::fs::read(dir.join(BRIDGE_INFO_FILE)).map_err(|_| "TaiASST 未运行".to_string())?;
+    let info: BridgeInfo =
+        serde_json::from_slice(&info).map_err(|e| format!("监听信息无效: {}", e))?;
+    let stream = TcpStream::connect(("127.0.0.1", info.port))
+        .map_err(|_| "无法连接 TaiASST，请确认应用正在运行".to_string())?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(RESPONSE_TIMEOUT_SECS)))
+        .map_err(|e| format!("设置超时失败: {}", e))?;
+    Ok(stream)
+}
+
+/**
+ * 向应用发送一条消息
+ */
+fn send(stream: &mut TcpStream, message: &BridgeMessage) -> Result<(), String> {
+    stream
+        .write_all(&encode_frame(message)?)
+        .map_err(|e| format!("发送消息失败: {}", e))
+}
+
+/**
+ * 读取应用的一条消息内容
+ */
+fn receive_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut header = [0u8; 4];
+    stream
+        .read_exact(&mut header)
+        .map_err(|e| format!("读取消息失败: {}", e))?;
+    let mut body = vec![0u8; frame_len(header)?];
+    stream
+        .read_exact(&mut body)
+        .map_err(|e| format!("读取消息失败: {}", e))?;
+    Ok(body)
+}
+
+/// 错误消息转为错误返回
+fn into_result(message: BridgeMessage) -> Result<BridgeMessage, String> {
+    match message {
+        BridgeMessage::Error { message } => Err(message),
+        message => Ok(message),
+    }
+}
+
+/**
+ * 读取应用的一条明文消息
+ */
+fn receive(stream: &mut TcpStream) -> Result<BridgeMessage, String> {
+    into_result(decode_frame(&receive_frame(stream)?)?)
+}
+
+/**
+ * 读取应用的一条加密消息
+ */
+fn receive_sealed(
+    stream: &mut TcpStream,
+    channel: &mut SecureChannel,
+) -> Result<BridgeMessage, String> {
+    into_result(channel.open(&receive_frame(stream)?)?)
+}
+
+/**
+ * 与应用进行双向认证
+ *
+ * @param stream 连接
+ * @param key 宿主私钥
+ * @param app_key 已配对的应用公钥
+ * @return 加密通道
+ */
+fn authenticate(
+    stream: &mut TcpStream,
+    key: &RsaPrivateKey,
+    app_key: &RsaPublicKey,
+) -> Result<SecureChannel, String> {
+    let host_nonce = random_nonce();
+    send(
+        stream,
+        &BridgeMessage::Hello {
+            key_fingerprint: key_fingerprint(&key.to_public_key())?,
+            nonce: host_nonce.clone(),
+        },
+    )?;
+
+    let BridgeMessage::Challenge { nonce, signature } = receive(stream)? else {
+        return Err("认证消息无效".to_string());
+    };
+    verify(app_key, &app_proof(&host_nonce, &nonce), &signature)
+        .map_err(|_| "应用身份验证失败，请重新配对".to_string())?;
+    let (secret, session_key) = wrap_session_key(app_key)?;
+    send(
+        stream,
+        &BridgeMessage::Auth {
+            signature: sign(key, &host_proof(&nonce, &host_nonce, &session_key))?,
+            session_key,
+        },
+    )?;
+
+    let mut channel = SecureChannel::new(&secret, &host_nonce, &nonce, Role::Host)?;
+    let body = receive_frame(stream)?;
+    match channel.open(&body) {
+        Ok(BridgeMessage::Ready) => Ok(channel),
+        Ok(_) => Err("认证消息无效".to_string()),
+        // 认证失败时应用以明文返回错误
+        Err(e) => match decode_frame(&body) {
+            Ok(BridgeMessage::Error { message }) => Err(message),
+            _ => Err(e),
+        },
+    }
+}
+
+/**
+ * 处理扩展的一条请求
+ *
+ * @param request 请求
+ * @return 返回给扩展的 JSON
+ */
+fn handle(request: ExtensionRequest) -> Result<serde_json::Value, String> {
+    let dir = data_dir()?;
+    let key = host_key(&dir)?;
+    let fingerprint = key_fingerprint(&key.to_public_key())?;
+
+    let request = match request {
+        ExtensionRequest::Status => {
+            return Ok(serde_json::json!({
+                "type": "status",
+                "paired": app_public_key(&dir)?.is_some(),
+                "key_fingerprint": fingerprint,
+            }));
+        }
+        ExtensionRequest::Pair => {
+            let mut stream = connect(&dir)?;
+            send(
+                &mut stream,
+                &BridgeMessage::Pair {
+                    public_key: public_key_to_pem(&key.to_public_key())?,
+                },
+            )?;
+            let BridgeMessage::Paired { app_public_key } = receive(&mut stream)? else {
+                return Err("配对响应无效".to_string());
+            };
+            public_key_from_pem(&app_public_key)?;
+            write_private_file(&dir.join(APP_PUBLIC_KEY_FILE), app_public_key.as_bytes())?;
+            return Ok(serde_json::json!({
+                "type": "paired",
+                "key_fingerprint": fingerprint,
+            }));
+        }
+        ExtensionRequest::Find { url } => BridgeMessage::Find { url },
+        ExtensionRequest::Get { entry_id, url } => BridgeMessage::Get { entry_id, url },
+    };
+
+    let app_key = app_public_key(&dir)?.ok_or_else(|| "尚未与 TaiASST 配对".to_string())?;
+    let mut stream = connect(&dir)?;
+    let mut channel = authenticate(&mut stream, &key, &app_key)?;
+    stream
+        .write_all(&channel.seal(&request)?)
+        .map_err(|e| format!("发送消息失败: {}", e))?;
+    let response = receive_sealed(&mut stream, &mut channel)?;
+    serde_json::to_value(&response).map_err(|e| format!("序列化响应失败: {}", e))
+}
+
+/**
+ * 读取扩展的一条消息
+ *
+ * @return 消息内容，标准输入关闭时为空
+ */
+fn read_extension_message(input: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0u8; 4];
+    match input.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_ne_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "消息过长"));
+    }
+    let mut body = vec![0u8; len];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/**
+ * 向扩展发送一条消息
+ */
+fn write_extension_message(output: &mut impl Write, value: &serde_json::Value) -> io::Result<()> {
+    let body = serde_json::to_vec(value)?;
+    output.write_all(&(body.len() as u32).to_ne_bytes())?;
+    output.write_all(&body)?;
+    output.flush()
+}
+
+fn main() {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    loop {
+        let body = match read_extension_message(&mut stdin) {
+            Ok(Some(body)) => body,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("读取扩展消息失败: {}", e);
+                break;
+            }
+        };
+
+        let response = serde_json::from_slice::<ExtensionRequest>(&body)
+            .map_err(|e| format!("请求格式无效: {}", e))
+            .and_then(handle)
+            .unwrap_or_else(|message| serde_json::json!({ "type": "error", "message": message }));
+        if let Err(e) = write_extension_message(&mut stdout, &response) {
+            eprintln!("发送扩展消息失败: {}", e);
+            break;
+        }
+    }
+}
@@ -0,0 +1,174 @@
+/**
+ * 模型的图片输入能力
+ *
+ * 默认按模型名推断，可在 ai_model_config.supports_images 中为单个模型覆盖
+ */
+use crate::ai::types::ProviderKind;
+use sqlx::Row;
+
+/// 支持图片的 Ollama 模型名关键字
+const OLLAMA_VISION_MODELS: [&str; 9] = [
+    "llava",
+    "vision",
+    "moondream",
+    "minicpm-v",
+    "gemma3",
+    "qwen2.5vl",
+    "-vl",
+    "mistral-small3",
+    "llama4",
+];
+
+/**
+ * 按模型名推断是否支持图片输入
+ *
+ * @param kind 提供商
+ * @param model 模型ID
+ * @return 是否支持
+ */
+fn builtin_supports_images(kind: ProviderKind, model: &str) -> bool {
+    let model = model.to_lowercase();
+    match kind {
+        ProviderKind::OpenAi => {
+            [
+                "gpt-4o",
+                "gpt-4.1",
+                "gpt-4-turbo",
+                "gpt-4-vision",
+                "gpt-5",
+                "o4",
+            ]
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+                // o1-mini 与 o3-mini 只支持文本
+                || ((model.starts_with("o1") || model.starts_with("o3")) && !model.contains("mini"))
+        }
+        ProviderKind::Claude => {
+            !model.starts_with("claude-2") && !model.starts_with("claude-instant")
+        }
+        ProviderKind::Qianwen => {
+            model.contains("-vl") || model.starts_with("qvq") || model.contains("omni")
+        }
+        ProviderKind::Ollama => OLLAMA_VISION_MODELS
+            .iter()
+            .any(|keyword| model.contains(keyword)),
+        ProviderKind::DeepSeek => false,
+    }
+}
+
+/**
+ * 模型是否支持图片输入，优先使用 ai_model_config 中的配置
+ *
+ * @param kind 提供商
+ * @param model 模型ID
+ * @return 是否支持
+ */
+pub async fn supports_images(kind: ProviderKind, model: &str) -> Result<bool, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let configured: Option<i64> = sqlx::query(
+        "SELECT supports_images FROM ai_model_config
+         WHERE provider = ? AND model_id = ? AND supports_images IS NOT NULL",
+    )
+    .bind(kind.as_str())
+    .bind(model)
+    .fetch_optional(db.get_pool())
+    .await
+    .map_err(|e| format!("查询模型配置失败: {}", e))?
+    .map(|row| row.get("supports_images"));
+
+    Ok(configured
+        .map(|supported| supported != 0)
+        .unwrap_or_else(|| builtin_supports_images(kind, model)))
+}
+
+/**
+ * 查询模型是否支持图片输入
+ *
+ * @param provider 提供商
+ * @param model 模型ID
+ * @return 是否支持
+ */
+#[tauri::command]
+pub async fn get_ai_model_image_support(
+    provider: ProviderKind,
+    model: String,
+) -> Result<bool, String> {
+    supports_images(provider, model.trim()).await
+}
+
+/**
+ * 设置模型是否支持图片输入
+ *
+ * @param provider 提供商
+ * @param model 模型ID
+ * @param supported 是否支持，为空时恢复按模型名推断
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_model_image_support(
+    provider: ProviderKind,
+    model: String,
+    supported: Option<bool>,
+) -> Result<(), String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("未指定模型".to_string());
+    }
+    let supported = supported.map(|supported| supported as i64);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    // ai_model_config 的外键指向旧的 ai_provider_config 表，需先确保提供商记录存在
+    crate::execute_with_params!(
+        db,
+        "INSERT OR IGNORE INTO ai_provider_config (provider, updated_at) VALUES (?, ?)",
+        provider.as_str(),
+        now
+    )?;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO ai_model_config
+         (provider, model_id, name, enabled, supports_images, created_at, updated_at)
+         VALUES (?, ?, ?, 1, ?, ?, ?)
+         ON CONFLICT(provider, model_id) DO UPDATE SET supports_images = excluded.supports_images,
+                                                       updated_at = excluded.updated_at",
+        provider.as_str(),
+        model.as_str(),
+        model.as_str(),
+        supported,
+        now,
+        now
+    )?;
+    log::info!(
+        "模型图片输入能力已更新: {}/{} -> {:?}",
+        provider.as_str(),
+        model,
+        supported
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_openai_image_support() {
+        for model in ["gpt-4o-mini", "o1", "o3", "o3-pro", "o4-mini"] {
+            assert!(
+                builtin_supports_images(ProviderKind::OpenAi, model),
+                "{}",
+                model
+            );
+        }
+        for model in ["gpt-3.5-turbo", "o1-mini", "o3-mini", "O3-mini-high"] {
+            assert!(
+                !builtin_supports_images(ProviderKind::OpenAi, model),
+                "{}",
+                model
+            );
+        }
+    }
+}
@@ -0,0 +1,464 @@
+/**
+ * 图片附件处理
+ *
+ * 支持 PNG 与 JPEG。图片解码后按最长边缩小并按原格式重新编码，JPEG 先按 EXIF
+ * 方向旋转，重新编码时去除 EXIF 等元数据；编码后仍超出大小上限时继续缩小
+ */
+use std::io::Cursor;
+
+/// 最长边的像素上限，超出时缩小
+const MAX_EDGE: u32 = 1568;
+/// 继续缩小时最长边的下限
+const MIN_EDGE: u32 = 256;
+/// 编码后的字节上限，base64 编码后不超过多数提供商的 5 MB 限制
+const MAX_ENCODED_BYTES: usize = 3 * 1024 * 1024 + 768 * 1024;
+/// 解码的像素上限
+const MAX_PIXELS: u64 = 50_000_000;
+/// 超出大小上限时每次缩小的比例
+const SHRINK_RATIO: f64 = 0.75;
+/// 重新编码 JPEG 的质量
+const JPEG_QUALITY: u8 = 85;
+/// EXIF 中的方向标签
+const EXIF_ORIENTATION: u16 = 0x0112;
+
+/**
+ * 处理后的图片
+ */
+pub struct ProcessedImage {
+    /// 编码后的内容
+    pub data: Vec<u8>,
+    /// MIME 类型
+    pub mime_type: &'static str,
+    /// 文件扩展名
+    pub extension: &'static str,
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+}
+
+/// 解码后的 RGBA 像素
+struct RgbaImage {
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// 是否为支持的图片格式
+pub fn is_image(data: &[u8]) -> bool {
+    is_png(data) || is_jpeg(data)
+}
+
+fn is_png(data: &[u8]) -> bool {
+    data.starts_with(b"\x89PNG\r\n\x1a\n")
+}
+
+fn is_jpeg(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0xD8, 0xFF])
+}
+
+fn check_pixels(width: u32, height: u32) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err("图片尺寸无效".to_string());
+    }
+    if width as u64 * height as u64 > MAX_PIXELS {
+        return Err(format!("图片尺寸过大: {}x{}", width, height));
+    }
+    Ok(())
+}
+
+/// 解码 PNG，统一转换为 8 位 RGBA
+fn decode_png(data: &[u8]) -> Result<RgbaImage, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    decoder.set_limits(png::Limits {
+        bytes: MAX_PIXELS as usize * 4,
+    });
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("解析 PNG 图片失败: {}", e))?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    check_pixels(width, height)?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("解码 PNG 图片失败: {}", e))?;
+    buf.truncate(frame.buffer_size());
+
+    let pixels = match frame.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        png::ColorType::Indexed => return Err("不支持的 PNG 颜色类型".to_string()),
+    };
+    Ok(RgbaImage {
+        pixels,
+        width,
+        height,
+    })
+}
+
+/// 解码 JPEG，统一转换为 8 位 RGBA
+fn decode_jpeg(data: &[u8]) -> Result<RgbaImage, String> {
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(data));
+    decoder
+        .read_info()
+        .map_err(|e| format!("解析 JPEG 图片失败: {}", e))?;
+    let info = decoder.info().ok_or("解析 JPEG 图片失败")?;
+    let (width, height) = (info.width as u32, info.height as u32);
+    check_pixels(width, height)?;
+    decoder.set_max_decoding_buffer_size(MAX_PIXELS as usize * 4);
+    let buf = decoder
+        .decode()
+        .map_err(|e| format!("解码 JPEG 图片失败: {}", e))?;
+    let orientation = decoder.exif_data().map_or(1, exif_orientation);
+
+    let pixels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        jpeg_decoder::PixelFormat::L8 => buf.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => buf
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+        jpeg_decoder::PixelFormat::L16 => return Err("不支持 16 位 JPEG 图片".to_string()),
+    };
+    Ok(orient(
+        RgbaImage {
+            pixels,
+            width,
+            height,
+        },
+        orientation,
+    ))
+}
+
+/**
+ * 读取 EXIF 中的方向
+ *
+ * @param exif APP1 段中 "Exif\0\0" 之后的 TIFF 数据
+ * @return 方向（1-8），缺失或无效时为 1
+ */
+fn exif_orientation(exif: &[u8]) -> u16 {
+    let little_endian = match exif.get(..4) {
+        Some(b"II*\0") => true,
+        Some(b"MM\0*") => false,
+        _ => return 1,
+    };
+    let u16_at = |offset: usize| {
+        let bytes: [u8; 2] = exif.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize| {
+        let bytes: [u8; 4] = exif.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let find = || {
+        let ifd = u32_at(4)? as usize;
+        let count = u16_at(ifd)? as usize;
+        (0..count)
+            .map(|index| ifd + 2 + index * 12)
+            .find(|&entry| u16_at(entry) == Some(EXIF_ORIENTATION))
+            .and_then(|entry| u16_at(entry + 8))
+    };
+    find().filter(|value| (1..=8).contains(value)).unwrap_or(1)
+}
+
+/**
+ * 按 EXIF 方向旋转或翻转图片，使其按正常方向显示
+ *
+ * @param image 解码后的图片
+ * @param orientation EXIF 方向
+ * @return 旋转后的图片
+ */
+fn orient(image: RgbaImage, orientation: u16) -> RgbaImage {
+    if !(2..=8).contains(&orientation) {
+        return image;
+    }
+    let (w, h) = (image.width, image.height);
+    // 5-8 需要交换宽高
+    let (width, height) = if orientation >= 5 { (h, w) } else { (w, h) };
+    let mut pixels = Vec::with_capacity(image.pixels.len());
+    for y in 0..height {
+        for x in 0..width {
+            let (sx, sy) = match orientation {
+                2 => (w - 1 - x, y),
+                3 => (w - 1 - x, h - 1 - y),
+                4 => (x, h - 1 - y),
+                5 => (y, x),
+                6 => (y, h - 1 - x),
+                7 => (w - 1 - y, h - 1 - x),
+                _ => (w - 1 - y, x),
+            };
+            let offset = (sy as usize * w as usize + sx as usize) * 4;
+            pixels.extend_from_slice(&image.pixels[offset..offset + 4]);
+        }
+    }
+    RgbaImage {
+        pixels,
+        width,
+        height,
+    }
+}
+
+/**
+ * 按区域平均缩小图片
+ *
+ * 每个目标像素取其覆盖的源像素的平均值，缩小比例较大时也不会产生锯齿
+ */
+fn downscale(image: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    let span = |index: u32, target: u32, source: u32| {
+        let start = (index as u64 * source as u64 / target as u64) as u32;
+        let end = ((index as u64 + 1) * source as u64).div_ceil(target as u64) as u32;
+        (start, end.clamp(start + 1, source))
+    };
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        let (y0, y1) = span(y, height, image.height);
+        for x in 0..width {
+            let (x0, x1) = span(x, width, image.width);
+            let mut sum = [0u64; 4];
+            for sy in y0..y1 {
+                let row = (sy as usize * image.width as usize) * 4;
+                for sx in x0..x1 {
+                    let offset = row + sx as usize * 4;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += image.pixels[offset + channel] as u64;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u64;
+            pixels.extend(sum.map(|total| ((total + count / 2) / count) as u8));
+        }
+    }
+    RgbaImage {
+        pixels,
+        width,
+        height,
+    }
+}
+
+/// 编码为 PNG，完全不透明的图片省略透明通道
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let opaque = image.pixels.chunks_exact(4).all(|p| p[3] == 255);
+    let (color, data) = if opaque {
+        let rgb: Vec<u8> = image
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|p| [p[0], p[1], p[2]])
+            .collect();
+        (png::ColorType::Rgb, rgb)
+    } else {
+        (png::ColorType::Rgba, image.pixels.clone())
+    };
+
+    let mut output = Vec::new();
+    let mut encoder = png::Encoder::new(&mut output, image.width, image.height);
+    encoder.set_color(color);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Best);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("编码图片失败: {}", e))?;
+    writer
+        .write_image_data(&data)
+        .map_err(|e| format!("编码图片失败: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("编码图片失败: {}", e))?;
+    Ok(output)
+}
+
+/// 编码为 JPEG，照片保持有损压缩以控制大小
+fn encode_jpeg(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    jpeg_encoder::Encoder::new(&mut output, JPEG_QUALITY)
+        .encode(
+            &image.pixels,
+            image.width as u16,
+            image.height as u16,
+            jpeg_encoder::ColorType::Rgba,
+        )
+        .map_err(|e| format!("编码图片失败: {}", e))?;
+    Ok(output)
+}
+
+/// 按最长边计算缩小后的尺寸
+fn fit(width: u32, height: u32, max_edge: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= max_edge {
+        return (width, height);
+    }
+    let scale = max_edge as f64 / longest as f64;
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/**
+ * 缩小并重新编码图片
+ *
+ * @param data PNG 或 JPEG 文件内容
+ * @return 处理后的图片，格式与原图相同
+ */
+pub fn process_image(data: &[u8]) -> Result<ProcessedImage, String> {
+    let jpeg = is_jpeg(data);
+    let image = if is_png(data) {
+        decode_png(data)?
+    } else if jpeg {
+        decode_jpeg(data)?
+    } else {
+        return Err("仅支持 PNG 与 JPEG 图片".to_string());
+    };
+    let encode: fn(&RgbaImage) -> Result<Vec<u8>, String> =
+        if jpeg { encode_jpeg } else { encode_png };
+    let (mime_type, extension) = if jpeg {
+        ("image/jpeg", "jpg")
+    } else {
+        ("image/png", "png")
+    };
+
+    let mut max_edge = MAX_EDGE;
+    loop {
+        let (width, height) = fit(image.width, image.height, max_edge);
+        let encoded = if (width, height) == (image.width, image.height) {
+            encode(&image)?
+        } else {
+            encode(&downscale(&image, width, height))?
+        };
+        if encoded.len() <= MAX_ENCODED_BYTES || width.max(height) <= MIN_EDGE {
+            return Ok(ProcessedImage {
+                data: encoded,
+                mime_type,
+                extension,
+                width,
+                height,
+            });
+        }
+        max_edge = ((width.max(height) as f64 * SHRINK_RATIO) as u32).max(MIN_EDGE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造只包含方向标签的 EXIF 数据
+    fn exif(little_endian: bool, orientation: u16) -> Vec<u8> {
+        let u16_bytes = |value: u16| {
+            if little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+        let u32_bytes = |value: u32| {
+            if little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+        let mut data = if little_endian {
+            b"II*\0".to_vec()
+        } else {
+            b"MM\0*".to_vec()
+        };
+        data.extend(u32_bytes(8));
+        data.extend(u16_bytes(1));
+        data.extend(u16_bytes(EXIF_ORIENTATION));
+        data.extend(u16_bytes(3));
+        data.extend(u32_bytes(1));
+        data.extend(u16_bytes(orientation));
+        data.extend([0, 0]);
+        data.extend(u32_bytes(0));
+        data
+    }
+
+    /// 2x1 的图片，左红右绿
+    fn red_green() -> RgbaImage {
+        RgbaImage {
+            pixels: vec![255, 0, 0, 255, 0, 255, 0, 255],
+            width: 2,
+            height: 1,
+        }
+    }
+
+    #[test]
+    fn reads_exif_orientation() {
+        assert_eq!(exif_orientation(&exif(true, 6)), 6);
+        assert_eq!(exif_orientation(&exif(false, 8)), 8);
+        assert_eq!(exif_orientation(&exif(true, 9)), 1);
+        assert_eq!(exif_orientation(b"II*\0"), 1);
+        assert_eq!(exif_orientation(&[]), 1);
+    }
+
+    #[test]
+    fn applies_orientation() {
+        let rotated = orient(red_green(), 6);
+        assert_eq!((rotated.width, rotated.height), (1, 2));
+        assert_eq!(rotated.pixels[..4], [255, 0, 0, 255]);
+
+        let rotated = orient(red_green(), 8);
+        assert_eq!((rotated.width, rotated.height), (1, 2));
+        assert_eq!(rotated.pixels[..4], [0, 255, 0, 255]);
+
+        let flipped = orient(red_green(), 2);
+        assert_eq!(flipped.pixels[..4], [0, 255, 0, 255]);
+        assert_eq!(orient(red_green(), 1).pixels, red_green().pixels);
+    }
+
+    #[test]
+    fn fits_longest_edge() {
+        assert_eq!(fit(800, 600, MAX_EDGE), (800, 600));
+        assert_eq!(fit(4000, 3000, 2000), (2000, 1500));
+        assert_eq!(fit(3000, 4000, 2000), (1500, 2000));
+        assert_eq!(fit(10_000, 1, 100), (100, 1));
+    }
+
+    #[test]
+    fn downscale_averages_pixels() {
+        let image = downscale(&red_green(), 1, 1);
+        assert_eq!(image.pixels, [128, 128, 0, 255]);
+    }
+
+    #[test]
+    fn keeps_jpeg_as_jpeg() {
+        let image = RgbaImage {
+            pixels: [200, 100, 50, 255].repeat(16 * 8),
+            width: 16,
+            height: 8,
+        };
+        let processed = process_image(&encode_jpeg(&image).unwrap()).unwrap();
+        assert_eq!(processed.mime_type, "image/jpeg");
+        assert!(is_jpeg(&processed.data));
+        assert_eq!((processed.width, processed.height), (16, 8));
+
+        let processed = process_image(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(processed.mime_type, "image/png");
+        assert!(is_png(&processed.data));
+    }
+}
@@ -0,0 +1,13 @@
+/**
+ * 对话附件
+ *
+ * - image: 图片解码、缩小与重新编码
+ * - capability: 模型的图片输入能力
+ * - store: 附件的保存、关联消息与发送前加载
+ */
+mod capability;
+mod image;
+mod store;
+
+pub use capability::*;
+pub use store::*;
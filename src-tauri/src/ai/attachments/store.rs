@@ -0,0 +1,612 @@
+/**
+ * 对话附件的保存与加载
+ *
+ * 添加附件时即完成处理：图片缩小并重新编码为 PNG，文档提取为文本，结果写入应用
+ * 数据目录下的 ai_attachments 目录。附件先处于待发送状态，保存消息时关联到消息ID；
+ * 分支会话复制附件记录并共用同一文件。发送请求前网关按提供商格式加载附件：图片以
+ * base64 附在消息中，文档文本追加到消息内容之后
+ */
+use super::capability::supports_images;
+use super::image::{is_image, process_image};
+use crate::ai::knowledge::document_text;
+use crate::ai::types::{ChatRequest, ChatRole, ImageContent};
+use crate::file::paths::get_data_dir;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// 附件目录名
+const ATTACHMENT_DIR: &str = "ai_attachments";
+/// 源文件大小上限（字节）
+const MAX_SOURCE_BYTES: u64 = 20 * 1024 * 1024;
+/// 文档文本的字符上限，超出部分截断
+const MAX_DOCUMENT_CHARS: usize = 100_000;
+/// 未关联消息的附件保留时间（毫秒）
+const PENDING_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+/// 按文本读取的文档扩展名
+const TEXT_EXTENSIONS: [&str; 27] = [
+    "md", "markdown", "txt", "text", "log", "csv", "tsv", "json", "xml", "html", "htm", "yaml",
+    "yml", "toml", "ini", "sql", "rs", "py", "js", "ts", "tsx", "jsx", "vue", "java", "go", "c",
+    "sh",
+];
+
+/**
+ * 附件类型
+ */
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentKind {
+    /// 图片
+    Image,
+    /// 文档，以文本形式发送
+    Document,
+}
+
+impl AttachmentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Document => "document",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "image" => Ok(AttachmentKind::Image),
+            "document" => Ok(AttachmentKind::Document),
+            _ => Err(format!("未知的附件类型: {}", value)),
+        }
+    }
+}
+
+/**
+ * 对话附件
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct ChatAttachment {
+    /// 附件ID
+    pub id: String,
+    /// 所属消息ID，尚未发送时为空
+    pub message_id: Option<String>,
+    /// 类型
+    pub kind: AttachmentKind,
+    /// 原始文件名
+    pub file_name: String,
+    /// 原始文件的 MIME 类型，图片为处理后的类型
+    pub mime_type: String,
+    /// 处理后的大小（字节）
+    pub size: i64,
+    /// 图片宽度
+    pub width: Option<i64>,
+    /// 图片高度
+    pub height: Option<i64>,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+}
+
+/// 附件查询的公共列
+const ATTACHMENT_COLUMNS: &str =
+    "id, message_id, kind, file_name, mime_type, size, width, height, file, created_at";
+
+fn attachment_from_row(row: &SqliteRow) -> Result<ChatAttachment, String> {
+    Ok(ChatAttachment {
+        id: row.get("id"),
+        message_id: row.get("message_id"),
+        kind: AttachmentKind::parse(&row.get::<String, _>("kind"))?,
+        file_name: row.get("file_name"),
+        mime_type: row.get("mime_type"),
+        size: row.get("size"),
+        width: row.get("width"),
+        height: row.get("height"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// 获取附件目录
+fn attachment_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_data_dir(app.clone())?.join(ATTACHMENT_DIR))
+}
+
+/// 生成 IN 查询的占位符
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// 文档的 MIME 类型
+fn document_mime_type(extension: &str) -> &'static str {
+    match extension {
+        "pdf" => "application/pdf",
+        "md" | "markdown" => "text/markdown",
+        "json" => "application/json",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        _ => "text/plain",
+    }
+}
+
+/**
+ * 提取文档文本
+ *
+ * PDF 提取其中的文本，其余格式须为 UTF-8 文本，超出字符上限的部分截断
+ */
+fn extract_document(path: &Path, data: &[u8]) -> Result<String, String> {
+    let extension = extension(path);
+    if extension != "pdf" {
+        if !TEXT_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(format!(
+                "不支持的附件类型: {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+        std::str::from_utf8(data).map_err(|_| "文件不是 UTF-8 文本".to_string())?;
+    }
+    let text = document_text(path, data)?;
+    if text.trim().is_empty() {
+        return Err("文档中没有可提取的文本".to_string());
+    }
+    Ok(match text.char_indices().nth(MAX_DOCUMENT_CHARS) {
+        Some((end, _)) => format!("{}\n…（内容过长，已截断）", &text[..end]),
+        None => text,
+    })
+}
+
+/**
+ * 添加对话附件
+ *
+ * 图片缩小并重新编码，文档提取为文本，处理结果保存后等待随消息发送
+ *
+ * @param app Tauri应用句柄
+ * @param file_path 文件路径
+ * @return 附件信息
+ */
+#[tauri::command]
+pub async fn add_chat_attachment(
+    app: AppHandle,
+    file_path: String,
+) -> Result<ChatAttachment, String> {
+    let source = PathBuf::from(&file_path);
+    let file_size = tokio::fs::metadata(&source)
+        .await
+        .map_err(|e| format!("无法读取附件文件: {}", e))?
+        .len();
+    if file_size > MAX_SOURCE_BYTES {
+        return Err(format!(
+            "附件大小超过上限 {} MB",
+            MAX_SOURCE_BYTES / 1024 / 1024
+        ));
+    }
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| "无效的附件文件路径".to_string())?;
+    let data = tokio::fs::read(&source)
+        .await
+        .map_err(|e| format!("无法读取附件文件: {}", e))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let (kind, mime_type, extension, content, width, height) = if is_image(&data) {
+        let image = tokio::task::spawn_blocking(move || process_image(&data))
+            .await
+            .map_err(|e| e.to_string())??;
+        (
+            AttachmentKind::Image,
+            image.mime_type,
+            image.extension,
+            image.data,
+            Some(image.width as i64),
+            Some(image.height as i64),
+        )
+    } else {
+        let text = {
+            let source = source.clone();
+            tokio::task::spawn_blocking(move || extract_document(&source, &data))
+                .await
+                .map_err(|e| e.to_string())??
+        };
+        (
+            AttachmentKind::Document,
+            document_mime_type(&extension(&source)),
+            "txt",
+            text.into_bytes(),
+            None,
+            None,
+        )
+    };
+    let file = format!("{}.{}", id, extension);
+
+    let dir = attachment_dir(&app)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("创建附件目录失败: {}", e))?;
+    let target = dir.join(&file);
+    tokio::fs::write(&target, &content)
+        .await
+        .map_err(|e| format!("保存附件文件失败: {}", e))?;
+
+    let attachment = ChatAttachment {
+        id,
+        message_id: None,
+        kind,
+        file_name,
+        mime_type: mime_type.to_string(),
+        size: content.len() as i64,
+        width,
+        height,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let inserted = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_chat_attachments
+             (id, kind, file_name, mime_type, size, width, height, file, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            &attachment.id,
+            attachment.kind.as_str(),
+            &attachment.file_name,
+            &attachment.mime_type,
+            attachment.size,
+            attachment.width,
+            attachment.height,
+            &file,
+            attachment.created_at
+        )
+    };
+    if let Err(e) = inserted {
+        let _ = tokio::fs::remove_file(&target).await;
+        return Err(e);
+    }
+    Ok(attachment)
+}
+
+/**
+ * 删除对话附件
+ *
+ * 文件没有其他记录引用时一并删除
+ *
+ * @param app Tauri应用句柄
+ * @param id 附件ID
+ * @return 是否存在并被删除
+ */
+#[tauri::command]
+pub async fn delete_chat_attachment(app: AppHandle, id: String) -> Result<bool, String> {
+    let orphan = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let Some(row) = crate::query_one_with_params!(
+            db,
+            "SELECT file FROM ai_chat_attachments WHERE id = ?",
+            &id
+        )?
+        else {
+            return Ok(false);
+        };
+        let file: String = row.get("file");
+        crate::execute_with_params!(db, "DELETE FROM ai_chat_attachments WHERE id = ?", &id)?;
+        crate::query_one_with_params!(
+            db,
+            "SELECT 1 FROM ai_chat_attachments WHERE file = ? LIMIT 1",
+            &file
+        )?
+        .is_none()
+        .then_some(file)
+    };
+    if let Some(file) = orphan {
+        let path = attachment_dir(&app)?.join(file);
+        if path.exists() {
+            tokio::fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("删除附件文件失败: {}", e))?;
+        }
+    }
+    Ok(true)
+}
+
+/**
+ * 读取附件内容，用于预览
+ *
+ * @param app Tauri应用句柄
+ * @param id 附件ID
+ * @return 图片为 data URL，文档为提取的文本
+ */
+#[tauri::command]
+pub async fn get_chat_attachment_content(app: AppHandle, id: String) -> Result<String, String> {
+    let (attachment, file) = load_attachments(std::slice::from_ref(&id))
+        .await?
+        .remove(&id)
+        .ok_or_else(|| format!("附件不存在: {}", id))?;
+    let data = tokio::fs::read(attachment_dir(&app)?.join(file))
+        .await
+        .map_err(|e| format!("读取附件文件失败: {}", e))?;
+    Ok(match attachment.kind {
+        AttachmentKind::Image => format!(
+            "data:{};base64,{}",
+            attachment.mime_type,
+            STANDARD.encode(data)
+        ),
+        AttachmentKind::Document => String::from_utf8_lossy(&data).into_owned(),
+    })
+}
+
+/**
+ * 读取附件记录
+ *
+ * @param ids 附件ID
+ * @return 附件信息与文件名，键为附件ID
+ */
+async fn load_attachments(
+    ids: &[String],
+) -> Result<HashMap<String, (ChatAttachment, String)>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let sql = format!(
+        "SELECT {} FROM ai_chat_attachments WHERE id IN ({})",
+        ATTACHMENT_COLUMNS,
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    let rows = query
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询附件失败: {}", e))?;
+    rows.iter()
+        .map(|row| Ok((row.get("id"), (attachment_from_row(row)?, row.get("file")))))
+        .collect()
+}
+
+/**
+ * 将待发送的附件关联到消息
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param message_id 消息ID
+ * @param ids 附件ID
+ * @return 关联的附件
+ */
+pub async fn link_attachments(
+    conn: &mut SqliteConnection,
+    message_id: &str,
+    ids: &[String],
+) -> Result<Vec<ChatAttachment>, String> {
+    for id in ids {
+        let linked = sqlx::query(
+            "UPDATE ai_chat_attachments SET message_id = ? WHERE id = ? AND message_id IS NULL",
+        )
+        .bind(message_id)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("关联附件失败: {}", e))?
+        .rows_affected();
+        if linked == 0 {
+            return Err(format!("附件不存在或已发送: {}", id));
+        }
+    }
+    Ok(load_message_attachments(conn, &[message_id.to_string()])
+        .await?
+        .remove(message_id)
+        .unwrap_or_default())
+}
+
+/**
+ * 读取消息的附件
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param message_ids 消息ID
+ * @return 附件，键为消息ID，按添加时间排列
+ */
+pub async fn load_message_attachments(
+    conn: &mut SqliteConnection,
+    message_ids: &[String],
+) -> Result<HashMap<String, Vec<ChatAttachment>>, String> {
+    let mut result: HashMap<String, Vec<ChatAttachment>> = HashMap::new();
+    if message_ids.is_empty() {
+        return Ok(result);
+    }
+    let sql = format!(
+        "SELECT {} FROM ai_chat_attachments WHERE message_id IN ({}) ORDER BY created_at",
+        ATTACHMENT_COLUMNS,
+        placeholders(message_ids.len())
+    );
+    let mut query = sqlx::query(&sql);
+    for id in message_ids {
+        query = query.bind(id);
+    }
+    let rows = query
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("查询附件失败: {}", e))?;
+    for row in &rows {
+        let attachment = attachment_from_row(row)?;
+        if let Some(message_id) = attachment.message_id.clone() {
+            result.entry(message_id).or_default().push(attachment);
+        }
+    }
+    Ok(result)
+}
+
+/**
+ * 为分支会话复制附件记录
+ *
+ * 按消息序号对应，复制的记录与原记录共用同一文件
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param source_id 原会话ID
+ * @param target_id 分支会话ID
+ * @param max_seq 复制的最大消息序号
+ * @return 操作结果
+ */
+pub async fn copy_session_attachments(
+    conn: &mut SqliteConnection,
+    source_id: &str,
+    target_id: &str,
+    max_seq: i64,
+) -> Result<(), String> {
+    let rows = sqlx::query(
+        "SELECT target.id AS target_id, a.kind, a.file_name, a.mime_type, a.size,
+                a.width, a.height, a.file, a.created_at
+         FROM ai_chat_attachments a
+         JOIN ai_chat_messages source ON source.id = a.message_id
+         JOIN ai_chat_messages target ON target.session_id = ? AND target.seq = source.seq
+         WHERE source.session_id = ? AND source.seq <= ?",
+    )
+    .bind(target_id)
+    .bind(source_id)
+    .bind(max_seq)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("查询附件失败: {}", e))?;
+
+    for row in rows {
+        sqlx::query(
+            "INSERT INTO ai_chat_attachments
+             (id, message_id, kind, file_name, mime_type, size, width, height, file, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(row.get::<String, _>("target_id"))
+        .bind(row.get::<String, _>("kind"))
+        .bind(row.get::<String, _>("file_name"))
+        .bind(row.get::<String, _>("mime_type"))
+        .bind(row.get::<i64, _>("size"))
+        .bind(row.get::<Option<i64>, _>("width"))
+        .bind(row.get::<Option<i64>, _>("height"))
+        .bind(row.get::<String, _>("file"))
+        .bind(row.get::<i64, _>("created_at"))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("复制附件失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/**
+ * 加载请求中消息的附件
+ *
+ * 图片以 base64 附在消息中，由各提供商转换为对应格式；文档文本追加到消息内容
+ * 之后。请求包含图片而模型不支持图片输入时返回错误
+ *
+ * @param app Tauri应用句柄
+ * @param request 对话请求
+ * @return 加载附件后的请求
+ */
+pub async fn resolve_attachments(
+    app: &AppHandle,
+    request: &ChatRequest,
+) -> Result<ChatRequest, String> {
+    let ids: Vec<String> = request
+        .messages
+        .iter()
+        .flat_map(|message| message.attachments.iter().cloned())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(request.clone());
+    }
+    let attachments = load_attachments(&ids).await?;
+    if attachments
+        .values()
+        .any(|(attachment, _)| attachment.kind == AttachmentKind::Image)
+        && !supports_images(request.provider, &request.model).await?
+    {
+        return Err(format!(
+            "模型 {} 不支持图片输入，请更换模型或移除图片附件",
+            request.model
+        ));
+    }
+
+    let dir = attachment_dir(app)?;
+    let mut request = request.clone();
+    for message in &mut request.messages {
+        for id in &message.attachments {
+            let (attachment, file) = attachments
+                .get(id)
+                .ok_or_else(|| format!("附件不存在: {}", id))?;
+            let data = tokio::fs::read(dir.join(file))
+                .await
+                .map_err(|e| format!("读取附件 {} 失败: {}", attachment.file_name, e))?;
+            match attachment.kind {
+                AttachmentKind::Image if message.role != ChatRole::User => {
+                    return Err("只有用户消息可以附带图片".to_string());
+                }
+                AttachmentKind::Image => message.images.push(ImageContent {
+                    mime_type: attachment.mime_type.clone(),
+                    data: STANDARD.encode(&data),
+                    width: attachment.width.unwrap_or_default() as u32,
+                    height: attachment.height.unwrap_or_default() as u32,
+                }),
+                AttachmentKind::Document => message.content.push_str(&format!(
+                    "\n\n[附件: {}]\n{}",
+                    attachment.file_name,
+                    String::from_utf8_lossy(&data)
+                )),
+            }
+        }
+    }
+    Ok(request)
+}
+
+/**
+ * 清理过期的待发送附件与没有记录引用的附件文件
+ *
+ * 消息被删除时附件记录随外键级联删除，对应的文件在启动时清理
+ *
+ * @param app Tauri应用句柄
+ * @return 清理的文件数
+ */
+pub async fn cleanup_chat_attachments(app: AppHandle) -> Result<usize, String> {
+    let dir = attachment_dir(&app)?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let known: HashSet<String> = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "DELETE FROM ai_chat_attachments WHERE message_id IS NULL AND created_at < ?",
+            chrono::Utc::now().timestamp_millis() - PENDING_TTL_MS
+        )?;
+        sqlx::query("SELECT DISTINCT file FROM ai_chat_attachments")
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询附件失败: {}", e))?
+            .iter()
+            .map(|row| row.get("file"))
+            .collect()
+    };
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(&dir).map_err(|e| format!("读取附件目录失败: {}", e))? {
+        let path = entry
+            .map_err(|e| format!("读取附件目录失败: {}", e))?
+            .path();
+        let is_known = path
+            .file_name()
+            .is_some_and(|name| known.contains(name.to_string_lossy().as_ref()));
+        if !is_known && path.is_file() {
+            match std::fs::remove_file(&path) {
+                Ok(_) => removed += 1,
+                Err(e) => log::warn!("删除孤立附件失败 ({}): {}", path.display(), e),
+            }
+        }
+    }
+    Ok(removed)
+}
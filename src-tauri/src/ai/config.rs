@@ -0,0 +1,339 @@
+/**
+ * AI 提供商配置
+ *
+ * 接口地址与启用状态保存在 ai_provider_configs 表中，API 密钥以设备密钥加密保存在
+ * 加密存储（ai.api_key.<提供商>）中，只在 Rust 端发起请求时解密，前端只能看到
+ * 掩码后的预览。其他 AI 相关设置以键值形式保存在 ai_settings 表中
+ */
+use super::types::ProviderKind;
+use crate::security::storage::{delete_secret, get_secret, set_secret, EncryptionLevel};
+use serde::Serialize;
+use sqlx::Row;
+use tauri::AppHandle;
+
+/// API 密钥在加密存储中的键名前缀
+const API_KEY_SECRET_PREFIX: &str = "ai.api_key.";
+
+/**
+ * 提供商的连接配置
+ */
+pub struct ProviderConfig {
+    /// API 密钥
+    pub api_key: Option<String>,
+    /// 接口地址，不以 / 结尾
+    pub base_url: String,
+}
+
+/**
+ * 返回给前端的提供商配置，不包含密钥明文
+ */
+#[derive(Debug, Serialize)]
+pub struct ProviderSettings {
+    /// 提供商
+    pub provider: ProviderKind,
+    /// 自定义接口地址，为空时使用默认地址
+    pub base_url: Option<String>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 是否已保存 API 密钥
+    pub has_api_key: bool,
+    /// 掩码后的密钥预览
+    pub api_key_preview: Option<String>,
+    /// 更新时间（毫秒）
+    pub updated_at: Option<i64>,
+}
+
+/// 密钥在加密存储中的键名
+fn api_key_secret(kind: ProviderKind) -> String {
+    format!("{}{}", API_KEY_SECRET_PREFIX, kind.as_str())
+}
+
+/**
+ * 生成密钥预览，只保留首尾少量字符
+ *
+ * @param key API 密钥
+ * @return 预览文本
+ */
+fn mask_api_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 12 {
+        return "••••••••".to_string();
+    }
+    let head: String = chars[..3].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}••••{}", head, tail)
+}
+
+/// 规范化密钥，空白视为未设置
+fn normalize_api_key(key: Option<String>) -> Option<String> {
+    key.map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+/**
+ * 读取提供商的密钥
+ *
+ * 尚未迁移到加密存储的旧配置直接使用表中的明文
+ *
+ * @param app Tauri应用句柄
+ * @param kind 提供商
+ * @param legacy 表中的明文密钥
+ * @return 密钥
+ */
+async fn read_api_key(
+    app: &AppHandle,
+    kind: ProviderKind,
+    legacy: Option<String>,
+) -> Result<Option<String>, String> {
+    match get_secret(app.clone(), &api_key_secret(kind)).await? {
+        Some(key) => Ok(normalize_api_key(Some(key))),
+        None => Ok(normalize_api_key(legacy)),
+    }
+}
+
+/**
+ * 读取提供商配置
+ *
+ * 未保存配置时，不需要密钥的提供商（Ollama）使用默认地址
+ *
+ * @param app Tauri应用句柄
+ * @param kind 提供商
+ * @return 连接配置
+ */
+pub async fn load_provider_config(
+    app: &AppHandle,
+    kind: ProviderKind,
+) -> Result<ProviderConfig, String> {
+    let row = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query("SELECT api_key, base_url, enabled FROM ai_provider_configs WHERE provider = ?")
+            .bind(kind.as_str())
+            .fetch_optional(db.get_pool())
+            .await
+            .map_err(|e| format!("查询AI提供商配置失败: {}", e))?
+    };
+
+    let (legacy_key, base_url) = match row {
+        Some(row) => {
+            if row.get::<i64, _>("enabled") == 0 {
+                return Err(format!("AI提供商未启用: {}", kind.as_str()));
+            }
+            (
+                row.get::<Option<String>, _>("api_key"),
+                row.get::<Option<String>, _>("base_url"),
+            )
+        }
+        None => (None, None),
+    };
+
+    let api_key = read_api_key(app, kind, legacy_key).await?;
+    if api_key.is_none() && kind.requires_api_key() {
+        return Err(format!("AI提供商未配置API密钥: {}", kind.as_str()));
+    }
+    Ok(ProviderConfig {
+        api_key,
+        base_url: normalize_base_url(kind, base_url)?,
+    })
+}
+
+/**
+ * 规范化接口地址，为空时使用默认地址
+ *
+ * @param kind 提供商
+ * @param base_url 配置的接口地址
+ * @return 不以 / 结尾的接口地址
+ */
+fn normalize_base_url(kind: ProviderKind, base_url: Option<String>) -> Result<String, String> {
+    let base_url = base_url
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| kind.default_base_url().to_string());
+    if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        return Err(format!("AI接口地址无效: {}", base_url));
+    }
+    Ok(base_url)
+}
+
+/**
+ * 读取单个提供商返回给前端的配置
+ */
+async fn load_settings(app: &AppHandle, kind: ProviderKind) -> Result<ProviderSettings, String> {
+    let row = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query(
+            "SELECT api_key, base_url, enabled, updated_at FROM ai_provider_configs
+             WHERE provider = ?",
+        )
+        .bind(kind.as_str())
+        .fetch_optional(db.get_pool())
+        .await
+        .map_err(|e| format!("查询AI提供商配置失败: {}", e))?
+    };
+
+    let api_key = read_api_key(app, kind, row.as_ref().and_then(|row| row.get("api_key"))).await?;
+    Ok(ProviderSettings {
+        provider: kind,
+        base_url: row.as_ref().and_then(|row| row.get("base_url")),
+        enabled: row
+            .as_ref()
+            .is_some_and(|row| row.get::<i64, _>("enabled") != 0),
+        has_api_key: api_key.is_some(),
+        api_key_preview: api_key.as_deref().map(mask_api_key),
+        updated_at: row.as_ref().map(|row| row.get("updated_at")),
+    })
+}
+
+/**
+ * 获取全部提供商配置
+ *
+ * @param app Tauri应用句柄
+ * @return 提供商配置列表，密钥只返回预览
+ */
+#[tauri::command]
+pub async fn list_ai_provider_configs(app: AppHandle) -> Result<Vec<ProviderSettings>, String> {
+    let mut settings = Vec::with_capacity(ProviderKind::ALL.len());
+    for kind in ProviderKind::ALL {
+        settings.push(load_settings(&app, kind).await?);
+    }
+    Ok(settings)
+}
+
+/**
+ * 保存提供商配置
+ *
+ * @param app Tauri应用句柄
+ * @param provider 提供商
+ * @param api_key 新的 API 密钥，为空时保留原密钥，空字符串表示清除
+ * @param base_url 接口地址
+ * @param enabled 是否启用
+ * @return 保存后的配置
+ */
+#[tauri::command]
+pub async fn save_ai_provider_config(
+    app: AppHandle,
+    provider: ProviderKind,
+    api_key: Option<String>,
+    base_url: Option<String>,
+    enabled: bool,
+) -> Result<ProviderSettings, String> {
+    let base_url = base_url
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    normalize_base_url(provider, base_url.clone())?;
+
+    let secret = api_key_secret(provider);
+    match api_key.map(|key| key.trim().to_string()) {
+        Some(key) if key.is_empty() => {
+            delete_secret(&secret).await?;
+        }
+        Some(key) => set_secret(app.clone(), &secret, &key, EncryptionLevel::Device, None).await?,
+        // 保留原密钥时，先把可能尚未迁移的明文密钥转入加密存储
+        None => {
+            migrate_plaintext_api_keys(app.clone()).await?;
+        }
+    }
+
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_provider_configs (provider, api_key, base_url, enabled, updated_at)
+             VALUES (?, NULL, ?, ?, ?)
+             ON CONFLICT(provider) DO UPDATE SET api_key = NULL,
+                                                 base_url = excluded.base_url,
+                                                 enabled = excluded.enabled,
+                                                 updated_at = excluded.updated_at",
+            provider.as_str(),
+            base_url,
+            enabled as i64,
+            chrono::Utc::now().timestamp_millis()
+        )?;
+    }
+
+    log::info!("AI提供商配置已保存: {}", provider.as_str());
+    load_settings(&app, provider).await
+}
+
+/**
+ * 将旧版本明文保存的密钥迁移到加密存储
+ *
+ * 应用启动时执行，已迁移的记录清除 api_key 列
+ *
+ * @param app Tauri应用句柄
+ * @return 迁移的数量
+ */
+pub async fn migrate_plaintext_api_keys(app: AppHandle) -> Result<usize, String> {
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query("SELECT provider, api_key FROM ai_provider_configs WHERE api_key IS NOT NULL")
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询AI提供商配置失败: {}", e))?
+    };
+
+    let mut migrated = 0;
+    for row in rows {
+        let provider: String = row.get("provider");
+        let Some(kind) = ProviderKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == provider)
+        else {
+            continue;
+        };
+        let secret = api_key_secret(kind);
+        // 已有加密密钥时以加密存储为准
+        if get_secret(app.clone(), &secret).await?.is_none() {
+            if let Some(key) = normalize_api_key(row.get("api_key")) {
+                set_secret(app.clone(), &secret, &key, EncryptionLevel::Device, None).await?;
+            }
+        }
+
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "UPDATE ai_provider_configs SET api_key = NULL WHERE provider = ?",
+            provider
+        )?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
+
+/**
+ * 读取 ai_settings 中的设置
+ *
+ * @param key 键名
+ * @return 设置值
+ */
+pub async fn load_ai_setting(key: &str) -> Result<Option<String>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    Ok(
+        crate::query_one_with_params!(db, "SELECT value FROM ai_settings WHERE key = ?", key)?
+            .map(|row| row.get("value")),
+    )
+}
+
+/**
+ * 保存 ai_settings 中的设置
+ *
+ * @param key 键名
+ * @param value 设置值
+ * @return 操作结果
+ */
+pub async fn save_ai_setting(key: &str, value: &str) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT OR REPLACE INTO ai_settings (key, value, updated_at) VALUES (?, ?, ?)",
+        key,
+        value,
+        chrono::Utc::now().timestamp_millis()
+    )
+}
@@ -0,0 +1,555 @@
+/**
+ * 上下文长度管理
+ *
+ * 发送前估算请求的 token 数，超出模型上下文长度（减去为回复预留的部分）时按
+ * 请求指定的方式处理：裁剪时从最早的对话消息开始丢弃；摘要时将较早的消息交给
+ * 同一模型压缩为摘要，保存在 ai_chat_sessions.summary 中，后续请求直接复用，
+ * 摘要失败时退回裁剪。摘要同时记录所覆盖消息的哈希，消息被编辑或删除后摘要失效。
+ * 系统消息与最后一条消息始终保留
+ */
+use super::attachments::resolve_attachments;
+use super::config::ProviderConfig;
+use super::gateway::send_chat;
+use super::provider::{get_provider, DEFAULT_MAX_TOKENS};
+use super::tokens::{estimate_message_tokens, estimate_text_tokens, request_overhead};
+use super::types::{
+    ChatMessage, ChatRequest, ChatRole, ContextStrategy, ProviderKind, TokenBreakdown,
+};
+use super::usage::record_chat_usage;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::time::Instant;
+use tauri::AppHandle;
+use tokio::sync::watch;
+
+/// 无法确定模型上下文长度时的默认值
+const DEFAULT_CONTEXT_WINDOW: u64 = 8192;
+/// 摘要的最大生成 token 数，同时作为摘要在上下文中的预算
+const SUMMARY_MAX_TOKENS: u32 = 800;
+/// 摘要消息的前缀
+const SUMMARY_PREFIX: &str = "以下是此前对话的摘要：\n";
+/// 生成摘要的系统提示词
+const SUMMARY_PROMPT: &str = "你是对话摘要助手。请将下面的对话压缩为简洁的摘要，保留用户的目标、\
+     已确认的事实、结论与未解决的问题，使用对话所用的语言，只输出摘要本身。";
+
+/**
+ * 按模型名推断常见模型的上下文长度
+ *
+ * @param kind 提供商
+ * @param model 模型ID
+ * @return 上下文长度
+ */
+fn builtin_context_window(kind: ProviderKind, model: &str) -> u64 {
+    let model = model.to_lowercase();
+    match kind {
+        ProviderKind::Claude => 200_000,
+        // Ollama 默认的 num_ctx，超出部分会被服务端静默截断
+        ProviderKind::Ollama => 4096,
+        ProviderKind::DeepSeek => 65_536,
+        ProviderKind::Qianwen => {
+            if model.starts_with("qwen-long") {
+                1_000_000
+            } else if model.starts_with("qwen-plus") || model.starts_with("qwen-turbo") {
+                131_072
+            } else {
+                32_768
+            }
+        }
+        ProviderKind::OpenAi => {
+            if model.starts_with("gpt-4o")
+                || model.starts_with("gpt-4.1")
+                || model.starts_with("gpt-4-turbo")
+                || model.starts_with('o')
+            {
+                128_000
+            } else if model.starts_with("gpt-4-32k") {
+                32_768
+            } else if model.starts_with("gpt-4") {
+                8192
+            } else if model.starts_with("gpt-3.5-turbo-instruct") {
+                4096
+            } else if model.starts_with("gpt-3.5") {
+                16_385
+            } else {
+                DEFAULT_CONTEXT_WINDOW
+            }
+        }
+    }
+}
+
+/**
+ * 读取模型的上下文长度，优先使用 ai_model_config 中的配置
+ *
+ * @param kind 提供商
+ * @param model 模型ID
+ * @return 上下文长度
+ */
+async fn load_context_window(kind: ProviderKind, model: &str) -> Result<u64, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let configured: Option<i64> = sqlx::query(
+        "SELECT context_window FROM ai_model_config
+         WHERE provider = ? AND model_id = ? AND context_window IS NOT NULL",
+    )
+    .bind(kind.as_str())
+    .bind(model)
+    .fetch_optional(db.get_pool())
+    .await
+    .map_err(|e| format!("查询模型配置失败: {}", e))?
+    .map(|row| row.get("context_window"));
+
+    Ok(configured
+        .filter(|window| *window > 0)
+        .map(|window| window as u64)
+        .unwrap_or_else(|| builtin_context_window(kind, model)))
+}
+
+/**
+ * 会话中保存的摘要
+ */
+struct SessionSummary {
+    /// 摘要内容
+    content: String,
+    /// 摘要覆盖的对话消息数
+    count: usize,
+    /// 摘要覆盖的对话消息的哈希
+    hash: String,
+}
+
+/**
+ * 计算对话消息的哈希，用于判断摘要覆盖的消息是否仍与当前对话一致
+ *
+ * @param messages 对话消息
+ * @return 十六进制哈希
+ */
+fn messages_hash(messages: &[ChatMessage]) -> String {
+    let mut hasher = Sha256::new();
+    for message in messages {
+        let data = serde_json::to_vec(message).unwrap_or_default();
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+    }
+    hex::encode(hasher.finalize())
+}
+
+async fn load_summary(session_id: &str) -> Result<Option<SessionSummary>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let row = sqlx::query(
+        "SELECT summary, summarized_count, summarized_hash FROM ai_chat_sessions
+         WHERE id = ? AND summary IS NOT NULL",
+    )
+    .bind(session_id)
+    .fetch_optional(db.get_pool())
+    .await
+    .map_err(|e| format!("查询会话摘要失败: {}", e))?;
+
+    Ok(row.map(|row| SessionSummary {
+        content: row.get("summary"),
+        count: row.get::<i64, _>("summarized_count").max(0) as usize,
+        hash: row
+            .get::<Option<String>, _>("summarized_hash")
+            .unwrap_or_default(),
+    }))
+}
+
+async fn save_summary(session_id: &str, summary: &SessionSummary) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "UPDATE ai_chat_sessions SET summary = ?, summarized_count = ?, summarized_hash = ?
+         WHERE id = ?",
+        summary.content.as_str(),
+        summary.count as i64,
+        summary.hash.as_str(),
+        session_id
+    )
+}
+
+/// 将摘要包装为系统消息
+fn summary_message(content: &str) -> ChatMessage {
+    ChatMessage::text(ChatRole::System, format!("{}{}", SUMMARY_PREFIX, content))
+}
+
+/**
+ * 计算在预算内能保留的最早消息位置
+ *
+ * 最后一条消息始终保留；保留的第一条消息必须是用户消息，部分提供商要求对话以
+ * 用户消息开头，工具结果也不能脱离对应的调用
+ *
+ * @param history 对话消息
+ * @param costs 每条消息的 token 数
+ * @param from 最早可保留的位置
+ * @param available 可用的 token 数
+ * @return 保留的起始位置，最后一条消息也放不下时为空
+ */
+fn keep_from(history: &[ChatMessage], costs: &[u64], from: usize, available: u64) -> Option<usize> {
+    let last = history.len().checked_sub(1)?;
+    let mut start = last + 1;
+    let mut used = 0u64;
+    while start > from && used + costs[start - 1] <= available {
+        start -= 1;
+        used += costs[start];
+    }
+    if start > last {
+        return None;
+    }
+    while start < last && history[start].role != ChatRole::User {
+        start += 1;
+    }
+    Some(start)
+}
+
+/**
+ * 生成摘要所需的连接与取消信号
+ */
+pub struct Summarizer<'a> {
+    /// Tauri应用句柄，用于记录用量
+    pub app: &'a AppHandle,
+    /// 连接配置
+    pub config: &'a ProviderConfig,
+    /// 取消信号
+    pub cancel: watch::Receiver<bool>,
+}
+
+/**
+ * 调用模型生成摘要
+ *
+ * 待摘要的内容超出上下文长度时只保留较新的部分
+ *
+ * @param summarizer 连接与取消信号
+ * @param request 原始请求
+ * @param previous 已有的摘要
+ * @param messages 待摘要的消息
+ * @param available 可用的 token 数
+ * @return 摘要内容
+ */
+async fn summarize(
+    summarizer: &Summarizer<'_>,
+    request: &ChatRequest,
+    previous: Option<&str>,
+    messages: &[ChatMessage],
+    available: u64,
+) -> Result<String, String> {
+    let kind = request.provider;
+    let mut budget = available.saturating_sub(
+        estimate_text_tokens(kind, SUMMARY_PROMPT)
+            + previous.map_or(0, |text| estimate_text_tokens(kind, text)),
+    );
+    let mut lines = Vec::new();
+    for message in messages.iter().rev() {
+        let line = format!(
+            "{}: {}",
+            match message.role {
+                ChatRole::User => "用户",
+                ChatRole::Assistant => "助手",
+                ChatRole::System => "系统",
+                ChatRole::Tool => "工具结果",
+            },
+            message.content
+        );
+        let cost = estimate_text_tokens(kind, &line) + 1;
+        if cost > budget {
+            break;
+        }
+        budget -= cost;
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        return Err("待摘要的消息过长".to_string());
+    }
+    lines.reverse();
+
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("此前的摘要：\n{}\n\n后续对话：\n", previous));
+    }
+    transcript.push_str(&lines.join("\n"));
+
+    let summary_request = ChatRequest {
+        provider: kind,
+        model: request.model.clone(),
+        messages: vec![
+            ChatMessage::text(ChatRole::System, SUMMARY_PROMPT),
+            ChatMessage::text(ChatRole::User, transcript),
+        ],
+        temperature: Some(0.2),
+        max_tokens: Some(SUMMARY_MAX_TOKENS),
+        stream: Some(false),
+        session_id: None,
+        context_strategy: None,
+        use_tools: None,
+        knowledge_base_ids: Vec::new(),
+        knowledge_top_k: None,
+    };
+    let provider = get_provider(kind);
+    let started = Instant::now();
+    let result = send_chat(
+        provider.as_ref(),
+        summarizer.config,
+        &summary_request,
+        &[],
+        "summary",
+        summarizer.cancel.clone(),
+        &|_| {},
+    )
+    .await;
+
+    // 生成摘要同样计入用量
+    let prompt_tokens = request_overhead(kind)
+        + summary_request
+            .messages
+            .iter()
+            .map(|m| estimate_message_tokens(kind, m))
+            .sum::<u64>();
+    record_chat_usage(
+        summarizer.app,
+        request,
+        &result,
+        prompt_tokens,
+        None,
+        started.elapsed().as_millis() as u64,
+    )
+    .await;
+
+    let result = result?;
+    if result.cancelled {
+        return Err("请求已取消".to_string());
+    }
+    let summary = result.content.trim().to_string();
+    if summary.is_empty() {
+        return Err("模型未返回摘要".to_string());
+    }
+    Ok(summary)
+}
+
+/**
+ * 处理后的上下文
+ */
+pub struct PreparedContext {
+    /// 实际发送的消息
+    pub messages: Vec<ChatMessage>,
+    /// token 估算明细
+    pub tokens: TokenBreakdown,
+}
+
+/**
+ * 按模型上下文长度准备发送的消息
+ *
+ * @param request 对话请求
+ * @param summarizer 生成摘要所需的连接，为空时只使用已保存的摘要
+ * @return 处理后的上下文
+ */
+pub async fn prepare_context(
+    request: &ChatRequest,
+    summarizer: Option<Summarizer<'_>>,
+) -> Result<PreparedContext, String> {
+    let kind = request.provider;
+    let context_window = load_context_window(kind, &request.model).await?;
+    let reserved_output = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS) as u64;
+
+    // 开头的系统消息始终保留，其余为对话消息
+    let system_len = request
+        .messages
+        .iter()
+        .take_while(|m| m.role == ChatRole::System)
+        .count();
+    let (system, history) = request.messages.split_at(system_len);
+    let costs: Vec<u64> = history
+        .iter()
+        .map(|m| estimate_message_tokens(kind, m))
+        .collect();
+    let overhead = request_overhead(kind);
+    let system_tokens: u64 = system
+        .iter()
+        .map(|m| estimate_message_tokens(kind, m))
+        .sum();
+
+    let mut tokens = TokenBreakdown {
+        context_window,
+        reserved_output,
+        system_tokens,
+        ..Default::default()
+    };
+    let available = context_window
+        .saturating_sub(reserved_output)
+        .saturating_sub(overhead + system_tokens);
+
+    let total: u64 = costs.iter().sum();
+    if total <= available {
+        tokens.history_tokens = total;
+        tokens.prompt_tokens = overhead + system_tokens + total;
+        return Ok(PreparedContext {
+            messages: request.messages.clone(),
+            tokens,
+        });
+    }
+
+    let too_long = || {
+        format!(
+            "消息过长，超出模型上下文长度（{} tokens，其中 {} 预留给回复）",
+            context_window, reserved_output
+        )
+    };
+
+    let mut summary: Option<SessionSummary> = None;
+    if let (Some(ContextStrategy::Summarize), Some(session_id)) =
+        (request.context_strategy, request.session_id.as_deref())
+    {
+        // 摘要只能覆盖最后一条消息之前的内容，且所覆盖的消息未被修改
+        summary = load_summary(session_id).await?.filter(|summary| {
+            summary.count < history.len()
+                && summary.hash == messages_hash(&history[..summary.count])
+        });
+
+        let fits = summary.as_ref().is_some_and(|summary| {
+            let summary_cost = estimate_message_tokens(kind, &summary_message(&summary.content));
+            summary_cost + costs[summary.count..].iter().sum::<u64>() <= available
+        });
+        if !fits {
+            if let Some(summarizer) = summarizer.as_ref() {
+                let from = summary.as_ref().map_or(0, |summary| summary.count);
+                let budget = available.saturating_sub(SUMMARY_MAX_TOKENS as u64);
+                let start = keep_from(history, &costs, from, budget).unwrap_or(from);
+                if start > from {
+                    match summarize(
+                        summarizer,
+                        request,
+                        summary.as_ref().map(|summary| summary.content.as_str()),
+                        &history[from..start],
+                        context_window.saturating_sub(SUMMARY_MAX_TOKENS as u64),
+                    )
+                    .await
+                    {
+                        Ok(content) => {
+                            let updated = SessionSummary {
+                                content,
+                                count: start,
+                                hash: messages_hash(&history[..start]),
+                            };
+                            save_summary(session_id, &updated).await?;
+                            summary = Some(updated);
+                        }
+                        Err(e) => log::warn!("生成对话摘要失败，改为裁剪: {}", e),
+                    }
+                }
+            }
+        }
+    }
+
+    let mut messages = system.to_vec();
+    let mut available = available;
+    let mut from = 0;
+    if let Some(summary) = summary {
+        let message = summary_message(&summary.content);
+        let cost = estimate_message_tokens(kind, &message);
+        if cost < available {
+            available -= cost;
+            from = summary.count;
+            tokens.summary_tokens = cost;
+            tokens.summarized_messages = summary.count;
+            messages.push(message);
+        }
+    }
+
+    let start = keep_from(history, &costs, from, available).ok_or_else(too_long)?;
+    messages.extend_from_slice(&history[start..]);
+    tokens.dropped_messages = start - from;
+    tokens.history_tokens = costs[start..].iter().sum();
+    tokens.prompt_tokens = overhead + system_tokens + tokens.summary_tokens + tokens.history_tokens;
+    Ok(PreparedContext { messages, tokens })
+}
+
+/**
+ * 估算请求的 token 数
+ *
+ * 不会生成新的摘要，可在发送前展示给用户；附件按加载后的内容计算
+ *
+ * @param app Tauri应用句柄
+ * @param request 对话请求
+ * @return token 估算明细
+ */
+#[tauri::command]
+pub async fn estimate_chat_tokens(
+    app: AppHandle,
+    request: ChatRequest,
+) -> Result<TokenBreakdown, String> {
+    let request = resolve_attachments(&app, &request).await?;
+    Ok(prepare_context(&request, None).await?.tokens)
+}
+
+/**
+ * 设置模型的上下文长度
+ *
+ * @param provider 提供商
+ * @param model 模型ID
+ * @param context_window 上下文长度，为空时恢复按模型名推断
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_model_context_window(
+    provider: ProviderKind,
+    model: String,
+    context_window: Option<u64>,
+) -> Result<(), String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("未指定模型".to_string());
+    }
+    let context_window = context_window
+        .filter(|window| *window > 0)
+        .map(|w| w as i64);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    // ai_model_config 的外键指向旧的 ai_provider_config 表，需先确保提供商记录存在
+    crate::execute_with_params!(
+        db,
+        "INSERT OR IGNORE INTO ai_provider_config (provider, updated_at) VALUES (?, ?)",
+        provider.as_str(),
+        now
+    )?;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO ai_model_config
+         (provider, model_id, name, enabled, context_window, created_at, updated_at)
+         VALUES (?, ?, ?, 1, ?, ?, ?)
+         ON CONFLICT(provider, model_id) DO UPDATE SET context_window = excluded.context_window,
+                                                       updated_at = excluded.updated_at",
+        provider.as_str(),
+        model.as_str(),
+        model.as_str(),
+        context_window,
+        now,
+        now
+    )?;
+    log::info!(
+        "模型上下文长度已更新: {}/{} -> {:?}",
+        provider.as_str(),
+        model,
+        context_window
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_changes_when_summarized_messages_change() {
+        let history = vec![
+            ChatMessage::text(ChatRole::User, "你好"),
+            ChatMessage::text(ChatRole::Assistant, "你好，有什么可以帮你？"),
+        ];
+        let hash = messages_hash(&history);
+        assert_eq!(hash, messages_hash(&history.clone()));
+
+        let mut edited = history.clone();
+        edited[1].content = "已编辑".to_string();
+        assert_ne!(hash, messages_hash(&edited));
+        assert_ne!(hash, messages_hash(&history[1..]));
+    }
+}
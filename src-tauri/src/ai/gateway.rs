@@ -0,0 +1,604 @@
+use super::attachments::resolve_attachments;
+/**
+ * AI 网关
+ *
+ * 使用提供商配置中的密钥发起对话请求，将流式响应逐段通过 Channel 推送给前端。
+ * 每个请求以前端生成的请求ID登记，可随时取消，取消时返回已生成的部分。
+ * 启用工具时，模型发起的工具调用由网关执行后将结果交回模型继续生成；指定知识库时
+ * 先检索相关资料附在系统消息中
+ */
+use super::config::{load_provider_config, ProviderConfig};
+use super::context::{prepare_context, Summarizer};
+use super::knowledge::attach_knowledge;
+use super::provider::{
+    extract_error, get_provider, AiProvider, ResponseChunk, StreamFormat, ToolCallDelta,
+};
+use super::tools::{available_tools, invoke_tool};
+use super::types::{
+    ChatMessage, ChatRequest, ChatResult, ChatRole, ChatStreamEvent, ChatUsage, ProviderKind,
+    ToolCall, ToolDefinition,
+};
+use super::usage::record_chat_usage;
+use crate::http::client::get_http_client;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+use tauri_plugin_http::reqwest;
+use tokio::sync::watch;
+
+/// 错误信息的最大长度（字符）
+const MAX_ERROR_LEN: usize = 500;
+/// 测试连接的超时时间（秒）
+const TEST_TIMEOUT_SECS: u64 = 30;
+/// 单次对话中工具调用的最大轮数
+const MAX_TOOL_ROUNDS: usize = 5;
+
+// 使用lazy_static管理进行中的请求
+lazy_static::lazy_static! {
+    static ref ACTIVE_REQUESTS: Arc<Mutex<HashMap<String, watch::Sender<bool>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// 登记进行中的请求，结束时移除
+struct RequestGuard {
+    request_id: String,
+}
+
+impl RequestGuard {
+    fn register(request_id: &str) -> Result<(Self, watch::Receiver<bool>), String> {
+        let mut requests = ACTIVE_REQUESTS.lock().map_err(|e| e.to_string())?;
+        if requests.contains_key(request_id) {
+            return Err(format!("请求ID已存在: {}", request_id));
+        }
+        let (sender, receiver) = watch::channel(false);
+        requests.insert(request_id.to_string(), sender);
+        Ok((
+            RequestGuard {
+                request_id: request_id.to_string(),
+            },
+            receiver,
+        ))
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        if let Ok(mut requests) = ACTIVE_REQUESTS.lock() {
+            requests.remove(&self.request_id);
+        }
+    }
+}
+
+/**
+ * 累积流式响应
+ */
+#[derive(Default)]
+struct ResponseState {
+    content: String,
+    tool_calls: Vec<ToolCallDelta>,
+    usage: Option<ChatUsage>,
+    done: bool,
+}
+
+impl ResponseState {
+    /// 合并一段响应，返回新增的文本
+    fn apply(&mut self, chunk: ResponseChunk) -> Option<String> {
+        if let Some(usage) = chunk.usage {
+            self.usage
+                .get_or_insert_with(ChatUsage::default)
+                .merge(&usage);
+        }
+        for delta in chunk.tool_calls {
+            self.merge_tool_call(delta);
+        }
+        self.done |= chunk.done;
+        let text = chunk.text.filter(|text| !text.is_empty())?;
+        self.content.push_str(&text);
+        Some(text)
+    }
+
+    /// 按序号合并工具调用的片段
+    fn merge_tool_call(&mut self, delta: ToolCallDelta) {
+        let existing = delta.index.and_then(|index| {
+            self.tool_calls
+                .iter_mut()
+                .find(|call| call.index == Some(index))
+        });
+        match existing {
+            Some(call) => {
+                call.id = call.id.take().or(delta.id);
+                call.name = call.name.take().or(delta.name);
+                call.arguments.push_str(&delta.arguments);
+            }
+            None => self.tool_calls.push(delta),
+        }
+    }
+
+    /// 取出完整的工具调用，参数不是有效 JSON 时保留原始字符串
+    fn take_tool_calls(&mut self) -> Vec<ToolCall> {
+        self.tool_calls
+            .drain(..)
+            .enumerate()
+            .filter_map(|(position, delta)| {
+                let name = delta.name?;
+                let arguments = if delta.arguments.trim().is_empty() {
+                    Value::Object(Default::default())
+                } else {
+                    serde_json::from_str(&delta.arguments).unwrap_or(Value::String(delta.arguments))
+                };
+                Some(ToolCall {
+                    id: delta.id.unwrap_or_else(|| format!("call_{}", position)),
+                    name,
+                    arguments,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 截断过长的错误信息
+fn truncate_error(message: &str) -> String {
+    let mut message: String = message.trim().chars().take(MAX_ERROR_LEN).collect();
+    if message.is_empty() {
+        message = "无响应内容".to_string();
+    }
+    message
+}
+
+/**
+ * 从失败的响应中读取错误信息
+ *
+ * @param response HTTP响应
+ * @return 错误信息
+ */
+pub(super) async fn response_error(response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|body| extract_error(&body))
+        .unwrap_or(body);
+    format!("AI接口返回错误 ({}): {}", status, truncate_error(&message))
+}
+
+/// 取出流式响应一行中的数据部分
+fn frame_data(format: StreamFormat, line: &str) -> Option<&str> {
+    let data = match format {
+        // 忽略 event:、id: 及注释行，只处理 data 行
+        StreamFormat::Sse => line.strip_prefix("data:")?.trim_start(),
+        StreamFormat::JsonLines => line,
+    };
+    (!data.is_empty()).then_some(data)
+}
+
+/**
+ * 发送对话请求
+ *
+ * @param provider 提供商实现
+ * @param config 连接配置
+ * @param request 对话请求
+ * @param tools 可供调用的工具
+ * @param request_id 请求ID
+ * @param cancel 取消信号
+ * @param emit 推送流式事件
+ * @return 对话结果
+ */
+pub(super) async fn send_chat(
+    provider: &dyn AiProvider,
+    config: &ProviderConfig,
+    request: &ChatRequest,
+    tools: &[ToolDefinition],
+    request_id: &str,
+    mut cancel: watch::Receiver<bool>,
+    emit: &(dyn Fn(ChatStreamEvent) + Sync),
+) -> Result<ChatResult, String> {
+    let stream = request.stream.unwrap_or(true);
+    let builder = get_http_client()
+        .post(provider.endpoint(&config.base_url, request))
+        .json(&provider.build_body(request, tools, stream));
+    let builder = provider.apply_headers(builder, config.api_key.as_deref(), stream);
+
+    let cancelled = |state: ResponseState| ChatResult {
+        request_id: request_id.to_string(),
+        content: state.content,
+        usage: state.usage,
+        tokens: None,
+        tool_calls: Vec::new(),
+        citations: Vec::new(),
+        cancelled: true,
+    };
+
+    let mut response = tokio::select! {
+        _ = cancel.changed() => return Ok(cancelled(ResponseState::default())),
+        response = builder.send() => response.map_err(|e| format!("AI请求失败: {}", e))?,
+    };
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+    emit(ChatStreamEvent::Started {
+        request_id: request_id.to_string(),
+    });
+
+    let mut state = ResponseState::default();
+    if stream {
+        let format = provider.stream_format();
+        let mut buffer: Vec<u8> = Vec::new();
+        while !state.done {
+            let bytes = tokio::select! {
+                _ = cancel.changed() => return Ok(cancelled(state)),
+                bytes = response.chunk() => bytes.map_err(|e| format!("读取AI响应失败: {}", e))?,
+            };
+            let at_end = bytes.is_none();
+            match bytes {
+                Some(bytes) => buffer.extend_from_slice(&bytes),
+                // 处理末尾没有换行符的数据
+                None => buffer.push(b'\n'),
+            }
+
+            while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = frame_data(format, line.trim()) else {
+                    continue;
+                };
+                if let Some(text) = state.apply(provider.parse_stream_chunk(data)?) {
+                    emit(ChatStreamEvent::Delta { text });
+                }
+            }
+            if at_end {
+                break;
+            }
+        }
+    } else {
+        let body: Value = tokio::select! {
+            _ = cancel.changed() => return Ok(cancelled(state)),
+            body = response.json() => body.map_err(|e| format!("解析AI响应失败: {}", e))?,
+        };
+        if let Some(text) = state.apply(provider.parse_response(&body)?) {
+            emit(ChatStreamEvent::Delta { text });
+        }
+    }
+
+    let tool_calls = state.take_tool_calls();
+    // 需要执行工具时还会继续生成，由调用方在最终结束时推送结束事件
+    if tool_calls.is_empty() {
+        emit(ChatStreamEvent::Finished { usage: state.usage });
+    }
+    Ok(ChatResult {
+        request_id: request_id.to_string(),
+        content: state.content,
+        usage: state.usage,
+        tokens: None,
+        tool_calls,
+        citations: Vec::new(),
+        cancelled: false,
+    })
+}
+
+/**
+ * 发起对话
+ *
+ * 发送前按模型上下文长度裁剪或摘要较早的消息，token 估算明细通过 on_event
+ * 推送；生成的文本通过 on_event 逐段推送，命令在生成结束或取消后返回完整结果。
+ * 每次请求的用量、耗时与错误都会写入用量统计
+ *
+ * @param app Tauri应用句柄
+ * @param request_id 请求ID，由前端生成，用于取消
+ * @param request 对话请求
+ * @param on_event 流式事件通道
+ * @return 对话结果
+ */
+#[tauri::command]
+pub async fn ai_chat(
+    app: AppHandle,
+    request_id: String,
+    request: ChatRequest,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<ChatResult, String> {
+    if request.model.trim().is_empty() {
+        return Err("未指定模型".to_string());
+    }
+    if request.messages.is_empty() {
+        return Err("消息列表为空".to_string());
+    }
+
+    let config = load_provider_config(&app, request.provider).await?;
+    let (_guard, cancel) = RequestGuard::register(&request_id)?;
+    let provider = get_provider(request.provider);
+    // 工具调用会发起多轮请求，开始事件只推送一次
+    let started_sent = AtomicBool::new(false);
+    let emit = |event: ChatStreamEvent| {
+        if matches!(event, ChatStreamEvent::Started { .. })
+            && started_sent.swap(true, Ordering::Relaxed)
+        {
+            return;
+        }
+        if let Err(e) = on_event.send(event) {
+            log::warn!("推送AI响应失败: {}", e);
+        }
+    };
+
+    let started = Instant::now();
+    let result: Result<ChatResult, String> = async {
+        let (knowledge_request, citations) = attach_knowledge(&app, &request).await?;
+        let knowledge_request = resolve_attachments(&app, &knowledge_request).await?;
+        let context = prepare_context(
+            &knowledge_request,
+            Some(Summarizer {
+                app: &app,
+                config: &config,
+                cancel: cancel.clone(),
+            }),
+        )
+        .await?;
+        if *cancel.borrow() {
+            return Ok(ChatResult {
+                request_id: request_id.clone(),
+                content: String::new(),
+                usage: None,
+                tokens: Some(context.tokens),
+                tool_calls: Vec::new(),
+                citations,
+                cancelled: true,
+            });
+        }
+        emit(ChatStreamEvent::Context {
+            tokens: context.tokens,
+        });
+        if !citations.is_empty() {
+            emit(ChatStreamEvent::Citations {
+                citations: citations.clone(),
+            });
+        }
+
+        let tools = if request.use_tools == Some(true) {
+            available_tools(&app).await?
+        } else {
+            Vec::new()
+        };
+        let mut request = ChatRequest {
+            messages: context.messages,
+            ..request.clone()
+        };
+        let mut usage: Option<ChatUsage> = None;
+        let mut content = String::new();
+        let mut round = 0;
+        let mut result = loop {
+            // 达到轮数上限后不再提供工具，让模型直接作答
+            let round_tools = if round < MAX_TOOL_ROUNDS {
+                tools.as_slice()
+            } else {
+                &[]
+            };
+            let mut result = send_chat(
+                provider.as_ref(),
+                &config,
+                &request,
+                round_tools,
+                &request_id,
+                cancel.clone(),
+                &emit,
+            )
+            .await?;
+            if let Some(round_usage) = &result.usage {
+                usage
+                    .get_or_insert_with(ChatUsage::default)
+                    .add(round_usage);
+            }
+            content.push_str(&result.content);
+            if result.cancelled || result.tool_calls.is_empty() || round >= MAX_TOOL_ROUNDS {
+                break result;
+            }
+            round += 1;
+
+            let calls = std::mem::take(&mut result.tool_calls);
+            request.messages.push(ChatMessage {
+                tool_calls: calls.clone(),
+                ..ChatMessage::text(ChatRole::Assistant, result.content.clone())
+            });
+            for call in calls {
+                if *cancel.borrow() {
+                    break;
+                }
+                emit(ChatStreamEvent::ToolCall { call: call.clone() });
+                let (status, output) =
+                    invoke_tool(&app, &call, &request_id, request.session_id.as_deref()).await;
+                emit(ChatStreamEvent::ToolResult {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    status,
+                    output: output.clone(),
+                });
+                request.messages.push(ChatMessage {
+                    tool_call_id: Some(call.id),
+                    name: Some(call.name),
+                    ..ChatMessage::text(ChatRole::Tool, output)
+                });
+            }
+            if *cancel.borrow() {
+                result.cancelled = true;
+                break result;
+            }
+        };
+        if !result.tool_calls.is_empty() {
+            emit(ChatStreamEvent::Finished { usage });
+        }
+        result.content = content;
+        result.usage = usage;
+        result.citations = citations;
+        result.tokens = Some(context.tokens);
+        Ok(result)
+    }
+    .await;
+    match &result {
+        Ok(result) if result.cancelled => {
+            log::info!(
+                "AI请求已取消: {} ({})",
+                request_id,
+                request.provider.as_str()
+            )
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("AI请求失败 ({}): {}", request.provider.as_str(), e),
+    }
+
+    let prompt_tokens = result
+        .as_ref()
+        .ok()
+        .and_then(|result| result.tokens)
+        .map_or(0, |tokens| tokens.prompt_tokens);
+    record_chat_usage(
+        &app,
+        &request,
+        &result,
+        prompt_tokens,
+        Some(request_id.clone()),
+        started.elapsed().as_millis() as u64,
+    )
+    .await;
+    result
+}
+
+/**
+ * 取消进行中的对话
+ *
+ * @param request_id 请求ID
+ * @return 请求是否存在
+ */
+#[tauri::command]
+pub async fn cancel_ai_request(request_id: String) -> Result<bool, String> {
+    let requests = ACTIVE_REQUESTS.lock().map_err(|e| e.to_string())?;
+    Ok(match requests.get(&request_id) {
+        Some(sender) => {
+            let _ = sender.send(true);
+            true
+        }
+        None => false,
+    })
+}
+
+/**
+ * 测试连接结果
+ */
+#[derive(Debug, Serialize)]
+pub struct ProviderTestResult {
+    /// 是否成功
+    pub success: bool,
+    /// 耗时（毫秒）
+    pub latency_ms: u64,
+    /// 结果说明
+    pub message: String,
+}
+
+/**
+ * 使用已保存的密钥与接口地址测试连接
+ *
+ * @param config 连接配置
+ * @param kind 提供商
+ * @param model 用于测试的模型
+ * @return 操作结果
+ */
+async fn check_provider(
+    config: &ProviderConfig,
+    kind: ProviderKind,
+    model: Option<String>,
+) -> Result<(), String> {
+    let provider = get_provider(kind);
+
+    // 指定了模型时发送一次极短的对话，可同时验证模型是否可用
+    if let Some(model) = model {
+        let request = ChatRequest {
+            provider: kind,
+            model,
+            messages: vec![ChatMessage::text(ChatRole::User, "Hello")],
+            temperature: Some(0.1),
+            max_tokens: Some(8),
+            stream: Some(false),
+            session_id: None,
+            context_strategy: None,
+            use_tools: None,
+            knowledge_base_ids: Vec::new(),
+            knowledge_top_k: None,
+        };
+        let (_sender, cancel) = watch::channel(false);
+        send_chat(
+            provider.as_ref(),
+            config,
+            &request,
+            &[],
+            "test",
+            cancel,
+            &|_| {},
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let url = provider
+        .models_endpoint(&config.base_url)
+        .ok_or_else(|| "请先为该提供商设置默认模型".to_string())?;
+    let builder =
+        provider.apply_headers(get_http_client().get(url), config.api_key.as_deref(), false);
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| format!("AI请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+    Ok(())
+}
+
+/**
+ * 测试提供商连接
+ *
+ * 验证已保存的 API 密钥与接口地址，密钥不经过前端
+ *
+ * @param app Tauri应用句柄
+ * @param provider 提供商
+ * @param model 用于测试的模型，为空时请求模型列表接口
+ * @return 测试结果
+ */
+#[tauri::command]
+pub async fn test_ai_provider(
+    app: AppHandle,
+    provider: ProviderKind,
+    model: Option<String>,
+) -> Result<ProviderTestResult, String> {
+    let started = Instant::now();
+    let model = model
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty());
+
+    let result = match load_provider_config(&app, provider).await {
+        Ok(config) => tokio::time::timeout(
+            Duration::from_secs(TEST_TIMEOUT_SECS),
+            check_provider(&config, provider, model),
+        )
+        .await
+        .unwrap_or_else(|_| Err("连接超时".to_string())),
+        Err(e) => Err(e),
+    };
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    Ok(match result {
+        Ok(()) => ProviderTestResult {
+            success: true,
+            latency_ms,
+            message: "连接成功".to_string(),
+        },
+        Err(message) => {
+            log::warn!("AI提供商连接测试失败 ({}): {}", provider.as_str(), message);
+            ProviderTestResult {
+                success: false,
+                latency_ms,
+                message,
+            }
+        }
+    })
+}
@@ -0,0 +1,566 @@
+/**
+ * 对话历史
+ *
+ * 每条消息单独保存在 ai_chat_messages 表中，按会话内序号排列。全文检索使用
+ * FTS5 的 trigram 分词，可直接匹配中文子串；少于三个字符的关键词无法使用
+ * trigram 索引，退回 LIKE 查询
+ */
+use super::attachments::{
+    copy_session_attachments, link_attachments, load_message_attachments, ChatAttachment,
+};
+use super::types::{ChatRole, ProviderKind};
+use serde::Serialize;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection};
+
+/// 检索结果默认数量
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+/// 检索结果最大数量
+const MAX_SEARCH_LIMIT: i64 = 200;
+/// trigram 分词能匹配的最短关键词（字符）
+const MIN_TRIGRAM_LEN: usize = 3;
+/// 摘录中关键词前后保留的字符数
+const EXCERPT_CONTEXT: usize = 24;
+
+/**
+ * 对话会话信息
+ */
+#[derive(Debug, Serialize)]
+pub struct ChatSessionInfo {
+    /// 会话ID
+    pub id: String,
+    /// 标题
+    pub title: String,
+    /// 提供商
+    pub provider: String,
+    /// 模型ID
+    pub model: String,
+    /// 分支来源消息ID
+    pub branched_from: Option<String>,
+    /// 消息数量
+    pub message_count: i64,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+    /// 更新时间（毫秒）
+    pub updated_at: i64,
+}
+
+/**
+ * 已保存的消息
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct StoredChatMessage {
+    /// 消息ID
+    pub id: String,
+    /// 所属会话ID
+    pub session_id: String,
+    /// 会话内序号，从0开始
+    pub seq: i64,
+    /// 角色
+    pub role: ChatRole,
+    /// 内容
+    pub content: String,
+    /// 附件
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<ChatAttachment>,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 一页消息
+ */
+#[derive(Debug, Serialize)]
+pub struct ChatMessagePage {
+    /// 按序号升序排列的消息
+    pub messages: Vec<StoredChatMessage>,
+    /// 是否还有更早的消息
+    pub has_more: bool,
+}
+
+/**
+ * 检索结果
+ */
+#[derive(Debug, Serialize)]
+pub struct ChatSearchHit {
+    /// 消息ID
+    pub message_id: String,
+    /// 会话ID
+    pub session_id: String,
+    /// 会话标题
+    pub session_title: String,
+    /// 角色
+    pub role: ChatRole,
+    /// 包含关键词的摘录
+    pub snippet: String,
+    /// 消息时间（毫秒）
+    pub created_at: i64,
+}
+
+/// 会话查询的公共列
+const SESSION_COLUMNS: &str = "s.id, s.title, s.provider, s.model, s.branched_from, s.created_at,
+     s.updated_at, (SELECT COUNT(*) FROM ai_chat_messages m WHERE m.session_id = s.id)
+     AS message_count";
+
+/// 消息查询的公共列
+const MESSAGE_COLUMNS: &str = "id, session_id, seq, role, content, created_at";
+
+fn session_from_row(row: &SqliteRow) -> ChatSessionInfo {
+    ChatSessionInfo {
+        id: row.get("id"),
+        title: row.get("title"),
+        provider: row.get("provider"),
+        model: row.get("model"),
+        branched_from: row.get("branched_from"),
+        message_count: row.get("message_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn message_from_row(row: &SqliteRow) -> Result<StoredChatMessage, String> {
+    Ok(StoredChatMessage {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        seq: row.get("seq"),
+        role: ChatRole::parse(&row.get::<String, _>("role"))?,
+        content: row.get("content"),
+        attachments: Vec::new(),
+        created_at: row.get("created_at"),
+    })
+}
+
+/**
+ * 读取会话信息
+ */
+async fn load_session(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+) -> Result<ChatSessionInfo, String> {
+    let sql = format!(
+        "SELECT {} FROM ai_chat_sessions s WHERE s.id = ?",
+        SESSION_COLUMNS
+    );
+    sqlx::query(&sql)
+        .bind(session_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询对话会话失败: {}", e))?
+        .map(|row| session_from_row(&row))
+        .ok_or_else(|| format!("对话会话不存在: {}", session_id))
+}
+
+/**
+ * 追加消息
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param session_id 会话ID
+ * @param role 角色
+ * @param content 内容
+ * @return 保存的消息
+ */
+async fn insert_message(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+    role: ChatRole,
+    content: &str,
+) -> Result<StoredChatMessage, String> {
+    let seq: i64 = sqlx::query(
+        "SELECT COALESCE(MAX(seq) + 1, 0) AS seq FROM ai_chat_messages WHERE session_id = ?",
+    )
+    .bind(session_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("查询消息序号失败: {}", e))?
+    .get("seq");
+
+    let message = StoredChatMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        seq,
+        role,
+        content: content.to_string(),
+        attachments: Vec::new(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    sqlx::query(
+        "INSERT INTO ai_chat_messages (id, session_id, seq, role, content, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&message.id)
+    .bind(&message.session_id)
+    .bind(message.seq)
+    .bind(message.role.as_str())
+    .bind(&message.content)
+    .bind(message.created_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("保存消息失败: {}", e))?;
+
+    sqlx::query("UPDATE ai_chat_sessions SET updated_at = ? WHERE id = ?")
+        .bind(message.created_at)
+        .bind(session_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("更新对话会话失败: {}", e))?;
+    Ok(message)
+}
+
+/**
+ * 创建对话会话
+ *
+ * @param title 标题
+ * @param provider 提供商
+ * @param model 模型ID
+ * @return 会话信息
+ */
+#[tauri::command]
+pub async fn create_chat_session(
+    title: String,
+    provider: ProviderKind,
+    model: String,
+) -> Result<ChatSessionInfo, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO ai_chat_sessions (id, title, messages, provider, model, created_at, updated_at)
+         VALUES (?, ?, '[]', ?, ?, ?, ?)",
+        &id,
+        title.trim(),
+        provider.as_str(),
+        model,
+        now,
+        now
+    )?;
+
+    let mut conn = db
+        .get_pool()
+        .acquire()
+        .await
+        .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    load_session(&mut conn, &id).await
+}
+
+/**
+ * 列出对话会话
+ *
+ * @return 按更新时间倒序排列的会话
+ */
+#[tauri::command]
+pub async fn list_chat_sessions() -> Result<Vec<ChatSessionInfo>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let sql = format!(
+        "SELECT {} FROM ai_chat_sessions s ORDER BY s.updated_at DESC",
+        SESSION_COLUMNS
+    );
+    let rows = sqlx::query(&sql)
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询对话会话失败: {}", e))?;
+    Ok(rows.iter().map(session_from_row).collect())
+}
+
+/**
+ * 追加消息
+ *
+ * @param session_id 会话ID
+ * @param role 角色
+ * @param content 内容
+ * @param attachment_ids 随消息发送的附件ID
+ * @return 保存的消息
+ */
+#[tauri::command]
+pub async fn append_chat_message(
+    session_id: String,
+    role: ChatRole,
+    content: String,
+    attachment_ids: Option<Vec<String>>,
+) -> Result<StoredChatMessage, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    load_session(&mut tx, &session_id).await?;
+    let mut message = insert_message(&mut tx, &session_id, role, &content).await?;
+    if let Some(ids) = attachment_ids.filter(|ids| !ids.is_empty()) {
+        message.attachments = link_attachments(&mut tx, &message.id, &ids).await?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+    Ok(message)
+}
+
+/**
+ * 分页读取消息
+ *
+ * 从最新的消息向前翻页，每页按序号升序返回
+ *
+ * @param session_id 会话ID
+ * @param before_seq 只返回序号小于该值的消息，为空时从最新一条开始
+ * @param limit 每页数量，为空时返回全部
+ * @return 一页消息
+ */
+#[tauri::command]
+pub async fn get_chat_messages(
+    session_id: String,
+    before_seq: Option<i64>,
+    limit: Option<i64>,
+) -> Result<ChatMessagePage, String> {
+    let limit = limit.filter(|limit| *limit > 0);
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let sql = format!(
+        "SELECT {} FROM ai_chat_messages WHERE session_id = ? AND seq < ?
+         ORDER BY seq DESC LIMIT ?",
+        MESSAGE_COLUMNS
+    );
+    // 多取一条用于判断是否还有更早的消息，LIMIT -1 表示不限制
+    let rows = sqlx::query(&sql)
+        .bind(&session_id)
+        .bind(before_seq.unwrap_or(i64::MAX))
+        .bind(limit.map(|limit| limit + 1).unwrap_or(-1))
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询消息失败: {}", e))?;
+
+    let has_more = limit.is_some_and(|limit| rows.len() as i64 > limit);
+    let mut messages = rows
+        .iter()
+        .take(limit.unwrap_or(i64::MAX) as usize)
+        .map(message_from_row)
+        .collect::<Result<Vec<_>, _>>()?;
+    messages.reverse();
+
+    let mut conn = db
+        .get_pool()
+        .acquire()
+        .await
+        .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    let ids: Vec<String> = messages.iter().map(|message| message.id.clone()).collect();
+    let mut attachments = load_message_attachments(&mut conn, &ids).await?;
+    for message in &mut messages {
+        message.attachments = attachments.remove(&message.id).unwrap_or_default();
+    }
+    Ok(ChatMessagePage { messages, has_more })
+}
+
+/**
+ * 从指定消息创建分支会话
+ *
+ * 复制该消息及之前的全部消息与附件到新会话，原会话保持不变
+ *
+ * @param message_id 分支点消息ID
+ * @param title 新会话标题，为空时沿用原标题
+ * @return 新会话信息
+ */
+#[tauri::command]
+pub async fn branch_chat_session(
+    message_id: String,
+    title: Option<String>,
+) -> Result<ChatSessionInfo, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let row = sqlx::query("SELECT session_id, seq FROM ai_chat_messages WHERE id = ?")
+        .bind(&message_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("查询消息失败: {}", e))?
+        .ok_or_else(|| format!("消息不存在: {}", message_id))?;
+    let source_id: String = row.get("session_id");
+    let seq: i64 = row.get("seq");
+    let source = load_session(&mut tx, &source_id).await?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp_millis();
+    let title = title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| format!("{}（分支）", source.title));
+    sqlx::query(
+        "INSERT INTO ai_chat_sessions
+         (id, title, messages, provider, model, branched_from, created_at, updated_at)
+         VALUES (?, ?, '[]', ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&title)
+    .bind(&source.provider)
+    .bind(&source.model)
+    .bind(&message_id)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("创建分支会话失败: {}", e))?;
+
+    let messages = sqlx::query(
+        "SELECT seq, role, content, created_at FROM ai_chat_messages
+         WHERE session_id = ? AND seq <= ? ORDER BY seq",
+    )
+    .bind(&source_id)
+    .bind(seq)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("查询消息失败: {}", e))?;
+    for message in &messages {
+        sqlx::query(
+            "INSERT INTO ai_chat_messages (id, session_id, seq, role, content, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&id)
+        .bind(message.get::<i64, _>("seq"))
+        .bind(message.get::<String, _>("role"))
+        .bind(message.get::<String, _>("content"))
+        .bind(message.get::<i64, _>("created_at"))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("复制消息失败: {}", e))?;
+    }
+    copy_session_attachments(&mut tx, &source_id, &id, seq).await?;
+
+    let session = load_session(&mut tx, &id).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+    Ok(session)
+}
+
+/**
+ * 构造 FTS5 查询，每个关键词作为短语匹配，多个关键词需同时出现
+ *
+ * @param terms 关键词
+ * @return FTS5 查询语句
+ */
+fn fts_query(terms: &[&str]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// 转义 LIKE 通配符
+pub(crate) fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/**
+ * 截取关键词附近的内容
+ *
+ * @param content 消息内容
+ * @param term 关键词
+ * @return 摘录
+ */
+fn excerpt(content: &str, term: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let lower: Vec<char> = content.to_lowercase().chars().collect();
+    let needle: Vec<char> = term.to_lowercase().chars().collect();
+    // 大小写转换后长度变化时按开头截取
+    let position = if lower.len() == chars.len() && !needle.is_empty() {
+        lower
+            .windows(needle.len())
+            .position(|window| window == needle.as_slice())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let start = position.saturating_sub(EXCERPT_CONTEXT);
+    let end = (position + needle.len() + EXCERPT_CONTEXT).min(chars.len());
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet.replace('\n', " ")
+}
+
+/**
+ * 全文检索所有会话的消息
+ *
+ * @param query 关键词，多个关键词以空格分隔
+ * @param limit 最大结果数
+ * @return 按相关度排列的结果
+ */
+#[tauri::command]
+pub async fn search_chat_messages(
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<ChatSearchHit>, String> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = if terms
+        .iter()
+        .all(|term| term.chars().count() >= MIN_TRIGRAM_LEN)
+    {
+        sqlx::query(
+            "SELECT m.id, m.session_id, s.title, m.role, m.content, m.created_at
+             FROM ai_chat_messages_fts f
+             JOIN ai_chat_messages m ON m.rowid = f.rowid
+             JOIN ai_chat_sessions s ON s.id = m.session_id
+             WHERE ai_chat_messages_fts MATCH ?
+             ORDER BY f.rank, m.created_at DESC
+             LIMIT ?",
+        )
+        .bind(fts_query(&terms))
+        .bind(limit)
+        .fetch_all(db.get_pool())
+        .await
+    } else {
+        let mut sql = "SELECT m.id, m.session_id, s.title, m.role, m.content, m.created_at
+             FROM ai_chat_messages m
+             JOIN ai_chat_sessions s ON s.id = m.session_id
+             WHERE 1 = 1"
+            .to_string();
+        for _ in &terms {
+            sql.push_str(" AND m.content LIKE ? ESCAPE '\\'");
+        }
+        sql.push_str(" ORDER BY m.created_at DESC LIMIT ?");
+        let mut query = sqlx::query(&sql);
+        for term in &terms {
+            query = query.bind(format!("%{}%", escape_like(term)));
+        }
+        query.bind(limit).fetch_all(db.get_pool()).await
+    }
+    .map_err(|e| format!("检索消息失败: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(ChatSearchHit {
+                message_id: row.get("id"),
+                session_id: row.get("session_id"),
+                session_title: row.get("title"),
+                role: ChatRole::parse(&row.get::<String, _>("role"))?,
+                snippet: excerpt(&row.get::<String, _>("content"), terms[0]),
+                created_at: row.get("created_at"),
+            })
+        })
+        .collect()
+}
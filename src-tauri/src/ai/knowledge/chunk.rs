@@ -0,0 +1,149 @@
+/**
+ * 文档分块
+ *
+ * 按段落累积到目标长度后切分，Markdown 文档在标题处切分并记录所在小节，
+ * 过长的段落按固定窗口切分并保留少量重叠
+ */
+use std::path::Path;
+
+/// 每块的目标长度（字符）
+const TARGET_CHARS: usize = 1000;
+/// 单个段落的最大长度（字符），超出时按窗口切分
+const MAX_PARAGRAPH_CHARS: usize = 1500;
+/// 窗口切分时的重叠长度（字符）
+const OVERLAP_CHARS: usize = 150;
+
+/**
+ * 文档片段
+ */
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    /// 所在小节的标题
+    pub heading: Option<String>,
+    /// 内容
+    pub content: String,
+}
+
+impl Chunk {
+    /// 用于向量化的文本，包含小节标题
+    pub fn embedding_text(&self, title: &str) -> String {
+        match &self.heading {
+            Some(heading) => format!("{} › {}\n{}", title, heading, self.content),
+            None => format!("{}\n{}", title, self.content),
+        }
+    }
+}
+
+/// 是否为 Markdown 文档
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+/// 按窗口切分过长的段落
+fn split_long(paragraph: &str) -> Vec<String> {
+    let chars: Vec<char> = paragraph.chars().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let end = (start + TARGET_CHARS).min(chars.len());
+        parts.push(chars[start..end].iter().collect());
+        if end == chars.len() {
+            break;
+        }
+        start = end - OVERLAP_CHARS;
+    }
+    parts
+}
+
+/**
+ * 分块时累积的状态
+ */
+#[derive(Default)]
+struct Builder {
+    chunks: Vec<Chunk>,
+    heading: Option<String>,
+    buffer: String,
+    buffer_chars: usize,
+}
+
+impl Builder {
+    fn flush(&mut self) {
+        let content = self.buffer.trim();
+        if !content.is_empty() {
+            self.chunks.push(Chunk {
+                heading: self.heading.clone(),
+                content: content.to_string(),
+            });
+        }
+        self.buffer.clear();
+        self.buffer_chars = 0;
+    }
+
+    fn push_paragraph(&mut self, paragraph: &str) {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            return;
+        }
+        let chars = paragraph.chars().count();
+        if chars > MAX_PARAGRAPH_CHARS {
+            self.flush();
+            for part in split_long(paragraph) {
+                self.buffer = part;
+                self.flush();
+            }
+            return;
+        }
+        if self.buffer_chars > 0 && self.buffer_chars + chars > TARGET_CHARS {
+            self.flush();
+        }
+        if !self.buffer.is_empty() {
+            self.buffer.push_str("\n\n");
+        }
+        self.buffer.push_str(paragraph);
+        self.buffer_chars += chars;
+    }
+}
+
+/**
+ * 将文档切分为片段
+ *
+ * @param text 文档文本
+ * @param markdown 是否按 Markdown 标题切分
+ * @return 片段列表
+ */
+pub fn split_text(text: &str, markdown: bool) -> Vec<Chunk> {
+    let mut builder = Builder::default();
+    let mut paragraph = String::new();
+    let mut in_code = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if markdown && trimmed.starts_with("```") {
+            in_code = !in_code;
+        }
+        let heading = (markdown && !in_code && trimmed.starts_with('#'))
+            .then(|| trimmed.trim_start_matches('#'))
+            .filter(|rest| rest.starts_with(' '))
+            .map(str::trim);
+        if let Some(heading) = heading {
+            builder.push_paragraph(&paragraph);
+            paragraph.clear();
+            builder.flush();
+            builder.heading = Some(heading.to_string()).filter(|h| !h.is_empty());
+            continue;
+        }
+        // 代码块内的空行不切分段落
+        if line.trim().is_empty() && !in_code {
+            builder.push_paragraph(&paragraph);
+            paragraph.clear();
+            continue;
+        }
+        paragraph.push_str(line);
+        paragraph.push('\n');
+    }
+    builder.push_paragraph(&paragraph);
+    builder.flush();
+    builder.chunks
+}
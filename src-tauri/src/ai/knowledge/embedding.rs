@@ -0,0 +1,144 @@
+/**
+ * 文本向量化
+ *
+ * 使用提供商配置中的密钥与接口地址请求向量，支持 Ollama 与 OpenAI 兼容接口。
+ * 返回的向量已归一化，余弦相似度可直接用点积计算
+ */
+use crate::ai::config::load_provider_config;
+use crate::ai::gateway::response_error;
+use crate::ai::provider::get_provider;
+use crate::ai::tokens::estimate_text_tokens;
+use crate::ai::types::{ChatUsage, ProviderKind};
+use crate::ai::usage::{record_usage_quietly, UsageRecord, UsageStatus};
+use crate::http::client::get_http_client;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// 每次请求的最大文本数
+const BATCH_SIZE: usize = 16;
+/// 单次请求的超时时间（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// 归一化向量
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+/**
+ * 将向量编码为小端 f32 字节
+ *
+ * @param vector 向量
+ * @return 字节
+ */
+pub fn vector_to_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/**
+ * 计算归一化向量与编码后向量的点积
+ *
+ * @param query 查询向量
+ * @param bytes 编码后的向量
+ * @return 余弦相似度，维度不一致时为空
+ */
+pub fn dot_bytes(query: &[f32], bytes: &[u8]) -> Option<f32> {
+    if bytes.len() != query.len() * 4 {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .zip(query)
+            .map(|(chunk, q)| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) * q)
+            .sum(),
+    )
+}
+
+/**
+ * 获取文本的向量
+ *
+ * @param app Tauri应用句柄
+ * @param kind 提供商
+ * @param model 向量模型
+ * @param inputs 文本
+ * @return 与输入顺序一致的归一化向量
+ */
+pub async fn embed(
+    app: &AppHandle,
+    kind: ProviderKind,
+    model: &str,
+    inputs: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+    let provider = get_provider(kind);
+    let config = load_provider_config(app, kind).await?;
+    let url = provider
+        .embeddings_endpoint(&config.base_url)
+        .ok_or_else(|| format!("AI提供商不支持向量化: {}", kind.as_str()))?;
+
+    let started = Instant::now();
+    let result: Result<Vec<Vec<f32>>, String> = async {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(BATCH_SIZE) {
+            let builder = get_http_client()
+                .post(&url)
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .json(&provider.build_embeddings_body(model, batch));
+            let response = provider
+                .apply_headers(builder, config.api_key.as_deref(), false)
+                .send()
+                .await
+                .map_err(|e| format!("向量化请求失败: {}", e))?;
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            let body: Value = response
+                .json()
+                .await
+                .map_err(|e| format!("解析向量化响应失败: {}", e))?;
+            let batch_vectors = provider.parse_embeddings(&body)?;
+            if batch_vectors.len() != batch.len() {
+                return Err("向量化响应数量与输入不一致".to_string());
+            }
+            vectors.extend(batch_vectors.into_iter().map(normalize));
+        }
+        Ok(vectors)
+    }
+    .await;
+
+    // 向量化同样计入用量
+    let prompt_tokens = match &result {
+        Ok(_) => inputs
+            .iter()
+            .map(|input| estimate_text_tokens(kind, input))
+            .sum(),
+        Err(_) => 0,
+    };
+    record_usage_quietly(
+        app,
+        UsageRecord {
+            request_id: None,
+            provider: kind,
+            model: model.to_string(),
+            session_id: None,
+            usage: ChatUsage {
+                prompt_tokens,
+                completion_tokens: 0,
+            },
+            estimated: true,
+            latency_ms: started.elapsed().as_millis() as u64,
+            status: if result.is_ok() {
+                UsageStatus::Success
+            } else {
+                UsageStatus::Error
+            },
+            error: result.as_ref().err().cloned(),
+        },
+    )
+    .await;
+    result
+}
@@ -0,0 +1,63 @@
+/**
+ * 文档内容提取
+ *
+ * 支持 Markdown、纯文本与 PDF
+ */
+use super::chunk::is_markdown;
+use super::pdf::extract_pdf_text;
+use std::path::Path;
+
+/// 支持的文件扩展名
+const SUPPORTED_EXTENSIONS: [&str; 5] = ["md", "markdown", "txt", "text", "pdf"];
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// 是否为支持的文档
+pub fn is_supported(path: &Path) -> bool {
+    SUPPORTED_EXTENSIONS.contains(&extension(path).as_str())
+}
+
+/**
+ * 提取文档文本
+ *
+ * @param path 文件路径，用于判断格式
+ * @param data 文件内容
+ * @return 文本
+ */
+pub fn document_text(path: &Path, data: &[u8]) -> Result<String, String> {
+    if extension(path) == "pdf" {
+        return extract_pdf_text(data);
+    }
+    let text = String::from_utf8_lossy(data);
+    Ok(text.trim_start_matches('\u{feff}').replace("\r\n", "\n"))
+}
+
+/**
+ * 文档标题
+ *
+ * Markdown 文档使用第一个一级标题，其余使用文件名
+ *
+ * @param path 文件路径
+ * @param text 文档文本
+ * @return 标题
+ */
+pub fn document_title(path: &Path, text: &str) -> String {
+    if is_markdown(path) {
+        if let Some(title) = text
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("# "))
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+        {
+            return title.to_string();
+        }
+    }
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,671 @@
+/**
+ * 知识库管理与索引
+ *
+ * 知识库记录要索引的目录与使用的向量模型。索引时递归扫描目录中的文档，内容
+ * 未变化的文档跳过，已删除的文档移除；更换向量模型后需要重新索引全部文档
+ */
+use super::chunk::{is_markdown, split_text, Chunk};
+use super::embedding::{embed, vector_to_bytes};
+use super::extract::{document_text, document_title, is_supported};
+use crate::ai::types::ProviderKind;
+use crate::security::session::require_session;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+/// 单个文档的最大字节数
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+/// 单个知识库的最大文档数
+const MAX_FILES: usize = 10_000;
+
+// 使用lazy_static记录正在索引的知识库
+lazy_static::lazy_static! {
+    static ref INDEXING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/**
+ * 知识库
+ */
+#[derive(Debug, Serialize)]
+pub struct KnowledgeBase {
+    /// 知识库ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 索引的目录
+    pub folders: Vec<String>,
+    /// 向量化提供商
+    pub embedding_provider: ProviderKind,
+    /// 向量模型
+    pub embedding_model: String,
+    /// 向量维度，尚未索引时为空
+    pub dimensions: Option<i64>,
+    /// 文档数
+    pub document_count: i64,
+    /// 片段数
+    pub chunk_count: i64,
+    /// 上次索引时间（毫秒）
+    pub indexed_at: Option<i64>,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+    /// 更新时间（毫秒）
+    pub updated_at: i64,
+}
+
+/**
+ * 知识库中的文档
+ */
+#[derive(Debug, Serialize)]
+pub struct KnowledgeDocument {
+    /// 文档ID
+    pub id: String,
+    /// 文件路径
+    pub path: String,
+    /// 标题
+    pub title: String,
+    /// 文件大小（字节）
+    pub size: i64,
+    /// 片段数
+    pub chunk_count: i64,
+    /// 索引失败的原因
+    pub error: Option<String>,
+    /// 索引时间（毫秒）
+    pub indexed_at: i64,
+}
+
+/**
+ * 单个文档的索引结果
+ */
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexStatus {
+    /// 已索引
+    Indexed,
+    /// 内容未变化
+    Unchanged,
+    /// 索引失败
+    Failed,
+}
+
+/**
+ * 索引进度
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct IndexProgress {
+    /// 已处理的文档数
+    pub processed: usize,
+    /// 文档总数
+    pub total: usize,
+    /// 文件路径
+    pub path: String,
+    /// 索引结果
+    pub status: IndexStatus,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/**
+ * 索引结果统计
+ */
+#[derive(Debug, Serialize, Default)]
+pub struct IndexSummary {
+    /// 新索引或重新索引的文档数
+    pub indexed: usize,
+    /// 未变化的文档数
+    pub unchanged: usize,
+    /// 失败的文档数
+    pub failed: usize,
+    /// 已删除的文档数
+    pub removed: usize,
+}
+
+/// 正在索引的标记，结束时移除
+struct IndexGuard {
+    kb_id: String,
+}
+
+impl IndexGuard {
+    fn acquire(kb_id: &str) -> Result<Self, String> {
+        let mut indexing = INDEXING.lock().map_err(|e| e.to_string())?;
+        if !indexing.insert(kb_id.to_string()) {
+            return Err("该知识库正在索引".to_string());
+        }
+        Ok(IndexGuard {
+            kb_id: kb_id.to_string(),
+        })
+    }
+}
+
+impl Drop for IndexGuard {
+    fn drop(&mut self) {
+        if let Ok(mut indexing) = INDEXING.lock() {
+            indexing.remove(&self.kb_id);
+        }
+    }
+}
+
+/// 读取数据库行
+fn row_to_knowledge_base(row: &sqlx::sqlite::SqliteRow) -> Result<KnowledgeBase, String> {
+    Ok(KnowledgeBase {
+        id: row.get("id"),
+        name: row.get("name"),
+        folders: serde_json::from_str(&row.get::<String, _>("folders")).unwrap_or_default(),
+        embedding_provider: ProviderKind::parse(&row.get::<String, _>("embedding_provider"))?,
+        embedding_model: row.get("embedding_model"),
+        dimensions: row.get("dimensions"),
+        document_count: row.get("document_count"),
+        chunk_count: row.get("chunk_count"),
+        indexed_at: row.get("indexed_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/**
+ * 读取知识库
+ *
+ * @param id 知识库ID，为空时读取全部
+ * @return 知识库列表，按创建时间排序
+ */
+async fn load_knowledge_bases(id: Option<&str>) -> Result<Vec<KnowledgeBase>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT kb.*,
+                (SELECT COUNT(*) FROM ai_kb_documents d WHERE d.kb_id = kb.id) AS document_count,
+                (SELECT COUNT(*) FROM ai_kb_chunks c WHERE c.kb_id = kb.id) AS chunk_count
+         FROM ai_knowledge_bases kb
+         WHERE (? IS NULL OR kb.id = ?)
+         ORDER BY kb.created_at",
+        id,
+        id
+    )?;
+    rows.iter().map(row_to_knowledge_base).collect()
+}
+
+/**
+ * 读取单个知识库
+ *
+ * @param id 知识库ID
+ * @return 知识库
+ */
+pub async fn load_knowledge_base(id: &str) -> Result<KnowledgeBase, String> {
+    load_knowledge_bases(Some(id))
+        .await?
+        .pop()
+        .ok_or_else(|| format!("知识库不存在: {}", id))
+}
+
+/**
+ * 递归扫描目录中支持的文档
+ *
+ * 跳过隐藏文件、符号链接与过大的文件
+ *
+ * @param folders 目录
+ * @return 文件路径
+ */
+fn scan_folders(folders: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending: Vec<PathBuf> = folders.iter().map(PathBuf::from).collect();
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            log::warn!("无法读取目录: {}", dir.display());
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file()
+                && is_supported(&path)
+                && entry
+                    .metadata()
+                    .is_ok_and(|meta| meta.len() <= MAX_FILE_BYTES)
+            {
+                files.push(path);
+                if files.len() >= MAX_FILES {
+                    return files;
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/**
+ * 读取并切分文档
+ */
+struct ParsedDocument {
+    hash: String,
+    size: i64,
+    title: String,
+    chunks: Result<Vec<Chunk>, String>,
+}
+
+/**
+ * 读取文档，内容未变化时不解析
+ *
+ * @param path 文件路径
+ * @param previous_hash 上次索引时的内容哈希
+ * @return 解析结果，内容未变化时为空
+ */
+fn parse_document(
+    path: &Path,
+    previous_hash: Option<&str>,
+) -> Result<Option<ParsedDocument>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let hash = hex::encode(Sha256::digest(&data));
+    if previous_hash == Some(hash.as_str()) {
+        return Ok(None);
+    }
+    let text = document_text(path, &data);
+    let title = match &text {
+        Ok(text) => document_title(path, text),
+        Err(_) => document_title(path, ""),
+    };
+    let chunks = text.map(|text| split_text(&text, is_markdown(path)));
+    Ok(Some(ParsedDocument {
+        hash,
+        size: data.len() as i64,
+        title,
+        chunks,
+    }))
+}
+
+/**
+ * 写入文档与片段，替换同一路径的旧记录
+ *
+ * @param kb_id 知识库ID
+ * @param path 文件路径
+ * @param document 解析结果
+ * @param vectors 每个片段的向量
+ * @param error 失败原因
+ * @return 操作结果
+ */
+async fn save_document(
+    kb_id: &str,
+    path: &str,
+    document: &ParsedDocument,
+    vectors: &[Vec<f32>],
+    error: Option<&str>,
+) -> Result<(), String> {
+    let chunks: &[Chunk] = match &document.chunks {
+        Ok(chunks) if error.is_none() => chunks,
+        _ => &[],
+    };
+    // 失败的文档不记录哈希，下次索引时重试
+    let hash = if error.is_none() {
+        document.hash.as_str()
+    } else {
+        ""
+    };
+    let document_id = uuid::Uuid::new_v4().to_string();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    sqlx::query("DELETE FROM ai_kb_documents WHERE kb_id = ? AND path = ?")
+        .bind(kb_id)
+        .bind(path)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("删除旧文档失败: {}", e))?;
+    sqlx::query(
+        "INSERT INTO ai_kb_documents
+         (id, kb_id, path, title, content_hash, size, chunk_count, error, indexed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&document_id)
+    .bind(kb_id)
+    .bind(path)
+    .bind(&document.title)
+    .bind(hash)
+    .bind(document.size)
+    .bind(chunks.len() as i64)
+    .bind(error)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("保存文档失败: {}", e))?;
+    for (seq, (chunk, vector)) in chunks.iter().zip(vectors).enumerate() {
+        sqlx::query(
+            "INSERT INTO ai_kb_chunks (kb_id, document_id, seq, heading, content, embedding)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(kb_id)
+        .bind(&document_id)
+        .bind(seq as i64)
+        .bind(&chunk.heading)
+        .bind(&chunk.content)
+        .bind(vector_to_bytes(vector))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("保存文档片段失败: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))
+}
+
+/**
+ * 获取全部知识库
+ *
+ * @return 知识库列表
+ */
+#[tauri::command]
+pub async fn list_knowledge_bases() -> Result<Vec<KnowledgeBase>, String> {
+    load_knowledge_bases(None).await
+}
+
+/**
+ * 保存知识库
+ *
+ * 知识库中的文档会发送给向量化提供商并在对话中提供给模型，需要有效的会话。
+ * 更换向量模型时清除已有的索引
+ *
+ * @param session_id 会话ID
+ * @param id 知识库ID，为空时新建
+ * @param name 名称
+ * @param folders 索引的目录
+ * @param embedding_provider 向量化提供商
+ * @param embedding_model 向量模型
+ * @return 保存后的知识库
+ */
+#[tauri::command]
+pub async fn save_knowledge_base(
+    session_id: String,
+    id: Option<String>,
+    name: String,
+    folders: Vec<String>,
+    embedding_provider: ProviderKind,
+    embedding_model: String,
+) -> Result<KnowledgeBase, String> {
+    require_session(&session_id).await?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("请输入知识库名称".to_string());
+    }
+    let embedding_model = embedding_model.trim().to_string();
+    if embedding_model.is_empty() {
+        return Err("请输入向量模型".to_string());
+    }
+    if crate::ai::provider::get_provider(embedding_provider)
+        .embeddings_endpoint("")
+        .is_none()
+    {
+        return Err(format!(
+            "AI提供商不支持向量化: {}",
+            embedding_provider.as_str()
+        ));
+    }
+
+    let mut canonical: Vec<String> = Vec::with_capacity(folders.len());
+    for folder in folders {
+        let path = PathBuf::from(folder.trim())
+            .canonicalize()
+            .map_err(|e| format!("目录不存在: {} ({})", folder, e))?;
+        if !path.is_dir() {
+            return Err(format!("不是目录: {}", folder));
+        }
+        let path = path.to_string_lossy().into_owned();
+        if !canonical.contains(&path) {
+            canonical.push(path);
+        }
+    }
+    let folders_json = serde_json::to_string(&canonical).map_err(|e| e.to_string())?;
+
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let now = chrono::Utc::now().timestamp_millis();
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let existing = crate::query_one_with_params!(
+            db,
+            "SELECT embedding_provider, embedding_model FROM ai_knowledge_bases WHERE id = ?",
+            &id
+        )?;
+        let model_changed = existing.is_some_and(|row| {
+            row.get::<String, _>("embedding_provider") != embedding_provider.as_str()
+                || row.get::<String, _>("embedding_model") != embedding_model
+        });
+        if model_changed {
+            crate::execute_with_params!(db, "DELETE FROM ai_kb_documents WHERE kb_id = ?", &id)?;
+            crate::execute_with_params!(
+                db,
+                "UPDATE ai_knowledge_bases SET dimensions = NULL, indexed_at = NULL WHERE id = ?",
+                &id
+            )?;
+        }
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_knowledge_bases
+             (id, name, folders, embedding_provider, embedding_model, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name,
+                                           folders = excluded.folders,
+                                           embedding_provider = excluded.embedding_provider,
+                                           embedding_model = excluded.embedding_model,
+                                           updated_at = excluded.updated_at",
+            &id,
+            &name,
+            &folders_json,
+            embedding_provider.as_str(),
+            &embedding_model,
+            now,
+            now
+        )?;
+    }
+
+    log::info!("知识库已保存: {}", name);
+    load_knowledge_base(&id).await
+}
+
+/**
+ * 删除知识库及其索引
+ *
+ * @param id 知识库ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn delete_knowledge_base(id: String) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(db, "DELETE FROM ai_kb_chunks WHERE kb_id = ?", &id)?;
+    crate::execute_with_params!(db, "DELETE FROM ai_knowledge_bases WHERE id = ?", &id)
+}
+
+/**
+ * 获取知识库中的文档
+ *
+ * @param kb_id 知识库ID
+ * @return 文档列表，按路径排序
+ */
+#[tauri::command]
+pub async fn list_knowledge_documents(kb_id: String) -> Result<Vec<KnowledgeDocument>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT * FROM ai_kb_documents WHERE kb_id = ? ORDER BY path",
+        &kb_id
+    )?;
+    Ok(rows
+        .iter()
+        .map(|row| KnowledgeDocument {
+            id: row.get("id"),
+            path: row.get("path"),
+            title: row.get("title"),
+            size: row.get("size"),
+            chunk_count: row.get("chunk_count"),
+            error: row.get("error"),
+            indexed_at: row.get("indexed_at"),
+        })
+        .collect())
+}
+
+/**
+ * 索引知识库
+ *
+ * 扫描知识库目录，对新增或修改的文档重新分块与向量化，移除已删除的文档。
+ * 向量化请求失败时中止索引，已完成的文档保留
+ *
+ * @param app Tauri应用句柄
+ * @param id 知识库ID
+ * @param on_progress 索引进度通道
+ * @return 索引结果统计
+ */
+#[tauri::command]
+pub async fn index_knowledge_base(
+    app: AppHandle,
+    id: String,
+    on_progress: Channel<IndexProgress>,
+) -> Result<IndexSummary, String> {
+    let _guard = IndexGuard::acquire(&id)?;
+    let kb = load_knowledge_base(&id).await?;
+    if kb.folders.is_empty() {
+        return Err("知识库未设置目录".to_string());
+    }
+
+    let folders = kb.folders.clone();
+    let files = tokio::task::spawn_blocking(move || scan_folders(&folders))
+        .await
+        .map_err(|e| format!("扫描目录失败: {}", e))?;
+
+    let existing: HashMap<String, String> = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_with_params!(
+            db,
+            "SELECT path, content_hash FROM ai_kb_documents WHERE kb_id = ?",
+            &id
+        )?
+        .iter()
+        .map(|row| (row.get("path"), row.get("content_hash")))
+        .collect()
+    };
+
+    let mut summary = IndexSummary::default();
+    let mut dimensions = kb.dimensions;
+    let total = files.len();
+    for (processed, file) in files.iter().enumerate() {
+        let path = file.to_string_lossy().into_owned();
+        let previous_hash = existing.get(&path).cloned();
+        let parse_path = file.clone();
+        let parsed = tokio::task::spawn_blocking(move || {
+            parse_document(&parse_path, previous_hash.as_deref())
+        })
+        .await
+        .map_err(|e| format!("解析文档失败: {}", e))?;
+
+        let (status, error) = match parsed {
+            Ok(None) => (IndexStatus::Unchanged, None),
+            Err(e) => (IndexStatus::Failed, Some(e)),
+            Ok(Some(document)) => {
+                let error = match &document.chunks {
+                    Ok(chunks) if chunks.is_empty() => Some("文档没有内容".to_string()),
+                    Ok(_) => None,
+                    Err(e) => Some(e.clone()),
+                };
+                let vectors = match (&document.chunks, &error) {
+                    (Ok(chunks), None) => {
+                        let inputs: Vec<String> = chunks
+                            .iter()
+                            .map(|chunk| chunk.embedding_text(&document.title))
+                            .collect();
+                        embed(&app, kb.embedding_provider, &kb.embedding_model, &inputs).await?
+                    }
+                    _ => Vec::new(),
+                };
+                if let Some(vector) = vectors.first() {
+                    match dimensions {
+                        Some(expected) if expected != vector.len() as i64 => {
+                            return Err(format!(
+                                "向量维度与已有索引不一致 ({} / {})，请重新建立索引",
+                                vector.len(),
+                                expected
+                            ));
+                        }
+                        _ => dimensions = Some(vector.len() as i64),
+                    }
+                }
+                save_document(&id, &path, &document, &vectors, error.as_deref()).await?;
+                match error {
+                    Some(e) => (IndexStatus::Failed, Some(e)),
+                    None => (IndexStatus::Indexed, None),
+                }
+            }
+        };
+
+        match status {
+            IndexStatus::Indexed => summary.indexed += 1,
+            IndexStatus::Unchanged => summary.unchanged += 1,
+            IndexStatus::Failed => {
+                log::warn!(
+                    "索引文档失败 ({}): {}",
+                    path,
+                    error.as_deref().unwrap_or_default()
+                );
+                summary.failed += 1;
+            }
+        }
+        let _ = on_progress.send(IndexProgress {
+            processed: processed + 1,
+            total,
+            path,
+            status,
+            error,
+        });
+    }
+
+    let scanned: HashSet<String> = files
+        .iter()
+        .map(|file| file.to_string_lossy().into_owned())
+        .collect();
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        for path in existing.keys().filter(|path| !scanned.contains(*path)) {
+            crate::execute_with_params!(
+                db,
+                "DELETE FROM ai_kb_documents WHERE kb_id = ? AND path = ?",
+                &id,
+                path
+            )?;
+            summary.removed += 1;
+        }
+        crate::execute_with_params!(
+            db,
+            "UPDATE ai_knowledge_bases SET dimensions = ?, indexed_at = ? WHERE id = ?",
+            dimensions,
+            chrono::Utc::now().timestamp_millis(),
+            &id
+        )?;
+    }
+
+    log::info!(
+        "知识库索引完成: {} (新增 {}, 未变化 {}, 失败 {}, 删除 {})",
+        kb.name,
+        summary.indexed,
+        summary.unchanged,
+        summary.failed,
+        summary.removed
+    );
+    Ok(summary)
+}
@@ -0,0 +1,20 @@
+/**
+ * 知识库
+ *
+ * - extract: Markdown、纯文本与 PDF 的文本提取
+ * - pdf: PDF 内容流解析
+ * - chunk: 文档分块
+ * - embedding: 通过提供商接口向量化
+ * - index: 知识库管理、目录扫描与增量索引
+ * - search: 余弦相似度检索与对话中的引用
+ */
+mod chunk;
+mod embedding;
+mod extract;
+mod index;
+mod pdf;
+mod search;
+
+pub use extract::document_text;
+pub use index::*;
+pub use search::*;
@@ -0,0 +1,615 @@
+/**
+ * PDF 文本提取
+ *
+ * 只处理常见的文本型 PDF：解压 FlateDecode 内容流，按文本绘制操作符取出字符串，
+ * CID 字体使用文档中的 ToUnicode 映射表解码。扫描件、加密文档与其他压缩方式的
+ * 内容流无法提取
+ */
+use flate2::read::ZlibDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// 解压后单个流的最大字节数
+const MAX_STREAM_BYTES: u64 = 32 * 1024 * 1024;
+/// 整个文档解压后的最大字节数
+const MAX_TOTAL_BYTES: u64 = 128 * 1024 * 1024;
+/// TJ 数组中表示单词间隔的最小偏移（千分之一字号）
+const WORD_GAP: f64 = 200.0;
+
+/// 不含页面文本的流
+const SKIPPED_STREAM_KEYS: [&[u8]; 7] = [
+    b"/Image",
+    b"/FontFile",
+    b"/Length1",
+    b"/XRef",
+    b"/ObjStm",
+    b"/Metadata",
+    b"/EmbeddedFile",
+];
+
+/// ToUnicode 映射表，键为编码字节数与编码值
+type CMap = HashMap<(usize, u32), String>;
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    find(haystack, needle, 0).is_some()
+}
+
+/**
+ * 找出所有流对象
+ *
+ * @param data PDF 文件内容
+ * @return 流的字典与原始数据
+ */
+fn streams(data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut result = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = find(data, b"stream", pos) {
+        if start >= 3 && &data[start - 3..start] == b"end" {
+            pos = start + 6;
+            continue;
+        }
+        let dict_start = data[..start]
+            .windows(3)
+            .rposition(|window| window == b"obj")
+            .unwrap_or(0);
+        let mut body_start = start + 6;
+        if data.get(body_start) == Some(&b'\r') {
+            body_start += 1;
+        }
+        if data.get(body_start) == Some(&b'\n') {
+            body_start += 1;
+        }
+        let Some(end) = find(data, b"endstream", body_start) else {
+            break;
+        };
+        result.push((&data[dict_start..start], &data[body_start..end]));
+        pos = end + 9;
+    }
+    result
+}
+
+/// 解压流，没有压缩时原样返回，不支持的压缩方式返回空
+fn decode_stream(dict: &[u8], data: &[u8], limit: u64) -> Option<Vec<u8>> {
+    if contains(dict, b"/FlateDecode") {
+        let mut out = Vec::new();
+        let result = ZlibDecoder::new(data)
+            .take(limit.min(MAX_STREAM_BYTES))
+            .read_to_end(&mut out);
+        // 数据末尾损坏时保留已解压的部分
+        (result.is_ok() || !out.is_empty()).then_some(out)
+    } else if contains(dict, b"/Filter") {
+        None
+    } else {
+        Some(data.to_vec())
+    }
+}
+
+/**
+ * 内容流中的词法单元
+ */
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(Vec<u8>),
+    Num(f64),
+    Op(String),
+    ArrayStart,
+    ArrayEnd,
+    Other,
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(
+        b,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// 解析十六进制字符串，奇数位末尾补 0
+fn parse_hex(data: &[u8]) -> Vec<u8> {
+    let digits: Vec<u8> = data.iter().filter_map(|b| hex_value(*b)).collect();
+    digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+        .collect()
+}
+
+/**
+ * 内容流的词法分析器
+ */
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Lexer { data, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if b.is_ascii_whitespace() || b == 0 {
+                self.pos += 1;
+            } else if b == b'%' {
+                while let Some(b) = self.peek() {
+                    if b == b'\n' || b == b'\r' {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 读取括号字符串，处理转义与嵌套括号
+    fn literal_string(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut depth = 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => {
+                    let Some(next) = self.peek() else {
+                        break;
+                    };
+                    self.pos += 1;
+                    match next {
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        b'0'..=b'7' => {
+                            let mut value = (next - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        other => out.push(other),
+                    }
+                }
+                b'(' => {
+                    depth += 1;
+                    out.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    out.push(b);
+                }
+                _ => out.push(b),
+            }
+        }
+        out
+    }
+
+    /// 跳过内嵌图片 BI ... ID ... EI
+    fn skip_inline_image(&mut self) {
+        while self.pos + 2 < self.data.len() {
+            if self.data[self.pos].is_ascii_whitespace()
+                && &self.data[self.pos + 1..self.pos + 3] == b"EI"
+                && self
+                    .data
+                    .get(self.pos + 3)
+                    .is_none_or(|b| b.is_ascii_whitespace())
+            {
+                self.pos += 3;
+                return;
+            }
+            self.pos += 1;
+        }
+        self.pos = self.data.len();
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        self.skip_whitespace();
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(match b {
+            b'(' => Token::Str(self.literal_string()),
+            b'<' if self.peek() == Some(b'<') => {
+                self.pos += 1;
+                Token::Other
+            }
+            b'<' => {
+                let start = self.pos;
+                while self.peek().is_some_and(|b| b != b'>') {
+                    self.pos += 1;
+                }
+                let hex = parse_hex(&self.data[start..self.pos]);
+                self.pos += 1;
+                Token::Str(hex)
+            }
+            b'>' => {
+                if self.peek() == Some(b'>') {
+                    self.pos += 1;
+                }
+                Token::Other
+            }
+            b'[' => Token::ArrayStart,
+            b']' => Token::ArrayEnd,
+            b'/' => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !b.is_ascii_whitespace() && !is_delimiter(b))
+                {
+                    self.pos += 1;
+                }
+                Token::Other
+            }
+            _ => {
+                let start = self.pos - 1;
+                while self
+                    .peek()
+                    .is_some_and(|b| !b.is_ascii_whitespace() && !is_delimiter(b))
+                {
+                    self.pos += 1;
+                }
+                let word = String::from_utf8_lossy(&self.data[start..self.pos]).into_owned();
+                match word.parse::<f64>() {
+                    Ok(value) => Token::Num(value),
+                    Err(_) if word == "ID" => {
+                        self.skip_inline_image();
+                        Token::Other
+                    }
+                    Err(_) => Token::Op(word),
+                }
+            }
+        })
+    }
+}
+
+/// 将映射表中的目标值（UTF-16BE）转为文本
+fn utf16_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn code_value(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |acc, b| (acc << 8) | *b as u32)
+}
+
+/**
+ * 解析 ToUnicode 映射表中的 bfchar 与 bfrange
+ *
+ * @param data 解压后的映射表
+ * @param cmap 合并到的映射表
+ */
+fn parse_cmap(data: &[u8], cmap: &mut CMap) {
+    let mut lexer = Lexer::new(data);
+    let mut section = "";
+    let mut operands: Vec<Token> = Vec::new();
+    while let Some(token) = lexer.next_token() {
+        match token {
+            Token::Op(op) => {
+                match op.as_str() {
+                    "beginbfchar" => section = "bfchar",
+                    "beginbfrange" => section = "bfrange",
+                    "endbfchar" | "endbfrange" => section = "",
+                    _ => {}
+                }
+                operands.clear();
+            }
+            token => {
+                operands.push(token);
+                match section {
+                    "bfchar" if operands.len() == 2 => {
+                        if let [Token::Str(src), Token::Str(dst)] = operands.as_slice() {
+                            cmap.insert((src.len(), code_value(src)), utf16_string(dst));
+                        }
+                        operands.clear();
+                    }
+                    "bfrange" => {
+                        let complete = match operands.as_slice() {
+                            [Token::Str(_), Token::Str(_), Token::Str(_)] => true,
+                            [Token::Str(_), Token::Str(_), Token::ArrayStart, .., last] => {
+                                *last == Token::ArrayEnd
+                            }
+                            _ => operands.len() >= 3 && operands[2] != Token::ArrayStart,
+                        };
+                        if !complete {
+                            continue;
+                        }
+                        if let [Token::Str(lo), Token::Str(hi), rest @ ..] = operands.as_slice() {
+                            let (start, end) = (code_value(lo), code_value(hi));
+                            let width = lo.len();
+                            match rest {
+                                [Token::Str(dst)] => {
+                                    let mut dst = dst.clone();
+                                    for code in start..=end.min(start.saturating_add(0xFFFF)) {
+                                        cmap.insert((width, code), utf16_string(&dst));
+                                        if let Some(last) = dst.last_mut() {
+                                            *last = last.wrapping_add(1);
+                                        }
+                                    }
+                                }
+                                [Token::ArrayStart, items @ .., Token::ArrayEnd] => {
+                                    for (code, item) in (start..=end).zip(items) {
+                                        if let Token::Str(dst) = item {
+                                            cmap.insert((width, code), utf16_string(dst));
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        operands.clear();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/**
+ * 解码文本字符串
+ *
+ * 优先使用 ToUnicode 映射表，按双字节与单字节依次尝试；没有对应映射时按
+ * UTF-16BE（带 BOM）或单字节编码处理
+ */
+fn decode_text(bytes: &[u8], cmap: &CMap) -> String {
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return utf16_string(&bytes[2..]);
+    }
+    if !cmap.is_empty() {
+        for width in [2, 1] {
+            if !bytes.len().is_multiple_of(width) {
+                continue;
+            }
+            let decoded: Option<String> = bytes
+                .chunks(width)
+                .map(|code| cmap.get(&(width, code_value(code))).map(String::as_str))
+                .collect();
+            if let Some(decoded) = decoded {
+                return decoded;
+            }
+        }
+    }
+    bytes
+        .iter()
+        .filter(|b| **b >= 0x20 || **b == b'\t')
+        .map(|b| *b as char)
+        .collect()
+}
+
+/// 提取一个内容流中的文本
+fn extract_content(data: &[u8], cmap: &CMap, out: &mut String) {
+    let mut lexer = Lexer::new(data);
+    let mut operands: Vec<Token> = Vec::new();
+    let mut in_text = false;
+    while let Some(token) = lexer.next_token() {
+        let Token::Op(op) = token else {
+            operands.push(token);
+            continue;
+        };
+        match op.as_str() {
+            "BT" => in_text = true,
+            "ET" => {
+                in_text = false;
+                out.push('\n');
+            }
+            "Tj" | "'" | "\"" if in_text => {
+                if op != "Tj" {
+                    out.push('\n');
+                }
+                if let Some(Token::Str(text)) = operands.last() {
+                    out.push_str(&decode_text(text, cmap));
+                }
+            }
+            "TJ" if in_text => {
+                for operand in &operands {
+                    match operand {
+                        Token::Str(text) => out.push_str(&decode_text(text, cmap)),
+                        Token::Num(offset) if *offset < -WORD_GAP => out.push(' '),
+                        _ => {}
+                    }
+                }
+            }
+            "Td" | "TD" if in_text => {
+                if let [.., Token::Num(_), Token::Num(ty)] = operands.as_slice() {
+                    if *ty != 0.0 {
+                        out.push('\n');
+                    } else if !out.ends_with([' ', '\n']) {
+                        out.push(' ');
+                    }
+                }
+            }
+            "T*" | "Tm" if in_text => out.push('\n'),
+            _ => {}
+        }
+        operands.clear();
+    }
+}
+
+/// 合并多余的空白
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank = 0;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank > 0 { "\n\n" } else { "\n" });
+        }
+        blank = 0;
+        out.push_str(&line);
+    }
+    out
+}
+
+/**
+ * 提取 PDF 中的文本
+ *
+ * @param data PDF 文件内容
+ * @return 文本
+ */
+pub fn extract_pdf_text(data: &[u8]) -> Result<String, String> {
+    extract_with_budget(data, MAX_TOTAL_BYTES)
+}
+
+/**
+ * 按解压总量上限提取文本
+ *
+ * 解压后的全部流计入同一上限，超出时返回错误，避免大量压缩炸弹耗尽内存
+ *
+ * @param data PDF 文件内容
+ * @param budget 解压后的最大总字节数
+ * @return 文本
+ */
+fn extract_with_budget(data: &[u8], budget: u64) -> Result<String, String> {
+    if !data.starts_with(b"%PDF") {
+        return Err("不是有效的PDF文件".to_string());
+    }
+    if contains(data, b"/Encrypt") {
+        return Err("不支持加密的PDF文件".to_string());
+    }
+
+    let mut cmap = CMap::new();
+    let mut contents = Vec::new();
+    let mut remaining = budget;
+    for (dict, raw) in streams(data) {
+        if SKIPPED_STREAM_KEYS.iter().any(|key| contains(dict, key)) {
+            continue;
+        }
+        if remaining == 0 {
+            return Err(format!("PDF解压后的内容超过 {} MB", budget / 1024 / 1024));
+        }
+        let Some(decoded) = decode_stream(dict, raw, remaining) else {
+            continue;
+        };
+        remaining = remaining.saturating_sub(decoded.len() as u64);
+        if contains(&decoded, b"begincmap") {
+            parse_cmap(&decoded, &mut cmap);
+        } else if contains(&decoded, b"BT") {
+            contents.push(decoded);
+        }
+    }
+
+    let mut text = String::new();
+    for content in &contents {
+        extract_content(content, &cmap, &mut text);
+        text.push('\n');
+    }
+    let text = normalize(&text);
+    if text.is_empty() {
+        return Err("PDF中没有可提取的文本，可能是扫描件".to_string());
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// 由若干流对象拼出最简的 PDF
+    fn pdf(streams: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut data = b"%PDF-1.4\n".to_vec();
+        for (index, (dict, body)) in streams.iter().enumerate() {
+            data.extend(format!("{} 0 obj\n{}\nstream\n", index + 1, dict).as_bytes());
+            data.extend(body);
+            data.extend(b"\nendstream\nendobj\n");
+        }
+        data.extend(b"%%EOF");
+        data
+    }
+
+    fn deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn extracts_plain_and_compressed_text() {
+        let data = pdf(&[
+            ("<< /Length 0 >>", b"BT /F1 12 Tf (Hello) Tj ET".to_vec()),
+            (
+                "<< /Filter /FlateDecode >>",
+                deflate(b"BT [(Wor) -50 (ld) -400 (again)] TJ ET"),
+            ),
+        ]);
+        assert_eq!(extract_pdf_text(&data).unwrap(), "Hello\n\nWorld again");
+    }
+
+    #[test]
+    fn decodes_text_through_to_unicode_cmap() {
+        let cmap = b"/CIDInit begincmap 1 begincodespacerange <0000> <FFFF> endcodespacerange \
+                     1 beginbfchar <0001> <4F60> endbfchar \
+                     1 beginbfrange <0002> <0003> <597D> endbfrange endcmap";
+        let data = pdf(&[
+            ("<< /Length 0 >>", cmap.to_vec()),
+            ("<< /Length 0 >>", b"BT <00010002> Tj ET".to_vec()),
+        ]);
+        assert_eq!(extract_pdf_text(&data).unwrap(), "你好");
+    }
+
+    #[test]
+    fn wide_cmap_range_does_not_overflow() {
+        let mut cmap = CMap::new();
+        parse_cmap(
+            b"1 beginbfrange <FFFFFFFE> <FFFFFFFF> <0041> endbfrange",
+            &mut cmap,
+        );
+        assert_eq!(cmap.get(&(4, 0xFFFF_FFFF)).map(String::as_str), Some("B"));
+    }
+
+    #[test]
+    fn rejects_documents_over_the_decompression_budget() {
+        let bomb = deflate(&vec![b' '; 64 * 1024]);
+        let streams: Vec<(&str, Vec<u8>)> = (0..4)
+            .map(|_| ("<< /Filter /FlateDecode >>", bomb.clone()))
+            .collect();
+        let data = pdf(&streams);
+        assert!(extract_with_budget(&data, 128 * 1024).is_err());
+        assert!(extract_with_budget(&data, 1024 * 1024).is_err_and(|e| e.contains("没有可提取")));
+    }
+
+    #[test]
+    fn rejects_non_pdf_and_encrypted_files() {
+        assert!(extract_pdf_text(b"hello").is_err());
+        assert!(extract_pdf_text(b"%PDF-1.7 trailer << /Encrypt 5 0 R >>").is_err());
+    }
+}
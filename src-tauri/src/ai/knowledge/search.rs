@@ -0,0 +1,179 @@
+/**
+ * 知识库检索
+ *
+ * 将查询向量化后与知识库中的全部片段计算余弦相似度，取最相似的若干片段。
+ * 对话时检索结果以带编号的资料附在系统消息中，模型按编号标注引用
+ */
+use super::embedding::{dot_bytes, embed};
+use super::index::load_knowledge_base;
+use crate::ai::types::{ChatMessage, ChatRequest, ChatRole, KnowledgeCitation};
+use sqlx::Row;
+use tauri::AppHandle;
+
+/// 默认检索的片段数
+const DEFAULT_TOP_K: usize = 5;
+/// 最多检索的片段数
+const MAX_TOP_K: usize = 20;
+
+/// 提示模型使用资料并标注引用
+const KNOWLEDGE_PROMPT: &str = "以下是从知识库中检索到的资料。回答时优先依据这些资料，\
+并在引用处用 [编号] 标注来源；资料与问题无关时忽略它们，不要编造资料中没有的内容。";
+
+/**
+ * 在知识库中检索与查询最相似的片段
+ *
+ * @param app Tauri应用句柄
+ * @param kb_ids 知识库ID
+ * @param query 查询文本
+ * @param top_k 返回的片段数
+ * @return 按相似度降序排列的片段
+ */
+pub async fn retrieve(
+    app: &AppHandle,
+    kb_ids: &[String],
+    query: &str,
+    top_k: usize,
+) -> Result<Vec<KnowledgeCitation>, String> {
+    let top_k = top_k.clamp(1, MAX_TOP_K);
+    // (相似度, 片段ID)
+    let mut scored: Vec<(f32, i64)> = Vec::new();
+    for kb_id in kb_ids {
+        let kb = load_knowledge_base(kb_id).await?;
+        let query_vector = embed(
+            app,
+            kb.embedding_provider,
+            &kb.embedding_model,
+            &[query.to_string()],
+        )
+        .await?
+        .pop()
+        .ok_or("向量化结果为空")?;
+
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let rows = crate::query_with_params!(
+            db,
+            "SELECT id, embedding FROM ai_kb_chunks WHERE kb_id = ?",
+            kb_id
+        )?;
+        scored.extend(rows.iter().filter_map(|row| {
+            let score = dot_bytes(&query_vector, &row.get::<Vec<u8>, _>("embedding"))?;
+            Some((score, row.get::<i64, _>("id")))
+        }));
+    }
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(top_k);
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut citations = Vec::with_capacity(scored.len());
+    for (score, chunk_id) in scored {
+        let Some(row) = crate::query_one_with_params!(
+            db,
+            "SELECT c.kb_id, c.document_id, c.seq, c.heading, c.content, d.title, d.path
+             FROM ai_kb_chunks c JOIN ai_kb_documents d ON d.id = c.document_id
+             WHERE c.id = ?",
+            chunk_id
+        )?
+        else {
+            continue;
+        };
+        citations.push(KnowledgeCitation {
+            index: citations.len() + 1,
+            kb_id: row.get("kb_id"),
+            document_id: row.get("document_id"),
+            title: row.get("title"),
+            path: row.get("path"),
+            heading: row.get("heading"),
+            chunk_seq: row.get("seq"),
+            score,
+            content: row.get("content"),
+        });
+    }
+    Ok(citations)
+}
+
+/// 将检索结果整理为系统消息
+fn knowledge_message(citations: &[KnowledgeCitation]) -> ChatMessage {
+    let mut content = String::from(KNOWLEDGE_PROMPT);
+    for citation in citations {
+        let source = match &citation.heading {
+            Some(heading) => format!("{} › {}", citation.title, heading),
+            None => citation.title.clone(),
+        };
+        content.push_str(&format!(
+            "\n\n[{}] {}（{}）\n{}",
+            citation.index, source, citation.path, citation.content
+        ));
+    }
+    ChatMessage::text(ChatRole::System, content)
+}
+
+/**
+ * 按最后一条用户消息检索知识库，并将结果附在系统消息之后
+ *
+ * @param app Tauri应用句柄
+ * @param request 对话请求
+ * @return 附加资料后的请求与引用的片段
+ */
+pub async fn attach_knowledge(
+    app: &AppHandle,
+    request: &ChatRequest,
+) -> Result<(ChatRequest, Vec<KnowledgeCitation>), String> {
+    let query = request
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == ChatRole::User)
+        .map(|message| message.content.trim())
+        .filter(|query| !query.is_empty());
+    let (Some(query), false) = (query, request.knowledge_base_ids.is_empty()) else {
+        return Ok((request.clone(), Vec::new()));
+    };
+
+    let citations = retrieve(
+        app,
+        &request.knowledge_base_ids,
+        query,
+        request.knowledge_top_k.unwrap_or(DEFAULT_TOP_K),
+    )
+    .await
+    .map_err(|e| format!("知识库检索失败: {}", e))?;
+    if citations.is_empty() {
+        return Ok((request.clone(), citations));
+    }
+
+    let mut request = request.clone();
+    let position = request
+        .messages
+        .iter()
+        .take_while(|message| message.role == ChatRole::System)
+        .count();
+    request
+        .messages
+        .insert(position, knowledge_message(&citations));
+    Ok((request, citations))
+}
+
+/**
+ * 检索知识库
+ *
+ * @param app Tauri应用句柄
+ * @param kb_ids 知识库ID
+ * @param query 查询文本
+ * @param top_k 返回的片段数，默认 5
+ * @return 按相似度降序排列的片段
+ */
+#[tauri::command]
+pub async fn search_knowledge_base(
+    app: AppHandle,
+    kb_ids: Vec<String>,
+    query: String,
+    top_k: Option<usize>,
+) -> Result<Vec<KnowledgeCitation>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("请输入检索内容".to_string());
+    }
+    retrieve(&app, &kb_ids, query, top_k.unwrap_or(DEFAULT_TOP_K)).await
+}
@@ -0,0 +1,418 @@
+/**
+ * MCP 客户端
+ *
+ * 完成初始化握手后按服务器声明的能力发现工具、资源与提示词，并提供调用工具、
+ * 读取资源与获取提示词的方法
+ */
+use super::transport::{Transport, PROTOCOL_VERSION};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// 普通请求的超时时间（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+/// 工具调用的超时时间（秒）
+const TOOL_TIMEOUT_SECS: u64 = 120;
+/// 列表分页的最大页数
+const MAX_LIST_PAGES: usize = 20;
+
+/**
+ * 服务器信息
+ */
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct McpServerInfo {
+    /// 服务器名称
+    pub name: String,
+    /// 服务器版本
+    pub version: String,
+    /// 协议版本
+    pub protocol_version: String,
+    /// 使用说明
+    pub instructions: Option<String>,
+}
+
+/**
+ * 服务器提供的工具
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpTool {
+    /// 工具名称
+    pub name: String,
+    /// 工具说明
+    pub description: Option<String>,
+    /// 参数的 JSON Schema
+    pub input_schema: Value,
+}
+
+/**
+ * 服务器提供的资源
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpResource {
+    /// 资源地址
+    pub uri: String,
+    /// 资源名称
+    pub name: String,
+    /// 资源说明
+    pub description: Option<String>,
+    /// MIME 类型
+    pub mime_type: Option<String>,
+}
+
+/**
+ * 提示词参数
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpPromptArgument {
+    /// 参数名称
+    pub name: String,
+    /// 参数说明
+    pub description: Option<String>,
+    /// 是否必填
+    pub required: bool,
+}
+
+/**
+ * 服务器提供的提示词
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpPrompt {
+    /// 提示词名称
+    pub name: String,
+    /// 提示词说明
+    pub description: Option<String>,
+    /// 参数
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/**
+ * 提示词展开后的消息
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpPromptMessage {
+    /// 角色，user 或 assistant
+    pub role: String,
+    /// 文本内容
+    pub content: String,
+}
+
+/// 读取可选的字符串字段
+fn optional_str(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/**
+ * 将内容块转为文本
+ *
+ * 图片与音频等二进制内容只保留类型说明
+ */
+fn content_to_text(content: &Value) -> String {
+    match content["type"].as_str().unwrap_or_default() {
+        "text" => content["text"].as_str().unwrap_or_default().to_string(),
+        "resource" => {
+            let resource = &content["resource"];
+            resource["text"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    format!("[资源: {}]", resource["uri"].as_str().unwrap_or_default())
+                })
+        }
+        "resource_link" => format!("[资源: {}]", content["uri"].as_str().unwrap_or_default()),
+        other => format!(
+            "[{}: {}]",
+            other,
+            content["mimeType"].as_str().unwrap_or("未知类型")
+        ),
+    }
+}
+
+/**
+ * 已连接的 MCP 服务器
+ */
+pub struct McpClient {
+    transport: Transport,
+    /// 服务器信息
+    pub info: McpServerInfo,
+    /// 工具
+    pub tools: Vec<McpTool>,
+    /// 资源
+    pub resources: Vec<McpResource>,
+    /// 提示词
+    pub prompts: Vec<McpPrompt>,
+}
+
+impl McpClient {
+    /**
+     * 初始化连接并发现服务器的能力
+     *
+     * @param transport 传输
+     * @return 客户端
+     */
+    pub async fn connect(transport: Transport) -> Result<Self, String> {
+        let mut client = McpClient {
+            transport,
+            info: McpServerInfo::default(),
+            tools: Vec::new(),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+        };
+        if let Err(e) = client.initialize().await {
+            client.transport.close();
+            return Err(e);
+        }
+        Ok(client)
+    }
+
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        self.transport
+            .request(method, params, Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .await
+    }
+
+    async fn initialize(&mut self) -> Result<(), String> {
+        let result = self
+            .request(
+                "initialize",
+                Some(json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "TaiASST",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                })),
+            )
+            .await?;
+        self.info = McpServerInfo {
+            name: result["serverInfo"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            version: result["serverInfo"]["version"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            protocol_version: result["protocolVersion"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            instructions: optional_str(&result["instructions"]),
+        };
+        self.transport.notify("notifications/initialized").await?;
+
+        let capabilities = &result["capabilities"];
+        if capabilities.get("tools").is_some() {
+            self.tools = self
+                .list("tools/list", "tools")
+                .await?
+                .iter()
+                .filter_map(|tool| {
+                    Some(McpTool {
+                        name: optional_str(&tool["name"])?,
+                        description: optional_str(&tool["description"]),
+                        input_schema: match &tool["inputSchema"] {
+                            Value::Object(_) => tool["inputSchema"].clone(),
+                            _ => json!({ "type": "object", "properties": {} }),
+                        },
+                    })
+                })
+                .collect();
+        }
+        // 资源与提示词只用于展示，获取失败不影响连接
+        if capabilities.get("resources").is_some() {
+            match self.list("resources/list", "resources").await {
+                Ok(resources) => {
+                    self.resources = resources
+                        .iter()
+                        .filter_map(|resource| {
+                            let uri = optional_str(&resource["uri"])?;
+                            Some(McpResource {
+                                name: optional_str(&resource["name"])
+                                    .unwrap_or_else(|| uri.clone()),
+                                uri,
+                                description: optional_str(&resource["description"]),
+                                mime_type: optional_str(&resource["mimeType"]),
+                            })
+                        })
+                        .collect()
+                }
+                Err(e) => log::warn!("获取MCP资源列表失败 ({}): {}", self.info.name, e),
+            }
+        }
+        if capabilities.get("prompts").is_some() {
+            match self.list("prompts/list", "prompts").await {
+                Ok(prompts) => {
+                    self.prompts = prompts
+                        .iter()
+                        .filter_map(|prompt| {
+                            Some(McpPrompt {
+                                name: optional_str(&prompt["name"])?,
+                                description: optional_str(&prompt["description"]),
+                                arguments: prompt["arguments"]
+                                    .as_array()
+                                    .map(|arguments| {
+                                        arguments
+                                            .iter()
+                                            .filter_map(|argument| {
+                                                Some(McpPromptArgument {
+                                                    name: optional_str(&argument["name"])?,
+                                                    description: optional_str(
+                                                        &argument["description"],
+                                                    ),
+                                                    required: argument["required"]
+                                                        .as_bool()
+                                                        .unwrap_or(false),
+                                                })
+                                            })
+                                            .collect()
+                                    })
+                                    .unwrap_or_default(),
+                            })
+                        })
+                        .collect()
+                }
+                Err(e) => log::warn!("获取MCP提示词列表失败 ({}): {}", self.info.name, e),
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * 读取分页列表
+     *
+     * @param method 方法名
+     * @param field 结果中列表所在的字段
+     * @return 全部条目
+     */
+    async fn list(&self, method: &str, field: &str) -> Result<Vec<Value>, String> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = cursor.as_ref().map(|cursor| json!({ "cursor": cursor }));
+            let result = self.request(method, params).await?;
+            if let Some(page) = result[field].as_array() {
+                items.extend(page.iter().cloned());
+            }
+            cursor = optional_str(&result["nextCursor"]);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /**
+     * 调用工具
+     *
+     * @param name 工具名称
+     * @param arguments 调用参数
+     * @return 工具返回的文本，工具报告错误时返回错误
+     */
+    pub async fn call_tool(&self, name: &str, arguments: &Value) -> Result<String, String> {
+        let result = self
+            .transport
+            .request(
+                "tools/call",
+                Some(json!({ "name": name, "arguments": arguments })),
+                Duration::from_secs(TOOL_TIMEOUT_SECS),
+            )
+            .await?;
+        let mut text = result["content"]
+            .as_array()
+            .map(|content| {
+                content
+                    .iter()
+                    .map(content_to_text)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        if text.is_empty() {
+            if let Some(structured) = result.get("structuredContent") {
+                text = structured.to_string();
+            }
+        }
+        if result["isError"].as_bool() == Some(true) {
+            return Err(if text.is_empty() {
+                "MCP工具执行失败".to_string()
+            } else {
+                text
+            });
+        }
+        Ok(text)
+    }
+
+    /**
+     * 读取资源
+     *
+     * @param uri 资源地址
+     * @return 资源的文本内容
+     */
+    pub async fn read_resource(&self, uri: &str) -> Result<String, String> {
+        let result = self
+            .request("resources/read", Some(json!({ "uri": uri })))
+            .await?;
+        Ok(result["contents"]
+            .as_array()
+            .map(|contents| {
+                contents
+                    .iter()
+                    .map(|content| match content["text"].as_str() {
+                        Some(text) => text.to_string(),
+                        None => format!(
+                            "[二进制内容: {}]",
+                            content["mimeType"].as_str().unwrap_or("未知类型")
+                        ),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default())
+    }
+
+    /**
+     * 获取提示词
+     *
+     * @param name 提示词名称
+     * @param arguments 参数
+     * @return 展开后的消息
+     */
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &serde_json::Map<String, Value>,
+    ) -> Result<Vec<McpPromptMessage>, String> {
+        let result = self
+            .request(
+                "prompts/get",
+                Some(json!({ "name": name, "arguments": arguments })),
+            )
+            .await?;
+        Ok(result["messages"]
+            .as_array()
+            .map(|messages| {
+                messages
+                    .iter()
+                    .map(|message| McpPromptMessage {
+                        role: message["role"].as_str().unwrap_or("user").to_string(),
+                        content: content_to_text(&message["content"]),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// 连接是否仍然可用
+    pub fn is_alive(&self) -> bool {
+        self.transport.is_alive()
+    }
+
+    /// 关闭连接
+    pub fn close(&self) {
+        self.transport.close();
+    }
+}
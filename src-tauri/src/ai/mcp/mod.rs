@@ -0,0 +1,12 @@
+/**
+ * MCP（Model Context Protocol）客户端
+ *
+ * - transport: stdio 与 Streamable HTTP 传输
+ * - client: 初始化握手、能力发现与调用
+ * - servers: 服务器定义的保存、连接管理与对话工具
+ */
+mod client;
+mod servers;
+mod transport;
+
+pub use servers::*;
@@ -0,0 +1,691 @@
+/**
+ * MCP 服务器管理
+ *
+ * 服务器定义保存在 ai_mcp_servers 表中，环境变量与请求头可能包含令牌，以设备密钥
+ * 加密保存在加密存储（ai.mcp.<服务器ID>）中。连接在首次使用时建立并复用，
+ * 发现的工具以 mcp_<服务器>_<工具> 的名称提供给对话，确认策略按服务器ID和工具名称保存
+ */
+use super::client::{McpClient, McpPrompt, McpPromptMessage, McpResource, McpServerInfo, McpTool};
+use super::transport::{HttpTransport, StdioTransport, Transport};
+use crate::ai::tools::mcp_policy_prefix;
+use crate::ai::types::ToolDefinition;
+use crate::security::session::require_session;
+use crate::security::storage::{delete_secret, get_secret, set_secret, EncryptionLevel};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// 服务器机密配置在加密存储中的键名前缀
+const SECRET_PREFIX: &str = "ai.mcp.";
+/// 工具名称前缀
+const TOOL_PREFIX: &str = "mcp_";
+/// 工具名称的最大长度，部分提供商限制为 64
+const MAX_TOOL_NAME_LEN: usize = 64;
+/// 连接失败后暂停自动重连的时间（秒）
+const RETRY_COOLDOWN_SECS: u64 = 60;
+
+/**
+ * 传输方式
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransportKind {
+    /// 本地进程
+    Stdio,
+    /// Streamable HTTP
+    Http,
+}
+
+impl McpTransportKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            McpTransportKind::Stdio => "stdio",
+            McpTransportKind::Http => "http",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "stdio" => Ok(McpTransportKind::Stdio),
+            "http" => Ok(McpTransportKind::Http),
+            _ => Err(format!("未知的MCP传输方式: {}", value)),
+        }
+    }
+}
+
+/**
+ * MCP 服务器定义，不包含环境变量与请求头的值
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct McpServer {
+    /// 服务器ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 传输方式
+    pub transport: McpTransportKind,
+    /// 可执行文件，仅 stdio
+    pub command: Option<String>,
+    /// 命令行参数，仅 stdio
+    pub args: Vec<String>,
+    /// 端点地址，仅 HTTP
+    pub url: Option<String>,
+    /// 已设置的环境变量名
+    pub env_keys: Vec<String>,
+    /// 已设置的请求头名
+    pub header_keys: Vec<String>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 是否已连接
+    pub connected: bool,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+    /// 更新时间（毫秒）
+    pub updated_at: i64,
+}
+
+/**
+ * 保存 MCP 服务器的参数
+ */
+#[derive(Debug, Deserialize)]
+pub struct McpServerInput {
+    /// 服务器ID，为空时新建
+    pub id: Option<String>,
+    /// 名称
+    pub name: String,
+    /// 传输方式
+    pub transport: McpTransportKind,
+    /// 可执行文件，仅 stdio
+    pub command: Option<String>,
+    /// 命令行参数，仅 stdio
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 端点地址，仅 HTTP
+    pub url: Option<String>,
+    /// 环境变量，为空时保留原值
+    pub env: Option<HashMap<String, String>>,
+    /// 请求头，为空时保留原值
+    pub headers: Option<HashMap<String, String>>,
+    /// 是否启用
+    pub enabled: bool,
+}
+
+/**
+ * 环境变量与请求头，加密保存
+ */
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct McpSecrets {
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+/**
+ * 服务器能力发现结果
+ */
+#[derive(Debug, Serialize)]
+pub struct McpServerDiscovery {
+    /// 服务器信息
+    pub info: McpServerInfo,
+    /// 工具
+    pub tools: Vec<McpTool>,
+    /// 资源
+    pub resources: Vec<McpResource>,
+    /// 提示词
+    pub prompts: Vec<McpPrompt>,
+}
+
+impl McpServerDiscovery {
+    fn from_client(client: &McpClient) -> Self {
+        McpServerDiscovery {
+            info: client.info.clone(),
+            tools: client.tools.clone(),
+            resources: client.resources.clone(),
+            prompts: client.prompts.clone(),
+        }
+    }
+}
+
+/**
+ * 提供给对话的 MCP 工具
+ */
+pub struct McpToolBinding {
+    /// 提供给模型的工具定义
+    pub definition: ToolDefinition,
+    /// 服务器ID
+    pub server_id: String,
+    /// 服务器中的工具名称
+    pub tool: String,
+}
+
+/**
+ * 已建立的连接
+ */
+#[derive(Default)]
+struct Registry {
+    clients: HashMap<String, Arc<McpClient>>,
+    failures: HashMap<String, Instant>,
+}
+
+// 使用lazy_static管理已建立的连接
+lazy_static::lazy_static! {
+    static ref REGISTRY: tokio::sync::Mutex<Registry> = tokio::sync::Mutex::new(Registry::default());
+}
+
+/// 机密配置在加密存储中的键名
+fn secret_key(id: &str) -> String {
+    format!("{}{}", SECRET_PREFIX, id)
+}
+
+/// 读取机密配置
+async fn load_secrets(app: &AppHandle, id: &str) -> Result<McpSecrets, String> {
+    match get_secret(app.clone(), &secret_key(id)).await? {
+        Some(value) => {
+            serde_json::from_str(&value).map_err(|e| format!("解析MCP服务器配置失败: {}", e))
+        }
+        None => Ok(McpSecrets::default()),
+    }
+}
+
+/// 排序后的键名
+fn sorted_keys(map: &HashMap<String, String>) -> Vec<String> {
+    let mut keys: Vec<String> = map.keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// 读取数据库行
+fn row_to_server(row: &sqlx::sqlite::SqliteRow) -> Result<McpServer, String> {
+    Ok(McpServer {
+        id: row.get("id"),
+        name: row.get("name"),
+        transport: McpTransportKind::parse(&row.get::<String, _>("transport"))?,
+        command: row.get("command"),
+        args: serde_json::from_str(&row.get::<String, _>("args")).unwrap_or_default(),
+        url: row.get("url"),
+        env_keys: Vec::new(),
+        header_keys: Vec::new(),
+        enabled: row.get::<i64, _>("enabled") != 0,
+        connected: false,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/**
+ * 读取服务器定义
+ *
+ * @param id 服务器ID，为空时读取全部
+ * @return 服务器列表，按创建时间排序
+ */
+async fn load_servers(id: Option<&str>) -> Result<Vec<McpServer>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT * FROM ai_mcp_servers WHERE (? IS NULL OR id = ?) ORDER BY created_at",
+        id,
+        id
+    )?;
+    rows.iter().map(row_to_server).collect()
+}
+
+/// 读取单个服务器定义
+async fn load_server(id: &str) -> Result<McpServer, String> {
+    load_servers(Some(id))
+        .await?
+        .pop()
+        .ok_or_else(|| format!("MCP服务器不存在: {}", id))
+}
+
+/// 补充连接状态与机密配置的键名
+async fn with_status(app: &AppHandle, mut server: McpServer) -> Result<McpServer, String> {
+    let secrets = load_secrets(app, &server.id).await?;
+    server.env_keys = sorted_keys(&secrets.env);
+    server.header_keys = sorted_keys(&secrets.headers);
+    server.connected = REGISTRY.lock().await.clients.contains_key(&server.id);
+    Ok(server)
+}
+
+/**
+ * 建立连接并完成能力发现
+ *
+ * @param app Tauri应用句柄
+ * @param server 服务器定义
+ * @return 客户端
+ */
+async fn open_client(app: &AppHandle, server: &McpServer) -> Result<McpClient, String> {
+    let secrets = load_secrets(app, &server.id).await?;
+    let transport = match server.transport {
+        McpTransportKind::Stdio => {
+            let command = server
+                .command
+                .as_deref()
+                .ok_or("未设置MCP服务器的启动命令")?;
+            Transport::Stdio(StdioTransport::spawn(
+                &server.name,
+                command,
+                &server.args,
+                &secrets.env,
+            )?)
+        }
+        McpTransportKind::Http => {
+            let url = server.url.as_deref().ok_or("未设置MCP服务器的地址")?;
+            Transport::Http(HttpTransport::new(url, secrets.headers))
+        }
+    };
+    let client = McpClient::connect(transport).await?;
+    log::info!(
+        "已连接MCP服务器: {} ({} 个工具)",
+        server.name,
+        client.tools.len()
+    );
+    Ok(client)
+}
+
+/// 断开连接
+async fn disconnect(id: &str) {
+    let mut registry = REGISTRY.lock().await;
+    registry.failures.remove(id);
+    if let Some(client) = registry.clients.remove(id) {
+        client.close();
+    }
+}
+
+/**
+ * 获取已建立的连接，没有时建立连接
+ *
+ * @param app Tauri应用句柄
+ * @param server 服务器定义
+ * @param force 是否忽略失败后的重连间隔
+ * @return 客户端
+ */
+async fn get_client(
+    app: &AppHandle,
+    server: &McpServer,
+    force: bool,
+) -> Result<Arc<McpClient>, String> {
+    {
+        let mut registry = REGISTRY.lock().await;
+        match registry.clients.get(&server.id) {
+            Some(client) if client.is_alive() => return Ok(client.clone()),
+            Some(_) => {
+                registry.clients.remove(&server.id);
+            }
+            None => {}
+        }
+        if !force {
+            if let Some(failed_at) = registry.failures.get(&server.id) {
+                if failed_at.elapsed() < Duration::from_secs(RETRY_COOLDOWN_SECS) {
+                    return Err(format!("MCP服务器暂时不可用: {}", server.name));
+                }
+            }
+        }
+    }
+
+    // 建立连接可能较慢，不持有锁
+    let result = open_client(app, server).await;
+    let mut registry = REGISTRY.lock().await;
+    match result {
+        Ok(client) => {
+            registry.failures.remove(&server.id);
+            let client = Arc::new(client);
+            if let Some(previous) = registry.clients.insert(server.id.clone(), client.clone()) {
+                previous.close();
+            }
+            Ok(client)
+        }
+        Err(e) => {
+            registry.failures.insert(server.id.clone(), Instant::now());
+            Err(e)
+        }
+    }
+}
+
+/// 将名称转为工具名称可用的字符
+fn slug(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 生成不重复的工具名称
+fn binding_name(server: &McpServer, tool: &str, used: &mut HashSet<String>) -> String {
+    let base: String = format!("{}{}_{}", TOOL_PREFIX, slug(&server.name), slug(tool))
+        .chars()
+        .take(MAX_TOOL_NAME_LEN)
+        .collect();
+    let mut name = base.clone();
+    let mut n = 2;
+    while used.contains(&name) {
+        let suffix = format!("_{}", n);
+        name = format!(
+            "{}{}",
+            &base[..base.len().min(MAX_TOOL_NAME_LEN - suffix.len())],
+            suffix
+        );
+        n += 1;
+    }
+    used.insert(name.clone());
+    name
+}
+
+/**
+ * 获取已启用服务器提供的工具
+ *
+ * 尚未连接的服务器会先建立连接，连接失败的服务器跳过
+ *
+ * @param app Tauri应用句柄
+ * @return 工具列表
+ */
+pub async fn mcp_tools(app: &AppHandle) -> Vec<McpToolBinding> {
+    let servers = match load_servers(None).await {
+        Ok(servers) => servers,
+        Err(e) => {
+            log::warn!("读取MCP服务器失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let servers: Vec<McpServer> = servers.into_iter().filter(|s| s.enabled).collect();
+    let clients = join_all(servers.iter().map(|server| get_client(app, server, false))).await;
+
+    let mut used = HashSet::new();
+    let mut bindings = Vec::new();
+    for (server, client) in servers.iter().zip(clients) {
+        let client = match client {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("MCP服务器不可用 ({}): {}", server.name, e);
+                continue;
+            }
+        };
+        for tool in &client.tools {
+            bindings.push(McpToolBinding {
+                definition: ToolDefinition {
+                    name: binding_name(server, &tool.name, &mut used),
+                    description: format!(
+                        "[MCP: {}] {}",
+                        server.name,
+                        tool.description.as_deref().unwrap_or(&tool.name)
+                    ),
+                    parameters: tool.input_schema.clone(),
+                },
+                server_id: server.id.clone(),
+                tool: tool.name.clone(),
+            });
+        }
+    }
+    bindings
+}
+
+/**
+ * 调用 MCP 工具
+ *
+ * @param app Tauri应用句柄
+ * @param server_id 服务器ID
+ * @param tool 服务器中的工具名称
+ * @param arguments 调用参数
+ * @return 工具返回的文本
+ */
+pub async fn call_mcp_tool(
+    app: &AppHandle,
+    server_id: &str,
+    tool: &str,
+    arguments: &Value,
+) -> Result<String, String> {
+    let server = load_server(server_id).await?;
+    let client = get_client(app, &server, false).await?;
+    client.call_tool(tool, arguments).await
+}
+
+/// 校验并规范化服务器定义
+fn normalize_input(input: &mut McpServerInput) -> Result<(), String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("请输入MCP服务器名称".to_string());
+    }
+    input.command = input
+        .command
+        .take()
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty());
+    input.url = input
+        .url
+        .take()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    match input.transport {
+        McpTransportKind::Stdio => {
+            if input.command.is_none() {
+                return Err("请输入MCP服务器的启动命令".to_string());
+            }
+            input.url = None;
+        }
+        McpTransportKind::Http => {
+            let url = input.url.as_deref().ok_or("请输入MCP服务器的地址")?;
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("MCP服务器地址只支持 http 与 https".to_string());
+            }
+            input.command = None;
+            input.args.clear();
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 获取全部 MCP 服务器
+ *
+ * @param app Tauri应用句柄
+ * @return 服务器列表
+ */
+#[tauri::command]
+pub async fn list_mcp_servers(app: AppHandle) -> Result<Vec<McpServer>, String> {
+    let mut servers = Vec::new();
+    for server in load_servers(None).await? {
+        servers.push(with_status(&app, server).await?);
+    }
+    Ok(servers)
+}
+
+/**
+ * 保存 MCP 服务器
+ *
+ * stdio 服务器会在本机执行命令，需要有效的会话
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @param server 服务器定义
+ * @return 保存后的服务器
+ */
+#[tauri::command]
+pub async fn save_mcp_server(
+    app: AppHandle,
+    session_id: String,
+    mut server: McpServerInput,
+) -> Result<McpServer, String> {
+    require_session(&session_id).await?;
+    normalize_input(&mut server)?;
+
+    let id = server
+        .id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    if server.env.is_some() || server.headers.is_some() {
+        let mut secrets = load_secrets(&app, &id).await?;
+        if let Some(env) = server.env.take() {
+            secrets.env = env;
+        }
+        if let Some(headers) = server.headers.take() {
+            secrets.headers = headers;
+        }
+        if secrets.env.is_empty() && secrets.headers.is_empty() {
+            delete_secret(&secret_key(&id)).await?;
+        } else {
+            let value = serde_json::to_string(&secrets).map_err(|e| e.to_string())?;
+            set_secret(
+                app.clone(),
+                &secret_key(&id),
+                &value,
+                EncryptionLevel::Device,
+                None,
+            )
+            .await?;
+        }
+    }
+
+    {
+        let now = chrono::Utc::now().timestamp_millis();
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_mcp_servers
+             (id, name, transport, command, args, url, enabled, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name,
+                                           transport = excluded.transport,
+                                           command = excluded.command,
+                                           args = excluded.args,
+                                           url = excluded.url,
+                                           enabled = excluded.enabled,
+                                           updated_at = excluded.updated_at",
+            &id,
+            &server.name,
+            server.transport.as_str(),
+            &server.command,
+            serde_json::to_string(&server.args).map_err(|e| e.to_string())?,
+            &server.url,
+            server.enabled as i64,
+            now,
+            now
+        )?;
+    }
+
+    // 配置变化后重新连接
+    disconnect(&id).await;
+    log::info!("MCP服务器已保存: {}", server.name);
+    with_status(&app, load_server(&id).await?).await
+}
+
+/**
+ * 删除 MCP 服务器及其工具的确认策略
+ *
+ * @param session_id 会话ID
+ * @param id 服务器ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn delete_mcp_server(session_id: String, id: String) -> Result<(), String> {
+    require_session(&session_id).await?;
+    disconnect(&id).await;
+    delete_secret(&secret_key(&id)).await?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let prefix = mcp_policy_prefix(&id);
+    crate::execute_with_params!(
+        db,
+        "DELETE FROM ai_tool_settings WHERE substr(name, 1, length(?)) = ?",
+        &prefix,
+        &prefix
+    )?;
+    crate::execute_with_params!(db, "DELETE FROM ai_mcp_servers WHERE id = ?", &id)
+}
+
+/**
+ * 连接 MCP 服务器并发现其工具、资源与提示词
+ *
+ * 已连接时重新建立连接
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @param id 服务器ID
+ * @return 发现结果
+ */
+#[tauri::command]
+pub async fn connect_mcp_server(
+    app: AppHandle,
+    session_id: String,
+    id: String,
+) -> Result<McpServerDiscovery, String> {
+    require_session(&session_id).await?;
+    let server = load_server(&id).await?;
+    disconnect(&id).await;
+    let client = get_client(&app, &server, true).await?;
+    Ok(McpServerDiscovery::from_client(&client))
+}
+
+/**
+ * 断开 MCP 服务器
+ *
+ * @param id 服务器ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn disconnect_mcp_server(id: String) -> Result<(), String> {
+    disconnect(&id).await;
+    Ok(())
+}
+
+/**
+ * 读取 MCP 资源
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @param server_id 服务器ID
+ * @param uri 资源地址
+ * @return 资源的文本内容
+ */
+#[tauri::command]
+pub async fn read_mcp_resource(
+    app: AppHandle,
+    session_id: String,
+    server_id: String,
+    uri: String,
+) -> Result<String, String> {
+    require_session(&session_id).await?;
+    let server = load_server(&server_id).await?;
+    get_client(&app, &server, true)
+        .await?
+        .read_resource(&uri)
+        .await
+}
+
+/**
+ * 获取 MCP 提示词
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @param server_id 服务器ID
+ * @param name 提示词名称
+ * @param arguments 参数
+ * @return 展开后的消息
+ */
+#[tauri::command]
+pub async fn get_mcp_prompt(
+    app: AppHandle,
+    session_id: String,
+    server_id: String,
+    name: String,
+    arguments: Option<Map<String, Value>>,
+) -> Result<Vec<McpPromptMessage>, String> {
+    require_session(&session_id).await?;
+    let server = load_server(&server_id).await?;
+    get_client(&app, &server, true)
+        .await?
+        .get_prompt(&name, &arguments.unwrap_or_default())
+        .await
+}
@@ -0,0 +1,476 @@
+/**
+ * MCP 传输层
+ *
+ * - stdio: 启动本地进程，通过标准输入输出逐行收发 JSON-RPC 消息
+ * - Streamable HTTP: 每条消息一次 POST，响应为 JSON 或 SSE 流，会话由
+ *   Mcp-Session-Id 头维持
+ */
+use crate::http::client::get_http_client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri_plugin_http::reqwest;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+
+/// 协议版本
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+/// 错误信息的最大长度（字符）
+const MAX_ERROR_LEN: usize = 500;
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// 构造 JSON-RPC 请求
+fn rpc_request(id: u64, method: &str, params: Option<Value>) -> Value {
+    let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+    if let Some(params) = params {
+        message["params"] = params;
+    }
+    message
+}
+
+/// 构造 JSON-RPC 通知
+fn rpc_notification(method: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method })
+}
+
+/// 取出 JSON-RPC 响应的结果
+fn rpc_result(message: &Value) -> Result<Value, String> {
+    if let Some(error) = message.get("error") {
+        return Err(format!(
+            "MCP服务器返回错误 ({}): {}",
+            error["code"].as_i64().unwrap_or_default(),
+            error["message"].as_str().unwrap_or("未知错误")
+        ));
+    }
+    Ok(message.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// 是否为对指定请求的响应
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message["id"].as_u64() == Some(id)
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+/// 截断过长的错误信息
+fn truncate_error(message: &str) -> String {
+    message.trim().chars().take(MAX_ERROR_LEN).collect()
+}
+
+/**
+ * 本地进程传输
+ */
+pub struct StdioTransport {
+    child: Mutex<Child>,
+    stdin: Arc<tokio::sync::Mutex<ChildStdin>>,
+    pending: PendingMap,
+    next_id: AtomicU64,
+}
+
+/// 写入一行消息
+async fn write_line(stdin: &tokio::sync::Mutex<ChildStdin>, message: &Value) -> Result<(), String> {
+    let mut line = message.to_string();
+    line.push('\n');
+    let mut stdin = stdin.lock().await;
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("写入MCP服务器失败: {}", e))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("写入MCP服务器失败: {}", e))
+}
+
+/**
+ * 处理服务器发起的请求
+ *
+ * 只响应 ping，其余请求（采样、根目录等）均返回不支持
+ */
+async fn reply_to_server(stdin: &tokio::sync::Mutex<ChildStdin>, message: &Value) {
+    let reply = if message["method"] == "ping" {
+        json!({ "jsonrpc": "2.0", "id": message["id"], "result": {} })
+    } else {
+        json!({
+            "jsonrpc": "2.0",
+            "id": message["id"],
+            "error": { "code": -32601, "message": "Method not found" },
+        })
+    };
+    if let Err(e) = write_line(stdin, &reply).await {
+        log::debug!("{}", e);
+    }
+}
+
+/// 请求结束或被取消（如超时）时移除等待中的响应
+struct PendingGuard<'a> {
+    pending: &'a PendingMap,
+    id: u64,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&self.id);
+        }
+    }
+}
+
+impl StdioTransport {
+    /**
+     * 启动 MCP 服务器进程
+     *
+     * @param name 服务器名称，用于日志
+     * @param command 可执行文件
+     * @param args 命令行参数
+     * @param env 额外的环境变量
+     * @return 传输实例
+     */
+    pub fn spawn(
+        name: &str,
+        command: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+    ) -> Result<Self, String> {
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        {
+            // CREATE_NO_WINDOW，避免弹出控制台窗口
+            cmd.creation_flags(0x0800_0000);
+        }
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("启动MCP服务器失败 ({}): {}", command, e))?;
+
+        let stdin = child.stdin.take().ok_or("无法获取MCP服务器的标准输入")?;
+        let stdout = child.stdout.take().ok_or("无法获取MCP服务器的标准输出")?;
+        let stderr = child.stderr.take();
+        let stdin = Arc::new(tokio::sync::Mutex::new(stdin));
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+
+        let reader_pending = pending.clone();
+        let reader_stdin = stdin.clone();
+        let server = name.to_string();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let Ok(message) = serde_json::from_str::<Value>(line.trim()) else {
+                    continue;
+                };
+                if message.get("method").is_some() {
+                    if message.get("id").is_some() {
+                        reply_to_server(&reader_stdin, &message).await;
+                    }
+                    continue;
+                }
+                let Some(id) = message["id"].as_u64() else {
+                    continue;
+                };
+                let sender = reader_pending
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.remove(&id));
+                if let Some(sender) = sender {
+                    let _ = sender.send(rpc_result(&message));
+                }
+            }
+            log::info!("MCP服务器已退出: {}", server);
+            if let Ok(mut pending) = reader_pending.lock() {
+                for (_, sender) in pending.drain() {
+                    let _ = sender.send(Err("MCP服务器已退出".to_string()));
+                }
+            }
+        });
+
+        if let Some(stderr) = stderr {
+            let server = name.to_string();
+            tauri::async_runtime::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log::debug!("MCP服务器 {}: {}", server, line);
+                }
+            });
+        }
+
+        Ok(StdioTransport {
+            child: Mutex::new(child),
+            stdin,
+            pending,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(id, sender);
+        let _guard = PendingGuard {
+            pending: &self.pending,
+            id,
+        };
+
+        write_line(&self.stdin, &rpc_request(id, method, params)).await?;
+        receiver
+            .await
+            .unwrap_or_else(|_| Err("MCP服务器已退出".to_string()))
+    }
+
+    async fn notify(&self, method: &str) -> Result<(), String> {
+        write_line(&self.stdin, &rpc_notification(method)).await
+    }
+
+    fn is_alive(&self) -> bool {
+        self.child
+            .lock()
+            .map(|mut child| matches!(child.try_wait(), Ok(None)))
+            .unwrap_or(false)
+    }
+
+    fn close(&self) {
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+/**
+ * Streamable HTTP 传输
+ */
+pub struct HttpTransport {
+    url: String,
+    headers: HashMap<String, String>,
+    session_id: Mutex<Option<String>>,
+    next_id: AtomicU64,
+}
+
+/**
+ * 从 SSE 流中读取指定请求的响应
+ *
+ * @param response HTTP响应
+ * @param id 请求ID
+ * @return JSON-RPC 响应
+ */
+async fn read_sse_response(mut response: reqwest::Response, id: u64) -> Result<Value, String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut data = String::new();
+    loop {
+        let bytes = response
+            .chunk()
+            .await
+            .map_err(|e| format!("读取MCP响应失败: {}", e))?;
+        let at_end = bytes.is_none();
+        match bytes {
+            Some(bytes) => buffer.extend_from_slice(&bytes),
+            None => buffer.extend_from_slice(b"\n\n"),
+        }
+
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if let Some(value) = line.strip_prefix("data:") {
+                if !data.is_empty() {
+                    data.push('\n');
+                }
+                data.push_str(value.trim_start());
+                continue;
+            }
+            // 空行表示一个事件结束
+            if line.is_empty() && !data.is_empty() {
+                if let Ok(message) = serde_json::from_str::<Value>(&data) {
+                    if is_response_to(&message, id) {
+                        return Ok(message);
+                    }
+                }
+                data.clear();
+            }
+        }
+        if at_end {
+            return Err("MCP服务器未返回响应".to_string());
+        }
+    }
+}
+
+impl HttpTransport {
+    /**
+     * 创建 HTTP 传输
+     *
+     * @param url MCP 端点地址
+     * @param headers 附加的请求头，如认证信息
+     * @return 传输实例
+     */
+    pub fn new(url: &str, headers: HashMap<String, String>) -> Self {
+        HttpTransport {
+            url: url.to_string(),
+            headers,
+            session_id: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.session_id.lock().ok().and_then(|id| id.clone())
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, String> {
+        let mut builder = get_http_client()
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .header("MCP-Protocol-Version", PROTOCOL_VERSION)
+            .json(message);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(session_id) = self.session_id() {
+            builder = builder.header("Mcp-Session-Id", session_id);
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| format!("MCP请求失败: {}", e))?;
+        if let Some(session_id) = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|value| value.to_str().ok())
+        {
+            if let Ok(mut current) = self.session_id.lock() {
+                *current = Some(session_id.to_string());
+            }
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "MCP服务器返回错误 ({}): {}",
+                status,
+                truncate_error(&body)
+            ));
+        }
+        Ok(response)
+    }
+
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self.post(&rpc_request(id, method, params)).await?;
+        let is_sse = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+
+        let message = if is_sse {
+            read_sse_response(response, id).await?
+        } else {
+            let body: Value = response
+                .json()
+                .await
+                .map_err(|e| format!("解析MCP响应失败: {}", e))?;
+            match body {
+                Value::Array(messages) => messages
+                    .into_iter()
+                    .find(|message| is_response_to(message, id))
+                    .ok_or("MCP服务器未返回响应")?,
+                message => message,
+            }
+        };
+        rpc_result(&message)
+    }
+
+    async fn notify(&self, method: &str) -> Result<(), String> {
+        self.post(&rpc_notification(method)).await.map(|_| ())
+    }
+
+    /// 结束会话，服务器不支持时忽略
+    fn close(&self) {
+        let Some(session_id) = self.session_id() else {
+            return;
+        };
+        let mut builder = get_http_client()
+            .delete(&self.url)
+            .header("Mcp-Session-Id", session_id);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        tauri::async_runtime::spawn(async move {
+            let _ = builder.send().await;
+        });
+    }
+}
+
+/**
+ * MCP 传输
+ */
+pub enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    /**
+     * 发送请求并等待响应
+     *
+     * @param method 方法名
+     * @param params 参数
+     * @param timeout 超时时间
+     * @return 响应结果
+     */
+    pub async fn request(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        let request = async {
+            match self {
+                Transport::Stdio(transport) => transport.request(method, params).await,
+                Transport::Http(transport) => transport.request(method, params).await,
+            }
+        };
+        tokio::time::timeout(timeout, request)
+            .await
+            .unwrap_or_else(|_| Err(format!("MCP请求超时: {}", method)))
+    }
+
+    /**
+     * 发送通知
+     *
+     * @param method 方法名
+     * @return 操作结果
+     */
+    pub async fn notify(&self, method: &str) -> Result<(), String> {
+        match self {
+            Transport::Stdio(transport) => transport.notify(method).await,
+            Transport::Http(transport) => transport.notify(method).await,
+        }
+    }
+
+    /// 连接是否仍然可用
+    pub fn is_alive(&self) -> bool {
+        match self {
+            Transport::Stdio(transport) => transport.is_alive(),
+            Transport::Http(_) => true,
+        }
+    }
+
+    /// 关闭连接
+    pub fn close(&self) {
+        match self {
+            Transport::Stdio(transport) => transport.close(),
+            Transport::Http(transport) => transport.close(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+/**
+ * AI模块
+ *
+ * 该模块在 Rust 端统一发起 AI 请求：
+ * - types: 请求、消息与流式事件类型
+ * - config: 提供商配置与加密保存的 API 密钥
+ * - tokens: 按提供商估算 token 数
+ * - context: 上下文长度管理，裁剪或摘要较早的消息
+ * - provider: 提供商接口
+ * - providers: OpenAI 兼容、Anthropic、Ollama、通义千问的实现
+ * - gateway: 流式对话、取消与连接测试
+ * - history: 对话消息的保存、分页、分支与全文检索
+ * - usage: 用量、费用统计与月度预算提醒
+ * - tools: 模型可调用的工具、确认策略与调用记录
+ * - mcp: MCP 客户端，连接外部服务器并将其工具提供给对话
+ * - knowledge: 知识库索引与检索，对话时附带引用
+ * - prompts: 带类型变量的提示词模板、渲染与模板包
+ * - attachments: 对话中的图片与文档附件
+ */
+mod attachments;
+mod config;
+mod context;
+mod gateway;
+mod history;
+mod knowledge;
+mod mcp;
+mod prompts;
+mod provider;
+mod providers;
+mod tokens;
+mod tools;
+mod types;
+mod usage;
+
+// 导出公开API
+pub use attachments::*;
+pub use config::*;
+pub use context::*;
+pub use gateway::*;
+pub use history::*;
+pub use knowledge::*;
+pub use mcp::*;
+pub use prompts::*;
+pub use tools::*;
+pub use usage::*;
@@ -0,0 +1,13 @@
+/**
+ * 提示词模板
+ *
+ * - template: 变量占位符语法、变量类型校验与渲染
+ * - store: 模板管理、收藏与使用次数
+ * - pack: JSON / YAML 模板包的导入与导出
+ */
+mod pack;
+mod store;
+mod template;
+
+pub use pack::*;
+pub use store::*;
@@ -0,0 +1,248 @@
+/**
+ * 提示词模板包
+ *
+ * 模板包是包含若干模板的 JSON 或 YAML 文件，按扩展名区分格式（.yaml / .yml 为
+ * YAML，其余为 JSON）。导出时不包含模板ID、收藏与使用次数。文件变量的默认路径
+ * 在导出和导入时都会去除，避免导入的模板在渲染时读取本机的任意文件
+ */
+use super::store::{load_prompt_templates, prepare_template, PromptTemplateInput};
+use super::template::VariableKind;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 当前的模板包格式版本
+const PACK_VERSION: u32 = 1;
+/// 导入文件的最大字节数
+const MAX_PACK_BYTES: u64 = 5 * 1024 * 1024;
+
+/**
+ * 模板包
+ */
+#[derive(Debug, Serialize, Deserialize)]
+struct PromptPack {
+    /// 格式版本
+    #[serde(default = "default_version")]
+    version: u32,
+    /// 导出时间（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exported_at: Option<i64>,
+    /// 模板
+    templates: Vec<PromptTemplateInput>,
+}
+
+fn default_version() -> u32 {
+    PACK_VERSION
+}
+
+/**
+ * 导入结果
+ */
+#[derive(Debug, Serialize)]
+pub struct PromptImportSummary {
+    /// 导入的模板数
+    pub imported: usize,
+    /// 与已有模板标题和内容相同而跳过的模板数
+    pub skipped: usize,
+    /// 校验失败的模板及原因
+    pub errors: Vec<String>,
+}
+
+/// 是否为 YAML 文件
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+}
+
+/// 去除文件变量的默认路径
+fn strip_file_defaults(template: &mut PromptTemplateInput) {
+    for variable in &mut template.variables {
+        if variable.kind == VariableKind::File {
+            variable.default = None;
+        }
+    }
+}
+
+/**
+ * 导出提示词模板包
+ *
+ * @param file_path 导出文件路径，扩展名为 .yaml 或 .yml 时导出为 YAML
+ * @param ids 导出的模板ID，为空时导出全部
+ * @return 导出的模板数
+ */
+#[tauri::command]
+pub async fn export_prompt_templates(
+    file_path: String,
+    ids: Option<Vec<i64>>,
+) -> Result<usize, String> {
+    let templates: Vec<PromptTemplateInput> = load_prompt_templates()
+        .await?
+        .into_iter()
+        .filter(|template| ids.as_ref().is_none_or(|ids| ids.contains(&template.id)))
+        .map(|template| {
+            let mut input = PromptTemplateInput {
+                id: None,
+                title: template.title,
+                description: template.description,
+                content: template.content,
+                category: template.category,
+                tags: template.tags,
+                variables: template.variables,
+            };
+            strip_file_defaults(&mut input);
+            input
+        })
+        .collect();
+    let count = templates.len();
+    let pack = PromptPack {
+        version: PACK_VERSION,
+        exported_at: Some(chrono::Utc::now().timestamp_millis()),
+        templates,
+    };
+
+    let path = Path::new(&file_path);
+    let content = if is_yaml(path) {
+        serde_yaml::to_string(&pack).map_err(|e| format!("序列化模板包失败: {}", e))?
+    } else {
+        serde_json::to_string_pretty(&pack).map_err(|e| format!("序列化模板包失败: {}", e))?
+    };
+    tokio::fs::write(path, content)
+        .await
+        .map_err(|e| format!("写入模板包失败: {}", e))?;
+
+    log::info!("已导出 {} 个提示词模板: {}", count, file_path);
+    Ok(count)
+}
+
+/**
+ * 导入提示词模板包
+ *
+ * 与已有模板标题和内容都相同的模板跳过，校验失败的模板记录原因后跳过，
+ * 其余模板在同一事务中写入
+ *
+ * @param file_path 模板包路径，扩展名为 .yaml 或 .yml 时按 YAML 解析
+ * @return 导入结果
+ */
+#[tauri::command]
+pub async fn import_prompt_templates(file_path: String) -> Result<PromptImportSummary, String> {
+    let path = Path::new(&file_path);
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("读取模板包失败: {}", e))?;
+    if metadata.len() > MAX_PACK_BYTES {
+        return Err(format!("模板包超过 {} MB", MAX_PACK_BYTES / 1024 / 1024));
+    }
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("读取模板包失败: {}", e))?;
+    let pack: PromptPack = if is_yaml(path) {
+        serde_yaml::from_str(&content).map_err(|e| format!("解析模板包失败: {}", e))?
+    } else {
+        serde_json::from_str(&content).map_err(|e| format!("解析模板包失败: {}", e))?
+    };
+    if pack.version > PACK_VERSION {
+        return Err(format!("不支持的模板包版本: {}", pack.version));
+    }
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut existing: HashSet<(String, String)> =
+        sqlx::query("SELECT title, content FROM ai_prompt_templates")
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询提示词模板失败: {}", e))?
+            .iter()
+            .map(|row| (row.get("title"), row.get("content")))
+            .collect();
+
+    let mut summary = PromptImportSummary {
+        imported: 0,
+        skipped: 0,
+        errors: Vec::new(),
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    for mut template in pack.templates {
+        strip_file_defaults(&mut template);
+        let title = template.title.clone();
+        let (template, tags_json, variables_json) = match prepare_template(template) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", title, e));
+                continue;
+            }
+        };
+        if !existing.insert((template.title.clone(), template.content.clone())) {
+            summary.skipped += 1;
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO ai_prompt_templates
+             (title, description, content, category, tags, variables, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&template.title)
+        .bind(&template.description)
+        .bind(&template.content)
+        .bind(&template.category)
+        .bind(&tags_json)
+        .bind(&variables_json)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("导入提示词模板失败: {}", e))?;
+        summary.imported += 1;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+
+    log::info!(
+        "已导入 {} 个提示词模板，跳过 {} 个，失败 {} 个",
+        summary.imported,
+        summary.skipped,
+        summary.errors.len()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imported_packs_lose_file_defaults() {
+        let pack: PromptPack = serde_yaml::from_str(
+            "templates:
+  - title: 读取
+    content: '{{path}} {{count}}'
+    variables:
+      - name: path
+        kind: file
+        default: /etc/passwd
+      - name: count
+        kind: number
+        default: 3
+",
+        )
+        .unwrap();
+        let mut template = pack.templates.into_iter().next().unwrap();
+        strip_file_defaults(&mut template);
+        assert_eq!(template.variables[0].default, None);
+        assert_eq!(template.variables[1].default.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn detects_yaml_extension() {
+        assert!(is_yaml(Path::new("pack.YML")));
+        assert!(is_yaml(Path::new("dir/pack.yaml")));
+        assert!(!is_yaml(Path::new("pack.json")));
+    }
+}
@@ -0,0 +1,322 @@
+/**
+ * 提示词模板管理
+ *
+ * 模板保存在 ai_prompt_templates 表中，变量定义以 JSON 保存。每次成功渲染都会
+ * 累计使用次数，列表中收藏的模板在前，其余按使用次数与最近使用时间排列
+ */
+use super::template::{normalize_variables, render, PromptVariable, RenderedPrompt};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/**
+ * 提示词模板
+ */
+#[derive(Debug, Serialize)]
+pub struct PromptTemplate {
+    /// 模板ID
+    pub id: i64,
+    /// 标题
+    pub title: String,
+    /// 说明
+    pub description: Option<String>,
+    /// 模板内容
+    pub content: String,
+    /// 分类
+    pub category: Option<String>,
+    /// 标签
+    pub tags: Vec<String>,
+    /// 变量定义，按模板中首次出现的顺序
+    pub variables: Vec<PromptVariable>,
+    /// 是否收藏
+    pub is_favorite: bool,
+    /// 使用次数
+    pub use_count: i64,
+    /// 最近使用时间（毫秒）
+    pub last_used_at: Option<i64>,
+    /// 创建时间（毫秒）
+    pub created_at: i64,
+    /// 更新时间（毫秒）
+    pub updated_at: i64,
+}
+
+/**
+ * 保存模板的参数
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptTemplateInput {
+    /// 模板ID，为空时新建
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// 标题
+    pub title: String,
+    /// 说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 模板内容
+    pub content: String,
+    /// 分类
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 标签
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 变量定义，未声明的变量按必填文本处理
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<PromptVariable>,
+}
+
+/// 模板列表的排序
+const TEMPLATE_ORDER: &str =
+    "ORDER BY is_favorite DESC, use_count DESC, last_used_at DESC, updated_at DESC";
+
+/**
+ * 解析标签
+ *
+ * 标签以 JSON 数组保存，兼容早期以逗号分隔的写法
+ */
+fn parse_tags(value: Option<String>) -> Vec<String> {
+    let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&value).unwrap_or_else(|_| {
+        value
+            .split([',', '，'])
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    })
+}
+
+fn template_from_row(row: &SqliteRow) -> PromptTemplate {
+    PromptTemplate {
+        id: row.get("id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        content: row.get("content"),
+        category: row.get("category"),
+        tags: parse_tags(row.get("tags")),
+        variables: serde_json::from_str(&row.get::<String, _>("variables")).unwrap_or_default(),
+        is_favorite: row.get::<i64, _>("is_favorite") != 0,
+        use_count: row.get("use_count"),
+        last_used_at: row.get("last_used_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+/**
+ * 读取模板
+ *
+ * @param id 模板ID
+ * @return 模板
+ */
+pub async fn load_prompt_template(id: i64) -> Result<PromptTemplate, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::query_one_with_params!(db, "SELECT * FROM ai_prompt_templates WHERE id = ?", id)?
+        .map(|row| template_from_row(&row))
+        .ok_or_else(|| format!("提示词模板不存在: {}", id))
+}
+
+/**
+ * 读取全部模板
+ *
+ * @return 按收藏、使用次数排列的模板
+ */
+pub async fn load_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let sql = format!("SELECT * FROM ai_prompt_templates {}", TEMPLATE_ORDER);
+    let rows = sqlx::query(&sql)
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询提示词模板失败: {}", e))?;
+    Ok(rows.iter().map(template_from_row).collect())
+}
+
+/**
+ * 校验模板并整理为待保存的列值
+ *
+ * @param input 模板参数
+ * @return 规范化后的参数、标签 JSON 与变量定义 JSON
+ */
+pub fn prepare_template(
+    mut input: PromptTemplateInput,
+) -> Result<(PromptTemplateInput, String, String), String> {
+    input.title = input.title.trim().to_string();
+    if input.title.is_empty() {
+        return Err("请输入模板标题".to_string());
+    }
+    if input.content.trim().is_empty() {
+        return Err("请输入模板内容".to_string());
+    }
+    input.description = input
+        .description
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    input.category = input
+        .category
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let mut tags: Vec<String> = Vec::with_capacity(input.tags.len());
+    for tag in input.tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    input.tags = tags;
+    input.variables = normalize_variables(&input.content, std::mem::take(&mut input.variables))?;
+
+    let tags_json = serde_json::to_string(&input.tags).map_err(|e| e.to_string())?;
+    let variables_json = serde_json::to_string(&input.variables).map_err(|e| e.to_string())?;
+    Ok((input, tags_json, variables_json))
+}
+
+/**
+ * 获取全部提示词模板
+ *
+ * @param category 分类，为空时返回全部
+ * @return 收藏的模板在前，其余按使用次数与最近使用时间排列
+ */
+#[tauri::command]
+pub async fn list_prompt_templates(
+    category: Option<String>,
+) -> Result<Vec<PromptTemplate>, String> {
+    let templates = load_prompt_templates().await?;
+    Ok(match category {
+        Some(category) => templates
+            .into_iter()
+            .filter(|template| template.category.as_deref() == Some(category.as_str()))
+            .collect(),
+        None => templates,
+    })
+}
+
+/**
+ * 保存提示词模板
+ *
+ * 模板中出现但未声明的变量按必填文本补全，变量定义不合法时返回错误
+ *
+ * @param template 模板参数
+ * @return 保存后的模板
+ */
+#[tauri::command]
+pub async fn save_prompt_template(template: PromptTemplateInput) -> Result<PromptTemplate, String> {
+    let (template, tags_json, variables_json) = prepare_template(template)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let id = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        match template.id {
+            Some(id) => {
+                let result = sqlx::query(
+                    "UPDATE ai_prompt_templates
+                     SET title = ?, description = ?, content = ?, category = ?, tags = ?,
+                         variables = ?, updated_at = ?
+                     WHERE id = ?",
+                )
+                .bind(&template.title)
+                .bind(&template.description)
+                .bind(&template.content)
+                .bind(&template.category)
+                .bind(&tags_json)
+                .bind(&variables_json)
+                .bind(now)
+                .bind(id)
+                .execute(db.get_pool())
+                .await
+                .map_err(|e| format!("保存提示词模板失败: {}", e))?;
+                if result.rows_affected() == 0 {
+                    return Err(format!("提示词模板不存在: {}", id));
+                }
+                id
+            }
+            None => sqlx::query(
+                "INSERT INTO ai_prompt_templates
+                 (title, description, content, category, tags, variables, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&template.title)
+            .bind(&template.description)
+            .bind(&template.content)
+            .bind(&template.category)
+            .bind(&tags_json)
+            .bind(&variables_json)
+            .bind(now)
+            .bind(now)
+            .execute(db.get_pool())
+            .await
+            .map_err(|e| format!("保存提示词模板失败: {}", e))?
+            .last_insert_rowid(),
+        }
+    };
+    load_prompt_template(id).await
+}
+
+/**
+ * 删除提示词模板
+ *
+ * @param id 模板ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn delete_prompt_template(id: i64) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(db, "DELETE FROM ai_prompt_templates WHERE id = ?", id)
+}
+
+/**
+ * 收藏或取消收藏提示词模板
+ *
+ * @param id 模板ID
+ * @param favorite 是否收藏
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_prompt_template_favorite(id: i64, favorite: bool) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "UPDATE ai_prompt_templates SET is_favorite = ? WHERE id = ?",
+        favorite as i64,
+        id
+    )
+}
+
+/**
+ * 渲染提示词模板
+ *
+ * 校验全部变量，存在错误时返回各变量的错误信息而不渲染；渲染成功时累计
+ * 模板的使用次数
+ *
+ * @param app Tauri应用句柄
+ * @param id 模板ID
+ * @param values 变量的值，键为变量名；文件变量传文件路径
+ * @return 渲染结果
+ */
+#[tauri::command]
+pub async fn render_prompt_template(
+    app: AppHandle,
+    id: i64,
+    values: HashMap<String, String>,
+) -> Result<RenderedPrompt, String> {
+    let template = load_prompt_template(id).await?;
+    let rendered = render(&app, &template.content, &template.variables, &values).await;
+    if rendered.content.is_some() {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "UPDATE ai_prompt_templates SET use_count = use_count + 1, last_used_at = ?
+             WHERE id = ?",
+            chrono::Utc::now().timestamp_millis(),
+            id
+        )?;
+    }
+    Ok(rendered)
+}
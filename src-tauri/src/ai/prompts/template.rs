@@ -0,0 +1,497 @@
+/**
+ * 提示词模板语法与渲染
+ *
+ * 模板中的 `{{name}}` 为变量占位符，名称两侧的空白会被忽略，名称只能包含字母、
+ * 数字、中文、下划线与连字符；`\{{` 输出字面量 `{{`。变量的类型、默认值与可选值
+ * 在变量定义中声明，模板中出现但未声明的变量按必填文本处理
+ */
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// 变量名的最大长度（字符）
+const MAX_NAME_CHARS: usize = 64;
+/// 文件变量读取的最大字节数
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+/**
+ * 变量类型
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableKind {
+    /// 单行文本
+    #[default]
+    Text,
+    /// 多行文本
+    Multiline,
+    /// 数字
+    Number,
+    /// 是或否，渲染为 true / false
+    Boolean,
+    /// 从可选值中选择
+    Enum,
+    /// 文件路径，渲染为文件的文本内容
+    File,
+    /// 未填写时读取剪贴板中的文本
+    Clipboard,
+}
+
+/**
+ * 变量定义
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptVariable {
+    /// 变量名，与模板中的占位符一致
+    pub name: String,
+    /// 显示名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 类型
+    #[serde(default)]
+    pub kind: VariableKind,
+    /// 默认值，导入时也接受数字与布尔值
+    #[serde(
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default: Option<String>,
+    /// 是否必填，默认是
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// 可选值，仅枚举类型
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// 将字符串、数字或布尔值读取为字符串
+fn scalar_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(Value::Number(value)) => Ok(Some(value.to_string())),
+        Some(Value::Bool(value)) => Ok(Some(value.to_string())),
+        Some(_) => Err(serde::de::Error::custom("默认值必须是字符串、数字或布尔值")),
+    }
+}
+
+impl PromptVariable {
+    /// 声明为必填文本的变量
+    fn text(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            label: None,
+            description: None,
+            kind: VariableKind::Text,
+            default: None,
+            required: true,
+            options: Vec::new(),
+        }
+    }
+
+    /// 错误提示中使用的名称
+    fn display_name(&self) -> &str {
+        self.label
+            .as_deref()
+            .filter(|label| !label.trim().is_empty())
+            .unwrap_or(&self.name)
+    }
+}
+
+/**
+ * 变量校验错误
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct PromptVariableError {
+    /// 变量名
+    pub name: String,
+    /// 错误信息
+    pub message: String,
+}
+
+/**
+ * 渲染结果
+ */
+#[derive(Debug, Serialize)]
+pub struct RenderedPrompt {
+    /// 渲染后的内容，存在校验错误时为空
+    pub content: Option<String>,
+    /// 校验错误，按变量定义的顺序排列
+    pub errors: Vec<PromptVariableError>,
+}
+
+/// 模板片段
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/**
+ * 将模板切分为文本与变量片段
+ *
+ * 名称不合法或未闭合的占位符按原样输出
+ */
+fn parse_segments(content: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            segments.push(Segment::Text(&rest[..start - 1]));
+            segments.push(Segment::Text("{{"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + length].trim();
+        let end = start + 2 + length + 2;
+        if is_valid_name(name) {
+            segments.push(Segment::Text(&rest[..start]));
+            segments.push(Segment::Variable(name));
+        } else {
+            segments.push(Segment::Text(&rest[..end]));
+        }
+        rest = &rest[end..];
+    }
+    segments.push(Segment::Text(rest));
+    segments
+}
+
+/**
+ * 模板中出现的变量名
+ *
+ * @param content 模板内容
+ * @return 去重后的变量名，按首次出现的顺序
+ */
+pub fn placeholder_names(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    parse_segments(content)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Variable(name) if seen.insert(name) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/**
+ * 校验并规范化单个值
+ *
+ * 数字去除两侧空白，布尔值统一为 true / false
+ */
+fn check_value(variable: &PromptVariable, value: &str) -> Result<String, String> {
+    match variable.kind {
+        VariableKind::Number => {
+            let value = value.trim();
+            match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => Ok(value.to_string()),
+                _ => Err(format!("{}必须是数字", variable.display_name())),
+            }
+        }
+        VariableKind::Boolean => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" | "是" => Ok("true".to_string()),
+            "false" | "no" | "0" | "否" => Ok("false".to_string()),
+            _ => Err(format!("{}必须是 true 或 false", variable.display_name())),
+        },
+        VariableKind::Enum if !variable.options.iter().any(|option| option == value) => {
+            Err(format!(
+                "{}必须是以下值之一: {}",
+                variable.display_name(),
+                variable.options.join("、")
+            ))
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+/**
+ * 校验变量定义，并补全模板中未声明的变量
+ *
+ * @param content 模板内容
+ * @param variables 声明的变量
+ * @return 按模板中首次出现的顺序排列的变量定义
+ */
+pub fn normalize_variables(
+    content: &str,
+    variables: Vec<PromptVariable>,
+) -> Result<Vec<PromptVariable>, String> {
+    let names = placeholder_names(content);
+    let mut declared: HashMap<String, PromptVariable> = HashMap::new();
+    for mut variable in variables {
+        variable.name = variable.name.trim().to_string();
+        if !is_valid_name(&variable.name) {
+            return Err(format!("变量名不合法: {}", variable.name));
+        }
+        if !names.contains(&variable.name) {
+            return Err(format!("变量未在模板中使用: {}", variable.name));
+        }
+        if variable.kind == VariableKind::Enum {
+            let mut seen = HashSet::new();
+            variable
+                .options
+                .retain(|option| !option.trim().is_empty() && seen.insert(option.clone()));
+            if variable.options.is_empty() {
+                return Err(format!("枚举变量缺少可选值: {}", variable.name));
+            }
+        } else {
+            variable.options.clear();
+        }
+        variable.default = variable.default.filter(|value| !value.trim().is_empty());
+        if let Some(default) = &variable.default {
+            if !matches!(variable.kind, VariableKind::File) {
+                variable.default = Some(check_value(&variable, default)?);
+            }
+        }
+        let name = variable.name.clone();
+        if declared.insert(name.clone(), variable).is_some() {
+            return Err(format!("变量重复定义: {}", name));
+        }
+    }
+    Ok(names
+        .iter()
+        .map(|name| {
+            declared
+                .remove(name)
+                .unwrap_or_else(|| PromptVariable::text(name))
+        })
+        .collect())
+}
+
+/// 读取文件变量的文本内容
+async fn read_file_value(variable: &PromptVariable, path: &str) -> Result<String, String> {
+    let path = Path::new(path.trim());
+    let metadata = tokio::fs::metadata(path).await.map_err(|e| {
+        format!(
+            "{}: 无法读取文件 {} ({})",
+            variable.display_name(),
+            path.display(),
+            e
+        )
+    })?;
+    if !metadata.is_file() {
+        return Err(format!(
+            "{}: 不是文件 {}",
+            variable.display_name(),
+            path.display()
+        ));
+    }
+    if metadata.len() > MAX_FILE_BYTES {
+        return Err(format!(
+            "{}: 文件超过 {} KB",
+            variable.display_name(),
+            MAX_FILE_BYTES / 1024
+        ));
+    }
+    let data = tokio::fs::read(path).await.map_err(|e| {
+        format!(
+            "{}: 无法读取文件 {} ({})",
+            variable.display_name(),
+            path.display(),
+            e
+        )
+    })?;
+    let text = String::from_utf8(data)
+        .map_err(|_| format!("{}: 文件不是 UTF-8 文本", variable.display_name()))?;
+    Ok(text.trim_start_matches('\u{feff}').replace("\r\n", "\n"))
+}
+
+/**
+ * 确定变量的值
+ *
+ * 依次使用填写的值、剪贴板（仅剪贴板类型）与默认值，均为空时必填变量报错，
+ * 可选变量渲染为空
+ */
+async fn resolve_value(
+    app: &AppHandle,
+    variable: &PromptVariable,
+    provided: Option<&String>,
+) -> Result<String, String> {
+    let provided = provided.filter(|value| !value.trim().is_empty()).cloned();
+    let value = match (provided, variable.kind) {
+        (Some(value), _) => Some(value),
+        (None, VariableKind::Clipboard) => app
+            .clipboard()
+            .read_text()
+            .ok()
+            .filter(|text| !text.trim().is_empty()),
+        (None, _) => None,
+    }
+    .or_else(|| variable.default.clone());
+
+    match value {
+        Some(path) if variable.kind == VariableKind::File => read_file_value(variable, &path).await,
+        Some(value) => check_value(variable, &value),
+        None if variable.required => Err(match variable.kind {
+            VariableKind::Clipboard => {
+                format!("{}为空，剪贴板中也没有文本", variable.display_name())
+            }
+            VariableKind::File => format!("请选择{}", variable.display_name()),
+            _ => format!("请填写{}", variable.display_name()),
+        }),
+        None => Ok(String::new()),
+    }
+}
+
+/**
+ * 渲染模板
+ *
+ * 所有变量都会校验，错误一并返回
+ *
+ * @param app Tauri应用句柄
+ * @param content 模板内容
+ * @param variables 规范化后的变量定义
+ * @param values 填写的值，键为变量名
+ * @return 渲染结果
+ */
+pub async fn render(
+    app: &AppHandle,
+    content: &str,
+    variables: &[PromptVariable],
+    values: &HashMap<String, String>,
+) -> RenderedPrompt {
+    let mut resolved: HashMap<String, String> = HashMap::new();
+    let mut errors = Vec::new();
+    for name in placeholder_names(content) {
+        let variable = variables
+            .iter()
+            .find(|variable| variable.name == name)
+            .cloned()
+            .unwrap_or_else(|| PromptVariable::text(&name));
+        match resolve_value(app, &variable, values.get(&name)).await {
+            Ok(value) => {
+                resolved.insert(name, value);
+            }
+            Err(message) => errors.push(PromptVariableError { name, message }),
+        }
+    }
+    if !errors.is_empty() {
+        return RenderedPrompt {
+            content: None,
+            errors,
+        };
+    }
+
+    let mut output = String::with_capacity(content.len());
+    for segment in parse_segments(content) {
+        match segment {
+            Segment::Text(text) => output.push_str(text),
+            Segment::Variable(name) => {
+                output.push_str(resolved.get(name).map(String::as_str).unwrap_or_default());
+            }
+        }
+    }
+    RenderedPrompt {
+        content: Some(output),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(kind: VariableKind) -> PromptVariable {
+        PromptVariable {
+            kind,
+            ..PromptVariable::text("value")
+        }
+    }
+
+    /// 将片段还原为便于比较的形式，变量写作 `<name>`
+    fn flatten(content: &str) -> String {
+        parse_segments(content)
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.to_string(),
+                Segment::Variable(name) => format!("<{}>", name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_placeholders() {
+        assert_eq!(
+            flatten("你好 {{ 名字 }}，{{lang-code}}"),
+            "你好 <名字>，<lang-code>"
+        );
+        assert_eq!(flatten(r"字面量 \{{name}}"), "字面量 {{name}}");
+        assert_eq!(flatten("{{bad name}} {{ok}}"), "{{bad name}} <ok>");
+        assert_eq!(flatten("未闭合 {{name"), "未闭合 {{name");
+    }
+
+    #[test]
+    fn placeholder_names_are_unique_in_order() {
+        assert_eq!(
+            placeholder_names("{{b}} {{a}} {{b}} {{c}}"),
+            vec!["b", "a", "c"]
+        );
+    }
+
+    #[test]
+    fn checks_values_by_kind() {
+        let number = variable(VariableKind::Number);
+        assert_eq!(check_value(&number, " 3.5 ").unwrap(), "3.5");
+        assert!(check_value(&number, "abc").is_err());
+        assert!(check_value(&number, "inf").is_err());
+
+        let boolean = variable(VariableKind::Boolean);
+        assert_eq!(check_value(&boolean, "是").unwrap(), "true");
+        assert_eq!(check_value(&boolean, "NO").unwrap(), "false");
+        assert!(check_value(&boolean, "maybe").is_err());
+
+        let choice = PromptVariable {
+            options: vec!["中文".to_string(), "English".to_string()],
+            ..variable(VariableKind::Enum)
+        };
+        assert_eq!(check_value(&choice, "English").unwrap(), "English");
+        assert!(check_value(&choice, "Deutsch").is_err());
+
+        assert_eq!(
+            check_value(&variable(VariableKind::Text), " a ").unwrap(),
+            " a "
+        );
+    }
+
+    #[test]
+    fn normalizes_variables() {
+        let choice = PromptVariable {
+            options: ["a", "b", "", "a", "b"].map(String::from).to_vec(),
+            ..variable(VariableKind::Enum)
+        };
+        let variables = normalize_variables("{{other}} {{value}}", vec![choice]).unwrap();
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].name, "other");
+        assert_eq!(variables[0].kind, VariableKind::Text);
+        assert_eq!(variables[1].options, vec!["a", "b"]);
+
+        assert!(normalize_variables("{{x}}", vec![variable(VariableKind::Text)]).is_err());
+        let invalid_default = PromptVariable {
+            default: Some("abc".to_string()),
+            ..variable(VariableKind::Number)
+        };
+        assert!(normalize_variables("{{value}}", vec![invalid_default]).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+/**
+ * AI 提供商接口
+ *
+ * 各提供商只负责请求的构造和响应的解析，连接、流式读取与取消由网关统一处理
+ */
+use super::providers::{AnthropicProvider, OllamaProvider, OpenAiProvider, QwenProvider};
+use super::types::{ChatRequest, ChatUsage, ProviderKind, ToolDefinition};
+use serde_json::Value;
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+/// 默认采样温度
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+/// 默认最大生成 token 数
+pub const DEFAULT_MAX_TOKENS: u32 = 2000;
+
+/**
+ * 流式响应的分帧方式
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamFormat {
+    /// Server-Sent Events，每个事件的 data 行为一段 JSON
+    Sse,
+    /// 每行一个 JSON 对象
+    JsonLines,
+}
+
+/**
+ * 响应中的一段工具调用
+ *
+ * 流式响应中参数分多帧返回，按 index 合并
+ */
+#[derive(Debug, Default)]
+pub struct ToolCallDelta {
+    /// 调用在本次回复中的序号，为空表示这是一个完整的调用
+    pub index: Option<usize>,
+    /// 调用ID
+    pub id: Option<String>,
+    /// 工具名称
+    pub name: Option<String>,
+    /// 参数 JSON 的片段
+    pub arguments: String,
+}
+
+/**
+ * 从响应中解析出的一段内容
+ */
+#[derive(Debug, Default)]
+pub struct ResponseChunk {
+    /// 新生成的文本
+    pub text: Option<String>,
+    /// 工具调用
+    pub tool_calls: Vec<ToolCallDelta>,
+    /// token 用量
+    pub usage: Option<ChatUsage>,
+    /// 是否已结束
+    pub done: bool,
+}
+
+/**
+ * AI 提供商
+ */
+pub trait AiProvider: Send + Sync {
+    /**
+     * 对话接口地址
+     *
+     * @param base_url 配置的接口地址，不以 / 结尾
+     * @param request 对话请求，部分提供商的多模态请求使用单独的接口
+     * @return 完整地址
+     */
+    fn endpoint(&self, base_url: &str, request: &ChatRequest) -> String;
+
+    /**
+     * 添加认证及提供商专有的请求头
+     *
+     * @param builder 请求构建器
+     * @param api_key API 密钥
+     * @param stream 是否流式请求
+     * @return 请求构建器
+     */
+    fn apply_headers(
+        &self,
+        builder: RequestBuilder,
+        api_key: Option<&str>,
+        stream: bool,
+    ) -> RequestBuilder;
+
+    /**
+     * 构造请求体
+     *
+     * @param request 对话请求
+     * @param tools 可供调用的工具
+     * @param stream 是否流式请求
+     * @return JSON 请求体
+     */
+    fn build_body(&self, request: &ChatRequest, tools: &[ToolDefinition], stream: bool) -> Value;
+
+    /**
+     * 列出模型的接口地址，用于测试连接
+     *
+     * @param base_url 配置的接口地址，不以 / 结尾
+     * @return 完整地址，提供商没有该接口时为空
+     */
+    fn models_endpoint(&self, _base_url: &str) -> Option<String> {
+        None
+    }
+
+    /// 流式响应的分帧方式
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Sse
+    }
+
+    /**
+     * 解析流式响应中的一帧
+     *
+     * @param data SSE 的 data 内容或一行 JSON
+     * @return 解析结果，响应中包含错误信息时返回错误
+     */
+    fn parse_stream_chunk(&self, data: &str) -> Result<ResponseChunk, String>;
+
+    /**
+     * 解析非流式响应
+     *
+     * @param body 响应 JSON
+     * @return 完整回复
+     */
+    fn parse_response(&self, body: &Value) -> Result<ResponseChunk, String>;
+
+    /**
+     * 向量化接口地址
+     *
+     * @param base_url 配置的接口地址，不以 / 结尾
+     * @return 完整地址，提供商不支持向量化时为空
+     */
+    fn embeddings_endpoint(&self, _base_url: &str) -> Option<String> {
+        None
+    }
+
+    /**
+     * 构造向量化请求体
+     *
+     * @param model 向量模型
+     * @param inputs 待向量化的文本
+     * @return JSON 请求体
+     */
+    fn build_embeddings_body(&self, model: &str, inputs: &[String]) -> Value {
+        serde_json::json!({ "model": model, "input": inputs })
+    }
+
+    /**
+     * 解析向量化响应
+     *
+     * @param body 响应 JSON
+     * @return 与输入顺序一致的向量
+     */
+    fn parse_embeddings(&self, _body: &Value) -> Result<Vec<Vec<f32>>, String> {
+        Err("该提供商不支持向量化".to_string())
+    }
+}
+
+/**
+ * 将 JSON 数组转为向量
+ *
+ * @param value JSON 数组
+ * @return 向量，格式不正确时为空
+ */
+pub fn parse_vector(value: &Value) -> Option<Vec<f32>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|x| x.as_f64().map(|x| x as f32))
+        .collect()
+}
+
+/**
+ * 获取提供商实现
+ *
+ * @param kind 提供商
+ * @return 提供商实现
+ */
+pub fn get_provider(kind: ProviderKind) -> Box<dyn AiProvider> {
+    match kind {
+        ProviderKind::OpenAi | ProviderKind::DeepSeek => Box::new(OpenAiProvider),
+        ProviderKind::Claude => Box::new(AnthropicProvider),
+        ProviderKind::Ollama => Box::new(OllamaProvider),
+        ProviderKind::Qianwen => Box::new(QwenProvider),
+    }
+}
+
+/**
+ * 从错误响应中提取错误信息
+ *
+ * 兼容 {"error": {"message": ...}}、{"error": "..."} 与 {"message": ...} 几种格式
+ *
+ * @param body 响应 JSON
+ * @return 错误信息
+ */
+pub fn extract_error(body: &Value) -> Option<String> {
+    let error = body.get("error");
+    error
+        .and_then(|e| e.get("message"))
+        .or(error.filter(|e| e.is_string()))
+        .or_else(|| body.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// 解析 JSON 文本
+pub fn parse_json(data: &str) -> Result<Value, String> {
+    serde_json::from_str(data).map_err(|e| format!("解析AI响应失败: {}", e))
+}
@@ -0,0 +1,231 @@
+/**
+ * Anthropic Claude 接口
+ *
+ * 系统提示词放在请求体的 system 字段中，不属于消息列表。工具调用是助手消息中的
+ * tool_use 内容块，工具结果以 tool_result 内容块放在用户消息中
+ */
+use crate::ai::provider::{
+    extract_error, parse_json, AiProvider, ResponseChunk, ToolCallDelta, DEFAULT_MAX_TOKENS,
+    DEFAULT_TEMPERATURE,
+};
+use crate::ai::types::{ChatMessage, ChatRequest, ChatRole, ChatUsage, ToolDefinition};
+use serde_json::{json, Value};
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+/// 接口版本
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+pub struct AnthropicProvider;
+
+/// 带版本号的接口根地址，兼容配置中是否包含 /v1
+fn api_root(base_url: &str) -> String {
+    if base_url.ends_with("/v1") {
+        base_url.to_string()
+    } else {
+        format!("{}/v1", base_url)
+    }
+}
+
+/// 解析 usage 字段
+fn parse_usage(usage: &Value) -> Option<ChatUsage> {
+    usage.is_object().then(|| ChatUsage {
+        prompt_tokens: usage["input_tokens"].as_u64().unwrap_or(0),
+        completion_tokens: usage["output_tokens"].as_u64().unwrap_or(0),
+    })
+}
+
+/**
+ * 转换为 Anthropic 格式的消息列表
+ *
+ * 连续的工具结果合并到同一条用户消息中，图片以 base64 内容块放在文本之前
+ *
+ * @param messages 不含系统消息的消息列表
+ * @return 消息 JSON 列表
+ */
+fn to_messages<'a>(messages: impl Iterator<Item = &'a ChatMessage>) -> Vec<Value> {
+    let mut result: Vec<Value> = Vec::new();
+    for message in messages {
+        match message.role {
+            ChatRole::Tool => {
+                let block = json!({
+                    "type": "tool_result",
+                    "tool_use_id": message.tool_call_id,
+                    "content": message.content,
+                });
+                let previous = result
+                    .last_mut()
+                    .filter(|last| last["role"] == "user")
+                    .and_then(|last| last["content"].as_array_mut())
+                    .filter(|blocks| blocks.iter().all(|b| b["type"] == "tool_result"));
+                match previous {
+                    Some(blocks) => blocks.push(block),
+                    None => result.push(json!({ "role": "user", "content": [block] })),
+                }
+            }
+            ChatRole::Assistant if !message.tool_calls.is_empty() => {
+                let mut blocks = Vec::new();
+                if !message.content.is_empty() {
+                    blocks.push(json!({ "type": "text", "text": message.content }));
+                }
+                for call in &message.tool_calls {
+                    // input 必须是对象
+                    let input = if call.arguments.is_object() {
+                        call.arguments.clone()
+                    } else {
+                        json!({})
+                    };
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.name,
+                        "input": input,
+                    }));
+                }
+                result.push(json!({ "role": "assistant", "content": blocks }));
+            }
+            ChatRole::User if !message.images.is_empty() => {
+                let mut blocks: Vec<Value> = message
+                    .images
+                    .iter()
+                    .map(|image| {
+                        json!({
+                            "type": "image",
+                            "source": {
+                                "type": "base64",
+                                "media_type": image.mime_type,
+                                "data": image.data,
+                            },
+                        })
+                    })
+                    .collect();
+                if !message.content.is_empty() {
+                    blocks.push(json!({ "type": "text", "text": message.content }));
+                }
+                result.push(json!({ "role": "user", "content": blocks }));
+            }
+            _ => result.push(json!({ "role": message.role, "content": message.content })),
+        }
+    }
+    result
+}
+
+impl AiProvider for AnthropicProvider {
+    fn endpoint(&self, base_url: &str, _request: &ChatRequest) -> String {
+        format!("{}/messages", api_root(base_url))
+    }
+
+    fn models_endpoint(&self, base_url: &str) -> Option<String> {
+        Some(format!("{}/models", api_root(base_url)))
+    }
+
+    fn apply_headers(
+        &self,
+        builder: RequestBuilder,
+        api_key: Option<&str>,
+        _stream: bool,
+    ) -> RequestBuilder {
+        let builder = builder.header("anthropic-version", ANTHROPIC_VERSION);
+        match api_key {
+            Some(key) => builder.header("x-api-key", key),
+            None => builder,
+        }
+    }
+
+    fn build_body(&self, request: &ChatRequest, tools: &[ToolDefinition], stream: bool) -> Value {
+        let system: Vec<&str> = request
+            .messages
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        let messages = to_messages(
+            request
+                .messages
+                .iter()
+                .filter(|m| m.role != ChatRole::System),
+        );
+
+        let mut body = json!({
+            "model": request.model,
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "temperature": request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            "stream": stream,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        if !tools.is_empty() {
+            body["tools"] = tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "input_schema": tool.parameters,
+                    })
+                })
+                .collect();
+        }
+        body
+    }
+
+    fn parse_stream_chunk(&self, data: &str) -> Result<ResponseChunk, String> {
+        let body = parse_json(data)?;
+        let mut chunk = ResponseChunk::default();
+        match body["type"].as_str().unwrap_or_default() {
+            "message_start" => chunk.usage = parse_usage(&body["message"]["usage"]),
+            "content_block_start" if body["content_block"]["type"] == "tool_use" => {
+                let block = &body["content_block"];
+                chunk.tool_calls.push(ToolCallDelta {
+                    index: body["index"].as_u64().map(|index| index as usize),
+                    id: block["id"].as_str().map(str::to_string),
+                    name: block["name"].as_str().map(str::to_string),
+                    arguments: String::new(),
+                });
+            }
+            "content_block_delta" => match body["delta"]["type"].as_str() {
+                Some("input_json_delta") => chunk.tool_calls.push(ToolCallDelta {
+                    index: body["index"].as_u64().map(|index| index as usize),
+                    arguments: body["delta"]["partial_json"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    ..Default::default()
+                }),
+                _ => chunk.text = body["delta"]["text"].as_str().map(str::to_string),
+            },
+            "message_delta" => chunk.usage = parse_usage(&body["usage"]),
+            "message_stop" => chunk.done = true,
+            "error" => {
+                return Err(extract_error(&body).unwrap_or_else(|| "AI服务返回错误".to_string()))
+            }
+            _ => {}
+        }
+        Ok(chunk)
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<ResponseChunk, String> {
+        let blocks = body["content"].as_array().cloned().unwrap_or_default();
+        let text = blocks
+            .iter()
+            .filter_map(|block| block["text"].as_str())
+            .collect::<String>();
+        let tool_calls = blocks
+            .iter()
+            .filter(|block| block["type"] == "tool_use")
+            .map(|block| ToolCallDelta {
+                index: None,
+                id: block["id"].as_str().map(str::to_string),
+                name: block["name"].as_str().map(str::to_string),
+                arguments: block["input"].to_string(),
+            })
+            .collect();
+        Ok(ResponseChunk {
+            text: Some(text),
+            tool_calls,
+            usage: parse_usage(&body["usage"]),
+            done: true,
+        })
+    }
+}
@@ -0,0 +1,17 @@
+/**
+ * AI 提供商实现
+ *
+ * - openai: OpenAI 及兼容接口（DeepSeek 等）
+ * - anthropic: Anthropic Claude
+ * - ollama: 本地 Ollama
+ * - qwen: 通义千问 DashScope
+ */
+mod anthropic;
+mod ollama;
+mod openai;
+mod qwen;
+
+pub use anthropic::AnthropicProvider;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiProvider;
+pub use qwen::QwenProvider;
@@ -0,0 +1,137 @@
+/**
+ * Ollama 本地接口
+ *
+ * 流式响应每行一个 JSON 对象，最后一行 done 为 true 并带有用量统计。工具调用
+ * 一次性返回完整的参数对象，没有调用ID
+ */
+use super::openai::to_tools;
+use crate::ai::provider::{
+    extract_error, parse_json, parse_vector, AiProvider, ResponseChunk, StreamFormat,
+    ToolCallDelta, DEFAULT_MAX_TOKENS, DEFAULT_TEMPERATURE,
+};
+use crate::ai::types::{ChatMessage, ChatRequest, ChatRole, ChatUsage, ToolDefinition};
+use serde_json::{json, Value};
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+pub struct OllamaProvider;
+
+/// 转换为 Ollama 格式的消息，工具调用的参数为 JSON 对象，图片为不带前缀的 base64
+fn to_message(message: &ChatMessage) -> Value {
+    let mut value = json!({ "role": message.role, "content": message.content });
+    if !message.images.is_empty() {
+        value["images"] = message
+            .images
+            .iter()
+            .map(|image| json!(image.data))
+            .collect();
+    }
+    if !message.tool_calls.is_empty() {
+        value["tool_calls"] = message
+            .tool_calls
+            .iter()
+            .map(|call| json!({ "function": { "name": call.name, "arguments": call.arguments } }))
+            .collect();
+    }
+    if message.role == ChatRole::Tool {
+        value["tool_name"] = json!(message.name);
+    }
+    value
+}
+
+/// 解析一个响应对象
+fn parse_message(body: &Value) -> Result<ResponseChunk, String> {
+    if let Some(message) = extract_error(body) {
+        return Err(message);
+    }
+    let done = body["done"].as_bool().unwrap_or(false);
+    let tool_calls = body["message"]["tool_calls"]
+        .as_array()
+        .map(|calls| {
+            calls
+                .iter()
+                .map(|call| ToolCallDelta {
+                    index: None,
+                    id: None,
+                    name: call["function"]["name"].as_str().map(str::to_string),
+                    arguments: call["function"]["arguments"].to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(ResponseChunk {
+        text: body["message"]["content"].as_str().map(str::to_string),
+        tool_calls,
+        usage: done.then(|| ChatUsage {
+            prompt_tokens: body["prompt_eval_count"].as_u64().unwrap_or(0),
+            completion_tokens: body["eval_count"].as_u64().unwrap_or(0),
+        }),
+        done,
+    })
+}
+
+impl AiProvider for OllamaProvider {
+    fn endpoint(&self, base_url: &str, _request: &ChatRequest) -> String {
+        format!("{}/api/chat", base_url)
+    }
+
+    fn models_endpoint(&self, base_url: &str) -> Option<String> {
+        Some(format!("{}/api/tags", base_url))
+    }
+
+    fn apply_headers(
+        &self,
+        builder: RequestBuilder,
+        api_key: Option<&str>,
+        _stream: bool,
+    ) -> RequestBuilder {
+        // 本地服务通常不需要认证，经反向代理访问时可配置密钥
+        match api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    fn build_body(&self, request: &ChatRequest, tools: &[ToolDefinition], stream: bool) -> Value {
+        let messages: Vec<Value> = request.messages.iter().map(to_message).collect();
+        let mut body = json!({
+            "model": request.model,
+            "messages": messages,
+            "options": {
+                "temperature": request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+                "num_predict": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            },
+            "stream": stream,
+        });
+        if !tools.is_empty() {
+            body["tools"] = to_tools(tools);
+        }
+        body
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::JsonLines
+    }
+
+    fn parse_stream_chunk(&self, data: &str) -> Result<ResponseChunk, String> {
+        parse_message(&parse_json(data)?)
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<ResponseChunk, String> {
+        let mut chunk = parse_message(body)?;
+        chunk.done = true;
+        Ok(chunk)
+    }
+
+    fn embeddings_endpoint(&self, base_url: &str) -> Option<String> {
+        Some(format!("{}/api/embed", base_url))
+    }
+
+    fn parse_embeddings(&self, body: &Value) -> Result<Vec<Vec<f32>>, String> {
+        body["embeddings"]
+            .as_array()
+            .ok_or("向量化响应格式不正确")?
+            .iter()
+            .map(|item| parse_vector(item).ok_or_else(|| "向量格式不正确".to_string()))
+            .collect()
+    }
+}
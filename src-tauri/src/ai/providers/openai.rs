@@ -0,0 +1,208 @@
+/**
+ * OpenAI 兼容接口
+ *
+ * DeepSeek 等提供商使用相同的 /chat/completions 协议
+ */
+use crate::ai::provider::{
+    extract_error, parse_json, parse_vector, AiProvider, ResponseChunk, ToolCallDelta,
+    DEFAULT_MAX_TOKENS, DEFAULT_TEMPERATURE,
+};
+use crate::ai::types::{ChatMessage, ChatRequest, ChatRole, ChatUsage, ToolDefinition};
+use serde_json::{json, Value};
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+pub struct OpenAiProvider;
+
+/// 解析 usage 字段
+fn parse_usage(body: &Value) -> Option<ChatUsage> {
+    let usage = body.get("usage").filter(|u| u.is_object())?;
+    Some(ChatUsage {
+        prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0),
+        completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0),
+    })
+}
+
+/**
+ * 转换为 OpenAI 格式的消息
+ *
+ * 工具调用的参数以 JSON 字符串传递，带图片的消息内容为文本与图片的数组
+ *
+ * @param message 消息
+ * @return 消息 JSON
+ */
+pub(crate) fn to_message(message: &ChatMessage) -> Value {
+    let mut value = json!({ "role": message.role, "content": message.content });
+    if !message.images.is_empty() {
+        let mut parts = vec![json!({ "type": "text", "text": message.content })];
+        parts.extend(
+            message.images.iter().map(
+                |image| json!({ "type": "image_url", "image_url": { "url": image.data_url() } }),
+            ),
+        );
+        value["content"] = Value::Array(parts);
+    }
+    if !message.tool_calls.is_empty() {
+        value["tool_calls"] = message
+            .tool_calls
+            .iter()
+            .map(|call| {
+                json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": { "name": call.name, "arguments": call.arguments.to_string() },
+                })
+            })
+            .collect();
+    }
+    if message.role == ChatRole::Tool {
+        value["tool_call_id"] = json!(message.tool_call_id);
+        value["name"] = json!(message.name);
+    }
+    value
+}
+
+/// 转换为 OpenAI 格式的工具定义
+pub(crate) fn to_tools(tools: &[ToolDefinition]) -> Value {
+    tools
+        .iter()
+        .map(|tool| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.parameters,
+                },
+            })
+        })
+        .collect()
+}
+
+/**
+ * 解析 OpenAI 格式的工具调用
+ *
+ * 流式响应的每一项带有 index，非流式响应按数组位置编号
+ *
+ * @param calls tool_calls 数组
+ * @return 工具调用片段
+ */
+pub(crate) fn parse_tool_calls(calls: &Value) -> Vec<ToolCallDelta> {
+    calls
+        .as_array()
+        .map(|calls| {
+            calls
+                .iter()
+                .enumerate()
+                .map(|(position, call)| ToolCallDelta {
+                    index: Some(
+                        call["index"]
+                            .as_u64()
+                            .map_or(position, |index| index as usize),
+                    ),
+                    id: call["id"]
+                        .as_str()
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string),
+                    name: call["function"]["name"]
+                        .as_str()
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_string),
+                    arguments: call["function"]["arguments"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl AiProvider for OpenAiProvider {
+    fn endpoint(&self, base_url: &str, _request: &ChatRequest) -> String {
+        format!("{}/chat/completions", base_url)
+    }
+
+    fn models_endpoint(&self, base_url: &str) -> Option<String> {
+        Some(format!("{}/models", base_url))
+    }
+
+    fn apply_headers(
+        &self,
+        builder: RequestBuilder,
+        api_key: Option<&str>,
+        _stream: bool,
+    ) -> RequestBuilder {
+        match api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    fn build_body(&self, request: &ChatRequest, tools: &[ToolDefinition], stream: bool) -> Value {
+        let messages: Vec<Value> = request.messages.iter().map(to_message).collect();
+        let mut body = json!({
+            "model": request.model,
+            "messages": messages,
+            "temperature": request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "stream": stream,
+        });
+        if stream {
+            // 在最后一帧返回用量
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        if !tools.is_empty() {
+            body["tools"] = to_tools(tools);
+        }
+        body
+    }
+
+    fn parse_stream_chunk(&self, data: &str) -> Result<ResponseChunk, String> {
+        if data == "[DONE]" {
+            return Ok(ResponseChunk {
+                done: true,
+                ..Default::default()
+            });
+        }
+        let body = parse_json(data)?;
+        if let Some(message) = extract_error(&body) {
+            return Err(message);
+        }
+        let delta = &body["choices"][0]["delta"];
+        Ok(ResponseChunk {
+            text: delta["content"].as_str().map(str::to_string),
+            tool_calls: parse_tool_calls(&delta["tool_calls"]),
+            usage: parse_usage(&body),
+            done: false,
+        })
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<ResponseChunk, String> {
+        let message = &body["choices"][0]["message"];
+        Ok(ResponseChunk {
+            text: Some(message["content"].as_str().unwrap_or_default().to_string()),
+            tool_calls: parse_tool_calls(&message["tool_calls"]),
+            usage: parse_usage(body),
+            done: true,
+        })
+    }
+
+    fn embeddings_endpoint(&self, base_url: &str) -> Option<String> {
+        Some(format!("{}/embeddings", base_url))
+    }
+
+    fn parse_embeddings(&self, body: &Value) -> Result<Vec<Vec<f32>>, String> {
+        let mut items: Vec<&Value> = body["data"]
+            .as_array()
+            .ok_or("向量化响应格式不正确")?
+            .iter()
+            .collect();
+        items.sort_by_key(|item| item["index"].as_u64().unwrap_or_default());
+        items
+            .iter()
+            .map(|item| {
+                parse_vector(&item["embedding"]).ok_or_else(|| "向量格式不正确".to_string())
+            })
+            .collect()
+    }
+}
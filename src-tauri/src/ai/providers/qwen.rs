@@ -0,0 +1,137 @@
+use super::openai::{parse_tool_calls, to_message, to_tools};
+/**
+ * 通义千问 DashScope 接口
+ *
+ * 流式请求通过 X-DashScope-SSE 请求头开启，incremental_output 使每帧只包含新增文本。
+ * 消息与工具调用沿用 OpenAI 的格式；带图片的请求改用多模态接口，消息内容为
+ * 图片与文本组成的数组
+ */
+use crate::ai::provider::{
+    extract_error, parse_json, AiProvider, ResponseChunk, DEFAULT_MAX_TOKENS, DEFAULT_TEMPERATURE,
+};
+use crate::ai::types::{ChatMessage, ChatRequest, ChatUsage, ToolDefinition};
+use serde_json::{json, Value};
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+pub struct QwenProvider;
+
+/// 请求中是否包含图片
+fn has_images(request: &ChatRequest) -> bool {
+    request
+        .messages
+        .iter()
+        .any(|message| !message.images.is_empty())
+}
+
+/// 转换为多模态接口的消息，图片在前、文本在后
+fn to_multimodal_message(message: &ChatMessage) -> Value {
+    let mut value = to_message(message);
+    let mut parts: Vec<Value> = message
+        .images
+        .iter()
+        .map(|image| json!({ "image": image.data_url() }))
+        .collect();
+    parts.push(json!({ "text": message.content }));
+    value["content"] = Value::Array(parts);
+    value
+}
+
+/// 读取消息文本，多模态接口返回的内容为数组
+fn content_text(content: &Value) -> Option<String> {
+    match content {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// 解析一个响应对象
+fn parse_output(body: &Value) -> Result<ResponseChunk, String> {
+    if let Some(message) = body["code"]
+        .as_str()
+        .filter(|code| !code.is_empty())
+        .and_then(|_| extract_error(body))
+    {
+        return Err(message);
+    }
+    let choice = &body["output"]["choices"][0];
+    let usage = &body["usage"];
+    Ok(ResponseChunk {
+        text: content_text(&choice["message"]["content"]),
+        tool_calls: parse_tool_calls(&choice["message"]["tool_calls"]),
+        usage: usage.is_object().then(|| ChatUsage {
+            prompt_tokens: usage["input_tokens"].as_u64().unwrap_or(0),
+            completion_tokens: usage["output_tokens"].as_u64().unwrap_or(0),
+        }),
+        done: choice["finish_reason"]
+            .as_str()
+            .is_some_and(|reason| reason != "null"),
+    })
+}
+
+impl AiProvider for QwenProvider {
+    fn endpoint(&self, base_url: &str, request: &ChatRequest) -> String {
+        if has_images(request) {
+            format!(
+                "{}/services/aigc/multimodal-generation/generation",
+                base_url
+            )
+        } else {
+            format!("{}/services/aigc/text-generation/generation", base_url)
+        }
+    }
+
+    fn apply_headers(
+        &self,
+        builder: RequestBuilder,
+        api_key: Option<&str>,
+        stream: bool,
+    ) -> RequestBuilder {
+        let builder = match api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        };
+        if stream {
+            builder.header("X-DashScope-SSE", "enable")
+        } else {
+            builder
+        }
+    }
+
+    fn build_body(&self, request: &ChatRequest, tools: &[ToolDefinition], stream: bool) -> Value {
+        let messages: Vec<Value> = if has_images(request) {
+            request.messages.iter().map(to_multimodal_message).collect()
+        } else {
+            request.messages.iter().map(to_message).collect()
+        };
+        let mut body = json!({
+            "model": request.model,
+            "input": { "messages": messages },
+            "parameters": {
+                "result_format": "message",
+                "temperature": request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+                "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+                "incremental_output": stream,
+            },
+        });
+        if !tools.is_empty() {
+            body["parameters"]["tools"] = to_tools(tools);
+        }
+        body
+    }
+
+    fn parse_stream_chunk(&self, data: &str) -> Result<ResponseChunk, String> {
+        parse_output(&parse_json(data)?)
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<ResponseChunk, String> {
+        let mut chunk = parse_output(body)?;
+        chunk.done = true;
+        Ok(chunk)
+    }
+}
@@ -0,0 +1,145 @@
+/**
+ * token 估算
+ *
+ * 各提供商的分词器不同且大多没有本地实现，这里按提供商的分词特点估算：
+ * 拉丁字符按平均每 token 字符数计算，中日韩字符按每字 token 数计算，再加上
+ * 每条消息的格式开销。估算值偏保守，仅用于上下文长度控制
+ */
+use super::types::{ChatMessage, ProviderKind};
+
+/// 单张图片的最少 token 数
+const MIN_IMAGE_TOKENS: u64 = 85;
+/// 单张图片的最多 token 数
+const MAX_IMAGE_TOKENS: u64 = 1600;
+
+/**
+ * 提供商的分词特点
+ */
+struct TokenProfile {
+    /// 拉丁字符平均每 token 字符数
+    chars_per_token: f64,
+    /// 中日韩字符每字 token 数
+    cjk_tokens_per_char: f64,
+    /// 每条消息的格式开销
+    message_overhead: u64,
+    /// 每次请求的固定开销
+    request_overhead: u64,
+}
+
+fn profile(kind: ProviderKind) -> TokenProfile {
+    match kind {
+        // cl100k / o200k
+        ProviderKind::OpenAi => TokenProfile {
+            chars_per_token: 4.0,
+            cjk_tokens_per_char: 1.0,
+            message_overhead: 4,
+            request_overhead: 3,
+        },
+        ProviderKind::Claude => TokenProfile {
+            chars_per_token: 3.5,
+            cjk_tokens_per_char: 1.2,
+            message_overhead: 5,
+            request_overhead: 10,
+        },
+        // 通义千问与 DeepSeek 的词表对中文做了优化，常用汉字多为单 token 或双字一 token
+        ProviderKind::Qianwen => TokenProfile {
+            chars_per_token: 3.8,
+            cjk_tokens_per_char: 0.8,
+            message_overhead: 4,
+            request_overhead: 3,
+        },
+        ProviderKind::DeepSeek => TokenProfile {
+            chars_per_token: 3.8,
+            cjk_tokens_per_char: 0.7,
+            message_overhead: 4,
+            request_overhead: 3,
+        },
+        // 本地模型以 Llama 系列为主
+        ProviderKind::Ollama => TokenProfile {
+            chars_per_token: 3.5,
+            cjk_tokens_per_char: 1.3,
+            message_overhead: 5,
+            request_overhead: 5,
+        },
+    }
+}
+
+/// 是否为中日韩字符（含全角标点）
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3000}'..='\u{303F}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/**
+ * 估算文本的 token 数
+ *
+ * @param kind 提供商
+ * @param text 文本
+ * @return token 数
+ */
+pub fn estimate_text_tokens(kind: ProviderKind, text: &str) -> u64 {
+    let profile = profile(kind);
+    let (mut cjk, mut other) = (0u64, 0u64);
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    (other as f64 / profile.chars_per_token + cjk as f64 * profile.cjk_tokens_per_char).ceil()
+        as u64
+}
+
+/**
+ * 估算单条消息的 token 数，包含消息格式的开销
+ *
+ * @param kind 提供商
+ * @param message 消息
+ * @return token 数
+ */
+pub fn estimate_message_tokens(kind: ProviderKind, message: &ChatMessage) -> u64 {
+    let tool_calls: u64 = message
+        .tool_calls
+        .iter()
+        .map(|call| {
+            estimate_text_tokens(kind, &call.name)
+                + estimate_text_tokens(kind, &call.arguments.to_string())
+        })
+        .sum();
+    let images: u64 = message
+        .images
+        .iter()
+        .map(|image| estimate_image_tokens(image.width, image.height))
+        .sum();
+    estimate_text_tokens(kind, &message.content)
+        + tool_calls
+        + images
+        + profile(kind).message_overhead
+}
+
+/**
+ * 估算图片的 token 数
+ *
+ * 各提供商的计算方式不同，统一按每 750 像素约 1 token 估算
+ *
+ * @param width 宽度
+ * @param height 高度
+ * @return token 数
+ */
+fn estimate_image_tokens(width: u32, height: u32) -> u64 {
+    (width as u64 * height as u64 / 750).clamp(MIN_IMAGE_TOKENS, MAX_IMAGE_TOKENS)
+}
+
+/// 每次请求的固定开销
+pub fn request_overhead(kind: ProviderKind) -> u64 {
+    profile(kind).request_overhead
+}
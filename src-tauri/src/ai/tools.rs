@@ -0,0 +1,909 @@
+/**
+ * AI 工具调用
+ *
+ * 内置工具包括读取允许目录中的文件、HTTP GET、系统资源监控与密码库查询，已加载的
+ * 后端插件与已启用的 MCP 服务器也会作为工具提供。每个工具有独立的确认策略：直接执行、执行前弹窗确认或
+ * 禁用，密码库查询始终需要确认。每次调用的参数、结果与耗时都会写入
+ * ai_tool_invocations 作为调用记录
+ */
+use super::config::{load_ai_setting, save_ai_setting};
+use super::history::escape_like;
+use super::mcp::{call_mcp_tool, mcp_tools};
+use super::types::{ToolCall, ToolDefinition, ToolInvocationStatus};
+use crate::file::read_text_file;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use crate::security::vault;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// 允许读取的目录在 ai_settings 中的键名
+const ALLOWED_DIRS_KEY: &str = "tools_allowed_dirs";
+/// 工具结果的最大长度（字符），超出部分截断
+const MAX_OUTPUT_CHARS: usize = 16_000;
+/// 读取文件的默认字节数
+const DEFAULT_READ_BYTES: u64 = 64 * 1024;
+/// 读取文件的最大字节数
+const MAX_READ_BYTES: u64 = 256 * 1024;
+/// 密码库查询返回的最大条目数
+const VAULT_RESULT_LIMIT: i64 = 5;
+/// 等待用户确认的超时时间（秒）
+const CONFIRM_TIMEOUT_SECS: u64 = 60;
+/// 插件工具名称前缀
+const PLUGIN_TOOL_PREFIX: &str = "plugin_";
+/// 调用记录默认返回数量
+const DEFAULT_INVOCATION_LIMIT: i64 = 100;
+/// 调用记录中代替机密内容的文本
+const REDACTED: &str = "[已隐藏]";
+
+const READ_FILE: &str = "read_file";
+const HTTP_GET: &str = "http_get";
+const SYSTEM_METRICS: &str = "get_system_metrics";
+const VAULT_LOOKUP: &str = "lookup_vault_entry";
+
+/**
+ * 工具的确认策略
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolPolicy {
+    /// 直接执行
+    Allow,
+    /// 执行前弹窗确认
+    Confirm,
+    /// 禁用，不提供给模型
+    Disabled,
+}
+
+impl ToolPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolPolicy::Allow => "allow",
+            ToolPolicy::Confirm => "confirm",
+            ToolPolicy::Disabled => "disabled",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" => Some(ToolPolicy::Allow),
+            "confirm" => Some(ToolPolicy::Confirm),
+            "disabled" => Some(ToolPolicy::Disabled),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * 工具来源
+ */
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ToolSource {
+    /// 内置工具
+    Builtin,
+    /// 后端插件
+    Plugin { plugin_id: String },
+    /// MCP 服务器
+    Mcp { server_id: String, tool: String },
+}
+
+/**
+ * 已注册的工具
+ */
+struct ToolSpec {
+    definition: ToolDefinition,
+    source: ToolSource,
+    default_policy: ToolPolicy,
+}
+
+impl ToolSpec {
+    /// 保存确认策略使用的键，MCP 工具按服务器ID和工具名称区分，不随服务器改名变化
+    fn policy_key(&self) -> String {
+        match &self.source {
+            ToolSource::Mcp { server_id, tool } => {
+                format!("{}{}", mcp_policy_prefix(server_id), tool)
+            }
+            _ => self.definition.name.clone(),
+        }
+    }
+
+    fn builtin(name: &str, description: &str, parameters: Value, policy: ToolPolicy) -> Self {
+        ToolSpec {
+            definition: ToolDefinition {
+                name: name.to_string(),
+                description: description.to_string(),
+                parameters,
+            },
+            source: ToolSource::Builtin,
+            default_policy: policy,
+        }
+    }
+}
+
+/// 内置工具
+fn builtin_tools() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec::builtin(
+            READ_FILE,
+            "读取本机文本文件的内容，只能读取用户允许的目录中的文件",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "文件的绝对路径" },
+                    "max_bytes": {
+                        "type": "integer",
+                        "description": "最多读取的字节数",
+                        "minimum": 1,
+                        "maximum": MAX_READ_BYTES,
+                    },
+                },
+                "required": ["path"],
+            }),
+            ToolPolicy::Confirm,
+        ),
+        ToolSpec::builtin(
+            HTTP_GET,
+            "发送 HTTP GET 请求并返回响应正文",
+            json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "http 或 https 地址" },
+                },
+                "required": ["url"],
+            }),
+            ToolPolicy::Confirm,
+        ),
+        ToolSpec::builtin(
+            SYSTEM_METRICS,
+            "获取本机 CPU、内存、磁盘与 GPU 的使用情况",
+            json!({ "type": "object", "properties": {} }),
+            ToolPolicy::Allow,
+        ),
+        ToolSpec::builtin(
+            VAULT_LOOKUP,
+            "按标题、用户名或网址查找密码库中的条目，返回用户名与网址，需要时可返回密码",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "查找的关键词" },
+                    "include_password": {
+                        "type": "boolean",
+                        "description": "是否返回密码，默认否",
+                    },
+                },
+                "required": ["query"],
+            }),
+            ToolPolicy::Confirm,
+        ),
+    ]
+}
+
+/// MCP 服务器的工具确认策略键前缀
+pub(crate) fn mcp_policy_prefix(server_id: &str) -> String {
+    format!("mcp:{}:", server_id)
+}
+
+/// 插件ID中不能用于工具名称的字符替换为下划线
+fn plugin_tool_name(plugin_id: &str) -> String {
+    let id: String = plugin_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64 - PLUGIN_TOOL_PREFIX.len())
+        .collect();
+    format!("{}{}", PLUGIN_TOOL_PREFIX, id)
+}
+
+/// 已加载的后端插件对应的工具
+async fn plugin_tools() -> Vec<ToolSpec> {
+    let plugins = match crate::plugin::get_loaded_plugins().await {
+        Ok(plugins) => plugins,
+        Err(e) => {
+            log::debug!("获取已加载插件失败: {}", e);
+            return Vec::new();
+        }
+    };
+    plugins
+        .into_iter()
+        .filter(|plugin| plugin.has_backend)
+        .map(|plugin| ToolSpec {
+            definition: ToolDefinition {
+                name: plugin_tool_name(&plugin.id),
+                description: format!(
+                    "调用插件“{}”的后端函数{}",
+                    plugin.name,
+                    plugin
+                        .description
+                        .as_deref()
+                        .map(|d| format!("。插件说明：{}", d))
+                        .unwrap_or_default()
+                ),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "function": { "type": "string", "description": "函数名称" },
+                        "args": {
+                            "type": "array",
+                            "items": { "type": "integer" },
+                            "description": "整数参数",
+                        },
+                    },
+                    "required": ["function"],
+                }),
+            },
+            source: ToolSource::Plugin {
+                plugin_id: plugin.id,
+            },
+            default_policy: ToolPolicy::Confirm,
+        })
+        .collect()
+}
+
+/// 全部已注册的工具
+async fn all_tools(app: &AppHandle) -> Vec<ToolSpec> {
+    let mut tools = builtin_tools();
+    tools.extend(plugin_tools().await);
+    tools.extend(mcp_tools(app).await.into_iter().map(|binding| ToolSpec {
+        definition: binding.definition,
+        source: ToolSource::Mcp {
+            server_id: binding.server_id,
+            tool: binding.tool,
+        },
+        default_policy: ToolPolicy::Confirm,
+    }));
+    tools
+}
+
+/// 读取已保存的确认策略
+async fn load_policies() -> Result<HashMap<String, ToolPolicy>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = sqlx::query("SELECT name, policy FROM ai_tool_settings")
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询工具设置失败: {}", e))?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let policy = ToolPolicy::parse(&row.get::<String, _>("policy"))?;
+            Some((row.get("name"), policy))
+        })
+        .collect())
+}
+
+/// 工具实际生效的策略，密码库查询不能跳过确认
+fn effective_policy(spec: &ToolSpec, policies: &HashMap<String, ToolPolicy>) -> ToolPolicy {
+    let policy = policies
+        .get(&spec.policy_key())
+        .copied()
+        .unwrap_or(spec.default_policy);
+    if spec.definition.name == VAULT_LOOKUP && policy == ToolPolicy::Allow {
+        ToolPolicy::Confirm
+    } else {
+        policy
+    }
+}
+
+/**
+ * 获取提供给模型的工具定义，不包含已禁用的工具
+ *
+ * @param app Tauri应用句柄
+ * @return 工具定义列表
+ */
+pub async fn available_tools(app: &AppHandle) -> Result<Vec<ToolDefinition>, String> {
+    let policies = load_policies().await?;
+    Ok(all_tools(app)
+        .await
+        .into_iter()
+        .filter(|spec| effective_policy(spec, &policies) != ToolPolicy::Disabled)
+        .map(|spec| spec.definition)
+        .collect())
+}
+
+/**
+ * 弹出对话框请求用户确认工具调用
+ *
+ * @param app Tauri应用句柄
+ * @param message 内容
+ * @return 用户是否允许
+ */
+async fn confirm(app: &AppHandle, message: String) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title("AI 工具调用")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "允许".to_string(),
+            "拒绝".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+
+    matches!(
+        tokio::time::timeout(Duration::from_secs(CONFIRM_TIMEOUT_SECS), rx).await,
+        Ok(Ok(true))
+    )
+}
+
+/// 确认对话框的内容
+fn confirm_message(spec: &ToolSpec, args: &Value) -> String {
+    let detail = match spec.definition.name.as_str() {
+        READ_FILE => format!("读取文件：{}", args["path"].as_str().unwrap_or_default()),
+        HTTP_GET => format!("访问网址：{}", args["url"].as_str().unwrap_or_default()),
+        VAULT_LOOKUP => format!(
+            "查找密码库条目：{}{}",
+            args["query"].as_str().unwrap_or_default(),
+            if args["include_password"].as_bool() == Some(true) {
+                "\n\n注意：匹配条目的密码将发送给AI模型"
+            } else {
+                ""
+            }
+        ),
+        _ => format!("参数：{}", truncate(&args.to_string(), 500)),
+    };
+    format!(
+        "AI 请求调用工具“{}”。\n\n{}\n\n结果将发送给AI模型，是否允许？",
+        spec.definition.name, detail
+    )
+}
+
+/// 按字符数截断文本
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((pos, _)) => format!("{}…（已截断）", &text[..pos]),
+        None => text.to_string(),
+    }
+}
+
+/// 读取字符串参数
+fn string_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str, String> {
+    args[name]
+        .as_str()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("缺少参数: {}", name))
+}
+
+/// 读取允许读取的目录
+async fn load_allowed_dirs() -> Result<Vec<PathBuf>, String> {
+    Ok(load_ai_setting(ALLOWED_DIRS_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str::<Vec<PathBuf>>(&value).ok())
+        .unwrap_or_default())
+}
+
+/**
+ * 读取允许目录中的文本文件
+ *
+ * 路径会先解析符号链接与 ..，再判断是否位于允许的目录中
+ */
+async fn run_read_file(args: &Value) -> Result<String, String> {
+    let path = Path::new(string_arg(args, "path")?);
+    if !path.is_absolute() {
+        return Err("请使用绝对路径".to_string());
+    }
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("文件不存在或无法访问: {}", e))?;
+
+    let allowed_dirs = load_allowed_dirs().await?;
+    if allowed_dirs.is_empty() {
+        return Err("未设置允许AI读取的目录".to_string());
+    }
+    if !allowed_dirs.iter().any(|dir| path.starts_with(dir)) {
+        return Err("该文件不在允许AI读取的目录中".to_string());
+    }
+    if !path.is_file() {
+        return Err("路径不是文件".to_string());
+    }
+
+    let max_bytes = args["max_bytes"]
+        .as_u64()
+        .unwrap_or(DEFAULT_READ_BYTES)
+        .clamp(1, MAX_READ_BYTES);
+    let (content, truncated) = read_text_file(&path, max_bytes)?;
+    Ok(if truncated {
+        format!("{}\n…（文件超过 {} 字节，已截断）", content, max_bytes)
+    } else {
+        content
+    })
+}
+
+/// 发送 HTTP GET 请求
+async fn run_http_get(args: &Value) -> Result<String, String> {
+    let url = string_arg(args, "url")?;
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("只支持 http 与 https 地址".to_string());
+    }
+    crate::http::http_get(url.to_string(), None, None).await
+}
+
+/// 获取系统资源使用情况
+async fn run_system_metrics() -> Result<String, String> {
+    let metrics = crate::system::get_system_metrics().await?;
+    serde_json::to_string(&metrics).map_err(|e| e.to_string())
+}
+
+/**
+ * 查找密码库条目
+ *
+ * 只在密码库已解锁时可用，返回密码时记录审计事件
+ */
+async fn run_vault_lookup(args: &Value) -> Result<String, String> {
+    if !vault::is_unlocked() {
+        return Err("密码库已锁定，请先在应用中解锁".to_string());
+    }
+    let query = string_arg(args, "query")?;
+    let include_password = args["include_password"].as_bool().unwrap_or(false);
+    let pattern = format!("%{}%", escape_like(query));
+
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_with_params!(
+            db,
+            "SELECT id, title, username, password, url FROM password_entries
+             WHERE title LIKE ? ESCAPE '\\' OR username LIKE ? ESCAPE '\\'
+                OR url LIKE ? ESCAPE '\\'
+             ORDER BY is_favorite DESC, last_used DESC
+             LIMIT ?",
+            &pattern,
+            &pattern,
+            &pattern,
+            VAULT_RESULT_LIMIT
+        )?
+    };
+
+    let entries: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            let mut entry = json!({
+                "title": row.get::<String, _>("title"),
+                "username": row.get::<String, _>("username"),
+                "url": row.get::<Option<String>, _>("url"),
+            });
+            if include_password {
+                entry["password"] = json!(row.get::<String, _>("password"));
+            }
+            entry
+        })
+        .collect();
+
+    if include_password && !entries.is_empty() {
+        let ids: Vec<String> = rows.iter().map(|row| row.get("id")).collect();
+        record_quietly(
+            AuditEvent::VaultReveal,
+            "AI工具读取密码库条目",
+            Some(json!({ "entry_ids": ids })),
+        )
+        .await;
+    }
+    serde_json::to_string(&entries).map_err(|e| e.to_string())
+}
+
+/**
+ * 隐藏调用记录中的机密内容
+ *
+ * 密码库查询返回的密码只交给模型，不写入调用记录
+ *
+ * @param tool 工具名称
+ * @param output 工具返回的内容
+ * @return 可以保存的内容
+ */
+fn redact_output(tool: &str, output: &str) -> String {
+    if tool != VAULT_LOOKUP {
+        return output.to_string();
+    }
+    let Ok(Value::Array(mut entries)) = serde_json::from_str::<Value>(output) else {
+        return output.to_string();
+    };
+    for entry in &mut entries {
+        if let Some(password) = entry.get_mut("password") {
+            *password = json!(REDACTED);
+        }
+    }
+    Value::Array(entries).to_string()
+}
+
+/// 调用插件的后端函数
+async fn run_plugin(plugin_id: &str, args: &Value) -> Result<String, String> {
+    let function = string_arg(args, "function")?;
+    let values = match &args["args"] {
+        Value::Null => Vec::new(),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_i64()
+                    .and_then(|value| i32::try_from(value).ok())
+                    .ok_or_else(|| "插件参数必须是32位整数".to_string())
+            })
+            .collect::<Result<Vec<i32>, String>>()?,
+        _ => return Err("参数 args 必须是数组".to_string()),
+    };
+    let result =
+        crate::plugin::call_plugin_function(plugin_id.to_string(), function.to_string(), values)
+            .await?;
+    Ok(result.to_string())
+}
+
+/// 执行工具
+async fn execute(app: &AppHandle, spec: &ToolSpec, args: &Value) -> Result<String, String> {
+    match &spec.source {
+        ToolSource::Plugin { plugin_id } => return run_plugin(plugin_id, args).await,
+        ToolSource::Mcp { server_id, tool } => {
+            return call_mcp_tool(app, server_id, tool, args).await
+        }
+        ToolSource::Builtin => {}
+    }
+    match spec.definition.name.as_str() {
+        READ_FILE => run_read_file(args).await,
+        HTTP_GET => run_http_get(args).await,
+        SYSTEM_METRICS => run_system_metrics().await,
+        VAULT_LOOKUP => run_vault_lookup(args).await,
+        name => Err(format!("未知的工具: {}", name)),
+    }
+}
+
+/**
+ * 写入调用记录，机密内容隐藏后保存，失败时仅记录日志
+ */
+async fn record_invocation(
+    request_id: &str,
+    session_id: Option<&str>,
+    call: &ToolCall,
+    output: &str,
+    status: ToolInvocationStatus,
+    duration_ms: u64,
+) {
+    let result = async {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_tool_invocations
+             (id, request_id, session_id, tool, arguments, output, status, duration_ms, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            uuid::Uuid::new_v4().to_string(),
+            request_id,
+            session_id,
+            call.name.as_str(),
+            call.arguments.to_string(),
+            redact_output(&call.name, output),
+            status.as_str(),
+            duration_ms as i64,
+            chrono::Utc::now().timestamp_millis()
+        )
+    }
+    .await;
+    if let Err(e) = result {
+        log::warn!("记录工具调用失败: {}", e);
+    }
+}
+
+/**
+ * 执行模型发起的工具调用
+ *
+ * 按工具的确认策略决定是否需要用户确认，执行结果截断后返回给模型，并写入调用记录
+ *
+ * @param app Tauri应用句柄
+ * @param call 工具调用
+ * @param request_id 对话请求ID
+ * @param session_id 所属会话ID
+ * @return 调用结果与返回给模型的内容
+ */
+pub async fn invoke_tool(
+    app: &AppHandle,
+    call: &ToolCall,
+    request_id: &str,
+    session_id: Option<&str>,
+) -> (ToolInvocationStatus, String) {
+    let started = Instant::now();
+    let (status, output) = async {
+        let policies = match load_policies().await {
+            Ok(policies) => policies,
+            Err(e) => return (ToolInvocationStatus::Error, e),
+        };
+        let Some(spec) = all_tools(app)
+            .await
+            .into_iter()
+            .find(|spec| spec.definition.name == call.name)
+        else {
+            return (
+                ToolInvocationStatus::Error,
+                format!("未知的工具: {}", call.name),
+            );
+        };
+        if !call.arguments.is_object() {
+            return (
+                ToolInvocationStatus::Error,
+                "工具参数不是有效的JSON对象".to_string(),
+            );
+        }
+
+        match effective_policy(&spec, &policies) {
+            ToolPolicy::Disabled => {
+                return (ToolInvocationStatus::Denied, "该工具已被禁用".to_string());
+            }
+            ToolPolicy::Confirm => {
+                if !confirm(app, confirm_message(&spec, &call.arguments)).await {
+                    record_quietly(
+                        AuditEvent::PermissionDenied,
+                        "拒绝AI工具调用",
+                        Some(json!({ "tool": call.name })),
+                    )
+                    .await;
+                    return (ToolInvocationStatus::Denied, "用户拒绝了该操作".to_string());
+                }
+            }
+            ToolPolicy::Allow => {}
+        }
+
+        match execute(app, &spec, &call.arguments).await {
+            Ok(output) => (
+                ToolInvocationStatus::Success,
+                truncate(&output, MAX_OUTPUT_CHARS),
+            ),
+            Err(e) => (ToolInvocationStatus::Error, e),
+        }
+    }
+    .await;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    log::info!(
+        "AI工具调用: {} ({}, {}ms)",
+        call.name,
+        status.as_str(),
+        duration_ms
+    );
+    record_invocation(request_id, session_id, call, &output, status, duration_ms).await;
+    (status, output)
+}
+
+/**
+ * 工具信息
+ */
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    /// 工具定义
+    #[serde(flatten)]
+    pub definition: ToolDefinition,
+    /// 来源
+    pub source: ToolSource,
+    /// 确认策略
+    pub policy: ToolPolicy,
+    /// 设置确认策略时使用的键
+    pub policy_key: String,
+}
+
+/**
+ * 获取全部工具及其确认策略
+ *
+ * @param app Tauri应用句柄
+ * @return 工具列表
+ */
+#[tauri::command]
+pub async fn list_ai_tools(app: AppHandle) -> Result<Vec<ToolInfo>, String> {
+    let policies = load_policies().await?;
+    Ok(all_tools(&app)
+        .await
+        .into_iter()
+        .map(|spec| ToolInfo {
+            policy: effective_policy(&spec, &policies),
+            policy_key: spec.policy_key(),
+            definition: spec.definition,
+            source: spec.source,
+        })
+        .collect())
+}
+
+/**
+ * 设置工具的确认策略
+ *
+ * @param session_id 会话ID
+ * @param key 工具信息中的 policy_key
+ * @param policy 确认策略
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_tool_policy(
+    session_id: String,
+    key: String,
+    policy: ToolPolicy,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+    if key == VAULT_LOOKUP && policy == ToolPolicy::Allow {
+        return Err("密码库查询必须经过确认".to_string());
+    }
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT OR REPLACE INTO ai_tool_settings (name, policy, updated_at) VALUES (?, ?, ?)",
+        key,
+        policy.as_str(),
+        chrono::Utc::now().timestamp_millis()
+    )
+}
+
+/**
+ * 获取允许AI读取的目录
+ *
+ * @return 目录列表
+ */
+#[tauri::command]
+pub async fn get_ai_tool_allowed_dirs() -> Result<Vec<String>, String> {
+    Ok(load_allowed_dirs()
+        .await?
+        .into_iter()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect())
+}
+
+/**
+ * 设置允许AI读取的目录
+ *
+ * @param session_id 会话ID
+ * @param dirs 目录列表，保存解析后的绝对路径
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_tool_allowed_dirs(session_id: String, dirs: Vec<String>) -> Result<(), String> {
+    require_session(&session_id).await?;
+
+    let mut allowed = Vec::with_capacity(dirs.len());
+    for dir in dirs {
+        let path = PathBuf::from(dir.trim())
+            .canonicalize()
+            .map_err(|e| format!("目录不存在: {} ({})", dir, e))?;
+        if !path.is_dir() {
+            return Err(format!("不是目录: {}", dir));
+        }
+        if !allowed.contains(&path) {
+            allowed.push(path);
+        }
+    }
+    let value = serde_json::to_string(&allowed).map_err(|e| e.to_string())?;
+    save_ai_setting(ALLOWED_DIRS_KEY, &value).await?;
+    log::info!("允许AI读取的目录已更新: {} 个", allowed.len());
+    Ok(())
+}
+
+/**
+ * 工具调用记录
+ */
+#[derive(Debug, Serialize)]
+pub struct ToolInvocation {
+    /// 记录ID
+    pub id: String,
+    /// 对话请求ID
+    pub request_id: Option<String>,
+    /// 所属会话ID
+    pub session_id: Option<String>,
+    /// 工具名称
+    pub tool: String,
+    /// 调用参数
+    pub arguments: Value,
+    /// 返回给模型的内容
+    pub output: String,
+    /// 调用结果
+    pub status: String,
+    /// 耗时（毫秒）
+    pub duration_ms: i64,
+    /// 调用时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 查询工具调用记录
+ *
+ * @param session_id 会话ID
+ * @param chat_session_id 只返回该对话会话的记录
+ * @param request_id 只返回该请求的记录
+ * @param limit 最大返回数量
+ * @return 调用记录，按时间倒序
+ */
+#[tauri::command]
+pub async fn list_ai_tool_invocations(
+    session_id: String,
+    chat_session_id: Option<String>,
+    request_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ToolInvocation>, String> {
+    require_session(&session_id).await?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT * FROM ai_tool_invocations
+         WHERE (? IS NULL OR session_id = ?) AND (? IS NULL OR request_id = ?)
+         ORDER BY created_at DESC
+         LIMIT ?",
+        &chat_session_id,
+        &chat_session_id,
+        &request_id,
+        &request_id,
+        limit
+            .filter(|limit| *limit > 0)
+            .unwrap_or(DEFAULT_INVOCATION_LIMIT)
+    )?;
+    Ok(rows
+        .iter()
+        .map(|row| ToolInvocation {
+            id: row.get("id"),
+            request_id: row.get("request_id"),
+            session_id: row.get("session_id"),
+            tool: row.get("tool"),
+            arguments: serde_json::from_str(&row.get::<String, _>("arguments"))
+                .unwrap_or(Value::Null),
+            output: row.get("output"),
+            status: row.get("status"),
+            duration_ms: row.get("duration_ms"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_vault_passwords() {
+        let output = r#"[{"title":"mail","username":"a","password":"secret"},{"title":"b"}]"#;
+        let redacted = redact_output(VAULT_LOOKUP, output);
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains(REDACTED));
+        assert!(redacted.contains("mail"));
+    }
+
+    #[test]
+    fn mcp_policies_are_keyed_by_server_and_tool() {
+        let spec = |server: &str, binding: &str| ToolSpec {
+            definition: ToolDefinition {
+                name: binding.to_string(),
+                description: String::new(),
+                parameters: json!({}),
+            },
+            source: ToolSource::Mcp {
+                server_id: server.to_string(),
+                tool: "search".to_string(),
+            },
+            default_policy: ToolPolicy::Confirm,
+        };
+        let policies = HashMap::from([("mcp:s1:search".to_string(), ToolPolicy::Allow)]);
+
+        // 服务器改名后绑定名称变化，策略仍然生效
+        assert_eq!(
+            effective_policy(&spec("s1", "mcp_renamed_search"), &policies),
+            ToolPolicy::Allow
+        );
+        // 其他服务器占用了原来的绑定名称时不会继承策略
+        let policies = HashMap::from([("mcp_old_search".to_string(), ToolPolicy::Allow)]);
+        assert_eq!(
+            effective_policy(&spec("s2", "mcp_old_search"), &policies),
+            ToolPolicy::Confirm
+        );
+    }
+
+    #[test]
+    fn keeps_other_outputs() {
+        let output = r#"[{"password":"not a vault entry"}]"#;
+        assert_eq!(redact_output(READ_FILE, output), output);
+        assert_eq!(redact_output(VAULT_LOOKUP, "密码库已锁定"), "密码库已锁定");
+    }
+}
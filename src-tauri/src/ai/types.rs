@@ -0,0 +1,404 @@
+/**
+ * AI 网关类型定义
+ */
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/**
+ * AI 提供商，与前端 AIProvider 及 ai_provider_configs.provider 一致
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// OpenAI 及兼容接口
+    OpenAi,
+    /// Anthropic Claude
+    Claude,
+    /// 通义千问（DashScope）
+    Qianwen,
+    /// 本地 Ollama
+    Ollama,
+    /// DeepSeek
+    DeepSeek,
+}
+
+impl ProviderKind {
+    /// 全部提供商
+    pub const ALL: [ProviderKind; 5] = [
+        ProviderKind::OpenAi,
+        ProviderKind::Claude,
+        ProviderKind::Qianwen,
+        ProviderKind::Ollama,
+        ProviderKind::DeepSeek,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Claude => "claude",
+            ProviderKind::Qianwen => "qianwen",
+            ProviderKind::Ollama => "ollama",
+            ProviderKind::DeepSeek => "deepseek",
+        }
+    }
+
+    /// 默认接口地址，与前端 DEFAULT_AI_PROVIDERS 一致
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "https://api.openai.com/v1",
+            ProviderKind::Claude => "https://api.anthropic.com",
+            ProviderKind::Qianwen => "https://dashscope.aliyuncs.com/api/v1",
+            ProviderKind::Ollama => "http://localhost:11434",
+            ProviderKind::DeepSeek => "https://api.deepseek.com/v1",
+        }
+    }
+
+    /// 是否需要 API 密钥
+    pub fn requires_api_key(&self) -> bool {
+        *self != ProviderKind::Ollama
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        ProviderKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| format!("未知的AI提供商: {}", value))
+    }
+}
+
+/**
+ * 消息角色
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+    /// 工具调用结果
+    Tool,
+}
+
+impl ChatRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatRole::System => "system",
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+            ChatRole::Tool => "tool",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "system" => Ok(ChatRole::System),
+            "user" => Ok(ChatRole::User),
+            "assistant" => Ok(ChatRole::Assistant),
+            "tool" => Ok(ChatRole::Tool),
+            _ => Err(format!("未知的消息角色: {}", value)),
+        }
+    }
+}
+
+/**
+ * 模型发起的工具调用
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolCall {
+    /// 调用ID，工具结果通过该ID与调用对应
+    pub id: String,
+    /// 工具名称
+    pub name: String,
+    /// 调用参数，模型返回的参数不是有效 JSON 时为原始字符串
+    pub arguments: Value,
+}
+
+/**
+ * 对话消息
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    /// 角色
+    pub role: ChatRole,
+    /// 文本内容
+    pub content: String,
+    /// 助手消息中的工具调用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// 工具结果对应的调用ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// 工具结果对应的工具名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 附件ID，发送前由网关加载
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// 网关加载的图片，不由前端传入
+    #[serde(skip)]
+    pub images: Vec<ImageContent>,
+}
+
+impl ChatMessage {
+    /// 纯文本消息
+    pub fn text(role: ChatRole, content: impl Into<String>) -> Self {
+        ChatMessage {
+            role,
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+            name: None,
+            attachments: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+}
+
+/**
+ * 消息中的图片
+ */
+#[derive(Clone)]
+pub struct ImageContent {
+    /// MIME 类型
+    pub mime_type: String,
+    /// base64 编码的内容
+    pub data: String,
+    /// 宽度
+    pub width: u32,
+    /// 高度
+    pub height: u32,
+}
+
+impl ImageContent {
+    /// data URL 形式的图片
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+}
+
+// 图片内容较大，调试输出中省略
+impl fmt::Debug for ImageContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageContent")
+            .field("mime_type", &self.mime_type)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/**
+ * 提供给模型的工具定义
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct ToolDefinition {
+    /// 工具名称，只包含字母、数字、下划线与连字符
+    pub name: String,
+    /// 工具说明
+    pub description: String,
+    /// 参数的 JSON Schema
+    pub parameters: Value,
+}
+
+/**
+ * 对话请求
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatRequest {
+    /// 提供商
+    pub provider: ProviderKind,
+    /// 模型ID
+    pub model: String,
+    /// 消息列表
+    pub messages: Vec<ChatMessage>,
+    /// 采样温度
+    pub temperature: Option<f32>,
+    /// 最大生成 token 数
+    pub max_tokens: Option<u32>,
+    /// 是否流式返回，默认是
+    pub stream: Option<bool>,
+    /// 所属会话ID，messages 中的非系统消息须与会话消息按顺序一一对应
+    pub session_id: Option<String>,
+    /// 超出上下文长度时的处理方式，默认裁剪
+    pub context_strategy: Option<ContextStrategy>,
+    /// 是否允许模型调用已启用的工具，默认否
+    pub use_tools: Option<bool>,
+    /// 检索的知识库，检索结果会附在系统消息中
+    #[serde(default)]
+    pub knowledge_base_ids: Vec<String>,
+    /// 检索的片段数
+    pub knowledge_top_k: Option<usize>,
+}
+
+/**
+ * 超出上下文长度时的处理方式
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextStrategy {
+    /// 丢弃最早的消息
+    #[default]
+    Trim,
+    /// 将较早的消息压缩为摘要，摘要保存在会话中供后续请求复用
+    Summarize,
+}
+
+/**
+ * 单次请求的 token 估算明细
+ */
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+pub struct TokenBreakdown {
+    /// 模型上下文长度
+    pub context_window: u64,
+    /// 为回复预留的 token 数
+    pub reserved_output: u64,
+    /// 系统消息
+    pub system_tokens: u64,
+    /// 历史摘要
+    pub summary_tokens: u64,
+    /// 保留的对话消息
+    pub history_tokens: u64,
+    /// 实际发送的输入 token 总数，包含消息格式的开销
+    pub prompt_tokens: u64,
+    /// 被丢弃的消息数
+    pub dropped_messages: usize,
+    /// 由摘要替代的消息数
+    pub summarized_messages: usize,
+}
+
+/**
+ * token 用量
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct ChatUsage {
+    /// 输入 token 数
+    pub prompt_tokens: u64,
+    /// 输出 token 数
+    pub completion_tokens: u64,
+}
+
+impl ChatUsage {
+    /**
+     * 合并流式响应中分段返回的用量，非零字段覆盖原值
+     *
+     * @param other 新的用量
+     */
+    pub fn merge(&mut self, other: &ChatUsage) {
+        if other.prompt_tokens > 0 {
+            self.prompt_tokens = other.prompt_tokens;
+        }
+        if other.completion_tokens > 0 {
+            self.completion_tokens = other.completion_tokens;
+        }
+    }
+
+    /**
+     * 累加多次请求的用量
+     *
+     * @param other 另一次请求的用量
+     */
+    pub fn add(&mut self, other: &ChatUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/**
+ * 推送给前端的流式事件
+ */
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ChatStreamEvent {
+    /// 上下文已准备好，即将发送请求
+    Context { tokens: TokenBreakdown },
+    /// 已连接，开始接收响应
+    Started { request_id: String },
+    /// 新生成的文本片段
+    Delta { text: String },
+    /// 模型请求调用工具
+    ToolCall { call: ToolCall },
+    /// 工具调用结束
+    ToolResult {
+        id: String,
+        name: String,
+        status: ToolInvocationStatus,
+        output: String,
+    },
+    /// 检索到的知识库片段
+    Citations { citations: Vec<KnowledgeCitation> },
+    /// 生成结束
+    Finished { usage: Option<ChatUsage> },
+}
+
+/**
+ * 对话结果
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct ChatResult {
+    /// 请求ID
+    pub request_id: String,
+    /// 完整回复（取消时为已生成的部分）
+    pub content: String,
+    /// token 用量，提供商未返回时为空
+    pub usage: Option<ChatUsage>,
+    /// 发送前的 token 估算明细
+    pub tokens: Option<TokenBreakdown>,
+    /// 尚未执行的工具调用，仅在达到工具调用轮数上限时非空
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// 引用的知识库片段
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<KnowledgeCitation>,
+    /// 是否被取消
+    pub cancelled: bool,
+}
+
+/**
+ * 检索到的知识库片段
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct KnowledgeCitation {
+    /// 引用编号，从 1 开始，与提示词中的 [编号] 一致
+    pub index: usize,
+    /// 知识库ID
+    pub kb_id: String,
+    /// 文档ID
+    pub document_id: String,
+    /// 文档标题
+    pub title: String,
+    /// 文档路径
+    pub path: String,
+    /// 所在小节的标题
+    pub heading: Option<String>,
+    /// 片段在文档中的序号
+    pub chunk_seq: i64,
+    /// 余弦相似度
+    pub score: f32,
+    /// 片段内容
+    pub content: String,
+}
+
+/**
+ * 工具调用结果
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolInvocationStatus {
+    /// 执行成功
+    Success,
+    /// 执行失败
+    Error,
+    /// 用户拒绝或工具已禁用
+    Denied,
+}
+
+impl ToolInvocationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolInvocationStatus::Success => "success",
+            ToolInvocationStatus::Error => "error",
+            ToolInvocationStatus::Denied => "denied",
+        }
+    }
+}
@@ -0,0 +1,601 @@
+/**
+ * 用量与费用统计
+ *
+ * 每次 AI 请求结束后写入 ai_usage_records，token 数优先使用提供商返回的用量，
+ * 未返回时使用估算值。费用按写入时 ai_model_prices 中的单价计算（每百万 token，
+ * 统一使用记账货币），之后修改单价不影响已有记录。设置了月度预算时，本月费用
+ * 达到提醒比例或超出预算会发送系统通知，每个月每个级别只提醒一次
+ */
+use super::config::{load_ai_setting, save_ai_setting};
+use super::tokens::estimate_text_tokens;
+use super::types::{ChatRequest, ChatResult, ChatUsage, ProviderKind};
+use chrono::{Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// 月度预算在 ai_settings 中的键名
+const BUDGET_SETTING_KEY: &str = "usage_monthly_budget";
+/// 已发送的预算提醒在 ai_settings 中的键名，值为 "月份:级别"
+const BUDGET_ALERTED_KEY: &str = "usage_budget_alerted";
+/// 默认的预算提醒比例（百分比）
+const DEFAULT_ALERT_PERCENT: u32 = 80;
+/// 适用于提供商全部模型的单价记录
+const ANY_MODEL: &str = "*";
+
+/**
+ * 请求结果
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageStatus {
+    Success,
+    Cancelled,
+    Error,
+}
+
+impl UsageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageStatus::Success => "success",
+            UsageStatus::Cancelled => "cancelled",
+            UsageStatus::Error => "error",
+        }
+    }
+}
+
+/**
+ * 一次请求的用量
+ */
+pub struct UsageRecord {
+    /// 请求ID，内部请求（如生成摘要）为空
+    pub request_id: Option<String>,
+    /// 提供商
+    pub provider: ProviderKind,
+    /// 模型ID
+    pub model: String,
+    /// 所属会话ID
+    pub session_id: Option<String>,
+    /// token 用量
+    pub usage: ChatUsage,
+    /// token 数是否为估算值
+    pub estimated: bool,
+    /// 耗时（毫秒）
+    pub latency_ms: u64,
+    /// 请求结果
+    pub status: UsageStatus,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+/**
+ * 查询模型单价，未单独设置时使用提供商的通配单价
+ *
+ * @return (输入单价, 输出单价)，每百万 token
+ */
+async fn load_price(provider: ProviderKind, model: &str) -> Result<(f64, f64), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let row = sqlx::query(
+        "SELECT prompt_price, completion_price FROM ai_model_prices
+         WHERE provider = ? AND model IN (?, ?)
+         ORDER BY model = ? LIMIT 1",
+    )
+    .bind(provider.as_str())
+    .bind(model)
+    .bind(ANY_MODEL)
+    .bind(ANY_MODEL)
+    .fetch_optional(db.get_pool())
+    .await
+    .map_err(|e| format!("查询模型单价失败: {}", e))?;
+    Ok(row.map_or((0.0, 0.0), |row| {
+        (row.get("prompt_price"), row.get("completion_price"))
+    }))
+}
+
+/**
+ * 写入一次请求的用量，并检查月度预算
+ *
+ * @param app Tauri应用句柄
+ * @param record 用量
+ * @return 操作结果
+ */
+pub async fn record_usage(app: &AppHandle, record: UsageRecord) -> Result<(), String> {
+    let (prompt_price, completion_price) = load_price(record.provider, &record.model).await?;
+    let cost = (record.usage.prompt_tokens as f64 * prompt_price
+        + record.usage.completion_tokens as f64 * completion_price)
+        / 1_000_000.0;
+
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO ai_usage_records
+             (request_id, provider, model, session_id, prompt_tokens, completion_tokens,
+              estimated, latency_ms, status, error, cost, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            record.request_id,
+            record.provider.as_str(),
+            record.model,
+            record.session_id,
+            record.usage.prompt_tokens as i64,
+            record.usage.completion_tokens as i64,
+            record.estimated as i64,
+            record.latency_ms as i64,
+            record.status.as_str(),
+            record.error,
+            cost,
+            chrono::Utc::now().timestamp_millis()
+        )?;
+    }
+
+    if cost > 0.0 {
+        check_budget(app).await?;
+    }
+    Ok(())
+}
+
+/**
+ * 写入用量，失败时仅记录日志
+ *
+ * 统计失败不应影响对话本身
+ *
+ * @param app Tauri应用句柄
+ * @param record 用量
+ */
+pub async fn record_usage_quietly(app: &AppHandle, record: UsageRecord) {
+    if let Err(e) = record_usage(app, record).await {
+        log::warn!("记录AI用量失败: {}", e);
+    }
+}
+
+/**
+ * 记录一次对话请求的用量
+ *
+ * 提供商未返回用量时使用估算值，失败的请求不计 token
+ *
+ * @param app Tauri应用句柄
+ * @param request 对话请求，用于确定提供商、模型和会话
+ * @param result 请求结果
+ * @param prompt_tokens 提供商未返回用量时使用的输入 token 估算值
+ * @param request_id 请求ID，内部请求（如生成摘要）为空
+ * @param latency_ms 耗时（毫秒）
+ */
+pub async fn record_chat_usage(
+    app: &AppHandle,
+    request: &ChatRequest,
+    result: &Result<ChatResult, String>,
+    prompt_tokens: u64,
+    request_id: Option<String>,
+    latency_ms: u64,
+) {
+    let (usage, estimated, status, error) = match result {
+        Ok(result) => {
+            let status = if result.cancelled {
+                UsageStatus::Cancelled
+            } else {
+                UsageStatus::Success
+            };
+            match result.usage {
+                Some(usage) => (usage, false, status, None),
+                None => {
+                    let usage = ChatUsage {
+                        prompt_tokens,
+                        completion_tokens: estimate_text_tokens(request.provider, &result.content),
+                    };
+                    (usage, true, status, None)
+                }
+            }
+        }
+        Err(e) => (
+            ChatUsage::default(),
+            true,
+            UsageStatus::Error,
+            Some(e.clone()),
+        ),
+    };
+    record_usage_quietly(
+        app,
+        UsageRecord {
+            request_id,
+            provider: request.provider,
+            model: request.model.clone(),
+            session_id: request.session_id.clone(),
+            usage,
+            estimated,
+            latency_ms,
+            status,
+            error,
+        },
+    )
+    .await;
+}
+
+/**
+ * 模型单价
+ */
+#[derive(Debug, Serialize)]
+pub struct ModelPrice {
+    /// 提供商
+    pub provider: String,
+    /// 模型ID，"*" 表示该提供商的全部模型
+    pub model: String,
+    /// 输入单价（每百万 token）
+    pub prompt_price: f64,
+    /// 输出单价（每百万 token）
+    pub completion_price: f64,
+    /// 更新时间（毫秒）
+    pub updated_at: i64,
+}
+
+/**
+ * 获取全部模型单价
+ *
+ * @return 单价列表
+ */
+#[tauri::command]
+pub async fn list_ai_model_prices() -> Result<Vec<ModelPrice>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = sqlx::query("SELECT * FROM ai_model_prices ORDER BY provider, model")
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询模型单价失败: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| ModelPrice {
+            provider: row.get("provider"),
+            model: row.get("model"),
+            prompt_price: row.get("prompt_price"),
+            completion_price: row.get("completion_price"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect())
+}
+
+/**
+ * 设置模型单价
+ *
+ * @param provider 提供商
+ * @param model 模型ID，"*" 表示该提供商的全部模型
+ * @param prompt_price 输入单价（每百万 token）
+ * @param completion_price 输出单价（每百万 token）
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_model_price(
+    provider: ProviderKind,
+    model: String,
+    prompt_price: f64,
+    completion_price: f64,
+) -> Result<(), String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("未指定模型".to_string());
+    }
+    if !prompt_price.is_finite()
+        || !completion_price.is_finite()
+        || prompt_price < 0.0
+        || completion_price < 0.0
+    {
+        return Err("单价无效".to_string());
+    }
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO ai_model_prices (provider, model, prompt_price, completion_price, updated_at)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(provider, model) DO UPDATE SET prompt_price = excluded.prompt_price,
+                                                    completion_price = excluded.completion_price,
+                                                    updated_at = excluded.updated_at",
+        provider.as_str(),
+        model,
+        prompt_price,
+        completion_price,
+        chrono::Utc::now().timestamp_millis()
+    )
+}
+
+/**
+ * 删除模型单价
+ *
+ * @param provider 提供商
+ * @param model 模型ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn delete_ai_model_price(provider: ProviderKind, model: String) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "DELETE FROM ai_model_prices WHERE provider = ? AND model = ?",
+        provider.as_str(),
+        model
+    )
+}
+
+/**
+ * 用量统计的分组方式
+ */
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    /// 按本地日期
+    Day,
+    Provider,
+    Model,
+    Session,
+}
+
+/**
+ * 一组用量合计
+ */
+#[derive(Debug, Serialize)]
+pub struct UsageTotal {
+    /// 分组键：日期（YYYY-MM-DD）、提供商、模型ID或会话ID
+    pub key: String,
+    /// 按模型分组时为提供商，按会话分组时为会话标题
+    pub label: Option<String>,
+    /// 请求数
+    pub requests: i64,
+    /// 失败的请求数
+    pub errors: i64,
+    /// 输入 token 数
+    pub prompt_tokens: i64,
+    /// 输出 token 数
+    pub completion_tokens: i64,
+    /// 费用
+    pub cost: f64,
+    /// 平均耗时（毫秒）
+    pub avg_latency_ms: f64,
+}
+
+/**
+ * 按分组统计用量
+ *
+ * @param group_by 分组方式
+ * @param start 起始时间（毫秒，含）
+ * @param end 结束时间（毫秒，不含）
+ * @return 用量合计，按日期分组时按日期倒序，其余按费用倒序
+ */
+#[tauri::command]
+pub async fn get_ai_usage_summary(
+    group_by: UsageGroupBy,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<Vec<UsageTotal>, String> {
+    let (key, label, join, order) = match group_by {
+        UsageGroupBy::Day => (
+            "strftime('%Y-%m-%d', u.created_at / 1000, 'unixepoch', 'localtime')",
+            None,
+            "",
+            "group_key DESC",
+        ),
+        UsageGroupBy::Provider => ("u.provider", None, "", "cost DESC"),
+        UsageGroupBy::Model => ("u.model", Some("u.provider"), "", "cost DESC"),
+        UsageGroupBy::Session => (
+            "u.session_id",
+            Some("s.title"),
+            "LEFT JOIN ai_chat_sessions s ON s.id = u.session_id",
+            "cost DESC",
+        ),
+    };
+    let group = match label {
+        Some(label) => format!("{}, {}", key, label),
+        None => key.to_string(),
+    };
+    // 按会话分组时忽略不属于会话的请求
+    let session_filter = match group_by {
+        UsageGroupBy::Session => "AND u.session_id IS NOT NULL",
+        _ => "",
+    };
+    let sql = format!(
+        "SELECT {key} AS group_key, {label} AS group_label, COUNT(*) AS requests,
+                SUM(u.status = 'error') AS errors,
+                SUM(u.prompt_tokens) AS prompt_tokens,
+                SUM(u.completion_tokens) AS completion_tokens,
+                SUM(u.cost) AS cost, AVG(u.latency_ms) AS avg_latency_ms
+         FROM ai_usage_records u {join}
+         WHERE u.created_at >= ? AND u.created_at < ? {session_filter}
+         GROUP BY {group}
+         ORDER BY {order}",
+        label = label.unwrap_or("NULL"),
+    );
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = sqlx::query(&sql)
+        .bind(start.unwrap_or(0))
+        .bind(end.unwrap_or(i64::MAX))
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询AI用量失败: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| UsageTotal {
+            key: row.get("group_key"),
+            label: row.get("group_label"),
+            requests: row.get("requests"),
+            errors: row.get("errors"),
+            prompt_tokens: row.get("prompt_tokens"),
+            completion_tokens: row.get("completion_tokens"),
+            cost: row.get("cost"),
+            avg_latency_ms: row.get("avg_latency_ms"),
+        })
+        .collect())
+}
+
+/**
+ * 月度预算设置
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct UsageBudget {
+    /// 每月预算，使用记账货币
+    pub monthly_limit: f64,
+    /// 费用达到预算的该百分比时提醒
+    pub alert_percent: u32,
+}
+
+/**
+ * 本月预算使用情况
+ */
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    /// 预算设置，未设置时为空
+    pub budget: Option<UsageBudget>,
+    /// 月份（YYYY-MM）
+    pub month: String,
+    /// 本月费用
+    pub month_cost: f64,
+}
+
+async fn load_budget() -> Result<Option<UsageBudget>, String> {
+    Ok(load_ai_setting(BUDGET_SETTING_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok()))
+}
+
+/**
+ * 查询本月的费用
+ *
+ * @return (月份, 本月费用)
+ */
+async fn month_cost() -> Result<(String, f64), String> {
+    let now = Local::now();
+    let month_start = Local
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .earliest()
+        .ok_or_else(|| "计算月份起始时间失败".to_string())?
+        .timestamp_millis();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let cost: f64 = sqlx::query(
+        "SELECT COALESCE(SUM(cost), 0.0) AS cost FROM ai_usage_records WHERE created_at >= ?",
+    )
+    .bind(month_start)
+    .fetch_one(db.get_pool())
+    .await
+    .map_err(|e| format!("查询AI用量失败: {}", e))?
+    .get("cost");
+    Ok((now.format("%Y-%m").to_string(), cost))
+}
+
+/**
+ * 检查本月费用，达到提醒比例或超出预算时发送通知
+ *
+ * @param app Tauri应用句柄
+ * @return 操作结果
+ */
+async fn check_budget(app: &AppHandle) -> Result<(), String> {
+    let Some(budget) = load_budget().await? else {
+        return Ok(());
+    };
+    if budget.monthly_limit <= 0.0 {
+        return Ok(());
+    }
+
+    let (month, cost) = month_cost().await?;
+    let percent = cost / budget.monthly_limit * 100.0;
+    let level = if percent >= 100.0 {
+        100
+    } else if percent >= budget.alert_percent as f64 {
+        budget.alert_percent
+    } else {
+        return Ok(());
+    };
+
+    // 本月已提醒过同级或更高级别时不再提醒
+    let alerted = load_ai_setting(BUDGET_ALERTED_KEY)
+        .await?
+        .and_then(|value| {
+            let (alerted_month, level) = value.split_once(':')?;
+            (alerted_month == month).then(|| level.parse::<u32>().ok())?
+        })
+        .unwrap_or(0);
+    if level <= alerted {
+        return Ok(());
+    }
+    save_ai_setting(BUDGET_ALERTED_KEY, &format!("{}:{}", month, level)).await?;
+
+    let (title, body) = if level >= 100 {
+        (
+            "AI费用已超出月度预算",
+            format!("本月费用 {:.2}，预算 {:.2}", cost, budget.monthly_limit),
+        )
+    } else {
+        (
+            "AI费用接近月度预算",
+            format!(
+                "本月费用 {:.2}，已达到预算 {:.2} 的 {:.0}%",
+                cost, budget.monthly_limit, percent
+            ),
+        )
+    };
+    log::warn!("{}: {}", title, body);
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("发送通知失败: {}", e);
+    }
+    Ok(())
+}
+
+/**
+ * 获取本月预算使用情况
+ *
+ * @return 预算使用情况
+ */
+#[tauri::command]
+pub async fn get_ai_usage_budget() -> Result<BudgetStatus, String> {
+    let budget = load_budget().await?;
+    let (month, month_cost) = month_cost().await?;
+    Ok(BudgetStatus {
+        budget,
+        month,
+        month_cost,
+    })
+}
+
+/**
+ * 设置月度预算
+ *
+ * @param monthly_limit 每月预算，为空或不大于0时关闭预算提醒
+ * @param alert_percent 提醒比例（百分比），默认 80
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_ai_usage_budget(
+    monthly_limit: Option<f64>,
+    alert_percent: Option<u32>,
+) -> Result<(), String> {
+    // 修改预算后重新判断是否需要提醒
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "DELETE FROM ai_settings WHERE key IN (?, ?)",
+            BUDGET_SETTING_KEY,
+            BUDGET_ALERTED_KEY
+        )?;
+    }
+    let Some(monthly_limit) = monthly_limit.filter(|limit| limit.is_finite() && *limit > 0.0)
+    else {
+        return Ok(());
+    };
+    let alert_percent = alert_percent.unwrap_or(DEFAULT_ALERT_PERCENT);
+    if !(1..=100).contains(&alert_percent) {
+        return Err("提醒比例应在 1 到 100 之间".to_string());
+    }
+
+    let budget = UsageBudget {
+        monthly_limit,
+        alert_percent,
+    };
+    let value = serde_json::to_string(&budget).map_err(|e| e.to_string())?;
+    save_ai_setting(BUDGET_SETTING_KEY, &value).await
+}
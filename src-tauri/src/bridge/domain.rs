@@ -0,0 +1,64 @@
+/**
+ * 网址域名匹配
+ *
+ * 条目网址与页面网址都先规范化为主机名（小写、去掉 www. 前缀和端口），
+ * 页面主机名与条目主机名相同或是其子域名时视为匹配
+ */
+use url::Url;
+
+/**
+ * 规范化网址中的主机名
+ *
+ * 未带协议的网址按 https 处理
+ *
+ * @param input 网址
+ * @return 规范化后的主机名，无法解析时为空
+ */
+pub fn normalize_host(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let url = if input.contains("://") {
+        Url::parse(input).ok()?
+    } else {
+        Url::parse(&format!("https://{}", input)).ok()?
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    if host.is_empty() {
+        return None;
+    }
+    Some(host.to_string())
+}
+
+/**
+ * 判断页面主机名是否匹配条目主机名
+ *
+ * @param entry_host 条目主机名
+ * @param page_host 页面主机名
+ * @return 是否匹配
+ */
+pub fn host_matches(entry_host: &str, page_host: &str) -> bool {
+    page_host == entry_host
+        || page_host
+            .strip_suffix(entry_host)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/**
+ * 判断条目网址是否匹配页面主机名
+ *
+ * @param entry_url 条目网址
+ * @param page_host 页面主机名
+ * @return 是否匹配
+ */
+pub fn url_matches(entry_url: Option<&str>, page_host: &str) -> bool {
+    entry_url
+        .and_then(normalize_host)
+        .is_some_and(|entry_host| host_matches(&entry_host, page_host))
+}
@@ -0,0 +1,14 @@
+/**
+ * 浏览器桥接模块
+ *
+ * 浏览器扩展通过原生消息宿主（taiasst-native-host）与应用通信，实现自动填充。
+ * 应用与宿主之间的消息格式、签名认证和加密通道由 taiasst-bridge crate 提供，
+ * 宿主程序以 sidecar 方式随应用打包：
+ * - domain: 网址域名的规范化与匹配
+ * - server: 回环地址上的桥接服务、配对管理
+ */
+mod domain;
+mod server;
+
+// 导出公开API
+pub use server::*;
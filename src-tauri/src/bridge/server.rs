@@ -0,0 +1,625 @@
+/**
+ * 浏览器桥接服务
+ *
+ * 在回环地址上监听原生消息宿主的连接，监听端口写入应用数据目录中的
+ * browser-bridge.json。宿主需先配对并在每次连接时完成双向签名认证，
+ * 之后才能通过加密通道按网址查找登录条目；返回用户名和密码前需用户在应用中确认
+ */
+use super::domain::{normalize_host, url_matches};
+use crate::file::paths::get_data_dir;
+use crate::password::fields::load_totp_secret;
+use crate::password::store::{get_entry, set_entry_last_used};
+use crate::password::totp::current_totp;
+use crate::password::types::EntryType;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use crate::security::storage::{
+    delete_secret, get_secret, list_secrets, set_secret, EncryptionLevel,
+};
+use crate::security::vault;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use rsa::RsaPrivateKey;
+use serde::Serialize;
+use sqlx::Row;
+use std::time::Duration;
+use taiasst_bridge::{
+    app_proof, decode_frame, encode_frame, frame_len, host_proof, key_fingerprint,
+    public_key_from_pem, public_key_to_pem, random_nonce, sign, unwrap_session_key, verify,
+    BridgeEntry, BridgeInfo, BridgeMessage, Role, SecureChannel, BRIDGE_INFO_FILE, RSA_KEY_BITS,
+};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 应用私钥在加密存储中的键名
+const APP_KEY_SECRET: &str = "browser.app_private_key";
+/// 已配对宿主公钥在加密存储中的键名前缀
+const PAIRING_PREFIX: &str = "browser.paired.";
+/// 原生消息宿主名称
+const NATIVE_HOST_NAME: &str = "com.teexue.taiasst";
+/// 原生消息宿主程序名称
+const NATIVE_HOST_BINARY: &str = "taiasst-native-host";
+/// 等待用户确认的最长时间（秒）
+const CONFIRM_TIMEOUT_SECS: u64 = 60;
+/// 连接空闲超时（秒）
+const IDLE_TIMEOUT_SECS: u64 = 120;
+
+/**
+ * 已配对的浏览器扩展
+ */
+#[derive(Debug, Serialize)]
+pub struct BrowserPairing {
+    /// 宿主公钥指纹
+    pub fingerprint: String,
+    /// 配对时间（秒）
+    pub paired_at: i64,
+}
+
+/// 已配对宿主的存储键名
+fn pairing_key(fingerprint: &str) -> Result<String, String> {
+    if fingerprint.len() != 64 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("公钥指纹格式无效".to_string());
+    }
+    Ok(format!("{}{}", PAIRING_PREFIX, fingerprint.to_lowercase()))
+}
+
+/// 展示给用户核对的短指纹
+fn short_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .take(16)
+        .collect::<Vec<_>>()
+        .chunks(4)
+        .map(|c| c.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/**
+ * 读取或生成应用的 RSA 私钥
+ *
+ * 私钥以设备密钥加密保存，密码库锁定时也可用于认证宿主
+ *
+ * @param app Tauri应用句柄
+ * @return 应用私钥
+ */
+async fn app_key(app: &AppHandle) -> Result<RsaPrivateKey, String> {
+    if let Some(pem) = get_secret(app.clone(), APP_KEY_SECRET).await? {
+        return RsaPrivateKey::from_pkcs8_pem(&pem).map_err(|e| format!("应用私钥无效: {}", e));
+    }
+
+    let key =
+        tokio::task::spawn_blocking(|| RsaPrivateKey::new(&mut rand::rngs::OsRng, RSA_KEY_BITS))
+            .await
+            .map_err(|e| format!("生成应用密钥失败: {}", e))?
+            .map_err(|e| format!("生成应用密钥失败: {}", e))?;
+    let pem = key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| format!("编码应用私钥失败: {}", e))?;
+    set_secret(
+        app.clone(),
+        APP_KEY_SECRET,
+        &pem,
+        EncryptionLevel::Device,
+        None,
+    )
+    .await?;
+    log::info!("已生成浏览器桥接密钥");
+    Ok(key)
+}
+
+/**
+ * 读取一条消息内容
+ */
+async fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut header = [0u8; 4];
+    tokio::time::timeout(
+        Duration::from_secs(IDLE_TIMEOUT_SECS),
+        stream.read_exact(&mut header),
+    )
+    .await
+    .map_err(|_| "等待消息超时".to_string())?
+    .map_err(|e| format!("读取消息失败: {}", e))?;
+
+    let mut body = vec![0u8; frame_len(header)?];
+    stream
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| format!("读取消息失败: {}", e))?;
+    Ok(body)
+}
+
+/**
+ * 读取一条明文消息
+ */
+async fn read_message(stream: &mut TcpStream) -> Result<BridgeMessage, String> {
+    decode_frame(&read_frame(stream).await?)
+}
+
+/**
+ * 读取一条加密消息
+ */
+async fn read_sealed(
+    stream: &mut TcpStream,
+    channel: &mut SecureChannel,
+) -> Result<BridgeMessage, String> {
+    channel.open(&read_frame(stream).await?)
+}
+
+/**
+ * 发送一条明文消息
+ */
+async fn write_message(stream: &mut TcpStream, message: &BridgeMessage) -> Result<(), String> {
+    write_frame(stream, &encode_frame(message)?).await
+}
+
+/**
+ * 发送编码后的消息
+ */
+async fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> Result<(), String> {
+    stream
+        .write_all(frame)
+        .await
+        .map_err(|e| format!("发送消息失败: {}", e))
+}
+
+/**
+ * 弹出对话框请求用户确认
+ *
+ * @param app Tauri应用句柄
+ * @param title 标题
+ * @param message 内容
+ * @return 用户是否允许
+ */
+async fn confirm(app: &AppHandle, title: &str, message: String) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title(title)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "允许".to_string(),
+            "拒绝".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+
+    matches!(
+        tokio::time::timeout(Duration::from_secs(CONFIRM_TIMEOUT_SECS), rx).await,
+        Ok(Ok(true))
+    )
+}
+
+/// 检查密码库是否已解锁
+fn ensure_unlocked() -> Result<(), String> {
+    if vault::is_unlocked() {
+        Ok(())
+    } else {
+        Err("密码库已锁定，请先在应用中解锁".to_string())
+    }
+}
+
+/**
+ * 处理配对请求
+ *
+ * @param app Tauri应用句柄
+ * @param public_key 宿主公钥（PEM）
+ * @return 包含应用公钥的响应
+ */
+async fn handle_pair(app: &AppHandle, public_key: &str) -> Result<BridgeMessage, String> {
+    let host_key = public_key_from_pem(public_key)?;
+    let fingerprint = key_fingerprint(&host_key)?;
+    let key = pairing_key(&fingerprint)?;
+
+    let allowed = confirm(
+        app,
+        "浏览器扩展配对",
+        format!(
+            "是否允许浏览器扩展连接到本应用？\n\n请确认扩展中显示的指纹一致: {}",
+            short_fingerprint(&fingerprint)
+        ),
+    )
+    .await;
+    if !allowed {
+        record_quietly(
+            AuditEvent::PermissionDenied,
+            "拒绝浏览器扩展配对",
+            Some(serde_json::json!({ "fingerprint": fingerprint })),
+        )
+        .await;
+        return Err("用户拒绝了配对请求".to_string());
+    }
+
+    set_secret(
+        app.clone(),
+        &key,
+        &public_key_to_pem(&host_key)?,
+        EncryptionLevel::Device,
+        Some(&serde_json::json!({
+            "fingerprint": fingerprint,
+            "paired_at": chrono::Utc::now().timestamp(),
+        })),
+    )
+    .await?;
+    log::info!("浏览器扩展已配对: {}", short_fingerprint(&fingerprint));
+
+    let app_public_key = public_key_to_pem(&app_key(app).await?.to_public_key())?;
+    Ok(BridgeMessage::Paired { app_public_key })
+}
+
+/**
+ * 与已配对的宿主进行双向认证
+ *
+ * 应用对宿主随机数签名证明自身身份，宿主对应用随机数和加密的会话密钥签名
+ * 证明持有配对私钥，之后的消息以会话密钥加密
+ *
+ * @param app Tauri应用句柄
+ * @param stream 连接
+ * @param fingerprint 宿主公钥指纹
+ * @param host_nonce 宿主随机数
+ * @return 加密通道
+ */
+async fn authenticate(
+    app: &AppHandle,
+    stream: &mut TcpStream,
+    fingerprint: &str,
+    host_nonce: &str,
+) -> Result<SecureChannel, String> {
+    let pem = get_secret(app.clone(), &pairing_key(fingerprint)?)
+        .await?
+        .ok_or_else(|| "浏览器扩展未配对".to_string())?;
+    let host_key = public_key_from_pem(&pem)?;
+
+    let key = app_key(app).await?;
+    let app_nonce = random_nonce();
+    let signature = sign(&key, &app_proof(host_nonce, &app_nonce))?;
+    write_message(
+        stream,
+        &BridgeMessage::Challenge {
+            nonce: app_nonce.clone(),
+            signature,
+        },
+    )
+    .await?;
+
+    let BridgeMessage::Auth {
+        signature,
+        session_key,
+    } = read_message(stream).await?
+    else {
+        return Err("认证消息无效".to_string());
+    };
+    if let Err(e) = verify(
+        &host_key,
+        &host_proof(&app_nonce, host_nonce, &session_key),
+        &signature,
+    ) {
+        record_quietly(
+            AuditEvent::PermissionDenied,
+            "浏览器扩展认证失败",
+            Some(serde_json::json!({ "fingerprint": fingerprint })),
+        )
+        .await;
+        return Err(e);
+    }
+
+    let secret = unwrap_session_key(&key, &session_key)?;
+    let mut channel = SecureChannel::new(&secret, host_nonce, &app_nonce, Role::App)?;
+    write_frame(stream, &channel.seal(&BridgeMessage::Ready)?).await?;
+    Ok(channel)
+}
+
+/**
+ * 按网址查找登录条目
+ *
+ * @param url 页面网址
+ * @return 匹配的条目，不包含密码
+ */
+async fn handle_find(url: &str) -> Result<BridgeMessage, String> {
+    ensure_unlocked()?;
+    let page_host = normalize_host(url).ok_or_else(|| "网址无效".to_string())?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT id, title, username, url FROM password_entries
+         WHERE entry_type = ? AND url IS NOT NULL AND url != ''
+         ORDER BY last_used DESC, title",
+        EntryType::Login.as_str()
+    )?;
+
+    let entries = rows
+        .iter()
+        .filter(|row| url_matches(row.get::<Option<String>, _>("url").as_deref(), &page_host))
+        .map(|row| BridgeEntry {
+            id: row.get("id"),
+            title: row.get("title"),
+            username: row.get("username"),
+            url: row.get("url"),
+        })
+        .collect();
+    Ok(BridgeMessage::Matches { entries })
+}
+
+/**
+ * 获取条目的登录信息
+ *
+ * 条目网址必须匹配页面网址，并且需要用户在应用中确认
+ *
+ * @param app Tauri应用句柄
+ * @param entry_id 条目ID
+ * @param url 页面网址
+ * @return 登录信息
+ */
+async fn handle_get(app: &AppHandle, entry_id: &str, url: &str) -> Result<BridgeMessage, String> {
+    ensure_unlocked()?;
+    let page_host = normalize_host(url).ok_or_else(|| "网址无效".to_string())?;
+
+    let entry = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        get_entry(&mut conn, entry_id)
+            .await?
+            .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?
+    };
+    if entry.entry_type != EntryType::Login || !url_matches(entry.url.as_deref(), &page_host) {
+        return Err("条目与当前网站不匹配".to_string());
+    }
+
+    let allowed = confirm(
+        app,
+        "浏览器自动填充",
+        format!(
+            "网站 {} 请求填充“{}”的登录信息，是否允许？",
+            page_host, entry.title
+        ),
+    )
+    .await;
+    if !allowed {
+        record_quietly(
+            AuditEvent::PermissionDenied,
+            "拒绝浏览器扩展填充",
+            Some(serde_json::json!({ "entry_id": entry_id, "host": page_host })),
+        )
+        .await;
+        return Err("用户拒绝了填充请求".to_string());
+    }
+    // 等待确认期间密码库可能已被锁定
+    ensure_unlocked()?;
+
+    let totp = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        set_entry_last_used(&mut conn, entry_id, chrono::Utc::now().timestamp_millis()).await?;
+        load_totp_secret(&mut conn, entry_id).await?
+    }
+    .map(|secret| current_totp(&secret))
+    .transpose()?
+    .map(|(code, _)| code);
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "浏览器扩展填充登录信息",
+        Some(serde_json::json!({ "entry_id": entry_id, "host": page_host })),
+    )
+    .await;
+    Ok(BridgeMessage::Credentials {
+        username: entry.username,
+        password: entry.password,
+        totp,
+    })
+}
+
+/**
+ * 处理一个宿主连接
+ *
+ * 第一条消息为配对请求时，配对完成后关闭连接；为认证请求时，认证通过后
+ * 通过加密通道循环处理查询请求直到宿主断开
+ *
+ * @param app Tauri应用句柄
+ * @param stream 连接
+ */
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) {
+    let result = async {
+        match read_message(&mut stream).await? {
+            BridgeMessage::Pair { public_key } => {
+                let response = handle_pair(&app, &public_key).await?;
+                write_message(&mut stream, &response).await
+            }
+            BridgeMessage::Hello {
+                key_fingerprint,
+                nonce,
+            } => {
+                let mut channel = authenticate(&app, &mut stream, &key_fingerprint, &nonce).await?;
+                // 宿主断开、超时或消息无法解密后结束
+                while let Ok(request) = read_sealed(&mut stream, &mut channel).await {
+                    let response = match request {
+                        BridgeMessage::Find { url } => handle_find(&url).await,
+                        BridgeMessage::Get { entry_id, url } => {
+                            handle_get(&app, &entry_id, &url).await
+                        }
+                        _ => Err("不支持的请求".to_string()),
+                    }
+                    .unwrap_or_else(|message| BridgeMessage::Error { message });
+                    write_frame(&mut stream, &channel.seal(&response)?).await?;
+                }
+                Ok(())
+            }
+            _ => Err("请先完成认证".to_string()),
+        }
+    }
+    .await;
+
+    if let Err(message) = result {
+        log::warn!("浏览器桥接请求失败: {}", message);
+        let _ = write_message(&mut stream, &BridgeMessage::Error { message }).await;
+    }
+}
+
+/**
+ * 写入监听信息文件
+ *
+ * @param app Tauri应用句柄
+ * @param port 监听端口
+ * @return 操作结果
+ */
+fn write_bridge_info(app: &AppHandle, port: u16) -> Result<(), String> {
+    let dir = get_data_dir(app.clone())?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+    let path = dir.join(BRIDGE_INFO_FILE);
+    let info = serde_json::to_vec(&BridgeInfo {
+        port,
+        pid: std::process::id(),
+    })
+    .map_err(|e| format!("序列化监听信息失败: {}", e))?;
+    std::fs::write(&path, info).map_err(|e| format!("写入监听信息失败: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("设置监听信息文件权限失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/**
+ * 启动浏览器桥接服务
+ *
+ * 应在数据库初始化完成后调用
+ *
+ * @param app Tauri应用句柄
+ */
+pub fn start_browser_bridge(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = app_key(&app).await {
+            log::error!("浏览器桥接密钥初始化失败: {}", e);
+            return;
+        }
+
+        let listener = match TcpListener::bind(("127.0.0.1", 0)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("浏览器桥接监听失败: {}", e);
+                return;
+            }
+        };
+        let port = match listener.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(e) => {
+                log::error!("获取浏览器桥接端口失败: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = write_bridge_info(&app, port) {
+            log::error!("{}", e);
+            return;
+        }
+        log::info!("浏览器桥接已启动: 127.0.0.1:{}", port);
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle_connection(app.clone(), stream));
+                }
+                Err(e) => log::warn!("接受浏览器桥接连接失败: {}", e),
+            }
+        }
+    });
+}
+
+/**
+ * 列出已配对的浏览器扩展
+ *
+ * @return 配对列表
+ */
+#[tauri::command]
+pub async fn list_browser_pairings() -> Result<Vec<BrowserPairing>, String> {
+    Ok(list_secrets(PAIRING_PREFIX)
+        .await?
+        .into_iter()
+        .map(|item| BrowserPairing {
+            fingerprint: item.key[PAIRING_PREFIX.len()..].to_string(),
+            paired_at: item.created_at,
+        })
+        .collect())
+}
+
+/**
+ * 取消浏览器扩展配对
+ *
+ * @param fingerprint 宿主公钥指纹
+ * @param session_id 会话ID
+ * @return 是否存在并被删除
+ */
+#[tauri::command]
+pub async fn remove_browser_pairing(
+    fingerprint: String,
+    session_id: String,
+) -> Result<bool, String> {
+    require_session(&session_id).await?;
+    delete_secret(&pairing_key(&fingerprint)?).await
+}
+
+/**
+ * 生成原生消息宿主清单
+ *
+ * 清单需由用户保存到浏览器规定的目录（Windows 下需写入注册表）
+ *
+ * @param browser 浏览器类型：chrome 或 firefox
+ * @param extension_id 扩展ID
+ * @return 清单 JSON
+ */
+#[tauri::command]
+pub async fn get_native_host_manifest(
+    browser: String,
+    extension_id: String,
+) -> Result<String, String> {
+    if extension_id.is_empty()
+        || !extension_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '-' | '_'))
+    {
+        return Err("扩展ID格式无效".to_string());
+    }
+
+    let exe = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let host_path = exe
+        .parent()
+        .ok_or_else(|| "获取程序目录失败".to_string())?
+        .join(format!(
+            "{}{}",
+            NATIVE_HOST_BINARY,
+            std::env::consts::EXE_SUFFIX
+        ));
+
+    let mut manifest = serde_json::json!({
+        "name": NATIVE_HOST_NAME,
+        "description": "TaiASST 浏览器自动填充",
+        "path": host_path.to_string_lossy(),
+        "type": "stdio",
+    });
+    match browser.as_str() {
+        "chrome" => {
+            manifest["allowed_origins"] =
+                serde_json::json!([format!("chrome-extension://{}/", extension_id)])
+        }
+        "firefox" => manifest["allowed_extensions"] = serde_json::json!([extension_id]),
+        _ => return Err(format!("不支持的浏览器: {}", browser)),
+    }
+
+    serde_json::to_string_pretty(&manifest).map_err(|e| format!("生成清单失败: {}", e))
+}
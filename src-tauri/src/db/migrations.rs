@@ -292,6 +292,245 @@ pub fn get_ai_system_migrations() -> Vec<Migration> {
                   ON workflow_executions(start_time);",
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 46,
+            description: "Create AI chat messages table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_chat_messages (
+                id TEXT PRIMARY KEY NOT NULL,
+                session_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES ai_chat_sessions(id) ON DELETE CASCADE,
+                UNIQUE (session_id, seq)
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 47,
+            description: "Create AI chat messages full-text index",
+            sql: "CREATE VIRTUAL TABLE IF NOT EXISTS ai_chat_messages_fts USING fts5(
+                content,
+                content='ai_chat_messages',
+                tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS ai_chat_messages_fts_insert
+            AFTER INSERT ON ai_chat_messages BEGIN
+                INSERT INTO ai_chat_messages_fts(rowid, content) VALUES (new.rowid, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS ai_chat_messages_fts_delete
+            AFTER DELETE ON ai_chat_messages BEGIN
+                INSERT INTO ai_chat_messages_fts(ai_chat_messages_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS ai_chat_messages_fts_update
+            AFTER UPDATE OF content ON ai_chat_messages BEGIN
+                INSERT INTO ai_chat_messages_fts(ai_chat_messages_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+                INSERT INTO ai_chat_messages_fts(rowid, content) VALUES (new.rowid, new.content);
+            END;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 48,
+            description: "Move AI chat session messages into the messages table",
+            sql: "INSERT INTO ai_chat_messages (id, session_id, seq, role, content, created_at)
+                  SELECT lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+                         substr(lower(hex(randomblob(2))), 2) || '-' ||
+                         substr('89ab', 1 + (abs(random()) % 4), 1) ||
+                         substr(lower(hex(randomblob(2))), 2) || '-' || lower(hex(randomblob(6))),
+                         s.id, CAST(m.key AS INTEGER),
+                         CASE
+                             WHEN json_extract(m.value, '$.role')
+                                  IN ('system', 'user', 'assistant', 'tool')
+                                 THEN json_extract(m.value, '$.role')
+                             WHEN json_extract(m.value, '$.role') = 'function' THEN 'tool'
+                             ELSE 'assistant'
+                         END,
+                         COALESCE(json_extract(m.value, '$.content'), ''),
+                         s.created_at + CAST(m.key AS INTEGER)
+                  FROM ai_chat_sessions s, json_each(s.messages) m
+                  WHERE json_valid(s.messages);
+                  UPDATE ai_chat_sessions SET messages = '[]' WHERE json_valid(messages);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 49,
+            description: "Add AI chat session branch source column",
+            sql: "ALTER TABLE ai_chat_sessions ADD COLUMN branched_from TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 50,
+            description: "Add AI model context window column",
+            sql: "ALTER TABLE ai_model_config ADD COLUMN context_window INTEGER;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 51,
+            description: "Add AI chat session summary columns",
+            sql: "ALTER TABLE ai_chat_sessions ADD COLUMN summary TEXT;
+                  ALTER TABLE ai_chat_sessions ADD COLUMN summarized_count INTEGER NOT NULL DEFAULT 0;
+                  ALTER TABLE ai_chat_sessions ADD COLUMN summarized_hash TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 52,
+            description: "Create AI usage records table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_usage_records (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                request_id TEXT,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                session_id TEXT,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                estimated INTEGER NOT NULL DEFAULT 0,
+                latency_ms INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL,
+                error TEXT,
+                cost REAL NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_ai_usage_records_created
+                ON ai_usage_records(created_at);
+            CREATE INDEX IF NOT EXISTS idx_ai_usage_records_session
+                ON ai_usage_records(session_id);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 53,
+            description: "Create AI model prices table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_model_prices (
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt_price REAL NOT NULL DEFAULT 0,
+                completion_price REAL NOT NULL DEFAULT 0,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (provider, model)
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 54,
+            description: "Create AI tool settings table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_tool_settings (
+                name TEXT PRIMARY KEY NOT NULL,
+                policy TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 55,
+            description: "Create AI tool invocations table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_tool_invocations (
+                id TEXT PRIMARY KEY NOT NULL,
+                request_id TEXT,
+                session_id TEXT,
+                tool TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                output TEXT NOT NULL,
+                status TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_ai_tool_invocations_session
+                ON ai_tool_invocations(session_id, created_at);
+            CREATE INDEX IF NOT EXISTS idx_ai_tool_invocations_request
+                ON ai_tool_invocations(request_id);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 56,
+            description: "Create AI MCP servers table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_mcp_servers (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                transport TEXT NOT NULL,
+                command TEXT,
+                args TEXT NOT NULL DEFAULT '[]',
+                url TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 57,
+            description: "Create AI knowledge base tables",
+            sql: "CREATE TABLE IF NOT EXISTS ai_knowledge_bases (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                folders TEXT NOT NULL DEFAULT '[]',
+                embedding_provider TEXT NOT NULL,
+                embedding_model TEXT NOT NULL,
+                dimensions INTEGER,
+                indexed_at INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS ai_kb_documents (
+                id TEXT PRIMARY KEY NOT NULL,
+                kb_id TEXT NOT NULL REFERENCES ai_knowledge_bases(id) ON DELETE CASCADE,
+                path TEXT NOT NULL,
+                title TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                size INTEGER NOT NULL DEFAULT 0,
+                chunk_count INTEGER NOT NULL DEFAULT 0,
+                error TEXT,
+                indexed_at INTEGER NOT NULL,
+                UNIQUE(kb_id, path)
+            );
+            CREATE TABLE IF NOT EXISTS ai_kb_chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kb_id TEXT NOT NULL,
+                document_id TEXT NOT NULL REFERENCES ai_kb_documents(id) ON DELETE CASCADE,
+                seq INTEGER NOT NULL,
+                heading TEXT,
+                content TEXT NOT NULL,
+                embedding BLOB NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_ai_kb_chunks_kb ON ai_kb_chunks(kb_id);
+            CREATE INDEX IF NOT EXISTS idx_ai_kb_chunks_document ON ai_kb_chunks(document_id);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 58,
+            description: "Add AI prompt template variables and usage columns",
+            sql: "ALTER TABLE ai_prompt_templates ADD COLUMN description TEXT;
+                  ALTER TABLE ai_prompt_templates ADD COLUMN variables TEXT NOT NULL DEFAULT '[]';
+                  ALTER TABLE ai_prompt_templates ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+                  ALTER TABLE ai_prompt_templates ADD COLUMN last_used_at INTEGER;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 59,
+            description: "Create AI chat attachments table",
+            sql: "CREATE TABLE IF NOT EXISTS ai_chat_attachments (
+                    id TEXT PRIMARY KEY NOT NULL,
+                    message_id TEXT REFERENCES ai_chat_messages(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    file_name TEXT NOT NULL,
+                    mime_type TEXT NOT NULL,
+                    size INTEGER NOT NULL DEFAULT 0,
+                    width INTEGER,
+                    height INTEGER,
+                    file TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_ai_chat_attachments_message
+                    ON ai_chat_attachments(message_id);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 60,
+            description: "Add AI model image support column",
+            sql: "ALTER TABLE ai_model_config ADD COLUMN supports_images INTEGER;",
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
@@ -365,6 +604,18 @@ pub fn get_security_system_migrations() -> Vec<Migration> {
             );",
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 36,
+            description: "Add previous hash to security audit log",
+            sql: "ALTER TABLE security_audit_log ADD COLUMN prev_hash TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 37,
+            description: "Add row hash to security audit log",
+            sql: "ALTER TABLE security_audit_log ADD COLUMN row_hash TEXT;",
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
@@ -458,6 +709,113 @@ pub fn get_password_management_migrations() -> Vec<Migration> {
                   ('other', '其他', 'RiFolderLine', 'gray');",
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 33,
+            description: "Create password history table",
+            sql: "CREATE TABLE IF NOT EXISTS password_history (
+                id TEXT PRIMARY KEY NOT NULL,
+                entry_id TEXT NOT NULL,
+                encrypted_data TEXT NOT NULL,
+                changed_fields TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (entry_id) REFERENCES password_entries(id) ON DELETE CASCADE
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 34,
+            description: "Create password history entry index",
+            sql: "CREATE INDEX IF NOT EXISTS idx_password_history_entry
+                  ON password_history(entry_id, created_at);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 38,
+            description: "Add entry type to password entries",
+            sql: "ALTER TABLE password_entries ADD COLUMN entry_type TEXT NOT NULL DEFAULT 'login';",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 39,
+            description: "Add encrypted fields to password entries",
+            sql: "ALTER TABLE password_entries ADD COLUMN encrypted_fields TEXT;",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 40,
+            description: "Create password attachments table",
+            sql: "CREATE TABLE IF NOT EXISTS password_attachments (
+                id TEXT PRIMARY KEY NOT NULL,
+                entry_id TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                chunk_size INTEGER NOT NULL,
+                encrypted_key TEXT NOT NULL,
+                sha256 TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (entry_id) REFERENCES password_entries(id) ON DELETE CASCADE
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 41,
+            description: "Create password attachments entry index",
+            sql: "CREATE INDEX IF NOT EXISTS idx_password_attachments_entry
+                  ON password_attachments(entry_id);",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 42,
+            description: "Create password auto-type table",
+            sql: "CREATE TABLE IF NOT EXISTS password_auto_type (
+                entry_id TEXT PRIMARY KEY NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                window_patterns TEXT NOT NULL DEFAULT '[]',
+                sequence TEXT,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (entry_id) REFERENCES password_entries(id) ON DELETE CASCADE
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 43,
+            description: "Create sync records table",
+            sql: "CREATE TABLE IF NOT EXISTS sync_records (
+                record_type TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                clock TEXT NOT NULL,
+                content_hash TEXT,
+                pending INTEGER NOT NULL DEFAULT 0,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (record_type, record_id)
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 44,
+            description: "Create sync conflicts table",
+            sql: "CREATE TABLE IF NOT EXISTS sync_conflicts (
+                id TEXT PRIMARY KEY NOT NULL,
+                record_type TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                remote_device TEXT NOT NULL,
+                remote_title TEXT,
+                remote_updated_at INTEGER,
+                remote_change TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 45,
+            description: "Create sync peers table",
+            sql: "CREATE TABLE IF NOT EXISTS sync_peers (
+                device_id TEXT PRIMARY KEY NOT NULL,
+                last_seq INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
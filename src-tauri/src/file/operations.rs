@@ -1,9 +1,10 @@
 /**
  * 文件基本操作
  *
- * 提供创建、删除、重命名、复制等基本文件操作功能
+ * 提供创建、删除、重命名、复制、读取等基本文件操作功能
  */
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 
 /**
@@ -63,6 +64,27 @@ pub fn copy_file(source: String, destination: String) -> Result<(), String> {
     Ok(())
 }
 
+/**
+ * 读取文本文件
+ *
+ * 超出最大长度的部分不会读取，非 UTF-8 内容按有损方式转换
+ *
+ * @param path 文件路径
+ * @param max_bytes 最多读取的字节数
+ * @return 文件内容及是否被截断
+ */
+pub fn read_text_file(path: &Path, max_bytes: u64) -> Result<(String, bool), String> {
+    let file = fs::File::open(path).map_err(|err| format!("打开文件失败: {}", err))?;
+    let mut buffer = Vec::new();
+    file.take(max_bytes + 1)
+        .read_to_end(&mut buffer)
+        .map_err(|err| format!("读取文件失败: {}", err))?;
+
+    let truncated = buffer.len() as u64 > max_bytes;
+    buffer.truncate(max_bytes as usize);
+    Ok((String::from_utf8_lossy(&buffer).into_owned(), truncated))
+}
+
 /**
  * 判断文件或目录是否存在
  *
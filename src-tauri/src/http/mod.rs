@@ -5,7 +5,7 @@
  * - client: HTTP客户端和基础请求方法
  * - handlers: 面向前端的API处理函数
  */
-mod client;
+pub mod client;
 mod handlers;
 
 // 将所有公开API导出
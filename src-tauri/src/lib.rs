@@ -21,10 +21,15 @@ use tauri_plugin_autostart::MacosLauncher;
 use tokio::sync::Mutex;
 
 // 导入模块
+mod ai;
+mod bridge;
 mod db;
 mod file;
 mod http;
+mod password;
 mod plugin;
+mod security;
+mod sync;
 mod system;
 
 // 全局数据库连接
@@ -187,12 +192,14 @@ fn init_plugins(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wr
             Some(vec![]),
         ))
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             let _ = show_window(app);
         }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
 }
 
 /**
@@ -235,6 +242,118 @@ fn init_functions(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::
         http::http_post,
         http::http_put,
         http::http_delete,
+        // 密码管理相关命令
+        password::preview_password_import,
+        password::import_passwords,
+        password::export_vault_encrypted,
+        password::export_vault_csv,
+        password::restore_vault_backup,
+        password::evaluate_password_strength,
+        password::audit_password_health,
+        password::refresh_breach_ranges,
+        password::update_master_password_strength,
+        password::generate_password,
+        password::update_password_entry,
+        password::list_password_history,
+        password::get_password_history_version,
+        password::restore_password_history,
+        password::get_password_history_retention,
+        password::set_password_history_retention,
+        password::copy_entry_field,
+        password::set_entry_fields,
+        password::get_entry_fields,
+        password::add_entry_attachment,
+        password::list_entry_attachments,
+        password::save_entry_attachment,
+        password::delete_entry_attachment,
+        password::start_ssh_agent,
+        password::stop_ssh_agent,
+        password::get_ssh_agent_status,
+        password::get_auto_type_config,
+        password::set_auto_type_config,
+        password::auto_type_entry,
+        password::get_auto_type_hotkey,
+        password::set_auto_type_hotkey,
+        // 浏览器桥接相关命令
+        bridge::list_browser_pairings,
+        bridge::remove_browser_pairing,
+        bridge::get_native_host_manifest,
+        // AI 网关相关命令
+        ai::ai_chat,
+        ai::cancel_ai_request,
+        ai::list_ai_provider_configs,
+        ai::save_ai_provider_config,
+        ai::test_ai_provider,
+        ai::create_chat_session,
+        ai::list_chat_sessions,
+        ai::append_chat_message,
+        ai::get_chat_messages,
+        ai::branch_chat_session,
+        ai::search_chat_messages,
+        ai::estimate_chat_tokens,
+        ai::set_ai_model_context_window,
+        ai::list_ai_model_prices,
+        ai::set_ai_model_price,
+        ai::delete_ai_model_price,
+        ai::get_ai_usage_summary,
+        ai::get_ai_usage_budget,
+        ai::set_ai_usage_budget,
+        ai::list_ai_tools,
+        ai::set_ai_tool_policy,
+        ai::get_ai_tool_allowed_dirs,
+        ai::set_ai_tool_allowed_dirs,
+        ai::list_ai_tool_invocations,
+        ai::list_mcp_servers,
+        ai::save_mcp_server,
+        ai::delete_mcp_server,
+        ai::connect_mcp_server,
+        ai::disconnect_mcp_server,
+        ai::read_mcp_resource,
+        ai::get_mcp_prompt,
+        ai::list_knowledge_bases,
+        ai::save_knowledge_base,
+        ai::delete_knowledge_base,
+        ai::list_knowledge_documents,
+        ai::index_knowledge_base,
+        ai::search_knowledge_base,
+        ai::list_prompt_templates,
+        ai::save_prompt_template,
+        ai::delete_prompt_template,
+        ai::set_prompt_template_favorite,
+        ai::render_prompt_template,
+        ai::export_prompt_templates,
+        ai::import_prompt_templates,
+        ai::add_chat_attachment,
+        ai::delete_chat_attachment,
+        ai::get_chat_attachment_content,
+        ai::get_ai_model_image_support,
+        ai::set_ai_model_image_support,
+        // 密码库同步相关命令
+        sync::configure_sync,
+        sync::disable_sync,
+        sync::get_sync_status,
+        sync::run_sync,
+        sync::list_sync_conflicts,
+        sync::get_sync_conflict,
+        sync::resolve_sync_conflict,
+        // 密码库解锁相关命令
+        security::vault::unlock_vault,
+        security::vault::lock_vault,
+        security::vault::rewrap_vault_key,
+        security::vault::get_vault_status,
+        // 会话相关命令
+        security::session::open_session,
+        security::session::touch_session,
+        // 加密存储相关命令
+        security::storage::secure_set,
+        security::storage::secure_get,
+        security::storage::secure_delete,
+        security::storage::secure_list,
+        // 安全审计相关命令
+        security::audit::record_auth_failure,
+        security::audit::query_audit_log,
+        security::audit::verify_audit_chain,
+        security::audit::export_audit_log,
         // 安全系统相关命令已清理
     ])
 }
@@ -266,6 +385,53 @@ pub fn run() {
                 Ok(())
             })?;
 
+            // 初始化安全审计日志
+            if let Err(e) = security::audit::init_audit_log(app.app_handle().clone()) {
+                log::error!("安全审计日志初始化失败: {}", e);
+            }
+
+            // 清理已删除条目遗留的附件文件
+            let handle = app.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match password::cleanup_orphan_attachments(handle).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("已清理 {} 个孤立附件文件", count),
+                    Err(e) => log::warn!("清理孤立附件失败: {}", e),
+                }
+            });
+
+            // 清理已删除消息遗留的AI附件文件
+            let handle = app.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match ai::cleanup_chat_attachments(handle).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("已清理 {} 个AI附件文件", count),
+                    Err(e) => log::warn!("清理AI附件失败: {}", e),
+                }
+            });
+
+            // 将明文保存的AI密钥迁移到加密存储
+            let handle = app.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match ai::migrate_plaintext_api_keys(handle).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("已迁移 {} 个AI提供商密钥到加密存储", count),
+                    Err(e) => log::warn!("迁移AI提供商密钥失败: {}", e),
+                }
+            });
+
+            // 启动过期会话检查
+            security::session::start_session_monitor();
+
+            // 监听系统睡眠与锁屏
+            security::lock_monitor::start_lock_monitor(app.app_handle().clone());
+
+            // 注册自动输入快捷键
+            password::start_auto_type(app.app_handle().clone());
+
+            // 启动浏览器扩展桥接服务
+            bridge::start_browser_bridge(app.app_handle().clone());
+
             // 初始化插件管理器
             match plugin::api::init_plugin_system(app.app_handle().clone()) {
                 Ok(_) => log::info!("插件管理器初始化成功"),
@@ -0,0 +1,707 @@
+/**
+ * 条目附件
+ *
+ * 附件内容按固定大小分块，每块使用 AES-256-GCM 单独加密后写入应用数据目录下的
+ * attachments 目录，数据库 password_attachments 表只保存文件名、大小和加密后的
+ * 文件密钥。每个附件使用独立的随机文件密钥，文件密钥由密码库密钥加密。
+ *
+ * 文件格式为 魔数(8字节) 后接若干块，每块为 长度(4字节大端) || 随机数 || 密文。
+ * 附件ID、块序号和是否为最后一块作为附加认证数据，块被调换、删除或截断时
+ * 解密会失败
+ */
+use crate::file::paths::get_data_dir;
+use crate::password::types::AttachmentInfo;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::crypto::{
+    decode_base64, decrypt, decrypt_from_base64, encode_base64, encrypt, encrypt_to_base64,
+    random_bytes, KEY_LEN, NONCE_LEN,
+};
+use crate::security::session::require_session;
+use crate::security::vault::with_vault_key;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zeroize::Zeroizing;
+
+/// 附件目录名
+const ATTACHMENT_DIR: &str = "attachments";
+/// 附件文件扩展名
+const ATTACHMENT_EXT: &str = "bin";
+/// 附件文件魔数
+const ATTACHMENT_MAGIC: &[u8; 8] = b"TAIATT01";
+/// 分块大小（字节）
+const CHUNK_SIZE: usize = 64 * 1024;
+/// AES-GCM 认证标签长度
+const TAG_LEN: usize = 16;
+/// 单个附件大小上限（字节）
+const MAX_ATTACHMENT_BYTES: u64 = 50 * 1024 * 1024;
+/// 单个条目的附件总大小上限（字节）
+const MAX_ENTRY_ATTACHMENT_BYTES: i64 = 200 * 1024 * 1024;
+/// 加密备份中附件的总大小上限（字节）
+const MAX_BACKUP_ATTACHMENT_BYTES: i64 = 512 * 1024 * 1024;
+
+/**
+ * 加密备份中的附件
+ *
+ * 附件文件保持分块加密的原样，文件密钥不再由密码库密钥加密，而是随备份载荷
+ * 一起由导出口令派生的密钥加密
+ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupAttachment {
+    /// 附件ID
+    pub id: String,
+    /// 所属条目ID
+    pub entry_id: String,
+    /// 文件名
+    pub file_name: String,
+    /// 文件大小（字节）
+    pub size: i64,
+    /// 分块大小（字节）
+    pub chunk_size: i64,
+    /// 明文的 SHA-256 摘要
+    pub sha256: String,
+    /// 创建时间
+    pub created_at: i64,
+    /// 文件密钥（Base64）
+    pub file_key: String,
+    /// 加密后的附件文件（Base64）
+    pub content: String,
+}
+
+/// 块的附加认证数据
+fn chunk_aad(attachment_id: &str, index: u64, is_last: bool) -> Vec<u8> {
+    format!(
+        "password_attachment:{}:{}:{}",
+        attachment_id, index, is_last as u8
+    )
+    .into_bytes()
+}
+
+/// 文件密钥的附加认证数据
+fn key_aad(attachment_id: &str) -> Vec<u8> {
+    format!("password_attachment_key:{}", attachment_id).into_bytes()
+}
+
+/// 获取附件目录
+fn attachment_dir(app: AppHandle) -> Result<PathBuf, String> {
+    Ok(get_data_dir(app)?.join(ATTACHMENT_DIR))
+}
+
+/// 获取附件文件路径
+fn attachment_path(dir: &Path, attachment_id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", attachment_id, ATTACHMENT_EXT))
+}
+
+/// 在路径后追加后缀，用于写入临时文件
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// 读取一块数据，直到块满或到达文件末尾
+fn read_chunk(reader: &mut impl Read) -> Result<Vec<u8>, String> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut filled = 0;
+    while filled < CHUNK_SIZE {
+        let n = reader
+            .read(&mut buf[filled..])
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    buf.truncate(filled);
+    Ok(buf)
+}
+
+/**
+ * 分块加密文件
+ *
+ * @param source 源文件路径
+ * @param target 加密文件路径
+ * @param attachment_id 附件ID
+ * @param key 文件密钥
+ * @return 明文大小与 SHA-256 摘要
+ */
+fn encrypt_file(
+    source: &Path,
+    target: &Path,
+    attachment_id: &str,
+    key: &[u8],
+) -> Result<(u64, String), String> {
+    let mut reader =
+        BufReader::new(File::open(source).map_err(|e| format!("无法打开附件文件: {}", e))?);
+    let mut writer =
+        BufWriter::new(File::create(target).map_err(|e| format!("无法创建附件文件: {}", e))?);
+    writer
+        .write_all(ATTACHMENT_MAGIC)
+        .map_err(|e| format!("写入附件文件失败: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    let mut size: u64 = 0;
+    let mut index: u64 = 0;
+    let mut current = Zeroizing::new(read_chunk(&mut reader)?);
+    loop {
+        let next = if current.len() == CHUNK_SIZE {
+            Zeroizing::new(read_chunk(&mut reader)?)
+        } else {
+            Zeroizing::new(Vec::new())
+        };
+        let is_last = next.is_empty();
+
+        size += current.len() as u64;
+        if size > MAX_ATTACHMENT_BYTES {
+            return Err(format!(
+                "附件大小超过上限 {} MB",
+                MAX_ATTACHMENT_BYTES / 1024 / 1024
+            ));
+        }
+        hasher.update(current.as_slice());
+
+        let sealed = encrypt(key, &current, &chunk_aad(attachment_id, index, is_last))?;
+        writer
+            .write_all(&(sealed.len() as u32).to_be_bytes())
+            .and_then(|_| writer.write_all(&sealed))
+            .map_err(|e| format!("写入附件文件失败: {}", e))?;
+
+        if is_last {
+            break;
+        }
+        current = next;
+        index += 1;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("写入附件文件失败: {}", e))?
+        .sync_all()
+        .map_err(|e| format!("写入附件文件失败: {}", e))?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/**
+ * 流式解密附件到指定文件
+ *
+ * @param source 加密文件路径
+ * @param target 输出文件路径
+ * @param attachment_id 附件ID
+ * @param key 文件密钥
+ * @return 明文的 SHA-256 摘要
+ */
+fn decrypt_file(
+    source: &Path,
+    target: &Path,
+    attachment_id: &str,
+    key: &[u8],
+) -> Result<String, String> {
+    let mut reader =
+        BufReader::new(File::open(source).map_err(|e| format!("无法打开附件文件: {}", e))?);
+    let mut magic = [0u8; 8];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| "附件文件已损坏".to_string())?;
+    if &magic != ATTACHMENT_MAGIC {
+        return Err("附件文件格式无效".to_string());
+    }
+
+    let mut writer =
+        BufWriter::new(File::create(target).map_err(|e| format!("无法创建输出文件: {}", e))?);
+    let mut hasher = Sha256::new();
+    let mut index: u64 = 0;
+    loop {
+        let mut len = [0u8; 4];
+        reader
+            .read_exact(&mut len)
+            .map_err(|_| "附件文件已被截断".to_string())?;
+        let len = u32::from_be_bytes(len) as usize;
+        if !(NONCE_LEN + TAG_LEN..=NONCE_LEN + CHUNK_SIZE + TAG_LEN).contains(&len) {
+            return Err("附件文件已损坏".to_string());
+        }
+        let mut sealed = vec![0u8; len];
+        reader
+            .read_exact(&mut sealed)
+            .map_err(|_| "附件文件已被截断".to_string())?;
+
+        let is_last = reader
+            .fill_buf()
+            .map_err(|e| format!("读取附件文件失败: {}", e))?
+            .is_empty();
+        let plain = Zeroizing::new(
+            decrypt(key, &sealed, &chunk_aad(attachment_id, index, is_last))
+                .map_err(|_| "附件解密失败，文件已损坏或被篡改".to_string())?,
+        );
+        hasher.update(plain.as_slice());
+        writer
+            .write_all(&plain)
+            .map_err(|e| format!("写入输出文件失败: {}", e))?;
+
+        if is_last {
+            break;
+        }
+        index += 1;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("写入输出文件失败: {}", e))?
+        .sync_all()
+        .map_err(|e| format!("写入输出文件失败: {}", e))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 将查询结果行转换为附件信息
+fn attachment_from_row(row: &sqlx::sqlite::SqliteRow) -> AttachmentInfo {
+    AttachmentInfo {
+        id: row.get("id"),
+        entry_id: row.get("entry_id"),
+        file_name: row.get("file_name"),
+        size: row.get("size"),
+        sha256: row.get("sha256"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/**
+ * 为条目添加附件
+ *
+ * @param app Tauri应用句柄
+ * @param entry_id 条目ID
+ * @param file_path 要添加的文件路径
+ * @param session_id 会话ID
+ * @return 附件信息
+ */
+#[tauri::command]
+pub async fn add_entry_attachment(
+    app: AppHandle,
+    entry_id: String,
+    file_path: String,
+    session_id: String,
+) -> Result<AttachmentInfo, String> {
+    require_session(&session_id).await?;
+
+    let source = PathBuf::from(&file_path);
+    let file_size = tokio::fs::metadata(&source)
+        .await
+        .map_err(|e| format!("无法读取附件文件: {}", e))?
+        .len();
+    if file_size > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "附件大小超过上限 {} MB",
+            MAX_ATTACHMENT_BYTES / 1024 / 1024
+        ));
+    }
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "无效的附件文件路径".to_string())?;
+
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_one_with_params!(
+            db,
+            "SELECT id FROM password_entries WHERE id = ?",
+            &entry_id
+        )?
+        .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?;
+        let used: i64 = crate::query_one_with_params!(
+            db,
+            "SELECT COALESCE(SUM(size), 0) AS total FROM password_attachments WHERE entry_id = ?",
+            &entry_id
+        )?
+        .map(|row| row.get("total"))
+        .unwrap_or(0);
+        if used + file_size as i64 > MAX_ENTRY_ATTACHMENT_BYTES {
+            return Err(format!(
+                "条目附件总大小超过上限 {} MB",
+                MAX_ENTRY_ATTACHMENT_BYTES / 1024 / 1024
+            ));
+        }
+    }
+
+    let attachment_id = uuid::Uuid::new_v4().to_string();
+    let file_key = Zeroizing::new(random_bytes(KEY_LEN));
+    let encrypted_key =
+        with_vault_key(|key| encrypt_to_base64(key, &file_key, &key_aad(&attachment_id)))?;
+
+    let dir = attachment_dir(app)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("创建附件目录失败: {}", e))?;
+    let target = attachment_path(&dir, &attachment_id);
+    let partial = with_suffix(&target, ".part");
+
+    let (size, sha256) = {
+        let id = attachment_id.clone();
+        let partial = partial.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = encrypt_file(&source, &partial, &id, &file_key);
+            if result.is_err() {
+                let _ = fs::remove_file(&partial);
+            }
+            result
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+    tokio::fs::rename(&partial, &target)
+        .await
+        .map_err(|e| format!("保存附件文件失败: {}", e))?;
+
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let inserted = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO password_attachments
+             (id, entry_id, file_name, size, chunk_size, encrypted_key, sha256, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            &attachment_id,
+            &entry_id,
+            &file_name,
+            size as i64,
+            CHUNK_SIZE as i64,
+            encrypted_key,
+            &sha256,
+            created_at
+        )
+    };
+    if let Err(e) = inserted {
+        let _ = tokio::fs::remove_file(&target).await;
+        return Err(e);
+    }
+
+    log::info!("已为条目 {} 添加附件 {}", entry_id, attachment_id);
+    Ok(AttachmentInfo {
+        id: attachment_id,
+        entry_id,
+        file_name,
+        size: size as i64,
+        sha256,
+        created_at,
+    })
+}
+
+/**
+ * 列出条目的附件
+ *
+ * @param entry_id 条目ID
+ * @return 附件列表
+ */
+#[tauri::command]
+pub async fn list_entry_attachments(entry_id: String) -> Result<Vec<AttachmentInfo>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT id, entry_id, file_name, size, sha256, created_at FROM password_attachments
+         WHERE entry_id = ? ORDER BY created_at",
+        &entry_id
+    )?;
+
+    Ok(rows.iter().map(attachment_from_row).collect())
+}
+
+/**
+ * 解密附件并保存到指定路径
+ *
+ * 先写入临时文件，全部块校验通过后再移动到目标路径
+ *
+ * @param app Tauri应用句柄
+ * @param attachment_id 附件ID
+ * @param target_path 保存路径
+ * @param session_id 会话ID
+ * @return 附件信息
+ */
+#[tauri::command]
+pub async fn save_entry_attachment(
+    app: AppHandle,
+    attachment_id: String,
+    target_path: String,
+    session_id: String,
+) -> Result<AttachmentInfo, String> {
+    require_session(&session_id).await?;
+
+    let row = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_one_with_params!(
+            db,
+            "SELECT id, entry_id, file_name, size, sha256, created_at, encrypted_key
+             FROM password_attachments WHERE id = ?",
+            &attachment_id
+        )?
+        .ok_or_else(|| format!("附件不存在: {}", attachment_id))?
+    };
+    let info = attachment_from_row(&row);
+    let encrypted_key: String = row.get("encrypted_key");
+    let file_key = Zeroizing::new(with_vault_key(|key| {
+        decrypt_from_base64(key, &encrypted_key, &key_aad(&attachment_id))
+    })?);
+
+    let source = attachment_path(&attachment_dir(app)?, &attachment_id);
+    let target = PathBuf::from(&target_path);
+    let partial = with_suffix(&target, ".part");
+    let sha256 = {
+        let id = attachment_id.clone();
+        let partial = partial.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = decrypt_file(&source, &partial, &id, &file_key);
+            if result.is_err() {
+                let _ = fs::remove_file(&partial);
+            }
+            result
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+    if sha256 != info.sha256 {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err("附件内容校验失败".to_string());
+    }
+    tokio::fs::rename(&partial, &target)
+        .await
+        .map_err(|e| format!("保存文件失败: {}", e))?;
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "导出条目附件",
+        Some(serde_json::json!({ "entry_id": info.entry_id, "attachment_id": attachment_id })),
+    )
+    .await;
+    Ok(info)
+}
+
+/**
+ * 删除附件
+ *
+ * @param app Tauri应用句柄
+ * @param attachment_id 附件ID
+ * @param session_id 会话ID
+ * @return 是否存在并被删除
+ */
+#[tauri::command]
+pub async fn delete_entry_attachment(
+    app: AppHandle,
+    attachment_id: String,
+    session_id: String,
+) -> Result<bool, String> {
+    require_session(&session_id).await?;
+    uuid::Uuid::parse_str(&attachment_id)
+        .map_err(|_| format!("无效的附件ID: {}", attachment_id))?;
+
+    let deleted = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query("DELETE FROM password_attachments WHERE id = ?")
+            .bind(&attachment_id)
+            .execute(db.get_pool())
+            .await
+            .map_err(|e| format!("删除附件失败: {}", e))?
+            .rows_affected()
+            > 0
+    };
+
+    if deleted {
+        let path = attachment_path(&attachment_dir(app)?, &attachment_id);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("删除附件文件失败: {}", e)),
+        }
+    }
+    Ok(deleted)
+}
+
+/**
+ * 读取全部附件，供加密备份使用
+ *
+ * @param app Tauri应用句柄
+ * @return 备份中的附件
+ */
+pub(crate) async fn export_attachments(app: AppHandle) -> Result<Vec<BackupAttachment>, String> {
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query(
+            "SELECT id, entry_id, file_name, size, chunk_size, encrypted_key, sha256, created_at
+             FROM password_attachments ORDER BY created_at",
+        )
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询附件失败: {}", e))?
+    };
+    let total: i64 = rows.iter().map(|row| row.get::<i64, _>("size")).sum();
+    if total > MAX_BACKUP_ATTACHMENT_BYTES {
+        return Err(format!(
+            "附件总大小超过备份上限 {} MB，请先导出并删除部分附件",
+            MAX_BACKUP_ATTACHMENT_BYTES / 1024 / 1024
+        ));
+    }
+
+    let dir = attachment_dir(app)?;
+    let mut attachments = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let id: String = row.get("id");
+        let encrypted_key: String = row.get("encrypted_key");
+        let file_key = Zeroizing::new(with_vault_key(|key| {
+            decrypt_from_base64(key, &encrypted_key, &key_aad(&id))
+        })?);
+        let content = tokio::fs::read(attachment_path(&dir, &id))
+            .await
+            .map_err(|e| format!("读取附件文件失败 ({}): {}", id, e))?;
+        attachments.push(BackupAttachment {
+            entry_id: row.get("entry_id"),
+            file_name: row.get("file_name"),
+            size: row.get("size"),
+            chunk_size: row.get("chunk_size"),
+            sha256: row.get("sha256"),
+            created_at: row.get("created_at"),
+            file_key: encode_base64(&file_key),
+            content: encode_base64(&content),
+            id,
+        });
+    }
+    Ok(attachments)
+}
+
+/**
+ * 从加密备份恢复附件
+ *
+ * 只恢复所属条目存在且本地没有同ID记录的附件，文件密钥使用当前的密码库密钥
+ * 重新加密。附件文件先于记录写入，事务回滚后遗留的文件在启动时清理
+ *
+ * @param app Tauri应用句柄
+ * @param conn 数据库连接（可为事务）
+ * @param attachments 备份中的附件
+ * @return 恢复的附件数
+ */
+pub(crate) async fn restore_attachments(
+    app: AppHandle,
+    conn: &mut SqliteConnection,
+    attachments: &[BackupAttachment],
+) -> Result<usize, String> {
+    if attachments.is_empty() {
+        return Ok(0);
+    }
+    let dir = attachment_dir(app)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("创建附件目录失败: {}", e))?;
+
+    let mut restored = 0;
+    for attachment in attachments {
+        uuid::Uuid::parse_str(&attachment.id)
+            .map_err(|_| format!("无效的附件ID: {}", attachment.id))?;
+        let row = sqlx::query(
+            "SELECT EXISTS(SELECT 1 FROM password_entries WHERE id = ?) AS has_entry,
+                    EXISTS(SELECT 1 FROM password_attachments WHERE id = ?) AS has_attachment",
+        )
+        .bind(&attachment.entry_id)
+        .bind(&attachment.id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("查询附件失败: {}", e))?;
+        if row.get::<i64, _>("has_entry") == 0 || row.get::<i64, _>("has_attachment") != 0 {
+            continue;
+        }
+
+        let file_key = Zeroizing::new(decode_base64(&attachment.file_key)?);
+        if file_key.len() != KEY_LEN {
+            return Err(format!("附件密钥无效: {}", attachment.id));
+        }
+        let encrypted_key =
+            with_vault_key(|key| encrypt_to_base64(key, &file_key, &key_aad(&attachment.id)))?;
+        let content = decode_base64(&attachment.content)?;
+        if !content.starts_with(ATTACHMENT_MAGIC) {
+            return Err(format!("附件文件格式无效: {}", attachment.file_name));
+        }
+
+        let target = attachment_path(&dir, &attachment.id);
+        let partial = with_suffix(&target, ".part");
+        tokio::fs::write(&partial, &content)
+            .await
+            .map_err(|e| format!("写入附件文件失败: {}", e))?;
+        tokio::fs::rename(&partial, &target)
+            .await
+            .map_err(|e| format!("保存附件文件失败: {}", e))?;
+
+        sqlx::query(
+            "INSERT INTO password_attachments
+             (id, entry_id, file_name, size, chunk_size, encrypted_key, sha256, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&attachment.id)
+        .bind(&attachment.entry_id)
+        .bind(&attachment.file_name)
+        .bind(attachment.size)
+        .bind(attachment.chunk_size)
+        .bind(encrypted_key)
+        .bind(&attachment.sha256)
+        .bind(attachment.created_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("恢复附件失败 ({}): {}", attachment.file_name, e))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/**
+ * 清理没有数据库记录的附件文件
+ *
+ * 条目被删除时附件记录随外键级联删除，对应的加密文件在启动时清理
+ *
+ * @param app Tauri应用句柄
+ * @return 清理的文件数
+ */
+pub async fn cleanup_orphan_attachments(app: AppHandle) -> Result<usize, String> {
+    let dir = attachment_dir(app)?;
+    if !tokio::fs::try_exists(&dir).await.unwrap_or(false) {
+        return Ok(0);
+    }
+
+    let known: HashSet<String> = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query("SELECT id FROM password_attachments")
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询附件失败: {}", e))?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect()
+    };
+
+    let mut removed = 0;
+    let mut entries = tokio::fs::read_dir(&dir)
+        .await
+        .map_err(|e| format!("读取附件目录失败: {}", e))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("读取附件目录失败: {}", e))?
+    {
+        let path = entry.path();
+        let is_known = path.extension().is_some_and(|ext| ext == ATTACHMENT_EXT)
+            && path
+                .file_stem()
+                .is_some_and(|stem| known.contains(stem.to_string_lossy().as_ref()));
+        let is_file = entry.file_type().await.is_ok_and(|t| t.is_file());
+        if !is_known && is_file {
+            match tokio::fs::remove_file(&path).await {
+                Ok(_) => removed += 1,
+                Err(e) => log::warn!("删除孤立附件失败 ({}): {}", path.display(), e),
+            }
+        }
+    }
+    Ok(removed)
+}
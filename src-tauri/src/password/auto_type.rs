@@ -0,0 +1,907 @@
+/**
+ * 自动输入
+ *
+ * 按下全局快捷键后读取当前焦点窗口的标题，匹配密码库条目的自动输入规则，
+ * 再按条目的输入序列模拟键盘输入：
+ * - 规则为窗口标题通配符（* 匹配任意字符，不区分大小写）；未设置规则的条目不会被
+ *   快捷键选中，多个条目匹配时优先最近使用的条目
+ * - 输入序列支持 {USERNAME} {PASSWORD} {TOTP} {URL} {TITLE} {TAB} {ENTER}
+ *   {SPACE} {DELAY 毫秒}，{{} 和 {}} 分别输入花括号，其余文字原样输入
+ * - Linux X11 下使用 xdotool，Wayland 下依次尝试 wtype、ydotool 和 xdotool
+ */
+use crate::password::fields::load_totp_secret;
+use crate::password::store::{get_entry, set_entry_last_used};
+use crate::password::totp::current_totp;
+use crate::password::types::EntryType;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use crate::security::vault;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+use zeroize::Zeroizing;
+
+/// 自动输入快捷键的系统设置项
+pub const AUTO_TYPE_HOTKEY_SETTING: &str = "autoTypeHotkey";
+/// 默认的自动输入快捷键
+const DEFAULT_AUTO_TYPE_HOTKEY: &str = "CommandOrControl+Alt+A";
+/// 默认的输入序列
+pub const DEFAULT_AUTO_TYPE_SEQUENCE: &str = "{USERNAME}{TAB}{PASSWORD}{ENTER}";
+/// {DELAY} 的最大毫秒数
+const MAX_DELAY_MS: u64 = 10_000;
+/// 每个条目最多的窗口规则数
+const MAX_WINDOW_PATTERNS: usize = 20;
+/// 开始输入前等待的时间，让用户松开快捷键（毫秒）
+const START_DELAY_MS: u64 = 300;
+/// 从应用内触发时等待焦点回到目标窗口的时间（毫秒）
+const REFOCUS_DELAY_MS: u64 = 500;
+
+/// 是否正在自动输入，避免重复触发时交错输入
+static TYPING: AtomicBool = AtomicBool::new(false);
+
+// 使用lazy_static管理当前注册的快捷键
+lazy_static::lazy_static! {
+    static ref CURRENT_HOTKEY: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 条目的自动输入配置
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutoTypeConfig {
+    /// 条目ID
+    pub entry_id: String,
+    /// 是否允许自动输入
+    pub enabled: bool,
+    /// 窗口标题规则，为空时不参与快捷键自动输入
+    pub window_patterns: Vec<String>,
+    /// 输入序列，为空时使用默认序列
+    pub sequence: Option<String>,
+}
+
+/// 序列中引用的条目字段
+#[derive(Debug, Clone, Copy)]
+enum SequenceField {
+    Username,
+    Password,
+    Totp,
+    Url,
+    Title,
+}
+
+/// 序列中的特殊按键
+#[derive(Debug, Clone, Copy)]
+enum TypeKey {
+    Tab,
+    Enter,
+    Space,
+}
+
+/// 解析后的输入序列片段
+#[derive(Debug)]
+enum SequenceToken {
+    Text(String),
+    Field(SequenceField),
+    Key(TypeKey),
+    Delay(u64),
+}
+
+/// 填入条目内容后的输入动作
+enum InputAction {
+    Text(Zeroizing<String>),
+    Key(TypeKey),
+    Delay(u64),
+}
+
+/// 自动输入期间持有，结束时清除输入标记
+struct TypingGuard;
+
+impl TypingGuard {
+    fn acquire() -> Result<Self, String> {
+        if TYPING.swap(true, Ordering::SeqCst) {
+            return Err("正在进行自动输入".to_string());
+        }
+        Ok(TypingGuard)
+    }
+}
+
+impl Drop for TypingGuard {
+    fn drop(&mut self) {
+        TYPING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 解析单个占位符
+fn parse_placeholder(name: &str) -> Result<SequenceToken, String> {
+    let upper = name.trim().to_uppercase();
+    let token = match upper.as_str() {
+        "USERNAME" => SequenceToken::Field(SequenceField::Username),
+        "PASSWORD" => SequenceToken::Field(SequenceField::Password),
+        "TOTP" => SequenceToken::Field(SequenceField::Totp),
+        "URL" => SequenceToken::Field(SequenceField::Url),
+        "TITLE" => SequenceToken::Field(SequenceField::Title),
+        "TAB" => SequenceToken::Key(TypeKey::Tab),
+        "ENTER" => SequenceToken::Key(TypeKey::Enter),
+        "SPACE" => SequenceToken::Key(TypeKey::Space),
+        _ => {
+            let delay = upper
+                .strip_prefix("DELAY ")
+                .and_then(|ms| ms.trim().parse::<u64>().ok())
+                .ok_or_else(|| format!("不支持的占位符: {{{}}}", name))?;
+            if delay > MAX_DELAY_MS {
+                return Err(format!("延迟不能超过{}毫秒", MAX_DELAY_MS));
+            }
+            SequenceToken::Delay(delay)
+        }
+    };
+    Ok(token)
+}
+
+/**
+ * 解析输入序列
+ *
+ * @param sequence 输入序列
+ * @return 序列片段
+ */
+fn parse_sequence(sequence: &str) -> Result<Vec<SequenceToken>, String> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = sequence;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(remaining) = after.strip_prefix("{}") {
+            text.push('{');
+            rest = remaining;
+            continue;
+        }
+        if let Some(remaining) = after.strip_prefix("}}") {
+            text.push('}');
+            rest = remaining;
+            continue;
+        }
+
+        let end = after
+            .find('}')
+            .ok_or_else(|| "输入序列中的 { 没有闭合".to_string())?;
+        let token = parse_placeholder(&after[..end])?;
+        if !text.is_empty() {
+            tokens.push(SequenceToken::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(token);
+        rest = &after[end + 1..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(SequenceToken::Text(text));
+    }
+
+    if tokens.is_empty() {
+        return Err("输入序列不能为空".to_string());
+    }
+    Ok(tokens)
+}
+
+/**
+ * 判断窗口标题是否匹配通配符规则
+ *
+ * @param pattern 规则，* 匹配任意字符
+ * @param title 窗口标题
+ * @return 是否匹配
+ */
+fn wildcard_match(pattern: &str, title: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let title: Vec<char> = title.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < title.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == title[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/**
+ * 自动输入候选条目
+ */
+struct Candidate {
+    id: String,
+    last_used: Option<i64>,
+    enabled: bool,
+    window_patterns: Vec<String>,
+    sequence: Option<String>,
+}
+
+impl Candidate {
+    /// 是否有窗口规则与窗口标题匹配，只按条目标题相似不足以确认输入目标
+    fn matches(&self, window_title: &str) -> bool {
+        self.enabled
+            && self
+                .window_patterns
+                .iter()
+                .any(|pattern| wildcard_match(pattern, window_title))
+    }
+}
+
+/**
+ * 读取所有登录条目及其自动输入配置
+ */
+async fn load_candidates() -> Result<Vec<Candidate>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT e.id, e.last_used, a.enabled, a.window_patterns, a.sequence
+         FROM password_entries e LEFT JOIN password_auto_type a ON a.entry_id = e.id
+         WHERE e.entry_type = ?",
+        EntryType::Login.as_str()
+    )?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Candidate {
+            id: row.get("id"),
+            last_used: row.get("last_used"),
+            enabled: row.get::<Option<i64>, _>("enabled").unwrap_or(1) != 0,
+            window_patterns: row
+                .get::<Option<String>, _>("window_patterns")
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or_default(),
+            sequence: row.get("sequence"),
+        })
+        .collect())
+}
+
+/**
+ * 按条目内容生成输入动作
+ *
+ * @param entry_id 条目ID
+ * @param sequence 输入序列，为空时使用默认序列
+ * @return 条目标题与输入动作
+ */
+async fn build_actions(
+    entry_id: &str,
+    sequence: Option<&str>,
+) -> Result<(String, Vec<InputAction>), String> {
+    let tokens = parse_sequence(
+        sequence
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(DEFAULT_AUTO_TYPE_SEQUENCE),
+    )?;
+
+    let needs_totp = tokens
+        .iter()
+        .any(|token| matches!(token, SequenceToken::Field(SequenceField::Totp)));
+    let (entry, totp_secret) = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let entry = get_entry(&mut conn, entry_id)
+            .await?
+            .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?;
+        let totp_secret = if needs_totp {
+            load_totp_secret(&mut conn, entry_id).await?
+        } else {
+            None
+        };
+        (entry, totp_secret)
+    };
+
+    let mut actions = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let action = match token {
+            SequenceToken::Text(text) => InputAction::Text(Zeroizing::new(text)),
+            SequenceToken::Key(key) => InputAction::Key(key),
+            SequenceToken::Delay(ms) => InputAction::Delay(ms),
+            SequenceToken::Field(field) => {
+                let value = match field {
+                    SequenceField::Username => entry.username.clone(),
+                    SequenceField::Password => entry.password.clone(),
+                    SequenceField::Url => entry.url.clone().unwrap_or_default(),
+                    SequenceField::Title => entry.title.clone(),
+                    SequenceField::Totp => {
+                        let secret = totp_secret
+                            .as_deref()
+                            .ok_or_else(|| "该条目未设置 TOTP 密钥".to_string())?;
+                        current_totp(secret)?.0
+                    }
+                };
+                InputAction::Text(Zeroizing::new(value))
+            }
+        };
+        actions.push(action);
+    }
+    Ok((entry.title, actions))
+}
+
+/**
+ * 对条目执行自动输入
+ *
+ * @param entry_id 条目ID
+ * @param sequence 输入序列，为空时使用默认序列
+ * @param window_title 目标窗口标题，用于审计
+ * @return 条目标题
+ */
+async fn perform_auto_type(
+    entry_id: &str,
+    sequence: Option<&str>,
+    window_title: Option<&str>,
+) -> Result<String, String> {
+    if !vault::is_unlocked() {
+        return Err("密码库已锁定，请先解锁".to_string());
+    }
+    let _guard = TypingGuard::acquire()?;
+
+    let (title, actions) = build_actions(entry_id, sequence).await?;
+    tokio::task::spawn_blocking(move || input::perform(&actions))
+        .await
+        .map_err(|e| format!("自动输入失败: {}", e))??;
+
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        set_entry_last_used(&mut conn, entry_id, chrono::Utc::now().timestamp_millis()).await?;
+    }
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "自动输入条目",
+        Some(serde_json::json!({ "entry_id": entry_id, "window": window_title })),
+    )
+    .await;
+    Ok(title)
+}
+
+/**
+ * 对当前焦点窗口执行自动输入
+ *
+ * @return 条目标题
+ */
+async fn auto_type_focused_window() -> Result<String, String> {
+    if !vault::is_unlocked() {
+        return Err("密码库已锁定，请先解锁".to_string());
+    }
+
+    let window_title = tokio::task::spawn_blocking(input::active_window_title)
+        .await
+        .map_err(|e| format!("获取窗口标题失败: {}", e))??;
+    let candidates = load_candidates().await?;
+    let best = candidates
+        .iter()
+        .filter(|c| c.matches(&window_title))
+        .max_by_key(|c| c.last_used.unwrap_or(0))
+        .ok_or_else(|| format!("没有与窗口“{}”匹配的条目", window_title))?;
+
+    perform_auto_type(&best.id, best.sequence.as_deref(), Some(&window_title)).await
+}
+
+/**
+ * 快捷键触发时执行自动输入，失败时发送系统通知
+ */
+async fn on_hotkey(app: AppHandle) {
+    match auto_type_focused_window().await {
+        Ok(title) => log::info!("已自动输入条目: {}", title),
+        Err(e) => {
+            log::warn!("自动输入失败: {}", e);
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title("自动输入失败")
+                .body(e)
+                .show()
+            {
+                log::warn!("发送通知失败: {}", e);
+            }
+        }
+    }
+}
+
+/**
+ * 注册自动输入快捷键
+ *
+ * @param app Tauri应用句柄
+ * @param hotkey 快捷键
+ * @return 操作结果
+ */
+fn register_hotkey(app: &AppHandle, hotkey: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(hotkey, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                tauri::async_runtime::spawn(on_hotkey(app.clone()));
+            }
+        })
+        .map_err(|e| format!("注册自动输入快捷键失败: {}", e))
+}
+
+/**
+ * 读取快捷键设置
+ */
+async fn hotkey_setting() -> Result<String, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    Ok(crate::query_one_with_params!(
+        db,
+        "SELECT value FROM system_settings WHERE key = ?",
+        AUTO_TYPE_HOTKEY_SETTING
+    )?
+    .and_then(|row| row.get::<Option<String>, _>("value"))
+    .unwrap_or_else(|| DEFAULT_AUTO_TYPE_HOTKEY.to_string()))
+}
+
+/**
+ * 注册已保存的自动输入快捷键
+ *
+ * 应在数据库初始化完成后调用
+ *
+ * @param app Tauri应用句柄
+ */
+pub fn start_auto_type(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let hotkey = match hotkey_setting().await {
+            Ok(hotkey) => hotkey,
+            Err(e) => {
+                log::warn!("读取自动输入快捷键失败: {}", e);
+                return;
+            }
+        };
+        if hotkey.trim().is_empty() {
+            return;
+        }
+
+        match register_hotkey(&app, &hotkey) {
+            Ok(()) => {
+                if let Ok(mut current) = CURRENT_HOTKEY.lock() {
+                    *current = Some(hotkey);
+                }
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+mod input {
+    use super::{InputAction, TypeKey, START_DELAY_MS};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    /// 输入模拟工具
+    #[derive(Debug, Clone, Copy)]
+    enum Backend {
+        Xdotool,
+        Wtype,
+        Ydotool,
+    }
+
+    impl Backend {
+        fn program(&self) -> &'static str {
+            match self {
+                Backend::Xdotool => "xdotool",
+                Backend::Wtype => "wtype",
+                Backend::Ydotool => "ydotool",
+            }
+        }
+    }
+
+    impl TypeKey {
+        /// X11 按键名称
+        fn keysym(&self) -> &'static str {
+            match self {
+                TypeKey::Tab => "Tab",
+                TypeKey::Enter => "Return",
+                TypeKey::Space => "space",
+            }
+        }
+
+        /// Linux 输入事件键码
+        fn keycode(&self) -> u32 {
+            match self {
+                TypeKey::Tab => 15,
+                TypeKey::Enter => 28,
+                TypeKey::Space => 57,
+            }
+        }
+    }
+
+    fn is_wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    /// 判断程序是否在 PATH 中
+    fn has_program(name: &str) -> bool {
+        std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+            .unwrap_or(false)
+    }
+
+    /// 选择可用的输入模拟工具
+    fn backend() -> Result<Backend, String> {
+        let candidates: &[Backend] = if is_wayland() {
+            &[Backend::Wtype, Backend::Ydotool, Backend::Xdotool]
+        } else {
+            &[Backend::Xdotool]
+        };
+        candidates
+            .iter()
+            .copied()
+            .find(|backend| has_program(backend.program()))
+            .ok_or_else(|| {
+                if is_wayland() {
+                    "未找到 wtype 或 ydotool，请先安装其中之一".to_string()
+                } else {
+                    "未找到 xdotool，请先安装".to_string()
+                }
+            })
+    }
+
+    /**
+     * 运行外部程序
+     *
+     * 需要输入的文字通过标准输入传递，避免出现在进程参数中
+     *
+     * @param program 程序名
+     * @param args 参数
+     * @param input 标准输入内容
+     * @return 标准输出
+     */
+    fn run(program: &str, args: &[&str], input: Option<&str>) -> Result<String, String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("启动 {} 失败: {}", program, e))?;
+        if let (Some(text), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("向 {} 写入失败: {}", program, e))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("等待 {} 失败: {}", program, e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} 执行失败: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn type_text(backend: Backend, text: &str) -> Result<(), String> {
+        let args: &[&str] = match backend {
+            Backend::Xdotool => &["type", "--clearmodifiers", "--delay", "12", "--file", "-"],
+            Backend::Wtype => &["-"],
+            Backend::Ydotool => &["type", "--file", "-"],
+        };
+        run(backend.program(), args, Some(text)).map(|_| ())
+    }
+
+    fn press_key(backend: Backend, key: TypeKey) -> Result<(), String> {
+        match backend {
+            Backend::Xdotool => run("xdotool", &["key", "--clearmodifiers", key.keysym()], None),
+            Backend::Wtype => run("wtype", &["-k", key.keysym()], None),
+            Backend::Ydotool => {
+                let code = key.keycode();
+                run(
+                    "ydotool",
+                    &["key", &format!("{}:1", code), &format!("{}:0", code)],
+                    None,
+                )
+            }
+        }
+        .map(|_| ())
+    }
+
+    /**
+     * 依次执行输入动作
+     *
+     * @param actions 输入动作
+     * @return 执行结果
+     */
+    pub fn perform(actions: &[InputAction]) -> Result<(), String> {
+        let backend = backend()?;
+        std::thread::sleep(Duration::from_millis(START_DELAY_MS));
+        for action in actions {
+            match action {
+                InputAction::Text(text) if text.is_empty() => {}
+                InputAction::Text(text) => type_text(backend, text)?,
+                InputAction::Key(key) => press_key(backend, *key)?,
+                InputAction::Delay(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+            }
+        }
+        Ok(())
+    }
+
+    /// 在 Sway 的窗口树中查找焦点窗口标题
+    fn sway_focused_title(node: &serde_json::Value) -> Option<String> {
+        if node["focused"].as_bool() == Some(true) {
+            return node["name"].as_str().map(str::to_string);
+        }
+        ["nodes", "floating_nodes"]
+            .iter()
+            .filter_map(|key| node[key].as_array())
+            .flatten()
+            .find_map(sway_focused_title)
+    }
+
+    /**
+     * 获取当前焦点窗口的标题
+     *
+     * Wayland 下支持 Sway 和 Hyprland，其他合成器只能获取 XWayland 窗口
+     *
+     * @return 窗口标题
+     */
+    pub fn active_window_title() -> Result<String, String> {
+        if is_wayland() {
+            if has_program("swaymsg") {
+                if let Some(title) = run("swaymsg", &["-t", "get_tree"], None)
+                    .ok()
+                    .and_then(|tree| serde_json::from_str(&tree).ok())
+                    .and_then(|tree| sway_focused_title(&tree))
+                {
+                    return Ok(title);
+                }
+            }
+            if has_program("hyprctl") {
+                if let Some(title) = run("hyprctl", &["activewindow", "-j"], None)
+                    .ok()
+                    .and_then(|window| serde_json::from_str::<serde_json::Value>(&window).ok())
+                    .and_then(|window| window["title"].as_str().map(str::to_string))
+                {
+                    return Ok(title);
+                }
+            }
+        }
+        if has_program("xdotool") {
+            return run("xdotool", &["getactivewindow", "getwindowname"], None);
+        }
+        Err("无法获取当前窗口标题，请安装 xdotool（Wayland 下仅支持 Sway 和 Hyprland）".to_string())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod input {
+    use super::InputAction;
+
+    pub fn perform(_actions: &[InputAction]) -> Result<(), String> {
+        Err("自动输入目前仅支持 Linux".to_string())
+    }
+
+    pub fn active_window_title() -> Result<String, String> {
+        Err("自动输入目前仅支持 Linux".to_string())
+    }
+}
+
+/**
+ * 获取条目的自动输入配置
+ *
+ * @param entry_id 条目ID
+ * @return 自动输入配置，未设置时为默认配置
+ */
+#[tauri::command]
+pub async fn get_auto_type_config(entry_id: String) -> Result<AutoTypeConfig, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let row = crate::query_one_with_params!(
+        db,
+        "SELECT enabled, window_patterns, sequence FROM password_auto_type WHERE entry_id = ?",
+        &entry_id
+    )?;
+
+    Ok(match row {
+        Some(row) => AutoTypeConfig {
+            enabled: row.get::<i64, _>("enabled") != 0,
+            window_patterns: serde_json::from_str(&row.get::<String, _>("window_patterns"))
+                .unwrap_or_default(),
+            sequence: row.get("sequence"),
+            entry_id,
+        },
+        None => AutoTypeConfig {
+            entry_id,
+            enabled: true,
+            window_patterns: Vec::new(),
+            sequence: None,
+        },
+    })
+}
+
+/**
+ * 设置条目的自动输入配置
+ *
+ * @param config 自动输入配置
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_auto_type_config(
+    config: AutoTypeConfig,
+    session_id: String,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+
+    let sequence = config
+        .sequence
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(sequence) = &sequence {
+        parse_sequence(sequence)?;
+    }
+    let patterns: Vec<String> = config
+        .window_patterns
+        .iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if patterns.len() > MAX_WINDOW_PATTERNS {
+        return Err(format!("窗口规则不能超过{}条", MAX_WINDOW_PATTERNS));
+    }
+    if patterns.iter().any(|p| p.chars().all(|c| c == '*')) {
+        return Err("窗口规则不能只包含通配符".to_string());
+    }
+    let patterns =
+        serde_json::to_string(&patterns).map_err(|e| format!("序列化窗口规则失败: {}", e))?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let exists = crate::query_one_with_params!(
+        db,
+        "SELECT id FROM password_entries WHERE id = ?",
+        &config.entry_id
+    )?;
+    if exists.is_none() {
+        return Err(format!("密码条目不存在: {}", config.entry_id));
+    }
+
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO password_auto_type (entry_id, enabled, window_patterns, sequence, updated_at)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(entry_id) DO UPDATE SET enabled = excluded.enabled,
+                                             window_patterns = excluded.window_patterns,
+                                             sequence = excluded.sequence,
+                                             updated_at = excluded.updated_at",
+        &config.entry_id,
+        config.enabled as i64,
+        patterns,
+        sequence,
+        chrono::Utc::now().timestamp_millis()
+    )
+}
+
+/**
+ * 在应用内触发条目的自动输入
+ *
+ * 先隐藏主窗口让焦点回到之前的窗口，再按条目的输入序列输入
+ *
+ * @param app Tauri应用句柄
+ * @param entry_id 条目ID
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn auto_type_entry(
+    app: AppHandle,
+    entry_id: String,
+    session_id: String,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+    let config = get_auto_type_config(entry_id.clone()).await?;
+    if !config.enabled {
+        return Err("该条目已禁用自动输入".to_string());
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        window.hide().map_err(|e| format!("隐藏窗口失败: {}", e))?;
+    }
+    tokio::time::sleep(Duration::from_millis(REFOCUS_DELAY_MS)).await;
+
+    perform_auto_type(&entry_id, config.sequence.as_deref(), None)
+        .await
+        .map(|_| ())
+}
+
+/**
+ * 获取自动输入快捷键
+ *
+ * @return 快捷键，空字符串表示已禁用
+ */
+#[tauri::command]
+pub async fn get_auto_type_hotkey() -> Result<String, String> {
+    hotkey_setting().await
+}
+
+/**
+ * 设置自动输入快捷键
+ *
+ * 新快捷键注册失败时恢复原快捷键
+ *
+ * @param app Tauri应用句柄
+ * @param hotkey 快捷键，如 CommandOrControl+Alt+A，空字符串表示禁用
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_auto_type_hotkey(
+    app: AppHandle,
+    hotkey: String,
+    session_id: String,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+    let hotkey = hotkey.trim().to_string();
+    if !hotkey.is_empty() {
+        hotkey
+            .parse::<Shortcut>()
+            .map_err(|e| format!("快捷键格式无效: {}", e))?;
+    }
+
+    {
+        let mut current = CURRENT_HOTKEY
+            .lock()
+            .map_err(|e| format!("获取快捷键锁失败: {}", e))?;
+        let previous = current.take();
+        if let Some(old) = &previous {
+            if let Err(e) = app.global_shortcut().unregister(old.as_str()) {
+                log::warn!("注销自动输入快捷键失败: {}", e);
+            }
+        }
+        if !hotkey.is_empty() {
+            if let Err(e) = register_hotkey(&app, &hotkey) {
+                if let Some(old) = previous {
+                    match register_hotkey(&app, &old) {
+                        Ok(()) => *current = Some(old),
+                        Err(e) => log::warn!("恢复原自动输入快捷键失败: {}", e),
+                    }
+                }
+                return Err(e);
+            }
+            *current = Some(hotkey.clone());
+        }
+    }
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO system_settings (key, value, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        AUTO_TYPE_HOTKEY_SETTING,
+        &hotkey,
+        chrono::Utc::now().timestamp()
+    )
+}
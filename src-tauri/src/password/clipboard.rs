@@ -0,0 +1,182 @@
+/**
+ * 安全剪贴板复制
+ *
+ * 由后端读取条目字段并写入系统剪贴板，明文不会返回给前端。复制后在设定的
+ * 时间后清空剪贴板，若剪贴板内容已被用户替换则保持不变
+ */
+use crate::password::fields::load_totp_secret;
+use crate::password::store::{get_entry, set_entry_last_used};
+use crate::password::totp::current_totp;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use zeroize::Zeroize;
+
+/// 剪贴板自动清空时间的系统设置项
+pub const CLIPBOARD_CLEAR_SETTING: &str = "clipboardClearSeconds";
+/// 默认的自动清空时间（秒）
+const DEFAULT_CLEAR_SECONDS: u64 = 30;
+/// 自动清空时间上限（秒）
+const MAX_CLEAR_SECONDS: u64 = 3600;
+
+/// 复制次数计数，用于让较早的清空任务在再次复制后失效
+static COPY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/**
+ * 可复制的条目字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ClipboardField {
+    /// 用户名
+    #[serde(rename = "username")]
+    Username,
+    /// 密码
+    #[serde(rename = "password")]
+    Password,
+    /// 当前的 TOTP 验证码
+    #[serde(rename = "totp")]
+    Totp,
+}
+
+/**
+ * 复制结果，不包含字段内容
+ */
+#[derive(Debug, Serialize)]
+pub struct ClipboardCopyResult {
+    /// 复制的字段
+    pub field: ClipboardField,
+    /// 自动清空时间（秒），0表示不清空
+    pub clear_after_secs: u64,
+    /// TOTP 验证码的剩余有效秒数
+    pub totp_remaining_secs: Option<u64>,
+}
+
+/**
+ * 在指定时间后清空剪贴板
+ *
+ * 仅在期间没有新的复制操作、且剪贴板内容仍为复制的值时清空
+ *
+ * @param app Tauri应用句柄
+ * @param generation 本次复制的序号
+ * @param digest 复制内容的 SHA-256 摘要
+ * @param delay 延迟秒数
+ */
+fn schedule_clear(app: AppHandle, generation: u64, digest: Vec<u8>, delay: u64) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+        if COPY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+
+        let clipboard = app.clipboard();
+        let Ok(mut current) = clipboard.read_text() else {
+            return;
+        };
+        let unchanged = Sha256::digest(current.as_bytes()).as_slice() == digest.as_slice();
+        current.zeroize();
+
+        if unchanged {
+            match clipboard.clear() {
+                Ok(_) => log::info!("剪贴板已自动清空"),
+                Err(e) => log::warn!("清空剪贴板失败: {}", e),
+            }
+        }
+    });
+}
+
+/**
+ * 复制条目字段到剪贴板
+ *
+ * @param app Tauri应用句柄
+ * @param entry_id 条目ID
+ * @param field 要复制的字段
+ * @param clear_after 自动清空时间（秒），未提供时使用系统设置
+ * @param session_id 会话ID
+ * @return 复制结果
+ */
+#[tauri::command]
+pub async fn copy_entry_field(
+    app: AppHandle,
+    entry_id: String,
+    field: ClipboardField,
+    clear_after: Option<u64>,
+    session_id: String,
+) -> Result<ClipboardCopyResult, String> {
+    require_session(&session_id).await?;
+    let (mut value, totp_remaining_secs, clear_after_secs) = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+
+        let clear_after_secs = match clear_after {
+            Some(secs) => secs,
+            None => crate::query_one_with_params!(
+                db,
+                "SELECT value FROM system_settings WHERE key = ?",
+                CLIPBOARD_CLEAR_SETTING
+            )?
+            .and_then(|row| row.get::<Option<String>, _>("value"))
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_CLEAR_SECONDS),
+        }
+        .min(MAX_CLEAR_SECONDS);
+
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let entry = get_entry(&mut conn, &entry_id)
+            .await?
+            .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?;
+
+        let (value, totp_remaining_secs) = match field {
+            ClipboardField::Username => (entry.username.clone(), None),
+            ClipboardField::Password => (entry.password.clone(), None),
+            ClipboardField::Totp => {
+                let secret = load_totp_secret(&mut conn, &entry_id)
+                    .await?
+                    .ok_or_else(|| "该条目未设置 TOTP 密钥".to_string())?;
+                let (code, remaining) = current_totp(&secret)?;
+                (code, Some(remaining))
+            }
+        };
+        if value.is_empty() {
+            return Err("要复制的字段为空".to_string());
+        }
+
+        set_entry_last_used(&mut conn, &entry_id, chrono::Utc::now().timestamp_millis()).await?;
+
+        (value, totp_remaining_secs, clear_after_secs)
+    };
+
+    let result = app.clipboard().write_text(value.as_str());
+    let digest = Sha256::digest(value.as_bytes()).to_vec();
+    value.zeroize();
+    result.map_err(|e| format!("写入剪贴板失败: {}", e))?;
+
+    if field != ClipboardField::Username {
+        record_quietly(
+            AuditEvent::VaultReveal,
+            "复制条目字段到剪贴板",
+            Some(serde_json::json!({ "entry_id": entry_id, "field": field })),
+        )
+        .await;
+    }
+
+    let generation = COPY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if clear_after_secs > 0 {
+        schedule_clear(app, generation, digest, clear_after_secs);
+    }
+
+    Ok(ClipboardCopyResult {
+        field,
+        clear_after_secs,
+        totp_remaining_secs,
+    })
+}
@@ -0,0 +1,472 @@
+/**
+ * 密码库导出与备份
+ *
+ * 提供两种导出方式：
+ * - 加密备份：使用独立的导出口令保护，可通过恢复命令还原
+ * - 明文CSV：便于迁移到其他密码管理器，导出前需重新输入主密码
+ *
+ * 加密备份文件为JSON格式，包含版本化的文件头（Argon2参数、盐值、算法）
+ * 与 AES-256-GCM 加密的载荷，文件头作为附加认证数据防止被篡改。载荷包含
+ * 条目、分类、解密后的条目专有字段与附件
+ */
+use crate::password::attachment::{export_attachments, restore_attachments, BackupAttachment};
+use crate::password::fields::{load_all_fields, store_fields};
+use crate::password::store::{
+    get_all_categories, get_all_entries, get_entry_updated_at, insert_category_if_missing,
+    insert_entry, update_entry,
+};
+use crate::password::types::{
+    EntryFields, EntryType, LoginFields, PasswordCategory, PasswordEntry,
+};
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::auth::verify_master_password;
+use crate::security::crypto::{
+    decode_base64, decrypt, derive_key, encode_base64, encrypt, random_bytes, KdfParams, SALT_LEN,
+};
+use crate::security::session::require_session;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashMap;
+use std::fs;
+use tauri::AppHandle;
+
+/// 备份文件格式标识
+pub const BACKUP_FORMAT: &str = "taiasst-vault-backup";
+/// 当前备份文件版本，版本 2 起包含条目专有字段与附件
+pub const BACKUP_VERSION: u32 = 2;
+/// 导出口令最小长度
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/**
+ * 备份文件头
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupHeader {
+    /// 格式标识
+    pub format: String,
+    /// 文件版本
+    pub version: u32,
+    /// 密钥派生算法
+    pub kdf: String,
+    /// 密钥派生参数
+    pub kdf_params: KdfParams,
+    /// 盐值（Base64）
+    pub salt: String,
+    /// 加密算法
+    pub cipher: String,
+    /// 导出时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 备份文件
+ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// 文件头
+    pub header: BackupHeader,
+    /// 加密载荷（Base64）
+    pub payload: String,
+}
+
+/**
+ * 备份载荷，与前端 PasswordExportData 结构对应
+ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupPayload {
+    /// 数据版本
+    pub version: String,
+    /// 导出时间（毫秒）
+    pub exported_at: i64,
+    /// 密码条目
+    pub entries: Vec<PasswordEntry>,
+    /// 密码分类
+    pub categories: Vec<PasswordCategory>,
+    /// 条目专有字段，键为条目ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fields: HashMap<String, EntryFields>,
+    /// 条目附件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<BackupAttachment>,
+}
+
+/**
+ * 导出结果
+ */
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    /// 导出文件路径
+    pub file_path: String,
+    /// 导出的条目数
+    pub entries: usize,
+    /// 导出的分类数
+    pub categories: usize,
+    /// 导出的附件数
+    pub attachments: usize,
+}
+
+/**
+ * 备份恢复结果
+ */
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    /// 新增的条目数
+    pub entries_added: usize,
+    /// 被备份中较新版本覆盖的条目数
+    pub entries_updated: usize,
+    /// 本地版本较新而跳过的条目数
+    pub entries_skipped: usize,
+    /// 新增的分类数
+    pub categories_added: usize,
+    /// 新增的附件数
+    pub attachments_added: usize,
+    /// 备份创建时间（毫秒）
+    pub backup_created_at: i64,
+}
+
+/**
+ * 读取密码库的全部条目和分类
+ */
+async fn load_vault() -> Result<(Vec<PasswordEntry>, Vec<PasswordCategory>), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let pool = db.get_pool();
+
+    Ok((
+        get_all_entries(pool).await?,
+        get_all_categories(pool).await?,
+    ))
+}
+
+/**
+ * 写入恢复条目的专有字段
+ *
+ * 备份包含该条目的字段时覆盖本地字段；早期版本的备份不包含字段，此时只在
+ * 条目类型发生变化时清除本地已失效的字段
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry 备份中的条目
+ * @param fields 备份中的专有字段
+ * @param previous_type 恢复前本地条目的类型，新增条目时为空
+ * @return 操作结果
+ */
+async fn restore_fields(
+    conn: &mut SqliteConnection,
+    entry: &PasswordEntry,
+    fields: Option<&EntryFields>,
+    previous_type: Option<EntryType>,
+) -> Result<(), String> {
+    match fields {
+        Some(fields) if fields.entry_type() == entry.entry_type => {
+            store_fields(conn, &entry.id, Some(fields)).await
+        }
+        Some(_) => Err(format!("条目字段与条目类型不一致: {}", entry.title)),
+        None if previous_type.is_some_and(|t| t != entry.entry_type) => {
+            store_fields(conn, &entry.id, None).await
+        }
+        None => Ok(()),
+    }
+}
+
+/**
+ * 加密密码库数据，生成备份文件内容
+ *
+ * @param payload 备份载荷
+ * @param passphrase 导出口令
+ * @return 备份文件
+ */
+pub fn seal_backup(payload: &BackupPayload, passphrase: &str) -> Result<BackupFile, String> {
+    let kdf_params = KdfParams::default();
+    let salt = random_bytes(SALT_LEN);
+
+    let header = BackupHeader {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        kdf: "argon2id".to_string(),
+        kdf_params,
+        salt: encode_base64(&salt),
+        cipher: "aes-256-gcm".to_string(),
+        created_at: payload.exported_at,
+    };
+
+    let key = derive_key(passphrase.as_bytes(), &salt, &header.kdf_params)?;
+    let aad = serde_json::to_vec(&header).map_err(|e| format!("序列化文件头失败: {}", e))?;
+    let plaintext =
+        serde_json::to_vec(payload).map_err(|e| format!("序列化备份数据失败: {}", e))?;
+    let ciphertext = encrypt(&key, &plaintext, &aad)?;
+
+    Ok(BackupFile {
+        header,
+        payload: encode_base64(&ciphertext),
+    })
+}
+
+/**
+ * 解密备份文件
+ *
+ * @param backup 备份文件
+ * @param passphrase 导出口令
+ * @return 备份载荷
+ */
+pub fn open_backup(backup: &BackupFile, passphrase: &str) -> Result<BackupPayload, String> {
+    let header = &backup.header;
+    if header.format != BACKUP_FORMAT {
+        return Err("不是有效的TaiASST密码库备份文件".to_string());
+    }
+    if header.version > BACKUP_VERSION {
+        return Err(format!("不支持的备份文件版本: {}", header.version));
+    }
+    if header.kdf != "argon2id" || header.cipher != "aes-256-gcm" {
+        return Err(format!(
+            "不支持的加密算法: {} / {}",
+            header.kdf, header.cipher
+        ));
+    }
+
+    header.kdf_params.check_untrusted()?;
+
+    let salt = decode_base64(&header.salt)?;
+    let key = derive_key(passphrase.as_bytes(), &salt, &header.kdf_params)?;
+    let aad = serde_json::to_vec(header).map_err(|e| format!("序列化文件头失败: {}", e))?;
+    let plaintext = decrypt(&key, &decode_base64(&backup.payload)?, &aad)
+        .map_err(|_| "导出口令错误或备份文件已损坏".to_string())?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("解析备份数据失败: {}", e))
+}
+
+/**
+ * 导出加密备份
+ *
+ * @param app Tauri应用句柄
+ * @param file_path 备份文件保存路径
+ * @param passphrase 导出口令
+ * @param session_id 会话ID
+ * @return 导出结果
+ */
+#[tauri::command]
+pub async fn export_vault_encrypted(
+    app: AppHandle,
+    file_path: String,
+    passphrase: String,
+    session_id: String,
+) -> Result<ExportSummary, String> {
+    require_session(&session_id).await?;
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("导出口令长度至少需要{}位", MIN_PASSPHRASE_LEN));
+    }
+
+    let (entries, categories) = load_vault().await?;
+    let payload = BackupPayload {
+        version: BACKUP_VERSION.to_string(),
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        entries,
+        categories,
+        fields: load_all_fields().await?,
+        attachments: export_attachments(app).await?,
+    };
+
+    // Argon2 计算开销较大，放到独立线程执行
+    let (backup, payload) = tokio::task::spawn_blocking(move || {
+        seal_backup(&payload, &passphrase).map(|backup| (backup, payload))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let content =
+        serde_json::to_string_pretty(&backup).map_err(|e| format!("序列化备份文件失败: {}", e))?;
+    fs::write(&file_path, content).map_err(|e| format!("写入备份文件失败: {}", e))?;
+
+    log::info!("密码库加密备份已导出: {}", file_path);
+    record_quietly(
+        AuditEvent::Export,
+        "导出密码库加密备份",
+        Some(serde_json::json!({ "file_path": file_path, "entries": payload.entries.len() })),
+    )
+    .await;
+    Ok(ExportSummary {
+        file_path,
+        entries: payload.entries.len(),
+        categories: payload.categories.len(),
+        attachments: payload.attachments.len(),
+    })
+}
+
+/**
+ * 导出明文CSV
+ *
+ * 导出的文件包含明文密码，需重新输入主密码确认
+ *
+ * @param file_path CSV文件保存路径
+ * @param master_password 主密码
+ * @param session_id 会话ID
+ * @return 导出结果
+ */
+#[tauri::command]
+pub async fn export_vault_csv(
+    file_path: String,
+    master_password: String,
+    session_id: String,
+) -> Result<ExportSummary, String> {
+    require_session(&session_id).await?;
+    if let Err(e) = verify_master_password(&master_password).await {
+        record_quietly(
+            AuditEvent::PermissionDenied,
+            "导出明文CSV时主密码验证失败",
+            None,
+        )
+        .await;
+        return Err(e);
+    }
+
+    let (entries, categories) = load_vault().await?;
+    let fields = load_all_fields().await?;
+
+    let mut writer =
+        csv::Writer::from_path(&file_path).map_err(|e| format!("创建CSV文件失败: {}", e))?;
+    writer
+        .write_record([
+            "title", "username", "password", "url", "notes", "totp", "category", "tags", "favorite",
+        ])
+        .map_err(|e| format!("写入CSV失败: {}", e))?;
+
+    for entry in &entries {
+        let category = categories
+            .iter()
+            .find(|c| c.id == entry.category)
+            .map_or(entry.category.as_str(), |c| c.name.as_str());
+        let totp = match fields.get(&entry.id) {
+            Some(EntryFields::Login(LoginFields { totp: Some(totp) })) => totp.as_str(),
+            _ => "",
+        };
+
+        writer
+            .write_record([
+                entry.title.as_str(),
+                entry.username.as_str(),
+                entry.password.as_str(),
+                entry.url.as_deref().unwrap_or_default(),
+                entry.notes.as_deref().unwrap_or_default(),
+                totp,
+                category,
+                entry.tags.join(",").as_str(),
+                if entry.is_favorite { "1" } else { "0" },
+            ])
+            .map_err(|e| format!("写入CSV失败: {}", e))?;
+    }
+    writer.flush().map_err(|e| format!("写入CSV失败: {}", e))?;
+
+    log::info!("密码库明文CSV已导出: {}", file_path);
+    record_quietly(
+        AuditEvent::Export,
+        "导出密码库明文CSV",
+        Some(serde_json::json!({ "file_path": file_path, "entries": entries.len() })),
+    )
+    .await;
+    Ok(ExportSummary {
+        file_path,
+        entries: entries.len(),
+        categories: categories.len(),
+        attachments: 0,
+    })
+}
+
+/**
+ * 从加密备份恢复密码库
+ *
+ * 按条目ID合并：本地不存在的条目直接写入，本地存在时保留更新时间较新的版本。
+ * 条目专有字段随条目一起恢复，附件只恢复本地尚不存在的。所有写入在同一事务中完成
+ *
+ * @param app Tauri应用句柄
+ * @param file_path 备份文件路径
+ * @param passphrase 导出口令
+ * @param session_id 会话ID
+ * @return 恢复结果
+ */
+#[tauri::command]
+pub async fn restore_vault_backup(
+    app: AppHandle,
+    file_path: String,
+    passphrase: String,
+    session_id: String,
+) -> Result<RestoreReport, String> {
+    require_session(&session_id).await?;
+    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取备份文件失败: {}", e))?;
+    let backup: BackupFile =
+        serde_json::from_str(&content).map_err(|e| format!("解析备份文件失败: {}", e))?;
+
+    let payload = tokio::task::spawn_blocking(move || open_backup(&backup, &passphrase))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let mut report = RestoreReport {
+        entries_added: 0,
+        entries_updated: 0,
+        entries_skipped: 0,
+        categories_added: 0,
+        attachments_added: 0,
+        backup_created_at: payload.exported_at,
+    };
+
+    for category in &payload.categories {
+        if insert_category_if_missing(&mut tx, category).await? {
+            report.categories_added += 1;
+        }
+    }
+
+    for entry in &payload.entries {
+        let fields = payload.fields.get(&entry.id);
+        match get_entry_updated_at(&mut tx, &entry.id).await? {
+            None => {
+                insert_entry(&mut tx, entry).await?;
+                restore_fields(&mut tx, entry, fields, None).await?;
+                report.entries_added += 1;
+            }
+            Some(local_updated_at) if entry.updated_at > local_updated_at => {
+                let previous_type =
+                    sqlx::query("SELECT entry_type FROM password_entries WHERE id = ?")
+                        .bind(&entry.id)
+                        .fetch_one(&mut *tx)
+                        .await
+                        .map_err(|e| format!("查询密码条目失败: {}", e))
+                        .map(|row| EntryType::parse(&row.get::<String, _>("entry_type")))?;
+                update_entry(&mut tx, entry).await?;
+                restore_fields(&mut tx, entry, fields, Some(previous_type)).await?;
+                report.entries_updated += 1;
+            }
+            Some(_) => report.entries_skipped += 1,
+        }
+    }
+    report.attachments_added = restore_attachments(app, &mut tx, &payload.attachments).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+    drop(db);
+
+    record_quietly(
+        AuditEvent::Import,
+        "从加密备份恢复密码库",
+        Some(serde_json::json!({
+            "file_path": file_path,
+            "entries_added": report.entries_added,
+            "entries_updated": report.entries_updated,
+            "attachments_added": report.attachments_added,
+        })),
+    )
+    .await;
+    log::info!(
+        "密码库备份恢复完成: 新增 {} 条, 更新 {} 条, 跳过 {} 条",
+        report.entries_added,
+        report.entries_updated,
+        report.entries_skipped
+    );
+    Ok(report)
+}
@@ -0,0 +1,426 @@
+/**
+ * 条目专有字段
+ *
+ * 安全笔记、信用卡、身份信息、SSH 密钥和 API 令牌等类型的专有字段以及登录条目的
+ * TOTP 密钥以 JSON 序列化后使用密码库密钥加密，保存在 password_entries.encrypted_fields
+ * 中，条目ID作为附加认证数据
+ */
+use crate::password::history::{history_available, save_snapshot, HistorySecrets};
+use crate::password::store::get_entry;
+use crate::password::totp::parse_totp;
+use crate::password::types::{CreditCardFields, EntryFields, EntryType, LoginFields};
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::crypto::{decrypt_from_base64, encrypt_to_base64};
+use crate::security::session::require_session;
+use crate::security::vault::with_vault_key;
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+/// 专有字段的附加认证数据
+fn fields_aad(entry_id: &str) -> Vec<u8> {
+    format!("password_entry_fields:{}", entry_id).into_bytes()
+}
+
+/// 按 Luhn 算法校验卡号
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// 校验信用卡字段
+fn validate_credit_card(card: &CreditCardFields) -> Result<(), String> {
+    let digits: Vec<u32> = card
+        .number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_digit(10).ok_or_else(|| "卡号只能包含数字".to_string()))
+        .collect::<Result<_, _>>()?;
+    if !(12..=19).contains(&digits.len()) || !luhn_valid(&digits) {
+        return Err("卡号无效".to_string());
+    }
+    if card.expiry_month.is_some_and(|m| !(1..=12).contains(&m)) {
+        return Err("有效期月份需在1到12之间".to_string());
+    }
+    Ok(())
+}
+
+/**
+ * 校验专有字段
+ *
+ * @param fields 专有字段
+ * @return 校验结果
+ */
+fn validate_fields(fields: &EntryFields) -> Result<(), String> {
+    match fields {
+        EntryFields::Login(LoginFields { totp: Some(totp) }) => parse_totp(totp).map(|_| ()),
+        EntryFields::Login(_) => Ok(()),
+        EntryFields::SecureNote(note) if note.content.is_empty() => {
+            Err("笔记内容不能为空".to_string())
+        }
+        EntryFields::SecureNote(_) => Ok(()),
+        EntryFields::CreditCard(card) => validate_credit_card(card),
+        EntryFields::Identity(identity) if identity.full_name.trim().is_empty() => {
+            Err("姓名不能为空".to_string())
+        }
+        EntryFields::Identity(_) => Ok(()),
+        EntryFields::SshKey(key) if !key.private_key.trim_start().starts_with("-----BEGIN") => {
+            Err("私钥格式无效，需为 PEM 或 OpenSSH 格式".to_string())
+        }
+        EntryFields::SshKey(_) => Ok(()),
+        EntryFields::ApiToken(token) if token.token.trim().is_empty() => {
+            Err("令牌不能为空".to_string())
+        }
+        EntryFields::ApiToken(_) => Ok(()),
+    }
+}
+
+/**
+ * 解密条目专有字段
+ *
+ * @param entry_id 条目ID
+ * @param encrypted 加密后的字段
+ * @return 专有字段
+ */
+pub(crate) fn open_fields(entry_id: &str, encrypted: &str) -> Result<EntryFields, String> {
+    let aad = fields_aad(entry_id);
+    let plaintext = Zeroizing::new(with_vault_key(|key| {
+        decrypt_from_base64(key, encrypted, &aad)
+    })?);
+    serde_json::from_slice(&plaintext).map_err(|e| format!("解析条目字段失败: {}", e))
+}
+
+/**
+ * 加密条目专有字段
+ *
+ * @param entry_id 条目ID
+ * @param fields 专有字段
+ * @return 加密后的字段，登录条目未设置 TOTP 密钥时为空
+ */
+pub(crate) fn seal_fields(entry_id: &str, fields: &EntryFields) -> Result<Option<String>, String> {
+    if matches!(fields, EntryFields::Login(LoginFields { totp: None })) {
+        return Ok(None);
+    }
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(fields).map_err(|e| format!("序列化条目字段失败: {}", e))?,
+    );
+    let aad = fields_aad(entry_id);
+    with_vault_key(|key| encrypt_to_base64(key, &plaintext, &aad)).map(Some)
+}
+
+/**
+ * 读取并解密条目的专有字段
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry_id 条目ID
+ * @return 专有字段，未保存时为空
+ */
+pub(crate) async fn load_fields(
+    conn: &mut SqliteConnection,
+    entry_id: &str,
+) -> Result<Option<EntryFields>, String> {
+    let encrypted: Option<String> =
+        sqlx::query("SELECT encrypted_fields FROM password_entries WHERE id = ?")
+            .bind(entry_id)
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| format!("查询条目字段失败: {}", e))?
+            .and_then(|row| row.get("encrypted_fields"));
+
+    encrypted
+        .map(|encrypted| open_fields(entry_id, &encrypted))
+        .transpose()
+}
+
+/**
+ * 读取登录条目的 TOTP 密钥
+ *
+ * @param conn 数据库连接
+ * @param entry_id 条目ID
+ * @return TOTP 密钥，未设置时为空
+ */
+pub(crate) async fn load_totp_secret(
+    conn: &mut SqliteConnection,
+    entry_id: &str,
+) -> Result<Option<String>, String> {
+    let encrypted: Option<String> = sqlx::query(
+        "SELECT encrypted_fields FROM password_entries WHERE id = ? AND entry_type = ?",
+    )
+    .bind(entry_id)
+    .bind(EntryType::Login.as_str())
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| format!("查询条目字段失败: {}", e))?
+    .and_then(|row| row.get("encrypted_fields"));
+
+    match encrypted {
+        Some(encrypted) => match open_fields(entry_id, &encrypted)? {
+            EntryFields::Login(fields) => Ok(fields.totp),
+            _ => Err("条目字段与条目类型不一致".to_string()),
+        },
+        None => Ok(None),
+    }
+}
+
+/**
+ * 读取指定类型的全部条目及其专有字段
+ *
+ * 供 SSH 代理等后端功能使用，无法解密的条目会被跳过
+ *
+ * @param entry_type 条目类型
+ * @return 条目ID、标题与专有字段
+ */
+pub(crate) async fn load_entries_with_fields(
+    entry_type: EntryType,
+) -> Result<Vec<(String, String, EntryFields)>, String> {
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_with_params!(
+            db,
+            "SELECT id, title, encrypted_fields FROM password_entries
+             WHERE entry_type = ? AND encrypted_fields IS NOT NULL ORDER BY title",
+            entry_type.as_str()
+        )?
+    };
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let id: String = row.get("id");
+            let encrypted: String = row.get("encrypted_fields");
+            match open_fields(&id, &encrypted) {
+                Ok(fields) if fields.entry_type() == entry_type => {
+                    Some((id, row.get("title"), fields))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    log::warn!("无法读取条目字段 ({}): {}", id, e);
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/**
+ * 读取全部条目的专有字段，供加密备份使用
+ *
+ * 任一条目无法解密时返回错误，避免生成缺少字段的备份
+ *
+ * @return 以条目ID为键的专有字段
+ */
+pub(crate) async fn load_all_fields() -> Result<HashMap<String, EntryFields>, String> {
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query(
+            "SELECT id, encrypted_fields FROM password_entries WHERE encrypted_fields IS NOT NULL",
+        )
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询条目字段失败: {}", e))?
+    };
+
+    rows.iter()
+        .map(|row| {
+            let id: String = row.get("id");
+            let fields = open_fields(&id, &row.get::<String, _>("encrypted_fields"))
+                .map_err(|e| format!("无法读取条目字段 ({}): {}", id, e))?;
+            Ok((id, fields))
+        })
+        .collect()
+}
+
+/**
+ * 写入条目的专有字段
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry_id 条目ID
+ * @param fields 专有字段，为空时清除已保存的字段
+ * @return 操作结果
+ */
+pub(crate) async fn store_fields(
+    conn: &mut SqliteConnection,
+    entry_id: &str,
+    fields: Option<&EntryFields>,
+) -> Result<(), String> {
+    let encrypted = match fields {
+        Some(fields) => {
+            validate_fields(fields)?;
+            seal_fields(entry_id, fields)?
+        }
+        None => None,
+    };
+    sqlx::query("UPDATE password_entries SET encrypted_fields = ? WHERE id = ?")
+        .bind(encrypted)
+        .bind(entry_id)
+        .execute(conn)
+        .await
+        .map_err(|e| format!("保存条目字段失败: {}", e))?;
+    Ok(())
+}
+
+/**
+ * 设置条目类型与专有字段
+ *
+ * 登录类型只保存 TOTP 密钥，未设置时清除已保存的专有字段。字段发生变化时先保存
+ * 修改前的加密版本
+ *
+ * @param entry_id 条目ID
+ * @param fields 专有字段
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_entry_fields(
+    entry_id: String,
+    fields: EntryFields,
+    session_id: String,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+    validate_fields(&fields)?;
+
+    let entry_type = fields.entry_type();
+    let encrypted = seal_fields(&entry_id, &fields)?;
+    let keep_history = history_available().await?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let current = get_entry(&mut tx, &entry_id)
+        .await?
+        .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?;
+
+    if keep_history {
+        let mut updated = current.clone();
+        updated.entry_type = entry_type;
+        let new_fields = encrypted.as_ref().map(|_| fields.clone());
+        let secrets = HistorySecrets::from_entry(&current, load_fields(&mut tx, &entry_id).await?);
+        let changed_fields =
+            secrets.changed_fields(&HistorySecrets::from_entry(&updated, new_fields));
+        if !changed_fields.is_empty() {
+            save_snapshot(&mut tx, &entry_id, &secrets, &changed_fields).await?;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE password_entries SET entry_type = ?, encrypted_fields = ?, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(entry_type.as_str())
+    .bind(encrypted)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .bind(&entry_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("保存条目字段失败: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))
+}
+
+/**
+ * 获取解密后的条目专有字段
+ *
+ * @param entry_id 条目ID
+ * @param session_id 会话ID
+ * @return 专有字段
+ */
+#[tauri::command]
+pub async fn get_entry_fields(entry_id: String, session_id: String) -> Result<EntryFields, String> {
+    require_session(&session_id).await?;
+
+    let row = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_one_with_params!(
+            db,
+            "SELECT entry_type, encrypted_fields FROM password_entries WHERE id = ?",
+            &entry_id
+        )?
+        .ok_or_else(|| format!("密码条目不存在: {}", entry_id))?
+    };
+
+    let entry_type = EntryType::parse(&row.get::<String, _>("entry_type"));
+    let Some(encrypted) = row.get::<Option<String>, _>("encrypted_fields") else {
+        return match entry_type {
+            EntryType::Login => Ok(EntryFields::Login(LoginFields::default())),
+            _ => Err("条目字段缺失".to_string()),
+        };
+    };
+
+    let fields = open_fields(&entry_id, &encrypted)?;
+    if fields.entry_type() != entry_type {
+        return Err("条目字段与条目类型不一致".to_string());
+    }
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "查看条目专有字段",
+        Some(serde_json::json!({ "entry_id": entry_id, "entry_type": entry_type })),
+    )
+    .await;
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_fields_accept_missing_totp() {
+        let fields: EntryFields = serde_json::from_str(r#"{"entry_type":"login"}"#).unwrap();
+        assert!(matches!(
+            fields,
+            EntryFields::Login(LoginFields { totp: None })
+        ));
+        assert!(validate_fields(&fields).is_ok());
+        assert!(seal_fields("entry", &fields).unwrap().is_none());
+    }
+
+    #[test]
+    fn validates_login_totp() {
+        let fields = |totp: &str| {
+            EntryFields::Login(LoginFields {
+                totp: Some(totp.to_string()),
+            })
+        };
+        assert!(validate_fields(&fields("GEZDGNBVGY3TQOJQ")).is_ok());
+        assert!(validate_fields(&fields("otpauth://totp/x?secret=GEZDGNBV")).is_ok());
+        assert!(validate_fields(&fields("不是密钥")).is_err());
+    }
+
+    #[test]
+    fn validates_card_numbers() {
+        let card = |number: &str| CreditCardFields {
+            number: number.to_string(),
+            ..Default::default()
+        };
+        assert!(validate_credit_card(&card("4111 1111 1111 1111")).is_ok());
+        assert!(validate_credit_card(&card("4111 1111 1111 1112")).is_err());
+        assert!(validate_credit_card(&card("4111-abcd")).is_err());
+    }
+}
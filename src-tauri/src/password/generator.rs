@@ -0,0 +1,360 @@
+/**
+ * 密码生成器
+ *
+ * 使用操作系统随机源（OsRng）生成密码，支持三种模式：
+ * - random: 按字符集随机生成，可设置每类字符的最少数量
+ * - pronounceable: 辅音/元音交替的易读密码
+ * - passphrase: 基于 BIP39 英文词表的助记口令
+ *
+ * 返回的熵值基于生成过程计算，而非对结果做模式分析
+ */
+use crate::password::strength::PasswordStrength;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NUMBERS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()_+-=[]{}|;:,.<>?";
+/// 易混淆的相似字符
+const SIMILAR_CHARS: &str = "il1Lo0O";
+/// 在部分场景下难以输入或识别的模糊字符
+const AMBIGUOUS_CHARS: &str = "{}[]()/\\'\"~,;.<>";
+const CONSONANTS: &str = "bcdfghjklmnpqrstvwxz";
+const VOWELS: &str = "aeiouy";
+
+/// 密码长度范围
+const LENGTH_RANGE: std::ops::RangeInclusive<usize> = 4..=128;
+/// 口令单词数范围
+const WORD_COUNT_RANGE: std::ops::RangeInclusive<usize> = 3..=20;
+/// 单次最多生成数量
+const MAX_COUNT: usize = 50;
+
+/**
+ * 生成模式
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum GeneratorMode {
+    /// 随机字符
+    #[default]
+    #[serde(rename = "random")]
+    Random,
+    /// 易读密码
+    #[serde(rename = "pronounceable")]
+    Pronounceable,
+    /// 助记口令
+    #[serde(rename = "passphrase")]
+    Passphrase,
+}
+
+/**
+ * 密码生成选项
+ *
+ * 字符集相关字段与前端 PasswordGenerateOptions 对应
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GenerateOptions {
+    /// 生成模式
+    pub mode: GeneratorMode,
+    /// 密码长度（random / pronounceable 模式）
+    pub length: usize,
+    /// 包含大写字母
+    pub include_uppercase: bool,
+    /// 包含小写字母
+    pub include_lowercase: bool,
+    /// 包含数字
+    pub include_numbers: bool,
+    /// 包含符号
+    pub include_symbols: bool,
+    /// 排除相似字符
+    pub exclude_similar: bool,
+    /// 排除模糊字符
+    pub exclude_ambiguous: bool,
+    /// 大写字母最少数量
+    pub min_uppercase: usize,
+    /// 小写字母最少数量
+    pub min_lowercase: usize,
+    /// 数字最少数量
+    pub min_numbers: usize,
+    /// 符号最少数量
+    pub min_symbols: usize,
+    /// 口令单词数（passphrase 模式）
+    pub word_count: usize,
+    /// 口令单词分隔符
+    pub separator: String,
+    /// 口令单词首字母大写
+    pub capitalize: bool,
+    /// 口令中随机插入一位数字
+    pub include_number_in_passphrase: bool,
+    /// 生成数量
+    pub count: usize,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            mode: GeneratorMode::Random,
+            length: 16,
+            include_uppercase: true,
+            include_lowercase: true,
+            include_numbers: true,
+            include_symbols: true,
+            exclude_similar: true,
+            exclude_ambiguous: false,
+            min_uppercase: 1,
+            min_lowercase: 1,
+            min_numbers: 1,
+            min_symbols: 1,
+            word_count: 6,
+            separator: "-".to_string(),
+            capitalize: false,
+            include_number_in_passphrase: false,
+            count: 1,
+        }
+    }
+}
+
+/**
+ * 生成结果
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct GeneratedPassword {
+    /// 生成的密码
+    pub password: String,
+    /// 估算熵值（比特）
+    pub entropy_bits: f64,
+    /// 对应的强度等级
+    pub strength: PasswordStrength,
+}
+
+/// 按排除选项过滤字符集
+fn filter_charset(charset: &str, options: &GenerateOptions) -> Vec<char> {
+    charset
+        .chars()
+        .filter(|c| !(options.exclude_similar && SIMILAR_CHARS.contains(*c)))
+        .filter(|c| !(options.exclude_ambiguous && AMBIGUOUS_CHARS.contains(*c)))
+        .collect()
+}
+
+/// 从字符集中随机选取一个字符
+fn pick(rng: &mut OsRng, charset: &[char]) -> char {
+    charset[rng.gen_range(0..charset.len())]
+}
+
+/// 计算 log2(n!)
+fn log2_factorial(n: usize) -> f64 {
+    (2..=n).map(|i| (i as f64).log2()).sum()
+}
+
+/**
+ * 随机字符模式
+ *
+ * 先按最少数量从各类字符中抽取，剩余位置从全部字符中抽取，最后打乱顺序
+ */
+fn generate_random(
+    rng: &mut OsRng,
+    options: &GenerateOptions,
+) -> Result<GeneratedPassword, String> {
+    let classes: Vec<(Vec<char>, usize)> = [
+        (options.include_lowercase, LOWERCASE, options.min_lowercase),
+        (options.include_uppercase, UPPERCASE, options.min_uppercase),
+        (options.include_numbers, NUMBERS, options.min_numbers),
+        (options.include_symbols, SYMBOLS, options.min_symbols),
+    ]
+    .into_iter()
+    .filter(|(enabled, _, _)| *enabled)
+    .map(|(_, charset, min)| (filter_charset(charset, options), min))
+    .filter(|(charset, _)| !charset.is_empty())
+    .collect();
+
+    if classes.is_empty() {
+        return Err("至少需要选择一种字符类型".to_string());
+    }
+
+    let required: usize = classes.iter().map(|(_, min)| min).sum();
+    if required > options.length {
+        return Err(format!(
+            "各类字符最少数量之和({})超过密码长度({})",
+            required, options.length
+        ));
+    }
+
+    let pool: Vec<char> = classes
+        .iter()
+        .flat_map(|(c, _)| c.iter().copied())
+        .collect();
+    let mut chars = Vec::with_capacity(options.length);
+    for (charset, min) in &classes {
+        for _ in 0..*min {
+            chars.push(pick(rng, charset));
+        }
+    }
+    while chars.len() < options.length {
+        chars.push(pick(rng, &pool));
+    }
+    chars.shuffle(rng);
+
+    // 熵值：必选字符与自由字符的选择空间加上位置排列，不超过完全随机的上限
+    let free = options.length - required;
+    let mut bits = free as f64 * (pool.len() as f64).log2();
+    for (charset, min) in &classes {
+        bits += *min as f64 * (charset.len() as f64).log2();
+    }
+    bits += log2_factorial(options.length)
+        - log2_factorial(free)
+        - classes
+            .iter()
+            .map(|(_, min)| log2_factorial(*min))
+            .sum::<f64>();
+    let bits = bits.min(options.length as f64 * (pool.len() as f64).log2());
+
+    Ok(GeneratedPassword {
+        password: chars.into_iter().collect(),
+        entropy_bits: bits,
+        strength: PasswordStrength::from_entropy(bits),
+    })
+}
+
+/**
+ * 易读密码模式
+ *
+ * 字母部分辅音与元音交替，可随机大写；数字和符号按最少数量（至少1个）追加在末尾
+ */
+fn generate_pronounceable(
+    rng: &mut OsRng,
+    options: &GenerateOptions,
+) -> Result<GeneratedPassword, String> {
+    let consonants = filter_charset(CONSONANTS, options);
+    let vowels = filter_charset(VOWELS, options);
+    let numbers = filter_charset(NUMBERS, options);
+    let symbols = filter_charset(SYMBOLS, options);
+
+    let digit_count = if options.include_numbers && !numbers.is_empty() {
+        options.min_numbers.max(1)
+    } else {
+        0
+    };
+    let symbol_count = if options.include_symbols && !symbols.is_empty() {
+        options.min_symbols.max(1)
+    } else {
+        0
+    };
+    let letter_count = options
+        .length
+        .checked_sub(digit_count + symbol_count)
+        .filter(|n| *n >= 2)
+        .ok_or_else(|| "密码长度不足以生成易读密码".to_string())?;
+
+    let mut bits = 0.0;
+    let mut password = String::with_capacity(options.length);
+    for i in 0..letter_count {
+        let set = if i % 2 == 0 { &consonants } else { &vowels };
+        let mut c = pick(rng, set);
+        bits += (set.len() as f64).log2();
+        if options.include_uppercase {
+            if rng.gen_bool(0.5) {
+                c = c.to_ascii_uppercase();
+            }
+            bits += 1.0;
+        }
+        password.push(c);
+    }
+    for _ in 0..digit_count {
+        password.push(pick(rng, &numbers));
+        bits += (numbers.len() as f64).log2();
+    }
+    for _ in 0..symbol_count {
+        password.push(pick(rng, &symbols));
+        bits += (symbols.len() as f64).log2();
+    }
+
+    Ok(GeneratedPassword {
+        password,
+        entropy_bits: bits,
+        strength: PasswordStrength::from_entropy(bits),
+    })
+}
+
+/**
+ * 助记口令模式
+ *
+ * 从 BIP39 英文词表（2048词）中随机选词，每个单词约11比特熵
+ */
+fn generate_passphrase(
+    rng: &mut OsRng,
+    options: &GenerateOptions,
+) -> Result<GeneratedPassword, String> {
+    if !WORD_COUNT_RANGE.contains(&options.word_count) {
+        return Err(format!(
+            "口令单词数需在{}到{}之间",
+            WORD_COUNT_RANGE.start(),
+            WORD_COUNT_RANGE.end()
+        ));
+    }
+
+    let word_list = bip39::Language::English.word_list();
+    let mut words: Vec<String> = (0..options.word_count)
+        .map(|_| word_list[rng.gen_range(0..word_list.len())].to_string())
+        .collect();
+
+    if options.capitalize {
+        for word in &mut words {
+            if let Some(first) = word.get_mut(0..1) {
+                first.make_ascii_uppercase();
+            }
+        }
+    }
+
+    let mut bits = options.word_count as f64 * (word_list.len() as f64).log2();
+    if options.include_number_in_passphrase {
+        let index = rng.gen_range(0..words.len());
+        words[index].push(char::from(b'0' + rng.gen_range(0..10u8)));
+        bits += 10f64.log2() + (words.len() as f64).log2();
+    }
+
+    Ok(GeneratedPassword {
+        password: words.join(&options.separator),
+        entropy_bits: bits,
+        strength: PasswordStrength::from_entropy(bits),
+    })
+}
+
+/**
+ * 生成密码
+ *
+ * @param options 生成选项，未提供时使用默认选项
+ * @return 生成结果列表
+ */
+#[tauri::command]
+pub fn generate_password(
+    options: Option<GenerateOptions>,
+) -> Result<Vec<GeneratedPassword>, String> {
+    let options = options.unwrap_or_default();
+
+    if options.mode != GeneratorMode::Passphrase && !LENGTH_RANGE.contains(&options.length) {
+        return Err(format!(
+            "密码长度需在{}到{}之间",
+            LENGTH_RANGE.start(),
+            LENGTH_RANGE.end()
+        ));
+    }
+    if options.count == 0 || options.count > MAX_COUNT {
+        return Err(format!("生成数量需在1到{}之间", MAX_COUNT));
+    }
+
+    let mut rng = OsRng;
+    (0..options.count)
+        .map(|_| {
+            let mut result = match options.mode {
+                GeneratorMode::Random => generate_random(&mut rng, &options),
+                GeneratorMode::Pronounceable => generate_pronounceable(&mut rng, &options),
+                GeneratorMode::Passphrase => generate_passphrase(&mut rng, &options),
+            }?;
+            result.entropy_bits = (result.entropy_bits * 10.0).round() / 10.0;
+            Ok(result)
+        })
+        .collect()
+}
@@ -0,0 +1,374 @@
+/**
+ * 密码健康审计
+ *
+ * 对密码库中每个设置了密码的登录条目进行检查，其他类型和空密码的条目不参与审计：
+ * - 强度评估（熵值与弱模式分析）
+ * - 跨条目的密码复用
+ * - 长期未修改（基于 updated_at）
+ * - 泄露检测：使用本地保存的 SHA-1 前缀列表（k-匿名，与 Have I Been Pwned
+ *   range 接口格式一致），每个前缀一个文件，内容为 "后缀:次数" 行
+ */
+use crate::file::paths::get_data_dir;
+use crate::http::client::get_http_client;
+use crate::password::store::get_all_entries;
+use crate::password::strength::{estimate_strength, PasswordStrength, PatternMatch};
+use crate::password::types::{EntryType, PasswordEntry};
+use crate::security::auth::verify_master_password;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// 泄露前缀列表目录名称
+pub const BREACH_RANGES_DIR: &str = "breach_ranges";
+/// k-匿名前缀长度
+const BREACH_PREFIX_LEN: usize = 5;
+/// 泄露前缀列表下载地址
+const BREACH_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+/// 默认的密码过期天数
+const DEFAULT_MAX_AGE_DAYS: u32 = 180;
+/// 一天的毫秒数
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/**
+ * 单个条目的健康检查结果
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct PasswordHealthEntry {
+    /// 条目ID
+    pub entry_id: String,
+    /// 条目标题
+    pub title: String,
+    /// 强度等级
+    pub strength: PasswordStrength,
+    /// 强度分值（0-100）
+    pub score: u8,
+    /// 有效熵值（比特）
+    pub entropy_bits: f64,
+    /// 命中的弱模式
+    pub patterns: Vec<PatternMatch>,
+    /// 使用相同密码的其他条目ID
+    pub reused_with: Vec<String>,
+    /// 距上次修改的天数
+    pub days_since_change: i64,
+    /// 是否超过过期天数未修改
+    pub is_old: bool,
+    /// 在泄露数据中出现的次数，无本地前缀数据时为空
+    pub breach_count: Option<u64>,
+    /// 改进建议
+    pub feedback: Vec<String>,
+}
+
+/**
+ * 密码库健康报告，与前端 PasswordSecurityReport 对应
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct PasswordHealthReport {
+    /// 参与审计的条目总数
+    pub total_passwords: usize,
+    /// 弱密码数量
+    pub weak_passwords: usize,
+    /// 复用密码的条目数量
+    pub reused_passwords: usize,
+    /// 长期未修改的条目数量
+    pub old_passwords: usize,
+    /// 已泄露的条目数量
+    pub compromised_passwords: usize,
+    /// 整体安全评分（0-100）
+    pub security_score: u8,
+    /// 改进建议
+    pub recommendations: Vec<String>,
+    /// 本地是否有可用的泄露前缀数据
+    pub breach_data_available: bool,
+    /// 各条目的检查结果
+    pub entries: Vec<PasswordHealthEntry>,
+}
+
+/**
+ * 泄露前缀列表更新结果
+ */
+#[derive(Debug, Serialize)]
+pub struct BreachRangeRefresh {
+    /// 成功更新的前缀数量
+    pub updated: usize,
+    /// 更新失败的前缀
+    pub failed: Vec<String>,
+}
+
+/// 计算密码的 SHA-1 十六进制摘要（大写）
+fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+/// 获取泄露前缀列表目录
+fn breach_ranges_dir(app: AppHandle) -> Result<PathBuf, String> {
+    Ok(get_data_dir(app)?.join(BREACH_RANGES_DIR))
+}
+
+/// 读取参与审计的条目：设置了密码的登录条目
+async fn load_audited_entries() -> Result<Vec<PasswordEntry>, String> {
+    let entries = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        get_all_entries(db.get_pool()).await?
+    };
+    Ok(entries
+        .into_iter()
+        .filter(|e| e.entry_type == EntryType::Login && !e.password.is_empty())
+        .collect())
+}
+
+/**
+ * 在本地前缀列表中查询密码的泄露次数
+ *
+ * @param dir 前缀列表目录
+ * @param password 密码
+ * @return 泄露次数，对应前缀文件不存在时为空
+ */
+fn lookup_breach_count(dir: &Path, password: &str) -> Option<u64> {
+    let hash = sha1_hex(password);
+    let (prefix, suffix) = hash.split_at(BREACH_PREFIX_LEN);
+    let content = fs::read_to_string(dir.join(format!("{}.txt", prefix))).ok()?;
+
+    let count = content
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0);
+    Some(count)
+}
+
+/**
+ * 审计密码库健康状况
+ *
+ * @param app Tauri应用句柄
+ * @param max_age_days 密码过期天数，默认180天
+ * @return 健康报告
+ */
+#[tauri::command]
+pub async fn audit_password_health(
+    app: AppHandle,
+    max_age_days: Option<u32>,
+) -> Result<PasswordHealthReport, String> {
+    let entries = load_audited_entries().await?;
+
+    let max_age_days = max_age_days.unwrap_or(DEFAULT_MAX_AGE_DAYS) as i64;
+    let breach_dir = breach_ranges_dir(app)?;
+    let breach_data_available = breach_dir.is_dir();
+
+    // 按密码分组以检测复用
+    let mut by_password: HashMap<&str, Vec<&str>> = HashMap::new();
+    for entry in &entries {
+        by_password
+            .entry(entry.password.as_str())
+            .or_default()
+            .push(entry.id.as_str());
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let results: Vec<PasswordHealthEntry> = entries
+        .iter()
+        .map(|entry| {
+            let strength = estimate_strength(&entry.password);
+            let reused_with: Vec<String> = by_password
+                .get(entry.password.as_str())
+                .into_iter()
+                .flatten()
+                .filter(|id| **id != entry.id)
+                .map(|id| id.to_string())
+                .collect();
+            let days_since_change = (now - entry.updated_at).max(0) / DAY_MILLIS;
+            let breach_count = if breach_data_available {
+                lookup_breach_count(&breach_dir, &entry.password)
+            } else {
+                None
+            };
+
+            let mut feedback = strength.feedback;
+            if !reused_with.is_empty() {
+                feedback.push(format!("与其他 {} 个条目使用相同密码", reused_with.len()));
+            }
+            if days_since_change > max_age_days {
+                feedback.push(format!("已有 {} 天未修改密码", days_since_change));
+            }
+            if let Some(count) = breach_count.filter(|c| *c > 0) {
+                feedback.push(format!("该密码已在泄露数据中出现 {} 次，请立即更换", count));
+            }
+
+            PasswordHealthEntry {
+                entry_id: entry.id.clone(),
+                title: entry.title.clone(),
+                strength: strength.strength,
+                score: strength.score,
+                entropy_bits: strength.entropy_bits,
+                patterns: strength.patterns,
+                reused_with,
+                days_since_change,
+                is_old: days_since_change > max_age_days,
+                breach_count,
+                feedback,
+            }
+        })
+        .collect();
+
+    let weak_passwords = results
+        .iter()
+        .filter(|r| r.strength <= PasswordStrength::Fair)
+        .count();
+    let reused_passwords = results.iter().filter(|r| !r.reused_with.is_empty()).count();
+    let old_passwords = results.iter().filter(|r| r.is_old).count();
+    let compromised_passwords = results
+        .iter()
+        .filter(|r| r.breach_count.is_some_and(|c| c > 0))
+        .count();
+
+    // 安全评分为无任何问题的条目占比
+    let healthy = results
+        .iter()
+        .filter(|r| {
+            r.strength > PasswordStrength::Fair
+                && r.reused_with.is_empty()
+                && !r.is_old
+                && r.breach_count.unwrap_or(0) == 0
+        })
+        .count();
+    let security_score = if results.is_empty() {
+        100
+    } else {
+        (healthy * 100 / results.len()) as u8
+    };
+
+    let mut recommendations = Vec::new();
+    if compromised_passwords > 0 {
+        recommendations.push(format!(
+            "有 {} 个密码已泄露，请立即更换",
+            compromised_passwords
+        ));
+    }
+    if weak_passwords > 0 {
+        recommendations.push(format!(
+            "有 {} 个弱密码，建议使用密码生成器替换",
+            weak_passwords
+        ));
+    }
+    if reused_passwords > 0 {
+        recommendations.push(format!(
+            "有 {} 个条目存在密码复用，建议为每个账户使用独立密码",
+            reused_passwords
+        ));
+    }
+    if old_passwords > 0 {
+        recommendations.push(format!(
+            "有 {} 个密码超过 {} 天未修改",
+            old_passwords, max_age_days
+        ));
+    }
+    if !breach_data_available {
+        recommendations.push("本地没有泄露前缀数据，可更新后再进行泄露检测".to_string());
+    }
+
+    Ok(PasswordHealthReport {
+        total_passwords: results.len(),
+        weak_passwords,
+        reused_passwords,
+        old_passwords,
+        compromised_passwords,
+        security_score,
+        recommendations,
+        breach_data_available,
+        entries: results,
+    })
+}
+
+/**
+ * 更新本地泄露前缀列表
+ *
+ * 仅下载密码库中现有密码对应的 SHA-1 前缀，完整哈希和密码不会离开本机
+ *
+ * @param app Tauri应用句柄
+ * @return 更新结果
+ */
+#[tauri::command]
+pub async fn refresh_breach_ranges(app: AppHandle) -> Result<BreachRangeRefresh, String> {
+    let entries = load_audited_entries().await?;
+
+    let prefixes: BTreeSet<String> = entries
+        .iter()
+        .map(|e| sha1_hex(&e.password)[..BREACH_PREFIX_LEN].to_string())
+        .collect();
+
+    let dir = breach_ranges_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建泄露数据目录失败: {}", e))?;
+
+    let client = get_http_client();
+    let mut result = BreachRangeRefresh {
+        updated: 0,
+        failed: Vec::new(),
+    };
+
+    for prefix in prefixes {
+        let response = client
+            .get(format!("{}{}", BREACH_RANGE_URL, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+
+        let body = match response {
+            Ok(resp) => resp.text().await.ok(),
+            Err(e) => {
+                log::warn!("下载泄露前缀 {} 失败: {}", prefix, e);
+                None
+            }
+        };
+
+        match body {
+            Some(body) => {
+                // 去除填充用的零次数记录
+                let content: Vec<&str> = body
+                    .lines()
+                    .filter(|line| !line.trim().ends_with(":0"))
+                    .collect();
+                fs::write(dir.join(format!("{}.txt", prefix)), content.join("\n"))
+                    .map_err(|e| format!("写入泄露前缀数据失败: {}", e))?;
+                result.updated += 1;
+            }
+            None => result.failed.push(prefix),
+        }
+    }
+
+    Ok(result)
+}
+
+/**
+ * 计算并保存主密码强度
+ *
+ * 校验主密码后将强度等级写入 security_config.password_strength
+ *
+ * @param master_password 主密码
+ * @return 强度等级
+ */
+#[tauri::command]
+pub async fn update_master_password_strength(
+    master_password: String,
+) -> Result<PasswordStrength, String> {
+    verify_master_password(&master_password).await?;
+    let strength = estimate_strength(&master_password).strength;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let now = chrono::Utc::now().timestamp();
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO security_config (id, password_strength, updated_at) VALUES (1, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET password_strength = excluded.password_strength,
+                                       updated_at = excluded.updated_at",
+        strength.as_str(),
+        now
+    )?;
+
+    Ok(strength)
+}
@@ -0,0 +1,594 @@
+/**
+ * 密码条目历史版本
+ *
+ * 条目的敏感字段（用户名、密码、备注及专有字段）发生变化时，将修改前的值使用
+ * 密码库密钥加密后写入 password_history 表。每个条目保留的版本数由系统设置
+ * passwordHistoryRetention 控制，设置为0时不保留历史
+ */
+use crate::password::fields::{load_fields, store_fields};
+use crate::password::store::{get_entry, update_entry};
+use crate::password::types::{EntryFields, EntryType, PasswordEntry};
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::auth::get_auth_setting;
+use crate::security::crypto::{decrypt_from_base64, encrypt_to_base64};
+use crate::security::session::require_session;
+use crate::security::vault::{is_unlocked, with_vault_key};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+use zeroize::Zeroizing;
+
+/// 历史版本保留数量的系统设置项
+pub const HISTORY_RETENTION_SETTING: &str = "passwordHistoryRetention";
+/// 默认保留的历史版本数量
+const DEFAULT_HISTORY_RETENTION: u32 = 10;
+/// 最多保留的历史版本数量
+const MAX_HISTORY_RETENTION: u32 = 100;
+/// 加密历史版本时使用的附加认证数据，绑定所属条目，密文无法被移动到其他条目下
+fn history_aad(entry_id: &str) -> Vec<u8> {
+    format!("password_history:{}", entry_id).into_bytes()
+}
+
+/**
+ * 历史版本中保存的敏感字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct HistorySecrets {
+    username: String,
+    password: String,
+    notes: Option<String>,
+    entry_type: EntryType,
+    /// 专有字段（包括登录条目的 TOTP 密钥）
+    fields: Option<EntryFields>,
+}
+
+impl HistorySecrets {
+    pub(crate) fn from_entry(entry: &PasswordEntry, fields: Option<EntryFields>) -> Self {
+        Self {
+            username: entry.username.clone(),
+            password: entry.password.clone(),
+            notes: entry.notes.clone(),
+            entry_type: entry.entry_type,
+            fields,
+        }
+    }
+
+    /// 与另一版本相比发生变化的字段名称
+    pub(crate) fn changed_fields(&self, other: &HistorySecrets) -> Vec<String> {
+        let mut fields = Vec::new();
+        if self.username != other.username {
+            fields.push("username".to_string());
+        }
+        if self.password != other.password {
+            fields.push("password".to_string());
+        }
+        if self.notes != other.notes {
+            fields.push("notes".to_string());
+        }
+        if self.entry_type != other.entry_type || self.fields != other.fields {
+            fields.push("fields".to_string());
+        }
+        fields
+    }
+}
+
+/**
+ * 密码条目更新内容
+ *
+ * 未提供的字段保持原值
+ */
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PasswordEntryUpdate {
+    /// 标题
+    pub title: Option<String>,
+    /// 用户名
+    pub username: Option<String>,
+    /// 密码
+    pub password: Option<String>,
+    /// 网址
+    pub url: Option<String>,
+    /// 备注
+    pub notes: Option<String>,
+    /// 分类ID
+    pub category: Option<String>,
+    /// 标签
+    pub tags: Option<Vec<String>>,
+    /// 是否收藏
+    pub is_favorite: Option<bool>,
+    /// 最后使用时间
+    pub last_used: Option<i64>,
+}
+
+/**
+ * 历史版本摘要，不包含敏感字段
+ */
+#[derive(Debug, Serialize)]
+pub struct PasswordHistoryItem {
+    /// 历史版本ID
+    pub id: String,
+    /// 条目ID
+    pub entry_id: String,
+    /// 该版本之后被修改的字段
+    pub changed_fields: Vec<String>,
+    /// 版本保存时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 解密后的历史版本
+ */
+#[derive(Debug, Serialize)]
+pub struct PasswordHistoryVersion {
+    /// 历史版本ID
+    pub id: String,
+    /// 条目ID
+    pub entry_id: String,
+    /// 用户名
+    pub username: String,
+    /// 密码
+    pub password: String,
+    /// 备注
+    pub notes: Option<String>,
+    /// 条目类型
+    pub entry_type: EntryType,
+    /// 专有字段
+    pub fields: Option<EntryFields>,
+    /// 该版本之后被修改的字段
+    pub changed_fields: Vec<String>,
+    /// 版本保存时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 读取历史版本保留数量
+ *
+ * @param conn 数据库连接（可为事务）
+ * @return 保留数量
+ */
+async fn get_retention(conn: &mut SqliteConnection) -> Result<u32, String> {
+    let row = sqlx::query("SELECT value FROM system_settings WHERE key = ?")
+        .bind(HISTORY_RETENTION_SETTING)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询系统设置失败: {}", e))?;
+
+    Ok(row
+        .and_then(|r| r.get::<Option<String>, _>("value"))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_HISTORY_RETENTION)
+        .min(MAX_HISTORY_RETENTION))
+}
+
+/**
+ * 删除超出保留数量的历史版本
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry_id 条目ID
+ * @param retention 保留数量
+ * @return 操作结果
+ */
+async fn prune_history(
+    conn: &mut SqliteConnection,
+    entry_id: &str,
+    retention: u32,
+) -> Result<(), String> {
+    sqlx::query(
+        "DELETE FROM password_history
+         WHERE entry_id = ? AND id NOT IN (
+             SELECT id FROM password_history WHERE entry_id = ?
+             ORDER BY created_at DESC LIMIT ?
+         )",
+    )
+    .bind(entry_id)
+    .bind(entry_id)
+    .bind(retention as i64)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("清理历史版本失败: {}", e))?;
+
+    Ok(())
+}
+
+/**
+ * 保存条目修改前敏感字段的加密快照
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry_id 条目ID
+ * @param secrets 修改前的敏感字段
+ * @param changed_fields 本次修改的字段
+ * @return 操作结果
+ */
+pub(crate) async fn save_snapshot(
+    conn: &mut SqliteConnection,
+    entry_id: &str,
+    secrets: &HistorySecrets,
+    changed_fields: &[String],
+) -> Result<(), String> {
+    let retention = get_retention(&mut *conn).await?;
+    if retention == 0 {
+        return Ok(());
+    }
+
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(secrets).map_err(|e| format!("序列化历史版本失败: {}", e))?,
+    );
+    let aad = history_aad(entry_id);
+    let encrypted = with_vault_key(|key| encrypt_to_base64(key, &plaintext, &aad))?;
+    let changed_fields =
+        serde_json::to_string(changed_fields).map_err(|e| format!("序列化字段列表失败: {}", e))?;
+
+    sqlx::query(
+        "INSERT INTO password_history (id, entry_id, encrypted_data, changed_fields, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(entry_id)
+    .bind(encrypted)
+    .bind(changed_fields)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("保存历史版本失败: {}", e))?;
+
+    prune_history(conn, entry_id, retention).await
+}
+
+/**
+ * 判断是否可以保存历史版本
+ *
+ * 已设置主密码但密码库未解锁时返回错误，避免在无法加密时丢失旧版本；
+ * 未设置主密码时无法生成密码库密钥，跳过历史记录
+ */
+pub(crate) async fn history_available() -> Result<bool, String> {
+    if is_unlocked() {
+        return Ok(true);
+    }
+    if get_auth_setting("passwordHash").await?.is_some() {
+        return Err("密码库未解锁，无法保存历史版本".to_string());
+    }
+
+    log::warn!("未设置主密码，密码条目修改将不保留历史版本");
+    Ok(false)
+}
+
+/**
+ * 更新密码条目
+ *
+ * 用户名、密码或备注发生变化时，先保存修改前的加密版本（连同专有字段）
+ *
+ * @param id 条目ID
+ * @param update 更新内容
+ * @return 更新后的条目
+ */
+#[tauri::command]
+pub async fn update_password_entry(
+    id: String,
+    update: PasswordEntryUpdate,
+) -> Result<PasswordEntry, String> {
+    let keep_history = history_available().await?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let current = get_entry(&mut tx, &id)
+        .await?
+        .ok_or_else(|| format!("密码条目不存在: {}", id))?;
+
+    let mut entry = current.clone();
+    if let Some(title) = update.title {
+        entry.title = title;
+    }
+    if let Some(username) = update.username {
+        entry.username = username;
+    }
+    if let Some(password) = update.password {
+        entry.password = password;
+    }
+    if update.url.is_some() {
+        entry.url = update.url;
+    }
+    if update.notes.is_some() {
+        entry.notes = update.notes;
+    }
+    if let Some(category) = update.category {
+        entry.category = category;
+    }
+    if let Some(tags) = update.tags {
+        entry.tags = tags;
+    }
+    if let Some(is_favorite) = update.is_favorite {
+        entry.is_favorite = is_favorite;
+    }
+    if update.last_used.is_some() {
+        entry.last_used = update.last_used;
+    }
+    entry.updated_at = chrono::Utc::now().timestamp_millis();
+
+    let changed_fields = HistorySecrets::from_entry(&current, None)
+        .changed_fields(&HistorySecrets::from_entry(&entry, None));
+    if keep_history && !changed_fields.is_empty() {
+        let fields = load_fields(&mut tx, &id).await?;
+        let secrets = HistorySecrets::from_entry(&current, fields);
+        save_snapshot(&mut tx, &id, &secrets, &changed_fields).await?;
+    }
+
+    update_entry(&mut tx, &entry).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+
+    Ok(entry)
+}
+
+/**
+ * 获取条目的历史版本列表
+ *
+ * @param entry_id 条目ID
+ * @return 历史版本摘要，按时间倒序
+ */
+#[tauri::command]
+pub async fn list_password_history(entry_id: String) -> Result<Vec<PasswordHistoryItem>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT id, entry_id, changed_fields, created_at FROM password_history
+         WHERE entry_id = ? ORDER BY created_at DESC",
+        &entry_id
+    )?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PasswordHistoryItem {
+            id: row.get("id"),
+            entry_id: row.get("entry_id"),
+            changed_fields: serde_json::from_str(&row.get::<String, _>("changed_fields"))
+                .unwrap_or_default(),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/**
+ * 读取并解密历史版本
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param history_id 历史版本ID
+ * @return 历史版本
+ */
+async fn load_version(
+    conn: &mut SqliteConnection,
+    history_id: &str,
+) -> Result<PasswordHistoryVersion, String> {
+    let row = sqlx::query(
+        "SELECT id, entry_id, encrypted_data, changed_fields, created_at
+         FROM password_history WHERE id = ?",
+    )
+    .bind(history_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| format!("查询历史版本失败: {}", e))?
+    .ok_or_else(|| format!("历史版本不存在: {}", history_id))?;
+
+    let entry_id: String = row.get("entry_id");
+    let encrypted: String = row.get("encrypted_data");
+    let aad = history_aad(&entry_id);
+    let plaintext = with_vault_key(|key| decrypt_from_base64(key, &encrypted, &aad))?;
+    let secrets: HistorySecrets =
+        serde_json::from_slice(&plaintext).map_err(|e| format!("解析历史版本失败: {}", e))?;
+
+    Ok(PasswordHistoryVersion {
+        id: row.get("id"),
+        entry_id,
+        username: secrets.username,
+        password: secrets.password,
+        notes: secrets.notes,
+        entry_type: secrets.entry_type,
+        fields: secrets.fields,
+        changed_fields: serde_json::from_str(&row.get::<String, _>("changed_fields"))
+            .unwrap_or_default(),
+        created_at: row.get("created_at"),
+    })
+}
+
+/**
+ * 获取解密后的历史版本
+ *
+ * @param history_id 历史版本ID
+ * @param session_id 会话ID
+ * @return 历史版本
+ */
+#[tauri::command]
+pub async fn get_password_history_version(
+    history_id: String,
+    session_id: String,
+) -> Result<PasswordHistoryVersion, String> {
+    require_session(&session_id).await?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut conn = db
+        .get_pool()
+        .acquire()
+        .await
+        .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+    let version = load_version(&mut conn, &history_id).await?;
+    drop(conn);
+    drop(db);
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "查看密码历史版本",
+        Some(serde_json::json!({ "entry_id": version.entry_id, "history_id": version.id })),
+    )
+    .await;
+    Ok(version)
+}
+
+/**
+ * 将条目恢复到指定历史版本
+ *
+ * 恢复用户名、密码、备注和专有字段，恢复前会保存当前版本，因此恢复操作本身也可以撤销
+ *
+ * @param history_id 历史版本ID
+ * @param session_id 会话ID
+ * @return 恢复后的条目
+ */
+#[tauri::command]
+pub async fn restore_password_history(
+    history_id: String,
+    session_id: String,
+) -> Result<PasswordEntry, String> {
+    require_session(&session_id).await?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let version = load_version(&mut tx, &history_id).await?;
+    let current = get_entry(&mut tx, &version.entry_id)
+        .await?
+        .ok_or_else(|| format!("密码条目不存在: {}", version.entry_id))?;
+
+    let current_fields = load_fields(&mut tx, &current.id).await?;
+
+    let mut entry = current.clone();
+    entry.username = version.username;
+    entry.password = version.password;
+    entry.notes = version.notes;
+    entry.entry_type = version.entry_type;
+    entry.updated_at = chrono::Utc::now().timestamp_millis();
+
+    let secrets = HistorySecrets::from_entry(&current, current_fields);
+    let changed_fields =
+        secrets.changed_fields(&HistorySecrets::from_entry(&entry, version.fields.clone()));
+    if !changed_fields.is_empty() {
+        save_snapshot(&mut tx, &entry.id, &secrets, &changed_fields).await?;
+        update_entry(&mut tx, &entry).await?;
+        store_fields(&mut tx, &entry.id, version.fields.as_ref()).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+
+    log::info!("密码条目已恢复到历史版本: {}", entry.id);
+    Ok(entry)
+}
+
+/**
+ * 获取历史版本保留数量
+ *
+ * @return 保留数量
+ */
+#[tauri::command]
+pub async fn get_password_history_retention() -> Result<u32, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut conn = db
+        .get_pool()
+        .acquire()
+        .await
+        .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+    get_retention(&mut conn).await
+}
+
+/**
+ * 设置历史版本保留数量
+ *
+ * 立即清理超出新保留数量的旧版本，设置为0时删除全部历史
+ *
+ * @param retention 保留数量
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn set_password_history_retention(retention: u32) -> Result<(), String> {
+    if retention > MAX_HISTORY_RETENTION {
+        return Err(format!("历史版本保留数量不能超过{}", MAX_HISTORY_RETENTION));
+    }
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let now = chrono::Utc::now().timestamp();
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO system_settings (key, value, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        HISTORY_RETENTION_SETTING,
+        retention.to_string(),
+        now
+    )?;
+    crate::execute_with_params!(
+        db,
+        "DELETE FROM password_history WHERE id IN (
+             SELECT id FROM (
+                 SELECT id, ROW_NUMBER() OVER (PARTITION BY entry_id ORDER BY created_at DESC) AS rn
+                 FROM password_history
+             ) WHERE rn > ?
+         )",
+        retention as i64
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password::types::LoginFields;
+
+    #[test]
+    fn detects_changed_entry_fields() {
+        let entry = PasswordEntry {
+            id: "e1".to_string(),
+            title: "GitHub".to_string(),
+            username: "alice".to_string(),
+            password: "pw".to_string(),
+            url: None,
+            notes: None,
+            category: String::new(),
+            tags: Vec::new(),
+            is_favorite: false,
+            entry_type: EntryType::Login,
+            created_at: 0,
+            updated_at: 0,
+            last_used: None,
+        };
+        let totp = |secret: &str| {
+            Some(EntryFields::Login(LoginFields {
+                totp: Some(secret.to_string()),
+            }))
+        };
+
+        let before = HistorySecrets::from_entry(&entry, totp("JBSWY3DPEHPK3PXP"));
+        assert!(before
+            .changed_fields(&HistorySecrets::from_entry(
+                &entry,
+                totp("JBSWY3DPEHPK3PXP")
+            ))
+            .is_empty());
+        assert_eq!(
+            before.changed_fields(&HistorySecrets::from_entry(&entry, None)),
+            ["fields"]
+        );
+
+        let mut changed = entry.clone();
+        changed.password = "new".to_string();
+        assert_eq!(
+            before.changed_fields(&HistorySecrets::from_entry(
+                &changed,
+                totp("JBSWY3DPEHPK3PXP")
+            )),
+            ["password"]
+        );
+    }
+}
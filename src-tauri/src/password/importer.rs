@@ -0,0 +1,922 @@
+/**
+ * 密码导入
+ *
+ * 支持从常见密码管理器的导出文件导入密码条目：
+ * - Bitwarden JSON / CSV
+ * - KeePass XML / CSV（KeePass 2.x 与 KeePassXC）
+ * - 1Password CSV
+ * - Chrome / Firefox CSV
+ *
+ * 导入时会检测重复条目、收集无法映射的字段，并在单个事务中完成写入
+ */
+use crate::password::fields::store_fields;
+use crate::password::store::{get_all_categories, get_all_entries, insert_entry};
+use crate::password::totp::parse_totp;
+use crate::password::types::{
+    EntryFields, EntryType, LoginFields, NewPasswordEntry, PasswordCategory, PasswordEntry,
+    DEFAULT_CATEGORY_ID,
+};
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+/**
+ * 导入文件格式
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    /// Bitwarden 未加密 JSON 导出
+    #[serde(rename = "bitwarden_json")]
+    BitwardenJson,
+    /// Bitwarden CSV 导出
+    #[serde(rename = "bitwarden_csv")]
+    BitwardenCsv,
+    /// KeePass 2.x XML 导出
+    #[serde(rename = "keepass_xml")]
+    KeepassXml,
+    /// KeePass 2.x / KeePassXC CSV 导出
+    #[serde(rename = "keepass_csv")]
+    KeepassCsv,
+    /// 1Password CSV 导出
+    #[serde(rename = "onepassword_csv")]
+    OnePasswordCsv,
+    /// Chrome 密码 CSV 导出
+    #[serde(rename = "chrome_csv")]
+    ChromeCsv,
+    /// Firefox 密码 CSV 导出
+    #[serde(rename = "firefox_csv")]
+    FirefoxCsv,
+}
+
+/**
+ * 重复条目信息
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateEntry {
+    /// 标题
+    pub title: String,
+    /// 用户名
+    pub username: String,
+    /// 网址
+    pub url: Option<String>,
+    /// 已存在条目的ID，为空表示与导入文件中的其他条目重复
+    pub existing_id: Option<String>,
+}
+
+/**
+ * 被跳过的条目信息
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct SkippedEntry {
+    /// 标题
+    pub title: String,
+    /// 跳过原因
+    pub reason: String,
+}
+
+/**
+ * 导入报告
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportReport {
+    /// 导入格式
+    pub format: ImportFormat,
+    /// 文件中解析出的条目数
+    pub total: usize,
+    /// 实际写入的条目数（预览时为0）
+    pub imported: usize,
+    /// 重复条目
+    pub duplicates: Vec<DuplicateEntry>,
+    /// 无法映射到密码条目的字段名
+    pub unmapped_fields: Vec<String>,
+    /// 被跳过的条目
+    pub skipped: Vec<SkippedEntry>,
+    /// 是否为预览（未写入数据库）
+    pub dry_run: bool,
+}
+
+/// 解析出的单个条目，分类尚未解析
+struct ParsedItem {
+    entry: NewPasswordEntry,
+    folder: Option<String>,
+}
+
+/// 导出文件的解析结果
+#[derive(Default)]
+struct ParsedImport {
+    items: Vec<ParsedItem>,
+    unmapped_fields: BTreeSet<String>,
+    skipped: Vec<SkippedEntry>,
+}
+
+impl ParsedImport {
+    /// 添加条目，空条目记为跳过
+    fn push(&mut self, mut item: ParsedItem) {
+        let entry = &mut item.entry;
+        if entry.username.is_empty() && entry.password.is_empty() && entry.url.is_none() {
+            self.skipped.push(SkippedEntry {
+                title: entry.title.clone(),
+                reason: "条目不包含用户名、密码或网址".to_string(),
+            });
+            return;
+        }
+
+        if entry.title.trim().is_empty() {
+            entry.title = entry
+                .url
+                .as_deref()
+                .and_then(url_host)
+                .or_else(|| (!entry.username.is_empty()).then(|| entry.username.clone()))
+                .unwrap_or_else(|| "未命名条目".to_string());
+        }
+
+        self.items.push(item);
+    }
+
+    /// 记录条目类型不受支持而被跳过
+    fn skip_kind(&mut self, title: &str, kind: &str) {
+        self.skipped.push(SkippedEntry {
+            title: title.to_string(),
+            reason: format!("不支持的条目类型: {}", kind),
+        });
+    }
+}
+
+//==============================================================================
+// CSV 格式解析
+//==============================================================================
+
+/// CSV 列对应的密码条目字段
+#[derive(Clone, Copy, PartialEq)]
+enum CsvField {
+    Title,
+    Username,
+    Password,
+    Url,
+    Notes,
+    /// TOTP 密钥或 otpauth:// 链接
+    Totp,
+    Tags,
+    Folder,
+    Favorite,
+    /// 条目类型列，仅导入登录类型
+    Kind,
+    /// 无需导入的技术性字段
+    Ignore,
+}
+
+/**
+ * 获取各CSV格式的列映射表
+ *
+ * 列名统一为小写比较
+ */
+fn csv_columns(format: ImportFormat) -> &'static [(&'static str, CsvField)] {
+    match format {
+        ImportFormat::BitwardenCsv => &[
+            ("name", CsvField::Title),
+            ("login_username", CsvField::Username),
+            ("login_password", CsvField::Password),
+            ("login_uri", CsvField::Url),
+            ("notes", CsvField::Notes),
+            ("login_totp", CsvField::Totp),
+            ("folder", CsvField::Folder),
+            ("favorite", CsvField::Favorite),
+            ("type", CsvField::Kind),
+            ("reprompt", CsvField::Ignore),
+        ],
+        ImportFormat::KeepassCsv => &[
+            // KeePassXC
+            ("group", CsvField::Folder),
+            ("title", CsvField::Title),
+            ("username", CsvField::Username),
+            ("password", CsvField::Password),
+            ("url", CsvField::Url),
+            ("notes", CsvField::Notes),
+            ("totp", CsvField::Totp),
+            ("icon", CsvField::Ignore),
+            ("last modified", CsvField::Ignore),
+            ("created", CsvField::Ignore),
+            // KeePass 2.x
+            ("account", CsvField::Title),
+            ("login name", CsvField::Username),
+            ("web site", CsvField::Url),
+            ("comments", CsvField::Notes),
+        ],
+        ImportFormat::OnePasswordCsv => &[
+            ("title", CsvField::Title),
+            ("username", CsvField::Username),
+            ("password", CsvField::Password),
+            ("url", CsvField::Url),
+            ("urls", CsvField::Url),
+            ("website", CsvField::Url),
+            ("notes", CsvField::Notes),
+            ("notesplain", CsvField::Notes),
+            ("otpauth", CsvField::Totp),
+            ("tags", CsvField::Tags),
+            ("favorite", CsvField::Favorite),
+            ("type", CsvField::Kind),
+            ("archived", CsvField::Ignore),
+            ("uuid", CsvField::Ignore),
+        ],
+        ImportFormat::ChromeCsv => &[
+            ("name", CsvField::Title),
+            ("url", CsvField::Url),
+            ("username", CsvField::Username),
+            ("password", CsvField::Password),
+            ("note", CsvField::Notes),
+        ],
+        ImportFormat::FirefoxCsv => &[
+            ("url", CsvField::Url),
+            ("username", CsvField::Username),
+            ("password", CsvField::Password),
+            ("httprealm", CsvField::Ignore),
+            ("formactionorigin", CsvField::Ignore),
+            ("guid", CsvField::Ignore),
+            ("timecreated", CsvField::Ignore),
+            ("timelastused", CsvField::Ignore),
+            ("timepasswordchanged", CsvField::Ignore),
+        ],
+        ImportFormat::BitwardenJson | ImportFormat::KeepassXml => &[],
+    }
+}
+
+/**
+ * 解析CSV格式的导出文件
+ *
+ * @param format 导入格式
+ * @param content 文件内容
+ * @return 解析结果
+ */
+fn parse_csv(format: ImportFormat, content: &str) -> Result<ParsedImport, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let columns = csv_columns(format);
+    let headers: Vec<(String, Option<CsvField>)> = reader
+        .headers()
+        .map_err(|e| format!("读取CSV表头失败: {}", e))?
+        .iter()
+        .map(|header| {
+            let name = header.trim().to_string();
+            let lower = name.to_lowercase();
+            let field = columns
+                .iter()
+                .find(|(column, _)| *column == lower)
+                .map(|(_, field)| *field);
+            (name, field)
+        })
+        .collect();
+
+    if !headers
+        .iter()
+        .any(|(_, field)| *field == Some(CsvField::Password))
+    {
+        return Err("CSV文件中未找到密码列，请确认导入格式是否正确".to_string());
+    }
+
+    let mut parsed = ParsedImport::default();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("解析CSV第{}行失败: {}", index + 2, e))?;
+
+        let mut entry = NewPasswordEntry::default();
+        let mut folder = None;
+        let mut kind = None;
+
+        for ((name, field), value) in headers.iter().zip(record.iter()) {
+            if value.is_empty() {
+                continue;
+            }
+            match field {
+                Some(CsvField::Title) => entry.title = value.trim().to_string(),
+                Some(CsvField::Username) => entry.username = value.to_string(),
+                Some(CsvField::Password) => entry.password = value.to_string(),
+                Some(CsvField::Url) => {
+                    // 1Password 等格式可能在同一列中包含多个网址
+                    let mut urls = value.split([',', '\n']).map(str::trim);
+                    entry.url = urls.next().filter(|u| !u.is_empty()).map(str::to_string);
+                    if urls.any(|u| !u.is_empty()) {
+                        parsed
+                            .unmapped_fields
+                            .insert(format!("{} (附加网址)", name));
+                    }
+                }
+                Some(CsvField::Notes) => entry.notes = Some(value.to_string()),
+                Some(CsvField::Totp) => entry.totp_secret = Some(value.trim().to_string()),
+                Some(CsvField::Tags) => entry.tags = split_tags(value),
+                Some(CsvField::Folder) => folder = Some(value.trim().to_string()),
+                Some(CsvField::Favorite) => entry.is_favorite = parse_flag(value),
+                Some(CsvField::Kind) => kind = Some(value.trim().to_lowercase()),
+                Some(CsvField::Ignore) => {}
+                None => {
+                    parsed.unmapped_fields.insert(name.clone());
+                }
+            }
+        }
+
+        if let Some(kind) = kind.filter(|k| k != "login") {
+            parsed.skip_kind(&entry.title, &kind);
+            continue;
+        }
+
+        parsed.push(ParsedItem { entry, folder });
+    }
+
+    Ok(parsed)
+}
+
+//==============================================================================
+// Bitwarden JSON 解析
+//==============================================================================
+
+/// Bitwarden 登录条目的类型值
+const BITWARDEN_LOGIN_TYPE: i64 = 1;
+
+/**
+ * 解析 Bitwarden 未加密的 JSON 导出文件
+ *
+ * @param content 文件内容
+ * @return 解析结果
+ */
+fn parse_bitwarden_json(content: &str) -> Result<ParsedImport, String> {
+    let root: Value =
+        serde_json::from_str(content).map_err(|e| format!("解析Bitwarden JSON失败: {}", e))?;
+
+    if root["encrypted"].as_bool().unwrap_or(false) {
+        return Err("不支持加密的Bitwarden导出文件，请使用未加密的JSON格式导出".to_string());
+    }
+
+    let folders: HashMap<&str, &str> = root["folders"]
+        .as_array()
+        .map(|folders| {
+            folders
+                .iter()
+                .filter_map(|f| Some((f["id"].as_str()?, f["name"].as_str()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let items = root["items"]
+        .as_array()
+        .ok_or_else(|| "Bitwarden JSON中缺少items字段".to_string())?;
+
+    let mut parsed = ParsedImport::default();
+    for item in items {
+        let title = item["name"].as_str().unwrap_or_default().trim().to_string();
+        let kind = item["type"].as_i64().unwrap_or_default();
+        if kind != BITWARDEN_LOGIN_TYPE {
+            let kind_name = match kind {
+                2 => "secure_note".to_string(),
+                3 => "card".to_string(),
+                4 => "identity".to_string(),
+                other => other.to_string(),
+            };
+            parsed.skip_kind(&title, &kind_name);
+            continue;
+        }
+
+        let login = &item["login"];
+        let mut uris = login["uris"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|u| u["uri"].as_str());
+
+        let entry = NewPasswordEntry {
+            title,
+            username: login["username"].as_str().unwrap_or_default().to_string(),
+            password: login["password"].as_str().unwrap_or_default().to_string(),
+            url: uris.next().map(str::to_string),
+            notes: item["notes"].as_str().map(str::to_string),
+            totp_secret: login["totp"]
+                .as_str()
+                .filter(|t| !t.is_empty())
+                .map(str::to_string),
+            is_favorite: item["favorite"].as_bool().unwrap_or(false),
+            ..Default::default()
+        };
+
+        if uris.next().is_some() {
+            parsed
+                .unmapped_fields
+                .insert("login.uris (附加网址)".to_string());
+        }
+        for field in item["fields"].as_array().into_iter().flatten() {
+            let name = field["name"].as_str().unwrap_or("未命名字段");
+            parsed.unmapped_fields.insert(format!("fields.{}", name));
+        }
+
+        let folder = item["folderId"]
+            .as_str()
+            .and_then(|id| folders.get(id))
+            .map(|name| name.to_string());
+
+        parsed.push(ParsedItem { entry, folder });
+    }
+
+    Ok(parsed)
+}
+
+//==============================================================================
+// KeePass XML 解析
+//==============================================================================
+
+/**
+ * 解析 KeePass 2.x XML 导出文件
+ *
+ * 回收站中的条目和条目历史版本不会被导入
+ *
+ * @param content 文件内容
+ * @return 解析结果
+ */
+fn parse_keepass_xml(content: &str) -> Result<ParsedImport, String> {
+    let document =
+        roxmltree::Document::parse(content).map_err(|e| format!("解析KeePass XML失败: {}", e))?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("KeePassFile") {
+        return Err("不是有效的KeePass XML导出文件".to_string());
+    }
+
+    let recycle_bin = root
+        .children()
+        .find(|n| n.has_tag_name("Meta"))
+        .and_then(|meta| child_text(meta, "RecycleBinUUID"));
+
+    let top_group = root
+        .children()
+        .find(|n| n.has_tag_name("Root"))
+        .and_then(|r| r.children().find(|n| n.has_tag_name("Group")))
+        .ok_or_else(|| "KeePass XML中缺少根分组".to_string())?;
+
+    let mut parsed = ParsedImport::default();
+    walk_keepass_group(top_group, None, recycle_bin.as_deref(), &mut parsed);
+    Ok(parsed)
+}
+
+/// 递归遍历 KeePass 分组，根分组下的条目不设置文件夹
+fn walk_keepass_group(
+    group: roxmltree::Node,
+    folder: Option<&str>,
+    recycle_bin: Option<&str>,
+    parsed: &mut ParsedImport,
+) {
+    for node in group.children().filter(|n| n.is_element()) {
+        if node.has_tag_name("Entry") {
+            parse_keepass_entry(node, folder, parsed);
+        } else if node.has_tag_name("Group") {
+            let uuid = child_text(node, "UUID");
+            if uuid.is_some() && uuid.as_deref() == recycle_bin {
+                continue;
+            }
+            let name = child_text(node, "Name");
+            walk_keepass_group(node, name.as_deref(), recycle_bin, parsed);
+        }
+    }
+}
+
+/// 解析单个 KeePass 条目
+fn parse_keepass_entry(node: roxmltree::Node, folder: Option<&str>, parsed: &mut ParsedImport) {
+    let mut entry = NewPasswordEntry::default();
+
+    for string in node.children().filter(|n| n.has_tag_name("String")) {
+        let Some(key) = child_text(string, "Key") else {
+            continue;
+        };
+        let value = child_text(string, "Value").unwrap_or_default();
+        if value.is_empty() {
+            continue;
+        }
+        match key.as_str() {
+            "Title" => entry.title = value.trim().to_string(),
+            "UserName" => entry.username = value,
+            "Password" => entry.password = value,
+            "URL" => entry.url = Some(value.trim().to_string()),
+            "Notes" => entry.notes = Some(value),
+            "otp" | "TOTP Seed" => entry.totp_secret = Some(value.trim().to_string()),
+            _ => {
+                parsed.unmapped_fields.insert(key);
+            }
+        }
+    }
+
+    if let Some(tags) = child_text(node, "Tags") {
+        entry.tags = split_tags(&tags);
+    }
+
+    parsed.push(ParsedItem {
+        entry,
+        folder: folder.map(str::to_string),
+    });
+}
+
+/// 获取指定子元素的文本内容
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .and_then(|n| n.text())
+        .map(str::to_string)
+}
+
+//==============================================================================
+// 通用辅助函数
+//==============================================================================
+
+/// 拆分以逗号或分号分隔的标签
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 解析布尔标记字段
+fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "true" | "yes" | "y"
+    )
+}
+
+/// 从网址中提取主机名
+fn url_host(url: &str) -> Option<String> {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()?
+        .rsplit('@')
+        .next()?
+        .split(':')
+        .next()?
+        .trim()
+        .to_lowercase();
+    (!host.is_empty()).then_some(host)
+}
+
+/// 生成用于判断重复条目的键
+fn duplicate_key(title: &str, username: &str, url: Option<&str>) -> (String, String, String) {
+    (
+        title.trim().to_lowercase(),
+        username.trim().to_lowercase(),
+        url.unwrap_or_default()
+            .trim()
+            .trim_end_matches('/')
+            .to_lowercase(),
+    )
+}
+
+/**
+ * 将文件夹/分组名称解析为分类ID
+ *
+ * 按分类ID或名称匹配已有分类，无法匹配时归入默认分类，并将原名称保留为标签
+ */
+fn resolve_category(item: &mut ParsedItem, categories: &[PasswordCategory]) {
+    let Some(folder) = item.folder.as_deref().filter(|f| !f.is_empty()) else {
+        item.entry.category = DEFAULT_CATEGORY_ID.to_string();
+        return;
+    };
+
+    let matched = categories
+        .iter()
+        .find(|c| c.id.eq_ignore_ascii_case(folder) || c.name.eq_ignore_ascii_case(folder));
+
+    match matched {
+        Some(category) => item.entry.category = category.id.clone(),
+        None => {
+            item.entry.category = DEFAULT_CATEGORY_ID.to_string();
+            if !item.entry.tags.iter().any(|t| t == folder) {
+                item.entry.tags.push(folder.to_string());
+            }
+        }
+    }
+}
+
+/**
+ * 按格式解析导出文件内容
+ *
+ * @param format 导入格式
+ * @param content 文件内容
+ * @return 解析结果
+ */
+fn parse_export(format: ImportFormat, content: &str) -> Result<ParsedImport, String> {
+    // 部分导出工具会写入UTF-8 BOM
+    let content = content.trim_start_matches('\u{feff}');
+
+    match format {
+        ImportFormat::BitwardenJson => parse_bitwarden_json(content),
+        ImportFormat::KeepassXml => parse_keepass_xml(content),
+        _ => parse_csv(format, content),
+    }
+}
+
+/**
+ * 执行导入流程
+ *
+ * @param format 导入格式
+ * @param file_path 导出文件路径
+ * @param dry_run 是否仅预览
+ * @param skip_duplicates 是否跳过重复条目
+ * @return 导入报告
+ */
+async fn run_import(
+    format: ImportFormat,
+    file_path: &str,
+    dry_run: bool,
+    skip_duplicates: bool,
+) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("读取导入文件失败: {}", e))?;
+    let mut parsed = parse_export(format, &content)?;
+    let total = parsed.items.len() + parsed.skipped.len();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let pool = db.get_pool();
+
+    let categories = get_all_categories(pool).await?;
+    let mut seen: HashMap<_, Option<String>> = get_all_entries(pool)
+        .await?
+        .into_iter()
+        .map(|e| {
+            (
+                duplicate_key(&e.title, &e.username, e.url.as_deref()),
+                Some(e.id),
+            )
+        })
+        .collect();
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut duplicates = Vec::new();
+    let mut to_insert = Vec::new();
+
+    for mut item in parsed.items.drain(..) {
+        resolve_category(&mut item, &categories);
+        let entry = item.entry;
+
+        let key = duplicate_key(&entry.title, &entry.username, entry.url.as_deref());
+        if let Some(existing_id) = seen.get(&key) {
+            duplicates.push(DuplicateEntry {
+                title: entry.title.clone(),
+                username: entry.username.clone(),
+                url: entry.url.clone(),
+                existing_id: existing_id.clone(),
+            });
+            if skip_duplicates {
+                continue;
+            }
+        } else {
+            seen.insert(key, None);
+        }
+
+        // 无法解析的 TOTP 密钥不导入，避免整个事务失败
+        let totp = entry.totp_secret.filter(|totp| !totp.is_empty());
+        let totp = match totp {
+            Some(totp) if parse_totp(&totp).is_err() => {
+                parsed.unmapped_fields.insert("totp (格式无效)".to_string());
+                None
+            }
+            totp => totp,
+        };
+        let entry = PasswordEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: entry.title,
+            username: entry.username,
+            password: entry.password,
+            url: entry.url,
+            notes: entry.notes,
+            category: entry.category,
+            tags: entry.tags,
+            is_favorite: entry.is_favorite,
+            entry_type: EntryType::Login,
+            created_at: now,
+            updated_at: now,
+            last_used: None,
+        };
+        to_insert.push((entry, totp));
+    }
+
+    if !dry_run && !to_insert.is_empty() {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("开启事务失败: {}", e))?;
+        for (entry, totp) in &to_insert {
+            insert_entry(&mut tx, entry).await?;
+            if let Some(totp) = totp {
+                let fields = EntryFields::Login(LoginFields {
+                    totp: Some(totp.clone()),
+                });
+                store_fields(&mut tx, &entry.id, Some(&fields)).await?;
+            }
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))?;
+        log::info!("密码导入完成: {:?} 共导入 {} 条", format, to_insert.len());
+    }
+
+    Ok(ImportReport {
+        format,
+        total,
+        imported: if dry_run { 0 } else { to_insert.len() },
+        duplicates,
+        unmapped_fields: parsed.unmapped_fields.into_iter().collect(),
+        skipped: parsed.skipped,
+        dry_run,
+    })
+}
+
+/**
+ * 预览导入结果
+ *
+ * 解析导出文件并生成导入报告，不写入数据库
+ *
+ * @param format 导入格式
+ * @param file_path 导出文件路径
+ * @return 导入报告
+ */
+#[tauri::command]
+pub async fn preview_password_import(
+    format: ImportFormat,
+    file_path: String,
+) -> Result<ImportReport, String> {
+    run_import(format, &file_path, true, true).await
+}
+
+/**
+ * 从其他密码管理器的导出文件导入密码条目
+ *
+ * 所有条目在同一事务中写入，任一条目失败则全部回滚
+ *
+ * @param format 导入格式
+ * @param file_path 导出文件路径
+ * @param skip_duplicates 是否跳过重复条目，默认跳过
+ * @param session_id 会话ID
+ * @return 导入报告
+ */
+#[tauri::command]
+pub async fn import_passwords(
+    format: ImportFormat,
+    file_path: String,
+    skip_duplicates: Option<bool>,
+    session_id: String,
+) -> Result<ImportReport, String> {
+    require_session(&session_id).await?;
+    let report = run_import(format, &file_path, false, skip_duplicates.unwrap_or(true)).await?;
+
+    record_quietly(
+        AuditEvent::Import,
+        "导入密码条目",
+        Some(serde_json::json!({
+            "format": report.format,
+            "file_path": file_path,
+            "imported": report.imported,
+        })),
+    )
+    .await;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bitwarden_csv() {
+        let content = "\u{feff}folder,favorite,type,name,notes,fields,reprompt,login_uri,login_username,login_password,login_totp\n\
+                       工作,1,login,GitHub,备注,,0,https://github.com,alice,secret,JBSWY3DPEHPK3PXP\n\
+                       ,,note,笔记,内容,,0,,,,\n\
+                       ,,login,,,,0,,,,\n";
+        let parsed = parse_export(ImportFormat::BitwardenCsv, content).unwrap();
+
+        assert_eq!(parsed.items.len(), 1);
+        let item = &parsed.items[0];
+        assert_eq!(item.entry.title, "GitHub");
+        assert_eq!(item.entry.username, "alice");
+        assert_eq!(item.entry.url.as_deref(), Some("https://github.com"));
+        assert_eq!(item.entry.totp_secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert!(item.entry.is_favorite);
+        assert_eq!(item.folder.as_deref(), Some("工作"));
+        // 安全笔记类型与空条目被跳过
+        assert_eq!(parsed.skipped.len(), 2);
+    }
+
+    #[test]
+    fn records_unmapped_columns_and_extra_urls() {
+        let content = "Title,Url,Username,Password,Custom\n\
+                       ,\"https://a.example.com/login, https://b.example.com\",bob,pw,x\n";
+        let parsed = parse_export(ImportFormat::OnePasswordCsv, content).unwrap();
+
+        let entry = &parsed.items[0].entry;
+        assert_eq!(entry.url.as_deref(), Some("https://a.example.com/login"));
+        // 缺少标题时使用网址的主机名
+        assert_eq!(entry.title, "a.example.com");
+        assert!(parsed.unmapped_fields.contains("Custom"));
+        assert!(parsed.unmapped_fields.contains("Url (附加网址)"));
+    }
+
+    #[test]
+    fn rejects_csv_without_password_column() {
+        let content = "name,url,username\nGitHub,https://github.com,alice\n";
+        assert!(parse_export(ImportFormat::ChromeCsv, content).is_err());
+    }
+
+    #[test]
+    fn parses_bitwarden_json() {
+        let content = r#"{
+            "encrypted": false,
+            "folders": [{ "id": "f1", "name": "个人" }],
+            "items": [
+                {
+                    "type": 1, "name": "邮箱", "folderId": "f1", "favorite": true,
+                    "login": {
+                        "username": "alice", "password": "pw",
+                        "uris": [{ "uri": "https://mail.example.com" }, { "uri": "https://m.example.com" }]
+                    },
+                    "fields": [{ "name": "PIN" }]
+                },
+                { "type": 3, "name": "信用卡" }
+            ]
+        }"#;
+        let parsed = parse_export(ImportFormat::BitwardenJson, content).unwrap();
+
+        assert_eq!(parsed.items.len(), 1);
+        assert_eq!(parsed.items[0].folder.as_deref(), Some("个人"));
+        assert!(parsed.items[0].entry.is_favorite);
+        assert!(parsed.unmapped_fields.contains("fields.PIN"));
+        assert!(parsed.unmapped_fields.contains("login.uris (附加网址)"));
+        assert_eq!(parsed.skipped[0].reason, "不支持的条目类型: card");
+
+        assert!(parse_export(ImportFormat::BitwardenJson, r#"{"encrypted": true}"#).is_err());
+    }
+
+    #[test]
+    fn parses_keepass_xml_without_recycle_bin() {
+        let content = r#"<KeePassFile>
+            <Meta><RecycleBinUUID>bin</RecycleBinUUID></Meta>
+            <Root><Group><Name>根</Name>
+                <Entry>
+                    <String><Key>Title</Key><Value>服务器</Value></String>
+                    <String><Key>UserName</Key><Value>root</Value></String>
+                    <String><Key>Password</Key><Value>pw</Value></String>
+                    <String><Key>Extra</Key><Value>x</Value></String>
+                    <Tags>运维;linux</Tags>
+                </Entry>
+                <Group><UUID>bin</UUID><Name>回收站</Name>
+                    <Entry><String><Key>Password</Key><Value>old</Value></String></Entry>
+                </Group>
+            </Group></Root>
+        </KeePassFile>"#;
+        let parsed = parse_export(ImportFormat::KeepassXml, content).unwrap();
+
+        assert_eq!(parsed.items.len(), 1);
+        let item = &parsed.items[0];
+        assert_eq!(item.entry.title, "服务器");
+        assert_eq!(item.entry.tags, ["运维", "linux"]);
+        assert!(item.folder.is_none());
+        assert!(parsed.unmapped_fields.contains("Extra"));
+    }
+
+    #[test]
+    fn helpers_normalize_values() {
+        assert_eq!(
+            url_host("https://user@Example.com:8443/path?q=1").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(url_host(""), None);
+        assert!(parse_flag(" Yes "));
+        assert!(!parse_flag("0"));
+        assert_eq!(split_tags("a, b;;c"), ["a", "b", "c"]);
+        assert_eq!(
+            duplicate_key(" GitHub ", "Alice", Some("https://github.com/")),
+            duplicate_key("github", "alice", Some("HTTPS://GITHUB.COM"))
+        );
+    }
+
+    #[test]
+    fn resolves_unknown_folders_to_tags() {
+        let categories = [PasswordCategory {
+            id: "work".to_string(),
+            name: "工作".to_string(),
+            icon: String::new(),
+            color: String::new(),
+        }];
+        let item = |folder: &str| ParsedItem {
+            entry: NewPasswordEntry::default(),
+            folder: Some(folder.to_string()),
+        };
+
+        let mut known = item("工作");
+        resolve_category(&mut known, &categories);
+        assert_eq!(known.entry.category, "work");
+
+        let mut unknown = item("旅行");
+        resolve_category(&mut unknown, &categories);
+        assert_eq!(unknown.entry.category, DEFAULT_CATEGORY_ID);
+        assert_eq!(unknown.entry.tags, ["旅行"]);
+    }
+}
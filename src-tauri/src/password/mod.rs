@@ -0,0 +1,46 @@
+/**
+ * 密码管理模块
+ *
+ * 该模块提供密码库相关的后端功能：
+ * - types: 密码管理类型和常量定义
+ * - store: 密码条目数据库访问
+ * - importer: 从其他密码管理器导入
+ * - export: 加密备份、明文导出与备份恢复
+ * - strength: 密码强度评估
+ * - health: 密码健康审计与泄露检测
+ * - generator: 安全随机密码与口令生成
+ * - history: 条目历史版本与保留策略
+ * - totp: TOTP 一次性密码
+ * - clipboard: 安全剪贴板复制与自动清空
+ * - fields: 安全笔记、信用卡等条目类型的加密字段
+ * - attachment: 分块加密的条目附件
+ * - ssh_agent: 使用密码库中 SSH 密钥的代理
+ * - auto_type: 按窗口标题匹配条目并模拟键盘输入
+ */
+mod attachment;
+mod auto_type;
+mod clipboard;
+mod export;
+pub(crate) mod fields;
+mod generator;
+mod health;
+mod history;
+mod importer;
+mod ssh_agent;
+pub(crate) mod store;
+mod strength;
+pub(crate) mod totp;
+pub(crate) mod types;
+
+// 导出公开API
+pub use attachment::*;
+pub use auto_type::*;
+pub use clipboard::*;
+pub use export::*;
+pub use fields::*;
+pub use generator::*;
+pub use health::*;
+pub use history::*;
+pub use importer::*;
+pub use ssh_agent::*;
+pub use strength::*;
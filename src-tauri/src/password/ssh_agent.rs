@@ -0,0 +1,564 @@
+/**
+ * SSH 代理
+ *
+ * 在应用数据目录下仅当前用户可访问的 ssh-agent 目录中创建 Unix 套接字，实现 SSH 代理协议中列出密钥和签名两种请求，
+ * 使用密码库中 SSH 密钥类型条目保存的私钥。私钥只在处理请求时解密，每次签名
+ * 都需要用户在对话框中确认。密码库锁定时代理立即停止服务。
+ *
+ * 使用方式：将 SSH_AUTH_SOCK 环境变量设置为 get_ssh_agent_status 返回的套接字路径
+ */
+#[cfg(unix)]
+use crate::file::paths::get_data_dir;
+#[cfg(unix)]
+use crate::password::fields::load_entries_with_fields;
+#[cfg(unix)]
+use crate::password::types::{EntryFields, EntryType};
+#[cfg(unix)]
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::session::require_session;
+use crate::security::vault;
+#[cfg(unix)]
+use rsa::signature::{SignatureEncoding, Signer};
+use serde::Serialize;
+#[cfg(unix)]
+use ssh_encoding::Encode;
+#[cfg(unix)]
+use ssh_key::private::KeypairData;
+#[cfg(unix)]
+use ssh_key::{Algorithm, HashAlg, PrivateKey, Signature};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+#[cfg(unix)]
+use std::time::Duration;
+use tauri::AppHandle;
+#[cfg(unix)]
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::watch;
+
+/// 存放套接字的目录名，权限为 0700
+#[cfg(unix)]
+const SOCKET_DIR: &str = "ssh-agent";
+/// 套接字文件名
+#[cfg(unix)]
+const SOCKET_FILE: &str = "agent.sock";
+/// 单条消息长度上限（字节）
+#[cfg(unix)]
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+/// 等待用户确认签名的时间（秒），超时视为拒绝
+#[cfg(unix)]
+const CONFIRM_TIMEOUT_SECS: u64 = 60;
+
+// SSH 代理协议消息类型（draft-miller-ssh-agent）
+#[cfg(unix)]
+const SSH_AGENT_FAILURE: u8 = 5;
+#[cfg(unix)]
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+#[cfg(unix)]
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+#[cfg(unix)]
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+#[cfg(unix)]
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+// 签名请求标志
+#[cfg(unix)]
+const SSH_AGENT_RSA_SHA2_256: u32 = 2;
+#[cfg(unix)]
+const SSH_AGENT_RSA_SHA2_512: u32 = 4;
+
+/**
+ * 运行中的代理
+ */
+struct AgentHandle {
+    /// 套接字路径
+    socket_path: PathBuf,
+    /// 停止信号
+    shutdown: watch::Sender<bool>,
+}
+
+// 使用lazy_static管理运行中的代理
+lazy_static::lazy_static! {
+    static ref AGENT: Arc<Mutex<Option<AgentHandle>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 代理状态
+ */
+#[derive(Debug, Serialize)]
+pub struct SshAgentStatus {
+    /// 是否正在运行
+    pub running: bool,
+    /// 套接字路径
+    pub socket_path: Option<String>,
+    /// 可用的密钥数量
+    pub key_count: usize,
+}
+
+/**
+ * 代理可用的密钥
+ */
+#[cfg(unix)]
+struct AgentKey {
+    /// 条目ID
+    entry_id: String,
+    /// 条目标题，作为密钥注释
+    title: String,
+    /// 私钥
+    key: PrivateKey,
+    /// 公钥的线上编码
+    blob: Vec<u8>,
+}
+
+/**
+ * 读取密码库中可用的 SSH 密钥
+ *
+ * 密码库未解锁时返回空列表，无法解析或缺少口令的私钥会被跳过
+ *
+ * @return 密钥列表
+ */
+#[cfg(unix)]
+async fn load_keys() -> Vec<AgentKey> {
+    if !vault::is_unlocked() {
+        return Vec::new();
+    }
+    let entries = match load_entries_with_fields(EntryType::SshKey).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("读取 SSH 密钥条目失败: {}", e);
+            return Vec::new();
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|(entry_id, title, fields)| {
+            let EntryFields::SshKey(fields) = fields else {
+                return None;
+            };
+            let mut key = match PrivateKey::from_openssh(fields.private_key.trim()) {
+                Ok(key) => key,
+                Err(e) => {
+                    log::warn!("无法解析 SSH 私钥 ({}): {}", title, e);
+                    return None;
+                }
+            };
+            if key.is_encrypted() {
+                let passphrase = fields.passphrase.as_deref().unwrap_or_default();
+                key = match key.decrypt(passphrase) {
+                    Ok(key) => key,
+                    Err(e) => {
+                        log::warn!("无法解密 SSH 私钥 ({}): {}", title, e);
+                        return None;
+                    }
+                };
+            }
+            let blob = key.public_key().to_bytes().ok()?;
+            Some(AgentKey {
+                entry_id,
+                title,
+                key,
+                blob,
+            })
+        })
+        .collect()
+}
+
+/// 读取大端 u32
+#[cfg(unix)]
+fn read_u32(buf: &[u8], pos: &mut usize) -> Option<u32> {
+    let bytes = buf.get(*pos..*pos + 4)?;
+    *pos += 4;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// 读取带长度前缀的字符串
+#[cfg(unix)]
+fn read_string<'a>(buf: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = read_u32(buf, pos)? as usize;
+    let data = buf.get(*pos..pos.checked_add(len)?)?;
+    *pos += len;
+    Some(data)
+}
+
+/// 写入带长度前缀的字符串
+#[cfg(unix)]
+fn put_string(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+/**
+ * 使用私钥签名
+ *
+ * RSA 密钥按请求标志选择 SHA-256 或 SHA-512，不支持已废弃的 SHA-1 签名
+ *
+ * @param key 私钥
+ * @param data 待签名数据
+ * @param flags 请求标志
+ * @return 签名的线上编码
+ */
+#[cfg(unix)]
+fn sign(key: &PrivateKey, data: &[u8], flags: u32) -> Result<Vec<u8>, String> {
+    let signature = match key.key_data() {
+        KeypairData::Rsa(rsa_key) if flags & SSH_AGENT_RSA_SHA2_256 != 0 => {
+            let signing_key = rsa::pkcs1v15::SigningKey::<sha2::Sha256>::try_from(rsa_key)
+                .map_err(|e| format!("RSA 私钥无效: {}", e))?;
+            let data = signing_key
+                .try_sign(data)
+                .map_err(|e| format!("签名失败: {}", e))?;
+            Signature::new(
+                Algorithm::Rsa {
+                    hash: Some(HashAlg::Sha256),
+                },
+                data.to_vec(),
+            )
+            .map_err(|e| format!("签名失败: {}", e))?
+        }
+        KeypairData::Rsa(_) if flags & SSH_AGENT_RSA_SHA2_512 == 0 => {
+            return Err("不支持 SHA-1 RSA 签名".to_string());
+        }
+        _ => key.try_sign(data).map_err(|e| format!("签名失败: {}", e))?,
+    };
+
+    let mut out = Vec::new();
+    signature
+        .encode(&mut out)
+        .map_err(|e| format!("编码签名失败: {}", e))?;
+    Ok(out)
+}
+
+/**
+ * 弹出对话框请求用户确认签名
+ *
+ * @param app Tauri应用句柄
+ * @param key 要使用的密钥
+ * @return 用户是否允许
+ */
+#[cfg(unix)]
+async fn confirm_sign(app: &AppHandle, key: &AgentKey) -> bool {
+    let fingerprint = key.key.public_key().fingerprint(HashAlg::Sha256);
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "是否允许使用 SSH 密钥“{}”进行签名？\n\n指纹: {}",
+            key.title, fingerprint
+        ))
+        .title("SSH 签名请求")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "允许".to_string(),
+            "拒绝".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+
+    matches!(
+        tokio::time::timeout(Duration::from_secs(CONFIRM_TIMEOUT_SECS), rx).await,
+        Ok(Ok(true))
+    )
+}
+
+/**
+ * 处理一条代理请求
+ *
+ * @param app Tauri应用句柄
+ * @param message 请求消息（不含长度前缀）
+ * @return 响应消息（不含长度前缀）
+ */
+#[cfg(unix)]
+async fn handle_request(app: &AppHandle, message: &[u8]) -> Vec<u8> {
+    let failure = vec![SSH_AGENT_FAILURE];
+    let Some((&kind, body)) = message.split_first() else {
+        return failure;
+    };
+
+    match kind {
+        SSH_AGENTC_REQUEST_IDENTITIES => {
+            let keys = load_keys().await;
+            let mut out = vec![SSH_AGENT_IDENTITIES_ANSWER];
+            out.extend_from_slice(&(keys.len() as u32).to_be_bytes());
+            for key in &keys {
+                put_string(&mut out, &key.blob);
+                put_string(&mut out, key.title.as_bytes());
+            }
+            out
+        }
+        SSH_AGENTC_SIGN_REQUEST => {
+            let mut pos = 0;
+            let (Some(blob), Some(data), Some(flags)) = (
+                read_string(body, &mut pos),
+                read_string(body, &mut pos),
+                read_u32(body, &mut pos),
+            ) else {
+                return failure;
+            };
+            let keys = load_keys().await;
+            let Some(key) = keys.iter().find(|key| key.blob == blob) else {
+                return failure;
+            };
+
+            if !confirm_sign(app, key).await {
+                log::info!("用户拒绝了 SSH 签名请求: {}", key.title);
+                return failure;
+            }
+            // 等待确认期间密码库可能已被锁定
+            if !vault::is_unlocked() {
+                return failure;
+            }
+
+            match sign(&key.key, data, flags) {
+                Ok(signature) => {
+                    record_quietly(
+                        AuditEvent::VaultReveal,
+                        "SSH 代理使用密钥签名",
+                        Some(serde_json::json!({ "entry_id": key.entry_id })),
+                    )
+                    .await;
+                    let mut out = vec![SSH_AGENT_SIGN_RESPONSE];
+                    put_string(&mut out, &signature);
+                    out
+                }
+                Err(e) => {
+                    log::warn!("SSH 签名失败 ({}): {}", key.title, e);
+                    failure
+                }
+            }
+        }
+        _ => failure,
+    }
+}
+
+#[cfg(unix)]
+mod server {
+    use super::{handle_request, MAX_MESSAGE_LEN};
+    use tauri::AppHandle;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{UnixListener, UnixStream};
+    use tokio::sync::watch;
+
+    /**
+     * 处理单个客户端连接
+     *
+     * 客户端可以在同一连接上发送多条请求，收到停止信号或连接关闭时返回
+     */
+    async fn handle_connection(
+        app: AppHandle,
+        mut stream: UnixStream,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        loop {
+            let mut len = [0u8; 4];
+            tokio::select! {
+                _ = shutdown.changed() => return,
+                result = stream.read_exact(&mut len) => {
+                    if result.is_err() {
+                        return;
+                    }
+                }
+            }
+            let len = u32::from_be_bytes(len) as usize;
+            if len == 0 || len > MAX_MESSAGE_LEN {
+                return;
+            }
+            let mut message = vec![0u8; len];
+            if stream.read_exact(&mut message).await.is_err() {
+                return;
+            }
+
+            let response = tokio::select! {
+                _ = shutdown.changed() => return,
+                response = handle_request(&app, &message) => response,
+            };
+            let mut out = (response.len() as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(&response);
+            if stream.write_all(&out).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /**
+     * 接受客户端连接直到收到停止信号
+     */
+    pub async fn serve(
+        app: AppHandle,
+        listener: UnixListener,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        tauri::async_runtime::spawn(handle_connection(
+                            app.clone(),
+                            stream,
+                            shutdown.clone(),
+                        ));
+                    }
+                    Err(e) => log::warn!("SSH 代理接受连接失败: {}", e),
+                },
+            }
+        }
+        log::info!("SSH 代理已停止");
+    }
+}
+
+/**
+ * 停止 SSH 代理并删除套接字
+ *
+ * 密码库锁定时调用
+ *
+ * @return 停止前是否正在运行
+ */
+pub fn stop_agent() -> bool {
+    let handle = match AGENT.lock() {
+        Ok(mut agent) => agent.take(),
+        Err(e) => e.into_inner().take(),
+    };
+    let Some(handle) = handle else {
+        return false;
+    };
+
+    let _ = handle.shutdown.send(true);
+    if let Err(e) = std::fs::remove_file(&handle.socket_path) {
+        log::warn!("删除 SSH 代理套接字失败: {}", e);
+    }
+    true
+}
+
+/**
+ * 获取当前代理状态
+ */
+async fn current_status() -> Result<SshAgentStatus, String> {
+    let socket_path = AGENT
+        .lock()
+        .map_err(|e| format!("获取 SSH 代理锁失败: {}", e))?
+        .as_ref()
+        .map(|handle| handle.socket_path.to_string_lossy().to_string());
+    #[cfg(unix)]
+    let key_count = if socket_path.is_some() {
+        load_keys().await.len()
+    } else {
+        0
+    };
+    // 其他平台上代理不会运行
+    #[cfg(not(unix))]
+    let key_count = 0;
+
+    Ok(SshAgentStatus {
+        running: socket_path.is_some(),
+        socket_path,
+        key_count,
+    })
+}
+
+/**
+ * 启动 SSH 代理
+ *
+ * 需要密码库已解锁，代理在密码库锁定时自动停止
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @return 代理状态
+ */
+#[tauri::command]
+pub async fn start_ssh_agent(app: AppHandle, session_id: String) -> Result<SshAgentStatus, String> {
+    require_session(&session_id).await?;
+    if !vault::is_unlocked() {
+        return Err("密码库未解锁".to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        let mut agent = AGENT
+            .lock()
+            .map_err(|e| format!("获取 SSH 代理锁失败: {}", e))?;
+        if agent.is_none() {
+            // 套接字创建后到修改权限之前可能被其他用户连接，因此放在仅当前用户可访问的目录中
+            let dir = get_data_dir(app.clone())?.join(SOCKET_DIR);
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(&dir)
+                .map_err(|e| format!("创建 SSH 代理目录失败: {}", e))?;
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+                .map_err(|e| format!("设置 SSH 代理目录权限失败: {}", e))?;
+            let socket_path = dir.join(SOCKET_FILE);
+            // 清理上次异常退出遗留的套接字
+            if socket_path.exists() {
+                std::fs::remove_file(&socket_path)
+                    .map_err(|e| format!("删除旧的 SSH 代理套接字失败: {}", e))?;
+            }
+
+            let listener = tokio::net::UnixListener::bind(&socket_path)
+                .map_err(|e| format!("创建 SSH 代理套接字失败: {}", e))?;
+            std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("设置 SSH 代理套接字权限失败: {}", e))?;
+
+            let (shutdown, receiver) = watch::channel(false);
+            tauri::async_runtime::spawn(server::serve(app, listener, receiver));
+            *agent = Some(AgentHandle {
+                socket_path: socket_path.clone(),
+                shutdown,
+            });
+            log::info!("SSH 代理已启动: {}", socket_path.display());
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = app;
+        return Err("SSH 代理目前仅支持 Linux 和 macOS".to_string());
+    }
+
+    #[cfg(unix)]
+    current_status().await
+}
+
+/**
+ * 停止 SSH 代理
+ *
+ * @return 停止前是否正在运行
+ */
+#[tauri::command]
+pub async fn stop_ssh_agent() -> bool {
+    stop_agent()
+}
+
+/**
+ * 获取 SSH 代理状态
+ *
+ * @return 代理状态
+ */
+#[tauri::command]
+pub async fn get_ssh_agent_status() -> Result<SshAgentStatus, String> {
+    current_status().await
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_round_trip() {
+        let mut out = Vec::new();
+        put_string(&mut out, b"key");
+        out.extend_from_slice(&7u32.to_be_bytes());
+
+        let mut pos = 0;
+        assert_eq!(read_string(&out, &mut pos), Some(&b"key"[..]));
+        assert_eq!(read_u32(&out, &mut pos), Some(7));
+        assert_eq!(read_u32(&out, &mut pos), None);
+    }
+
+    #[test]
+    fn truncated_strings_are_rejected() {
+        let mut pos = 0;
+        assert_eq!(read_string(&[0, 0, 0, 5, b'a'], &mut pos), None);
+        let mut pos = 0;
+        assert_eq!(read_string(&[0xff, 0xff, 0xff, 0xff], &mut pos), None);
+    }
+}
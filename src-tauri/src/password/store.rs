@@ -0,0 +1,252 @@
+/**
+ * 密码条目数据库访问
+ *
+ * 提供 password_entries / password_categories 表的读写辅助函数
+ */
+use crate::password::types::{EntryType, PasswordCategory, PasswordEntry};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+/// 查询密码条目时使用的字段列表
+const ENTRY_COLUMNS: &str = "id, title, username, password, url, notes, category, tags,
+     is_favorite, entry_type, created_at, updated_at, last_used";
+
+/**
+ * 将查询结果行转换为密码条目
+ *
+ * @param row 查询结果行
+ * @return 密码条目
+ */
+pub fn entry_from_row(row: &SqliteRow) -> PasswordEntry {
+    let tags: String = row.try_get("tags").unwrap_or_else(|_| "[]".to_string());
+
+    PasswordEntry {
+        id: row.get("id"),
+        title: row.get("title"),
+        username: row.get("username"),
+        password: row.get("password"),
+        url: row.get("url"),
+        notes: row.get("notes"),
+        category: row.get("category"),
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        is_favorite: row.get::<i64, _>("is_favorite") != 0,
+        entry_type: EntryType::parse(&row.get::<String, _>("entry_type")),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        last_used: row.get("last_used"),
+    }
+}
+
+/**
+ * 获取所有密码条目
+ *
+ * @param pool 数据库连接池
+ * @return 密码条目列表
+ */
+pub async fn get_all_entries(pool: &SqlitePool) -> Result<Vec<PasswordEntry>, String> {
+    let sql = format!("SELECT {} FROM password_entries", ENTRY_COLUMNS);
+    let rows = sqlx::query(&sql)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询密码条目失败: {}", e))?;
+
+    Ok(rows.iter().map(entry_from_row).collect())
+}
+
+/**
+ * 按ID获取密码条目
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param id 条目ID
+ * @return 密码条目，不存在时为空
+ */
+pub async fn get_entry(
+    conn: &mut SqliteConnection,
+    id: &str,
+) -> Result<Option<PasswordEntry>, String> {
+    let sql = format!(
+        "SELECT {} FROM password_entries WHERE id = ?",
+        ENTRY_COLUMNS
+    );
+    let row = sqlx::query(&sql)
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询密码条目失败: {}", e))?;
+
+    Ok(row.as_ref().map(entry_from_row))
+}
+
+/**
+ * 获取所有密码分类
+ *
+ * @param pool 数据库连接池
+ * @return 密码分类列表
+ */
+pub async fn get_all_categories(pool: &SqlitePool) -> Result<Vec<PasswordCategory>, String> {
+    let rows = sqlx::query("SELECT id, name, icon, color FROM password_categories")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("查询密码分类失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PasswordCategory {
+            id: row.get("id"),
+            name: row.get("name"),
+            icon: row.get("icon"),
+            color: row.get("color"),
+        })
+        .collect())
+}
+
+/**
+ * 插入密码条目
+ *
+ * 接收连接而非连接池，以便在事务中调用
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry 密码条目
+ * @return 操作结果
+ */
+pub async fn insert_entry(
+    conn: &mut SqliteConnection,
+    entry: &PasswordEntry,
+) -> Result<(), String> {
+    let tags = serde_json::to_string(&entry.tags).map_err(|e| format!("序列化标签失败: {}", e))?;
+
+    sqlx::query(
+        "INSERT INTO password_entries
+         (id, title, username, password, url, notes, category, tags, is_favorite,
+          entry_type, created_at, updated_at, last_used)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry.id)
+    .bind(&entry.title)
+    .bind(&entry.username)
+    .bind(&entry.password)
+    .bind(&entry.url)
+    .bind(&entry.notes)
+    .bind(&entry.category)
+    .bind(tags)
+    .bind(entry.is_favorite as i64)
+    .bind(entry.entry_type.as_str())
+    .bind(entry.created_at)
+    .bind(entry.updated_at)
+    .bind(entry.last_used)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("写入密码条目失败 ({}): {}", entry.title, e))?;
+
+    Ok(())
+}
+
+/**
+ * 更新密码条目
+ *
+ * 按ID覆盖条目的全部字段
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param entry 密码条目
+ * @return 操作结果
+ */
+pub async fn update_entry(
+    conn: &mut SqliteConnection,
+    entry: &PasswordEntry,
+) -> Result<(), String> {
+    let tags = serde_json::to_string(&entry.tags).map_err(|e| format!("序列化标签失败: {}", e))?;
+
+    sqlx::query(
+        "UPDATE password_entries
+         SET title = ?, username = ?, password = ?, url = ?, notes = ?, category = ?,
+             tags = ?, is_favorite = ?, entry_type = ?, created_at = ?, updated_at = ?,
+             last_used = ?
+         WHERE id = ?",
+    )
+    .bind(&entry.title)
+    .bind(&entry.username)
+    .bind(&entry.password)
+    .bind(&entry.url)
+    .bind(&entry.notes)
+    .bind(&entry.category)
+    .bind(tags)
+    .bind(entry.is_favorite as i64)
+    .bind(entry.entry_type.as_str())
+    .bind(entry.created_at)
+    .bind(entry.updated_at)
+    .bind(entry.last_used)
+    .bind(&entry.id)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("更新密码条目失败 ({}): {}", entry.title, e))?;
+
+    Ok(())
+}
+
+/**
+ * 获取条目的更新时间
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param id 条目ID
+ * @return 更新时间，条目不存在时为空
+ */
+pub async fn get_entry_updated_at(
+    conn: &mut SqliteConnection,
+    id: &str,
+) -> Result<Option<i64>, String> {
+    let row = sqlx::query("SELECT updated_at FROM password_entries WHERE id = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询密码条目失败: {}", e))?;
+
+    Ok(row.map(|r| r.get("updated_at")))
+}
+
+/**
+ * 更新条目的最后使用时间
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param id 条目ID
+ * @param last_used 最后使用时间（毫秒）
+ * @return 操作结果
+ */
+pub async fn set_entry_last_used(
+    conn: &mut SqliteConnection,
+    id: &str,
+    last_used: i64,
+) -> Result<(), String> {
+    sqlx::query("UPDATE password_entries SET last_used = ? WHERE id = ?")
+        .bind(last_used)
+        .bind(id)
+        .execute(conn)
+        .await
+        .map_err(|e| format!("更新密码使用时间失败: {}", e))?;
+
+    Ok(())
+}
+
+/**
+ * 写入密码分类，已存在同ID分类时忽略
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param category 密码分类
+ * @return 是否新写入
+ */
+pub async fn insert_category_if_missing(
+    conn: &mut SqliteConnection,
+    category: &PasswordCategory,
+) -> Result<bool, String> {
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO password_categories (id, name, icon, color) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&category.id)
+    .bind(&category.name)
+    .bind(&category.icon)
+    .bind(&category.color)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("写入密码分类失败 ({}): {}", category.name, e))?;
+
+    Ok(result.rows_affected() > 0)
+}
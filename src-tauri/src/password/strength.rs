@@ -0,0 +1,612 @@
+/**
+ * 密码强度评估
+ *
+ * 基于字符集熵值估算密码强度，并识别会显著降低实际强度的模式：
+ * 常见单词与弱密码（含字母数字替换）、键盘连续按键、字符序列、重复字符和日期。
+ * 命中模式的片段按模式本身的可猜测空间计算熵值，而非按随机字符计算
+ */
+use serde::{Deserialize, Serialize};
+
+/// 常见弱密码与单词表（小写，已去除字母数字替换）
+const COMMON_WORDS: &[&str] = &[
+    "password",
+    "passwd",
+    "admin",
+    "administrator",
+    "root",
+    "login",
+    "welcome",
+    "letmein",
+    "master",
+    "secret",
+    "hello",
+    "love",
+    "iloveyou",
+    "princess",
+    "dragon",
+    "monkey",
+    "shadow",
+    "sunshine",
+    "football",
+    "baseball",
+    "soccer",
+    "superman",
+    "batman",
+    "trustno",
+    "freedom",
+    "whatever",
+    "starwars",
+    "pokemon",
+    "michael",
+    "jordan",
+    "charlie",
+    "summer",
+    "winter",
+    "spring",
+    "autumn",
+    "flower",
+    "computer",
+    "internet",
+    "google",
+    "apple",
+    "china",
+    "beijing",
+    "shanghai",
+    "woaini",
+    "aini",
+    "wangyi",
+    "baidu",
+    "taobao",
+    "wechat",
+    "weixin",
+    "tencent",
+    "changeme",
+    "default",
+    "guest",
+    "test",
+    "user",
+    "qwerty",
+    "pass",
+    "ninja",
+    "mustang",
+    "access",
+    "killer",
+    "hunter",
+    "ranger",
+    "buster",
+    "thomas",
+    "robert",
+    "daniel",
+    "jessica",
+    "ashley",
+    "bailey",
+    "cheese",
+    "cookie",
+    "orange",
+    "banana",
+    "purple",
+    "silver",
+    "golden",
+    "tiger",
+    "lucky",
+    "happy",
+    "angel",
+    "family",
+    "friend",
+    "money",
+    "game",
+    "music",
+    "taiasst",
+];
+
+/// 键盘布局（行、列及数字小键盘），用于识别键盘连续按键
+const KEYBOARD_LINES: &[&str] = &[
+    "`1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+    "1qaz2wsx3edc4rfv5tgb6yhn7ujm8ik,9ol.0p;/",
+    "7894561230",
+];
+
+/// 连续按键、序列的最短识别长度
+const MIN_PATTERN_LEN: usize = 4;
+/// 重复字符的最短识别长度
+const MIN_REPEAT_LEN: usize = 3;
+
+/**
+ * 密码强度等级，与前端 PasswordStrength 枚举对应
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PasswordStrength {
+    #[serde(rename = "weak")]
+    Weak,
+    #[serde(rename = "fair")]
+    Fair,
+    #[serde(rename = "good")]
+    Good,
+    #[serde(rename = "strong")]
+    Strong,
+}
+
+impl PasswordStrength {
+    /// 根据有效熵值确定强度等级
+    pub fn from_entropy(bits: f64) -> Self {
+        if bits < 28.0 {
+            Self::Weak
+        } else if bits < 40.0 {
+            Self::Fair
+        } else if bits < 60.0 {
+            Self::Good
+        } else {
+            Self::Strong
+        }
+    }
+
+    /// 等级的字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Weak => "weak",
+            Self::Fair => "fair",
+            Self::Good => "good",
+            Self::Strong => "strong",
+        }
+    }
+}
+
+/**
+ * 识别出的弱模式类型
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum PatternKind {
+    /// 常见单词或弱密码
+    #[serde(rename = "dictionary")]
+    Dictionary,
+    /// 键盘连续按键
+    #[serde(rename = "keyboard_walk")]
+    KeyboardWalk,
+    /// 字母或数字序列
+    #[serde(rename = "sequence")]
+    Sequence,
+    /// 重复字符
+    #[serde(rename = "repeat")]
+    Repeat,
+    /// 日期或年份
+    #[serde(rename = "date")]
+    Date,
+}
+
+/**
+ * 模式匹配结果
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PatternMatch {
+    /// 模式类型
+    pub kind: PatternKind,
+    /// 命中的片段
+    pub token: String,
+    /// 片段起始位置（字符索引）
+    pub start: usize,
+    /// 片段结束位置（不含）
+    pub end: usize,
+}
+
+/**
+ * 强度评估结果
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StrengthResult {
+    /// 强度等级
+    pub strength: PasswordStrength,
+    /// 0-100 分值
+    pub score: u8,
+    /// 有效熵值（比特）
+    pub entropy_bits: f64,
+    /// 命中的弱模式
+    pub patterns: Vec<PatternMatch>,
+    /// 改进建议
+    pub feedback: Vec<String>,
+}
+
+/// 计算单个字符所属字符集的大小
+fn char_pool(c: char) -> u32 {
+    if c.is_ascii_alphabetic() {
+        26
+    } else if c.is_ascii_digit() {
+        10
+    } else if c.is_ascii() {
+        33
+    } else {
+        100
+    }
+}
+
+/// 计算密码整体字符集大小
+fn password_pool(chars: &[char]) -> u32 {
+    let mut pool = 0;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if chars
+        .iter()
+        .any(|c| c.is_ascii() && !c.is_ascii_alphanumeric())
+    {
+        pool += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100;
+    }
+    pool.max(1)
+}
+
+/// 还原常见的字母数字替换（leet）
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        other => other.to_ascii_lowercase(),
+    }
+}
+
+/// 判断区间内是否已有位置被其他模式覆盖
+fn is_free(covered: &[bool], start: usize, end: usize) -> bool {
+    covered[start..end].iter().all(|c| !c)
+}
+
+/// 记录匹配并标记覆盖区间
+fn record(
+    matches: &mut Vec<PatternMatch>,
+    covered: &mut [bool],
+    chars: &[char],
+    kind: PatternKind,
+    start: usize,
+    end: usize,
+) {
+    covered[start..end].iter_mut().for_each(|c| *c = true);
+    matches.push(PatternMatch {
+        kind,
+        token: chars[start..end].iter().collect(),
+        start,
+        end,
+    });
+}
+
+/// 校验年月日是否构成合理日期
+fn is_valid_date(year: u32, month: u32, day: u32) -> bool {
+    (1900..=2099).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+/// 判断数字串是否为日期或年份
+fn is_date_digits(digits: &str) -> bool {
+    let num = |s: &str| s.parse::<u32>().unwrap_or(0);
+    match digits.len() {
+        4 => (1900..=2099).contains(&num(digits)),
+        6 => {
+            // YYMMDD / DDMMYY / MMDDYY，两位年份按 1900/2000 补全
+            let (a, b, c) = (num(&digits[0..2]), num(&digits[2..4]), num(&digits[4..6]));
+            let full = |y: u32| if y < 50 { 2000 + y } else { 1900 + y };
+            is_valid_date(full(a), b, c)
+                || is_valid_date(full(c), b, a)
+                || is_valid_date(full(c), a, b)
+        }
+        8 => {
+            // YYYYMMDD / DDMMYYYY / MMDDYYYY
+            is_valid_date(num(&digits[0..4]), num(&digits[4..6]), num(&digits[6..8]))
+                || is_valid_date(num(&digits[4..8]), num(&digits[2..4]), num(&digits[0..2]))
+                || is_valid_date(num(&digits[4..8]), num(&digits[0..2]), num(&digits[2..4]))
+        }
+        _ => false,
+    }
+}
+
+/// 识别日期，支持纯数字和以 - / . _ 分隔的形式
+fn find_dates(chars: &[char], covered: &mut [bool], matches: &mut Vec<PatternMatch>) {
+    let is_date_char = |c: char| c.is_ascii_digit() || matches!(c, '-' | '/' | '.' | '_');
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let mut end = i;
+        while end < chars.len() && is_date_char(chars[end]) {
+            end += 1;
+        }
+
+        // 在连续的数字/分隔符片段中，优先识别较长的日期
+        'search: for len in [10, 8, 6, 4] {
+            for start in i..end.saturating_sub(len - 1) {
+                let segment = &chars[start..start + len];
+                let separators: Vec<char> = segment
+                    .iter()
+                    .copied()
+                    .filter(|c| !c.is_ascii_digit())
+                    .collect();
+                let shape_ok = match separators.len() {
+                    0 => true,
+                    2 => separators[0] == separators[1] && len >= 8,
+                    _ => false,
+                };
+                let digits: String = segment.iter().filter(|c| c.is_ascii_digit()).collect();
+                if shape_ok && is_free(covered, start, start + len) && is_date_digits(&digits) {
+                    record(
+                        matches,
+                        covered,
+                        chars,
+                        PatternKind::Date,
+                        start,
+                        start + len,
+                    );
+                    break 'search;
+                }
+            }
+        }
+
+        i = end;
+    }
+}
+
+/// 识别常见单词和弱密码
+fn find_dictionary_words(chars: &[char], covered: &mut [bool], matches: &mut Vec<PatternMatch>) {
+    let normalized: Vec<char> = chars.iter().map(|&c| unleet(c)).collect();
+    let mut words: Vec<&str> = COMMON_WORDS
+        .iter()
+        .copied()
+        .filter(|w| w.len() >= 4)
+        .collect();
+    words.sort_by_key(|w| std::cmp::Reverse(w.len()));
+
+    for word in words {
+        let word_chars: Vec<char> = word.chars().collect();
+        let len = word_chars.len();
+        if len > normalized.len() {
+            continue;
+        }
+        let mut start = 0;
+        while start + len <= normalized.len() {
+            if normalized[start..start + len] == word_chars[..]
+                && is_free(covered, start, start + len)
+            {
+                record(
+                    matches,
+                    covered,
+                    chars,
+                    PatternKind::Dictionary,
+                    start,
+                    start + len,
+                );
+                start += len;
+            } else {
+                start += 1;
+            }
+        }
+    }
+}
+
+/// 识别键盘连续按键（正向或反向）
+fn find_keyboard_walks(chars: &[char], covered: &mut [bool], matches: &mut Vec<PatternMatch>) {
+    let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let lines: Vec<String> = KEYBOARD_LINES
+        .iter()
+        .flat_map(|line| [line.to_string(), line.chars().rev().collect()])
+        .collect();
+
+    let mut start = 0;
+    while start + MIN_PATTERN_LEN <= lower.len() {
+        // 找到以 start 开头的最长连续按键
+        let mut best = 0;
+        for line in &lines {
+            let mut len = 0;
+            while start + len < lower.len() {
+                let candidate: String = lower[start..=start + len].iter().collect();
+                if line.contains(&candidate) {
+                    len += 1;
+                } else {
+                    break;
+                }
+            }
+            best = best.max(len);
+        }
+
+        if best >= MIN_PATTERN_LEN && is_free(covered, start, start + best) {
+            record(
+                matches,
+                covered,
+                chars,
+                PatternKind::KeyboardWalk,
+                start,
+                start + best,
+            );
+            start += best;
+        } else {
+            start += 1;
+        }
+    }
+}
+
+/// 识别字母或数字的递增/递减序列
+fn find_sequences(chars: &[char], covered: &mut [bool], matches: &mut Vec<PatternMatch>) {
+    let mut start = 0;
+    while start + 1 < chars.len() {
+        let delta = chars[start + 1] as i32 - chars[start] as i32;
+        let mut end = start + 1;
+        if (delta == 1 || delta == -1) && chars[start].is_ascii_alphanumeric() {
+            while end < chars.len()
+                && chars[end].is_ascii_alphanumeric()
+                && chars[end] as i32 - chars[end - 1] as i32 == delta
+            {
+                end += 1;
+            }
+        }
+
+        if end - start >= MIN_PATTERN_LEN && is_free(covered, start, end) {
+            record(matches, covered, chars, PatternKind::Sequence, start, end);
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+}
+
+/// 识别连续重复的字符
+fn find_repeats(chars: &[char], covered: &mut [bool], matches: &mut Vec<PatternMatch>) {
+    let mut start = 0;
+    while start < chars.len() {
+        let mut end = start + 1;
+        while end < chars.len() && chars[end] == chars[start] {
+            end += 1;
+        }
+        if end - start >= MIN_REPEAT_LEN && is_free(covered, start, end) {
+            record(matches, covered, chars, PatternKind::Repeat, start, end);
+        }
+        start = end;
+    }
+}
+
+/// 计算模式片段的熵值
+fn pattern_entropy(pattern: &PatternMatch) -> f64 {
+    let len = (pattern.end - pattern.start) as f64;
+    match pattern.kind {
+        PatternKind::Dictionary => {
+            let mut bits = (COMMON_WORDS.len() as f64).log2();
+            // 大小写变化与字母数字替换各增加少量猜测空间
+            if pattern.token.chars().any(|c| c.is_ascii_uppercase()) {
+                bits += 1.0;
+            }
+            if pattern.token.chars().any(|c| !c.is_ascii_alphabetic()) {
+                bits += 1.0;
+            }
+            bits
+        }
+        PatternKind::KeyboardWalk => (KEYBOARD_LINES.len() as f64 * 2.0 * 47.0).log2() + len.log2(),
+        PatternKind::Sequence => (26.0f64 * 2.0).log2() + len.log2(),
+        PatternKind::Repeat => {
+            let c = pattern.token.chars().next().unwrap_or('a');
+            (char_pool(c) as f64).log2() + len.log2()
+        }
+        PatternKind::Date => (366.0f64 * 200.0).log2(),
+    }
+}
+
+/**
+ * 评估密码强度
+ *
+ * @param password 密码
+ * @return 评估结果
+ */
+pub fn estimate_strength(password: &str) -> StrengthResult {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return StrengthResult {
+            strength: PasswordStrength::Weak,
+            score: 0,
+            entropy_bits: 0.0,
+            patterns: Vec::new(),
+            feedback: vec!["密码不能为空".to_string()],
+        };
+    }
+
+    let mut covered = vec![false; chars.len()];
+    let mut patterns = Vec::new();
+    find_dates(&chars, &mut covered, &mut patterns);
+    find_dictionary_words(&chars, &mut covered, &mut patterns);
+    find_keyboard_walks(&chars, &mut covered, &mut patterns);
+    find_sequences(&chars, &mut covered, &mut patterns);
+    find_repeats(&chars, &mut covered, &mut patterns);
+    patterns.sort_by_key(|p| p.start);
+
+    let per_char = (password_pool(&chars) as f64).log2();
+    let uncovered = covered.iter().filter(|c| !**c).count() as f64;
+    let entropy_bits = per_char * uncovered + patterns.iter().map(pattern_entropy).sum::<f64>();
+
+    let mut feedback = Vec::new();
+    for pattern in &patterns {
+        let message = match pattern.kind {
+            PatternKind::Dictionary => format!("包含常见单词或弱密码: {}", pattern.token),
+            PatternKind::KeyboardWalk => format!("包含键盘连续按键: {}", pattern.token),
+            PatternKind::Sequence => format!("包含连续字符序列: {}", pattern.token),
+            PatternKind::Repeat => format!("包含重复字符: {}", pattern.token),
+            PatternKind::Date => format!("包含日期或年份: {}", pattern.token),
+        };
+        feedback.push(message);
+    }
+    if chars.len() < 12 {
+        feedback.push("建议密码长度至少12位".to_string());
+    }
+    if password_pool(&chars) < 62 {
+        feedback.push("建议混合使用大小写字母、数字和符号".to_string());
+    }
+
+    StrengthResult {
+        strength: PasswordStrength::from_entropy(entropy_bits),
+        score: (entropy_bits / 80.0 * 100.0).round().min(100.0) as u8,
+        entropy_bits: (entropy_bits * 10.0).round() / 10.0,
+        patterns,
+        feedback,
+    }
+}
+
+/**
+ * 评估单个密码的强度
+ *
+ * @param password 密码
+ * @return 评估结果
+ */
+#[tauri::command]
+pub fn evaluate_password_strength(password: String) -> StrengthResult {
+    estimate_strength(&password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(password: &str) -> Vec<PatternKind> {
+        estimate_strength(password)
+            .patterns
+            .iter()
+            .map(|p| p.kind)
+            .collect()
+    }
+
+    #[test]
+    fn empty_password_is_weak() {
+        let result = estimate_strength("");
+        assert_eq!(result.strength, PasswordStrength::Weak);
+        assert_eq!(result.score, 0);
+    }
+
+    #[test]
+    fn detects_weak_patterns() {
+        assert!(kinds("password").contains(&PatternKind::Dictionary));
+        assert!(kinds("P@ssw0rd").contains(&PatternKind::Dictionary));
+        assert!(kinds("zxcvbn").contains(&PatternKind::KeyboardWalk));
+        assert!(kinds("x-lmnop-x").contains(&PatternKind::Sequence));
+        assert!(kinds("x-7777-x").contains(&PatternKind::Repeat));
+        assert!(kinds("x-19900101-x").contains(&PatternKind::Date));
+    }
+
+    #[test]
+    fn patterns_lower_strength() {
+        let weak = estimate_strength("Password2024!");
+        assert!(weak.strength <= PasswordStrength::Fair);
+        assert!(!weak.patterns.is_empty());
+
+        let strong = estimate_strength("T7#kq9!Lm2@vXz");
+        assert_eq!(strong.strength, PasswordStrength::Strong);
+        assert!(strong.patterns.is_empty());
+        assert!(strong.entropy_bits > weak.entropy_bits);
+    }
+}
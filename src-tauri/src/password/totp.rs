@@ -0,0 +1,219 @@
+/**
+ * TOTP 一次性密码（RFC 6238）
+ *
+ * 条目中的 TOTP 密钥可以是 Base32 编码的原始密钥，也可以是 otpauth:// 链接，
+ * 链接中的参数按百分号编码解码，digits / period / algorithm 参数会覆盖默认值
+ */
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use url::Url;
+
+/// 默认验证码位数
+const DEFAULT_DIGITS: u32 = 6;
+/// 默认时间步长（秒）
+const DEFAULT_PERIOD: u64 = 30;
+
+/**
+ * HMAC 哈希算法
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/**
+ * 解析后的 TOTP 配置
+ */
+#[derive(Debug, Clone)]
+pub struct TotpConfig {
+    /// 原始密钥
+    pub secret: Vec<u8>,
+    /// 验证码位数
+    pub digits: u32,
+    /// 时间步长（秒）
+    pub period: u64,
+    /// 哈希算法
+    pub algorithm: TotpAlgorithm,
+}
+
+/// 解码 Base32 密钥，忽略空格、大小写和填充
+fn decode_secret(secret: &str) -> Result<Vec<u8>, String> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &normalized)
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| "TOTP 密钥不是有效的 Base32 编码".to_string())
+}
+
+/**
+ * 解析 TOTP 密钥
+ *
+ * @param value Base32 密钥或 otpauth:// 链接
+ * @return TOTP 配置
+ */
+pub fn parse_totp(value: &str) -> Result<TotpConfig, String> {
+    let value = value.trim();
+    let mut config = TotpConfig {
+        secret: Vec::new(),
+        digits: DEFAULT_DIGITS,
+        period: DEFAULT_PERIOD,
+        algorithm: TotpAlgorithm::Sha1,
+    };
+
+    if !value.to_ascii_lowercase().starts_with("otpauth://") {
+        config.secret = decode_secret(value)?;
+        return Ok(config);
+    }
+
+    let url = Url::parse(value).map_err(|e| format!("无效的 otpauth 链接: {}", e))?;
+    let mut secret = None;
+    for (key, val) in url.query_pairs() {
+        let val = val.as_ref();
+        match key.to_ascii_lowercase().as_str() {
+            "secret" => secret = Some(val.to_string()),
+            "digits" => {
+                config.digits = val
+                    .parse()
+                    .ok()
+                    .filter(|d| (6..=8).contains(d))
+                    .ok_or_else(|| format!("不支持的 TOTP 位数: {}", val))?;
+            }
+            "period" => {
+                config.period = val
+                    .parse()
+                    .ok()
+                    .filter(|p| *p > 0)
+                    .ok_or_else(|| format!("无效的 TOTP 时间步长: {}", val))?;
+            }
+            "algorithm" => {
+                config.algorithm = match val.to_ascii_uppercase().as_str() {
+                    "SHA1" => TotpAlgorithm::Sha1,
+                    "SHA256" => TotpAlgorithm::Sha256,
+                    "SHA512" => TotpAlgorithm::Sha512,
+                    other => return Err(format!("不支持的 TOTP 算法: {}", other)),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    config.secret = decode_secret(&secret.ok_or_else(|| "otpauth 链接缺少密钥".to_string())?)?;
+    Ok(config)
+}
+
+/// 计算 HMAC 摘要
+fn hmac_digest(algorithm: TotpAlgorithm, key: &[u8], message: &[u8]) -> Vec<u8> {
+    // HMAC 接受任意长度的密钥，new_from_slice 不会失败
+    match algorithm {
+        TotpAlgorithm::Sha1 => {
+            let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC 密钥长度无效");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        TotpAlgorithm::Sha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC 密钥长度无效");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        TotpAlgorithm::Sha512 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC 密钥长度无效");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+    }
+}
+
+/**
+ * 生成指定时间的验证码
+ *
+ * @param config TOTP 配置
+ * @param timestamp Unix 时间戳（秒）
+ * @return 验证码
+ */
+pub fn totp_code(config: &TotpConfig, timestamp: u64) -> String {
+    let counter = timestamp / config.period;
+    let digest = hmac_digest(config.algorithm, &config.secret, &counter.to_be_bytes());
+
+    // 动态截断（RFC 4226 第5.3节）
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    let code = binary % 10u32.pow(config.digits);
+
+    format!("{:0width$}", code, width = config.digits as usize)
+}
+
+/**
+ * 生成当前时间的验证码
+ *
+ * @param value Base32 密钥或 otpauth:// 链接
+ * @return 验证码与剩余有效秒数
+ */
+pub fn current_totp(value: &str) -> Result<(String, u64), String> {
+    let config = parse_totp(value)?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let remaining = config.period - now % config.period;
+
+    Ok((totp_code(&config, now), remaining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 附录 B 中 SHA1 的测试密钥 "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_rfc6238_vectors() {
+        let mut config = parse_totp(RFC_SECRET).unwrap();
+        config.digits = 8;
+        assert_eq!(totp_code(&config, 59), "94287082");
+        assert_eq!(totp_code(&config, 1_111_111_109), "07081804");
+        assert_eq!(totp_code(&config, 20_000_000_000), "65353130");
+    }
+
+    #[test]
+    fn decodes_secret_loosely() {
+        let config = parse_totp("gezd gnbv-gy3t qojq gezd gnbv gy3t qojq====").unwrap();
+        assert_eq!(config.secret, b"12345678901234567890");
+        assert!(parse_totp("not base32!").is_err());
+        assert!(parse_totp("").is_err());
+    }
+
+    #[test]
+    fn parses_otpauth_parameters() {
+        let config = parse_totp(
+            "otpauth://totp/Example:alice@example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Example&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+        assert_eq!(config.secret, b"12345678901234567890");
+        assert_eq!(config.algorithm, TotpAlgorithm::Sha256);
+        assert_eq!(config.digits, 8);
+        assert_eq!(config.period, 60);
+
+        assert!(parse_totp("otpauth://totp/Example?issuer=Example").is_err());
+        assert!(parse_totp("otpauth://totp/Example?secret=GEZDGNBV&digits=10").is_err());
+        assert!(parse_totp("otpauth://totp/Example?secret=GEZDGNBV&algorithm=MD5").is_err());
+    }
+
+    #[test]
+    fn percent_decodes_otpauth_secret() {
+        let config =
+            parse_totp("otpauth://totp/Example?secret=GEZD%20GNBV%3DGY3TQOJQGEZDGNBVGY3TQOJQ")
+                .unwrap();
+        assert_eq!(config.secret, b"12345678901234567890");
+    }
+}
@@ -0,0 +1,288 @@
+/**
+ * 密码管理类型和常量定义
+ */
+use serde::{Deserialize, Serialize};
+
+/// 未匹配到分类时使用的默认分类ID
+pub const DEFAULT_CATEGORY_ID: &str = "other";
+
+/**
+ * 条目类型
+ *
+ * 登录条目使用 username / password 等通用字段，其他类型的专有字段加密后
+ * 保存在 password_entries.encrypted_fields 中
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum EntryType {
+    /// 登录信息
+    #[default]
+    #[serde(rename = "login")]
+    Login,
+    /// 安全笔记
+    #[serde(rename = "secure_note")]
+    SecureNote,
+    /// 信用卡
+    #[serde(rename = "credit_card")]
+    CreditCard,
+    /// 身份信息
+    #[serde(rename = "identity")]
+    Identity,
+    /// SSH 密钥
+    #[serde(rename = "ssh_key")]
+    SshKey,
+    /// API 令牌
+    #[serde(rename = "api_token")]
+    ApiToken,
+}
+
+impl EntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryType::Login => "login",
+            EntryType::SecureNote => "secure_note",
+            EntryType::CreditCard => "credit_card",
+            EntryType::Identity => "identity",
+            EntryType::SshKey => "ssh_key",
+            EntryType::ApiToken => "api_token",
+        }
+    }
+
+    /// 解析数据库中的类型，未知类型按登录条目处理
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "secure_note" => EntryType::SecureNote,
+            "credit_card" => EntryType::CreditCard,
+            "identity" => EntryType::Identity,
+            "ssh_key" => EntryType::SshKey,
+            "api_token" => EntryType::ApiToken,
+            _ => EntryType::Login,
+        }
+    }
+}
+
+/**
+ * 密码条目
+ *
+ * 与 password_entries 表结构一一对应，时间戳为毫秒
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasswordEntry {
+    /// 条目ID
+    pub id: String,
+    /// 标题
+    pub title: String,
+    /// 用户名
+    pub username: String,
+    /// 密码
+    pub password: String,
+    /// 网址
+    pub url: Option<String>,
+    /// 备注
+    pub notes: Option<String>,
+    /// 分类ID
+    pub category: String,
+    /// 标签
+    pub tags: Vec<String>,
+    /// 是否收藏
+    pub is_favorite: bool,
+    /// 条目类型
+    #[serde(default)]
+    pub entry_type: EntryType,
+    /// 创建时间
+    pub created_at: i64,
+    /// 更新时间
+    pub updated_at: i64,
+    /// 最后使用时间
+    pub last_used: Option<i64>,
+}
+
+/**
+ * 待写入的密码条目
+ *
+ * 导入等场景下尚未分配ID和时间戳的条目
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NewPasswordEntry {
+    /// 标题
+    pub title: String,
+    /// 用户名
+    pub username: String,
+    /// 密码
+    pub password: String,
+    /// 网址
+    pub url: Option<String>,
+    /// 备注
+    pub notes: Option<String>,
+    /// TOTP 密钥，导入后保存在登录条目的专有字段中
+    pub totp_secret: Option<String>,
+    /// 分类ID
+    pub category: String,
+    /// 标签
+    pub tags: Vec<String>,
+    /// 是否收藏
+    pub is_favorite: bool,
+}
+
+/**
+ * 密码分类
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasswordCategory {
+    /// 分类ID
+    pub id: String,
+    /// 分类名称
+    pub name: String,
+    /// 分类图标
+    pub icon: String,
+    /// 分类颜色
+    pub color: String,
+}
+
+/**
+ * 登录条目字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct LoginFields {
+    /// TOTP 密钥（Base32 或 otpauth:// 链接）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+}
+
+/**
+ * 安全笔记字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SecureNoteFields {
+    /// 笔记内容
+    pub content: String,
+}
+
+/**
+ * 信用卡字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct CreditCardFields {
+    /// 持卡人姓名
+    pub cardholder_name: String,
+    /// 卡号
+    pub number: String,
+    /// 有效期月份（1-12）
+    pub expiry_month: Option<u8>,
+    /// 有效期年份
+    pub expiry_year: Option<u16>,
+    /// 安全码
+    pub cvv: Option<String>,
+    /// 交易密码
+    pub pin: Option<String>,
+    /// 卡组织或发卡行
+    pub brand: Option<String>,
+}
+
+/**
+ * 身份信息字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct IdentityFields {
+    /// 姓名
+    pub full_name: String,
+    /// 电子邮箱
+    pub email: Option<String>,
+    /// 电话
+    pub phone: Option<String>,
+    /// 地址
+    pub address: Option<String>,
+    /// 证件类型
+    pub id_type: Option<String>,
+    /// 证件号码
+    pub id_number: Option<String>,
+    /// 出生日期
+    pub birth_date: Option<String>,
+}
+
+/**
+ * SSH 密钥字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SshKeyFields {
+    /// 私钥（PEM 或 OpenSSH 格式）
+    pub private_key: String,
+    /// 公钥
+    pub public_key: Option<String>,
+    /// 私钥口令
+    pub passphrase: Option<String>,
+    /// 注释
+    pub comment: Option<String>,
+}
+
+/**
+ * API 令牌字段
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ApiTokenFields {
+    /// 令牌
+    pub token: String,
+    /// 服务地址
+    pub endpoint: Option<String>,
+    /// 过期时间（毫秒）
+    pub expires_at: Option<i64>,
+}
+
+/**
+ * 条目的专有字段
+ *
+ * 按 entry_type 区分，登录条目只保存 TOTP 密钥
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "entry_type")]
+pub enum EntryFields {
+    #[serde(rename = "login")]
+    Login(LoginFields),
+    #[serde(rename = "secure_note")]
+    SecureNote(SecureNoteFields),
+    #[serde(rename = "credit_card")]
+    CreditCard(CreditCardFields),
+    #[serde(rename = "identity")]
+    Identity(IdentityFields),
+    #[serde(rename = "ssh_key")]
+    SshKey(SshKeyFields),
+    #[serde(rename = "api_token")]
+    ApiToken(ApiTokenFields),
+}
+
+impl EntryFields {
+    pub fn entry_type(&self) -> EntryType {
+        match self {
+            EntryFields::Login(_) => EntryType::Login,
+            EntryFields::SecureNote(_) => EntryType::SecureNote,
+            EntryFields::CreditCard(_) => EntryType::CreditCard,
+            EntryFields::Identity(_) => EntryType::Identity,
+            EntryFields::SshKey(_) => EntryType::SshKey,
+            EntryFields::ApiToken(_) => EntryType::ApiToken,
+        }
+    }
+}
+
+/**
+ * 附件信息，不包含内容
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct AttachmentInfo {
+    /// 附件ID
+    pub id: String,
+    /// 所属条目ID
+    pub entry_id: String,
+    /// 文件名
+    pub file_name: String,
+    /// 文件大小（字节）
+    pub size: i64,
+    /// 明文的 SHA-256 摘要
+    pub sha256: String,
+    /// 创建时间
+    pub created_at: i64,
+}
@@ -5,6 +5,7 @@
  */
 use crate::plugin::manager::{get_plugin_manager, init_plugin_manager};
 use crate::plugin::types::{PluginMetadata, PLUGIN_METADATA_FILE_NAME};
+use crate::security::session::require_session;
 use std::fs::{self, File};
 use std::io::{Read, Seek};
 use std::path::Path;
@@ -126,13 +127,17 @@ fn find_and_read_metadata<R: Read + Seek>(
  *
  * @param app Tauri应用句柄
  * @param zip_path ZIP文件路径
+ * @param session_id 会话ID
  * @return 安装结果，包含插件元数据
  */
 #[tauri::command]
 pub async fn install_plugin_from_zip(
     app: AppHandle,
     zip_path: String,
+    session_id: String,
 ) -> Result<PluginMetadata, String> {
+    require_session(&session_id).await?;
+
     // 打开ZIP文件
     let zip_path = Path::new(&zip_path);
     let file = File::open(zip_path).map_err(|e| format!("无法打开ZIP文件: {}", e))?;
@@ -170,6 +175,13 @@ pub async fn install_plugin_from_zip(
         let mut outfile = File::create(&outpath).map_err(|e| format!("无法创建输出文件: {}", e))?;
         std::io::copy(&mut file, &mut outfile).map_err(|e| format!("无法写入文件内容: {}", e))?;
     }
+    crate::security::audit::record_quietly(
+        crate::security::audit::AuditEvent::PluginInstall,
+        &format!("安装插件: {}", metadata.name),
+        Some(serde_json::json!({ "plugin_id": metadata.id, "version": metadata.version })),
+    )
+    .await;
+
     // 返回元数据
     Ok(metadata)
 }
@@ -182,6 +194,19 @@ pub async fn uninstall_plugin(app: AppHandle, plugin_id: String) -> Result<(), S
     let _ = unload_plugin(plugin_id.clone()).await?;
     let plugin_dir = crate::plugin::utils::get_plugin_base_dir(app.clone())?.join(&plugin_id);
     fs::remove_dir_all(&plugin_dir).map_err(|e| format!("无法删除插件目录: {}", e))?;
+
+    // 清理插件保存的机密数据
+    let prefix = format!("plugin.{}.", plugin_id);
+    if let Err(e) = crate::security::storage::delete_secrets_with_prefix(&prefix).await {
+        log::warn!("清理插件机密数据失败 ({}): {}", plugin_id, e);
+    }
+
+    crate::security::audit::record_quietly(
+        crate::security::audit::AuditEvent::PluginUninstall,
+        &format!("卸载插件: {}", plugin_id),
+        None,
+    )
+    .await;
     Ok(())
 }
 
@@ -0,0 +1,497 @@
+/**
+ * 安全审计日志
+ *
+ * 将解锁、锁定、查看明文、导出、插件安装和权限拒绝等事件写入
+ * security_audit_log 表。每条记录保存上一条记录的哈希（prev_hash）以及
+ * 本条记录内容与 prev_hash 的 HMAC-SHA256（row_hash），HMAC 密钥由设备密钥
+ * 派生，修改或删除中间记录都会使校验失败。
+ *
+ * 截断末尾记录无法仅凭链本身发现，导出的日志可作为外部锚点对比
+ */
+use crate::security::session::{current_session_id, require_session};
+use crate::security::storage::device_key;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::Row;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use zeroize::Zeroizing;
+
+/// 审计日志 HMAC 密钥的派生标签
+const AUDIT_KEY_LABEL: &[u8] = b"taiasst-security-audit-log";
+/// 按顺序读取全部审计记录
+const ALL_ENTRIES_SQL: &str =
+    "SELECT id, event_type, event_severity, description, session_id, additional_data,
+            created_at, prev_hash, row_hash
+     FROM security_audit_log ORDER BY id ASC";
+/// 单次查询的最大条数
+const MAX_QUERY_LIMIT: u32 = 1000;
+
+// 使用lazy_static保存审计日志密钥，应用启动时初始化
+lazy_static::lazy_static! {
+    static ref AUDIT_KEY: Arc<Mutex<Option<Zeroizing<Vec<u8>>>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 审计事件类型
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AuditEvent {
+    /// 解锁成功
+    #[serde(rename = "unlock_success")]
+    UnlockSuccess,
+    /// 解锁失败
+    #[serde(rename = "unlock_failure")]
+    UnlockFailure,
+    /// 因多次失败被锁定
+    #[serde(rename = "lockout")]
+    Lockout,
+    /// 密码库锁定
+    #[serde(rename = "vault_locked")]
+    VaultLocked,
+    /// 查看或复制明文
+    #[serde(rename = "vault_reveal")]
+    VaultReveal,
+    /// 导出数据
+    #[serde(rename = "export")]
+    Export,
+    /// 导入或恢复数据
+    #[serde(rename = "import")]
+    Import,
+    /// 安装插件
+    #[serde(rename = "plugin_install")]
+    PluginInstall,
+    /// 卸载插件
+    #[serde(rename = "plugin_uninstall")]
+    PluginUninstall,
+    /// 权限拒绝
+    #[serde(rename = "permission_denied")]
+    PermissionDenied,
+}
+
+impl AuditEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEvent::UnlockSuccess => "unlock_success",
+            AuditEvent::UnlockFailure => "unlock_failure",
+            AuditEvent::Lockout => "lockout",
+            AuditEvent::VaultLocked => "vault_locked",
+            AuditEvent::VaultReveal => "vault_reveal",
+            AuditEvent::Export => "export",
+            AuditEvent::Import => "import",
+            AuditEvent::PluginInstall => "plugin_install",
+            AuditEvent::PluginUninstall => "plugin_uninstall",
+            AuditEvent::PermissionDenied => "permission_denied",
+        }
+    }
+
+    /// 事件的默认严重程度
+    pub fn severity(&self) -> AuditSeverity {
+        match self {
+            AuditEvent::UnlockSuccess
+            | AuditEvent::VaultLocked
+            | AuditEvent::Import
+            | AuditEvent::PluginUninstall => AuditSeverity::Info,
+            AuditEvent::UnlockFailure
+            | AuditEvent::VaultReveal
+            | AuditEvent::Export
+            | AuditEvent::PluginInstall
+            | AuditEvent::PermissionDenied => AuditSeverity::Warning,
+            AuditEvent::Lockout => AuditSeverity::Critical,
+        }
+    }
+}
+
+/**
+ * 事件严重程度
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AuditSeverity {
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "critical")]
+    Critical,
+}
+
+impl AuditSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSeverity::Info => "info",
+            AuditSeverity::Warning => "warning",
+            AuditSeverity::Critical => "critical",
+        }
+    }
+}
+
+/**
+ * 审计日志记录
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditLogEntry {
+    /// 记录ID
+    pub id: i64,
+    /// 事件类型
+    pub event_type: String,
+    /// 严重程度
+    pub event_severity: String,
+    /// 事件描述
+    pub description: String,
+    /// 会话ID
+    pub session_id: Option<String>,
+    /// 附加数据（JSON）
+    pub additional_data: Option<String>,
+    /// 记录时间（秒）
+    pub created_at: i64,
+    /// 上一条记录的哈希
+    pub prev_hash: Option<String>,
+    /// 本条记录的哈希
+    pub row_hash: Option<String>,
+}
+
+/**
+ * 审计日志查询条件
+ */
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct AuditLogQuery {
+    /// 事件类型
+    pub event_type: Option<String>,
+    /// 严重程度
+    pub event_severity: Option<String>,
+    /// 起始时间（秒）
+    pub since: Option<i64>,
+    /// 结束时间（秒）
+    pub until: Option<i64>,
+    /// 返回条数，默认100
+    pub limit: Option<u32>,
+    /// 偏移量
+    pub offset: Option<u32>,
+}
+
+/**
+ * 哈希链校验结果
+ */
+#[derive(Debug, Serialize)]
+pub struct AuditChainReport {
+    /// 哈希链是否完整
+    pub valid: bool,
+    /// 已校验的记录数
+    pub checked: usize,
+    /// 第一条校验失败的记录ID
+    pub broken_at: Option<i64>,
+    /// 失败原因
+    pub reason: Option<String>,
+}
+
+/**
+ * 用于计算哈希的记录内容，字段顺序固定
+ */
+#[derive(Serialize)]
+struct ChainPayload<'a> {
+    prev_hash: &'a str,
+    event_type: &'a str,
+    event_severity: &'a str,
+    description: &'a str,
+    session_id: Option<&'a str>,
+    additional_data: Option<&'a str>,
+    created_at: i64,
+}
+
+/**
+ * 初始化审计日志密钥
+ *
+ * 应在应用启动时调用
+ *
+ * @param app Tauri应用句柄
+ * @return 操作结果
+ */
+pub fn init_audit_log(app: AppHandle) -> Result<(), String> {
+    let device_key = device_key(app)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&device_key).expect("HMAC 密钥长度无效");
+    mac.update(AUDIT_KEY_LABEL);
+    let key = Zeroizing::new(mac.finalize().into_bytes().to_vec());
+
+    *AUDIT_KEY
+        .lock()
+        .map_err(|e| format!("获取审计密钥锁失败: {}", e))? = Some(key);
+    Ok(())
+}
+
+/// 计算记录的 HMAC
+fn chain_hash(payload: &ChainPayload) -> Result<String, String> {
+    let guard = AUDIT_KEY
+        .lock()
+        .map_err(|e| format!("获取审计密钥锁失败: {}", e))?;
+    let key = guard
+        .as_ref()
+        .ok_or_else(|| "审计日志尚未初始化".to_string())?;
+
+    let message = serde_json::to_vec(payload).map_err(|e| format!("序列化审计记录失败: {}", e))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC 密钥长度无效");
+    mac.update(&message);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 将查询结果行转换为审计日志记录
+fn entry_from_row(row: &sqlx::sqlite::SqliteRow) -> AuditLogEntry {
+    AuditLogEntry {
+        id: row.get("id"),
+        event_type: row.get("event_type"),
+        event_severity: row.get("event_severity"),
+        description: row.get("description"),
+        session_id: row.get("session_id"),
+        additional_data: row.get("additional_data"),
+        created_at: row.get("created_at"),
+        prev_hash: row.get("prev_hash"),
+        row_hash: row.get("row_hash"),
+    }
+}
+
+/**
+ * 写入审计事件
+ *
+ * @param event 事件类型
+ * @param description 事件描述
+ * @param additional_data 附加数据
+ * @return 操作结果
+ */
+pub async fn record(
+    event: AuditEvent,
+    description: &str,
+    additional_data: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let additional_data = additional_data
+        .map(|data| serde_json::to_string(&data))
+        .transpose()
+        .map_err(|e| format!("序列化附加数据失败: {}", e))?;
+    let session_id = current_session_id();
+    let severity = event.severity();
+    let created_at = chrono::Utc::now().timestamp();
+
+    // 持有数据库锁直到写入完成，保证哈希链按顺序生成
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let prev_hash = sqlx::query(
+        "SELECT row_hash FROM security_audit_log WHERE id = (SELECT MAX(id) FROM security_audit_log)",
+    )
+    .fetch_optional(db.get_pool())
+    .await
+    .map_err(|e| format!("查询审计日志失败: {}", e))?
+    .and_then(|row| row.get::<Option<String>, _>("row_hash"))
+    .unwrap_or_default();
+
+    let row_hash = chain_hash(&ChainPayload {
+        prev_hash: &prev_hash,
+        event_type: event.as_str(),
+        event_severity: severity.as_str(),
+        description,
+        session_id: session_id.as_deref(),
+        additional_data: additional_data.as_deref(),
+        created_at,
+    })?;
+
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO security_audit_log
+         (event_type, event_severity, description, session_id, additional_data, created_at,
+          prev_hash, row_hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        event.as_str(),
+        severity.as_str(),
+        description,
+        session_id,
+        additional_data,
+        created_at,
+        prev_hash,
+        row_hash
+    )
+}
+
+/**
+ * 写入审计事件，失败时仅记录日志
+ *
+ * 审计失败不应影响业务操作本身
+ *
+ * @param event 事件类型
+ * @param description 事件描述
+ * @param additional_data 附加数据
+ */
+pub async fn record_quietly(
+    event: AuditEvent,
+    description: &str,
+    additional_data: Option<serde_json::Value>,
+) {
+    if let Err(e) = record(event, description, additional_data).await {
+        log::error!("写入审计日志失败 ({}): {}", event.as_str(), e);
+    }
+}
+
+/**
+ * 记录登录失败
+ *
+ * 主密码由前端校验，校验失败后调用此命令写入审计日志
+ *
+ * @param failed_attempts 连续失败次数
+ * @param locked_until 锁定截止时间（毫秒），触发锁定时提供
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn record_auth_failure(
+    failed_attempts: u32,
+    locked_until: Option<i64>,
+) -> Result<(), String> {
+    record(
+        AuditEvent::UnlockFailure,
+        "主密码验证失败",
+        Some(serde_json::json!({ "failed_attempts": failed_attempts })),
+    )
+    .await?;
+
+    if let Some(locked_until) = locked_until {
+        record(
+            AuditEvent::Lockout,
+            "连续验证失败，账户已被临时锁定",
+            Some(serde_json::json!({
+                "failed_attempts": failed_attempts,
+                "locked_until": locked_until,
+            })),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/**
+ * 查询审计日志
+ *
+ * @param query 查询条件
+ * @return 审计日志记录，按时间倒序
+ */
+#[tauri::command]
+pub async fn query_audit_log(query: Option<AuditLogQuery>) -> Result<Vec<AuditLogEntry>, String> {
+    let query = query.unwrap_or_default();
+    let limit = query.limit.unwrap_or(100).min(MAX_QUERY_LIMIT);
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT id, event_type, event_severity, description, session_id, additional_data,
+                created_at, prev_hash, row_hash
+         FROM security_audit_log
+         WHERE (? IS NULL OR event_type = ?)
+           AND (? IS NULL OR event_severity = ?)
+           AND (? IS NULL OR created_at >= ?)
+           AND (? IS NULL OR created_at <= ?)
+         ORDER BY id DESC LIMIT ? OFFSET ?",
+        &query.event_type,
+        &query.event_type,
+        &query.event_severity,
+        &query.event_severity,
+        query.since,
+        query.since,
+        query.until,
+        query.until,
+        limit as i64,
+        query.offset.unwrap_or(0) as i64
+    )?;
+
+    Ok(rows.iter().map(entry_from_row).collect())
+}
+
+/**
+ * 校验审计日志哈希链
+ *
+ * @return 校验结果
+ */
+#[tauri::command]
+pub async fn verify_audit_chain() -> Result<AuditChainReport, String> {
+    let rows = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query(ALL_ENTRIES_SQL)
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询审计日志失败: {}", e))?
+    };
+
+    let mut expected_prev = String::new();
+    for (index, entry) in rows.iter().map(entry_from_row).enumerate() {
+        let broken = |reason: &str| AuditChainReport {
+            valid: false,
+            checked: index,
+            broken_at: Some(entry.id),
+            reason: Some(reason.to_string()),
+        };
+
+        let (Some(prev_hash), Some(row_hash)) = (&entry.prev_hash, &entry.row_hash) else {
+            return Ok(broken("记录缺少哈希"));
+        };
+        if *prev_hash != expected_prev {
+            return Ok(broken("与上一条记录的哈希不一致，可能有记录被删除"));
+        }
+
+        let computed = chain_hash(&ChainPayload {
+            prev_hash,
+            event_type: &entry.event_type,
+            event_severity: &entry.event_severity,
+            description: &entry.description,
+            session_id: entry.session_id.as_deref(),
+            additional_data: entry.additional_data.as_deref(),
+            created_at: entry.created_at,
+        })?;
+        if computed != *row_hash {
+            return Ok(broken("记录内容与哈希不符，可能已被修改"));
+        }
+        expected_prev = row_hash.clone();
+    }
+
+    Ok(AuditChainReport {
+        valid: true,
+        checked: rows.len(),
+        broken_at: None,
+        reason: None,
+    })
+}
+
+/**
+ * 导出审计日志为JSON文件
+ *
+ * @param file_path 导出文件路径
+ * @param session_id 会话ID
+ * @return 导出的记录数
+ */
+#[tauri::command]
+pub async fn export_audit_log(file_path: String, session_id: String) -> Result<usize, String> {
+    require_session(&session_id).await?;
+    let entries: Vec<AuditLogEntry> = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query(ALL_ENTRIES_SQL)
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(|e| format!("查询审计日志失败: {}", e))?
+            .iter()
+            .map(entry_from_row)
+            .collect()
+    };
+
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "exported_at": chrono::Utc::now().timestamp(),
+        "entries": entries,
+    }))
+    .map_err(|e| format!("序列化审计日志失败: {}", e))?;
+    fs::write(&file_path, content).map_err(|e| format!("写入审计日志文件失败: {}", e))?;
+
+    record_quietly(
+        AuditEvent::Export,
+        "导出审计日志",
+        Some(serde_json::json!({ "file_path": file_path, "entries": entries.len() })),
+    )
+    .await;
+    Ok(entries.len())
+}
@@ -0,0 +1,79 @@
+/**
+ * 主密码校验
+ *
+ * 主密码哈希由前端写入 auth_settings 表（PBKDF2-SHA256，10万次迭代，
+ * 盐值为十六进制字符串的UTF-8字节），此处使用相同算法进行校验
+ */
+use crate::security::crypto::constant_time_eq;
+use sha2::Sha256;
+use sqlx::Row;
+
+/// 与前端一致的PBKDF2迭代次数
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/**
+ * 读取认证设置项
+ *
+ * @param key 设置项名称
+ * @return 设置值
+ */
+pub async fn get_auth_setting(key: &str) -> Result<Option<String>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+
+    let row =
+        crate::query_one_with_params!(db, "SELECT value FROM auth_settings WHERE key = ?", key)?;
+    Ok(row.map(|r| r.get::<String, _>("value")))
+}
+
+/**
+ * 校验主密码
+ *
+ * @param password 主密码
+ * @return 校验结果，密码错误或未设置主密码时返回错误
+ */
+pub async fn verify_master_password(password: &str) -> Result<(), String> {
+    let hash = get_auth_setting("passwordHash").await?;
+    let salt = get_auth_setting("passwordSalt").await?;
+
+    let (Some(hash), Some(salt)) = (hash, salt) else {
+        return Err("尚未设置主密码".to_string());
+    };
+
+    let mut derived = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(
+        password.as_bytes(),
+        salt.as_bytes(),
+        PBKDF2_ITERATIONS,
+        &mut derived,
+    );
+
+    let expected = hex::decode(hash.trim()).map_err(|e| format!("主密码哈希格式无效: {}", e))?;
+    if constant_time_eq(&derived, &expected) {
+        Ok(())
+    } else {
+        Err("主密码错误".to_string())
+    }
+}
+
+/**
+ * 写入认证设置项
+ *
+ * @param key 设置项名称
+ * @param value 设置值
+ * @return 操作结果
+ */
+pub async fn set_auth_setting(key: &str, value: &str) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let now = chrono::Utc::now().timestamp();
+
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO auth_settings (key, value, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        key,
+        value,
+        now
+    )
+}
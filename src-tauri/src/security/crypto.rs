@@ -0,0 +1,258 @@
+/**
+ * 加密工具
+ *
+ * 基于 Argon2id 派生密钥，使用 AES-256-GCM 进行认证加密
+ */
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// 对称密钥长度（字节）
+pub const KEY_LEN: usize = 32;
+/// AES-GCM 随机数长度（字节）
+pub const NONCE_LEN: usize = 12;
+/// 密钥派生盐值长度（字节）
+pub const SALT_LEN: usize = 16;
+/// 外部来源派生参数允许的最大内存开销（KiB）
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+/// 外部来源派生参数允许的最小内存开销（KiB）
+const MIN_KDF_MEMORY_KIB: u32 = 8 * 1024;
+/// 外部来源派生参数允许的最大迭代次数
+const MAX_KDF_ITERATIONS: u32 = 16;
+/// 外部来源派生参数允许的最大并行度
+const MAX_KDF_PARALLELISM: u32 = 16;
+
+/**
+ * Argon2id 密钥派生参数
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KdfParams {
+    /// 内存开销（KiB）
+    pub memory_kib: u32,
+    /// 迭代次数
+    pub iterations: u32,
+    /// 并行度
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /**
+     * 校验来自备份文件、同步目标等外部来源的派生参数
+     *
+     * 避免被篡改的参数在派生时耗尽内存或长时间占用 CPU
+     *
+     * @return 校验结果
+     */
+    pub fn check_untrusted(&self) -> Result<(), String> {
+        if !(MIN_KDF_MEMORY_KIB..=MAX_KDF_MEMORY_KIB).contains(&self.memory_kib)
+            || !(1..=MAX_KDF_ITERATIONS).contains(&self.iterations)
+            || !(1..=MAX_KDF_PARALLELISM).contains(&self.parallelism)
+        {
+            return Err(format!(
+                "密钥派生参数超出允许范围: 内存 {} KiB, 迭代 {} 次, 并行度 {}",
+                self.memory_kib, self.iterations, self.parallelism
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 4,
+        }
+    }
+}
+
+/**
+ * 生成指定长度的安全随机字节
+ *
+ * @param len 字节数
+ * @return 随机字节
+ */
+pub fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/**
+ * 使用 Argon2id 从口令派生对称密钥
+ *
+ * @param secret 口令
+ * @param salt 盐值
+ * @param params 派生参数
+ * @return 派生出的密钥
+ */
+pub fn derive_key(secret: &[u8], salt: &[u8], params: &KdfParams) -> Result<[u8; KEY_LEN], String> {
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| format!("密钥派生参数无效: {}", e))?;
+
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| format!("密钥派生失败: {}", e))?;
+    Ok(key)
+}
+
+/**
+ * 使用 AES-256-GCM 加密数据
+ *
+ * 输出格式为 随机数(12字节) || 密文和认证标签
+ *
+ * @param key 对称密钥
+ * @param plaintext 明文
+ * @param aad 附加认证数据
+ * @return 加密结果
+ */
+pub fn encrypt(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("密钥无效: {}", e))?;
+    let nonce = random_bytes(NONCE_LEN);
+
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| "加密失败".to_string())?;
+
+    let mut output = nonce;
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/**
+ * 解密 AES-256-GCM 数据
+ *
+ * @param key 对称密钥
+ * @param data 随机数与密文
+ * @param aad 附加认证数据
+ * @return 明文
+ */
+pub fn decrypt(key: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LEN {
+        return Err("密文长度无效".to_string());
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("密钥无效: {}", e))?;
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| "解密失败，密钥错误或数据已被篡改".to_string())
+}
+
+/**
+ * Base64 编码
+ */
+pub fn encode_base64(data: &[u8]) -> String {
+    STANDARD.encode(data)
+}
+
+/**
+ * Base64 解码
+ */
+pub fn decode_base64(data: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Base64解码失败: {}", e))
+}
+
+/**
+ * 常量时间比较两个字节序列
+ *
+ * 避免比较耗时泄露匹配长度
+ */
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/**
+ * 加密字符串并编码为 Base64
+ *
+ * @param key 对称密钥
+ * @param plaintext 明文
+ * @param aad 附加认证数据，用于区分数据用途
+ * @return Base64 编码的密文
+ */
+pub fn encrypt_to_base64(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<String, String> {
+    encrypt(key, plaintext, aad).map(|data| encode_base64(&data))
+}
+
+/**
+ * 解密 Base64 编码的密文
+ *
+ * @param key 对称密钥
+ * @param data Base64 编码的密文
+ * @param aad 附加认证数据
+ * @return 明文
+ */
+pub fn decrypt_from_base64(key: &[u8], data: &str, aad: &[u8]) -> Result<Vec<u8>, String> {
+    decrypt(key, &decode_base64(data)?, aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untrusted_kdf_params_are_bounded() {
+        assert!(KdfParams::default().check_untrusted().is_ok());
+
+        let too_much_memory = KdfParams {
+            memory_kib: u32::MAX,
+            ..KdfParams::default()
+        };
+        assert!(too_much_memory.check_untrusted().is_err());
+
+        let too_many_iterations = KdfParams {
+            iterations: 1_000_000,
+            ..KdfParams::default()
+        };
+        assert!(too_many_iterations.check_untrusted().is_err());
+
+        let no_parallelism = KdfParams {
+            parallelism: 0,
+            ..KdfParams::default()
+        };
+        assert!(no_parallelism.check_untrusted().is_err());
+
+        let too_little_memory = KdfParams {
+            memory_kib: 64,
+            ..KdfParams::default()
+        };
+        assert!(too_little_memory.check_untrusted().is_err());
+    }
+
+    #[test]
+    fn encrypt_round_trips_and_binds_aad() {
+        let key = random_bytes(KEY_LEN);
+        let data = encrypt(&key, b"secret", b"aad").unwrap();
+        assert_eq!(decrypt(&key, &data, b"aad").unwrap(), b"secret");
+        assert!(decrypt(&key, &data, b"other").is_err());
+    }
+}
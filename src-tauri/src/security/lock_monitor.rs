@@ -0,0 +1,457 @@
+/**
+ * 系统事件锁定
+ *
+ * 监听系统睡眠、锁屏和切换用户，在 auth_settings 的 lockOnSystemSleep 启用时
+ * 立即清除内存中的密码库密钥、结束当前会话，并向前端发送 locked 事件：
+ * - Linux: 通过 D-Bus 监听 logind 的 PrepareForSleep 信号，以及当前会话的
+ *   Lock 信号、LockedHint 和 Active 属性
+ * - Windows: 通过隐藏窗口接收 WTS 会话通知（锁屏、断开会话）和电源广播（挂起）
+ * - macOS: 监听 NSWorkspace 的睡眠、会话切换通知和屏幕锁定的分布式通知
+ * - 系统通知不可用时: 定时比较系统时间，时间跳跃视为系统曾经睡眠
+ */
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::auth::get_auth_setting;
+use crate::security::session::end_session;
+use crate::security::vault;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 发送给前端的锁定事件名称
+pub const LOCKED_EVENT: &str = "locked";
+/// 时间跳跃检测间隔（秒）
+const CLOCK_CHECK_INTERVAL_SECS: i64 = 10;
+/// 超过该时间差视为系统曾经睡眠（秒）
+const CLOCK_JUMP_THRESHOLD_SECS: i64 = 60;
+
+/**
+ * 锁定原因
+ */
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub enum LockReason {
+    /// 系统睡眠或休眠
+    #[serde(rename = "system_sleep")]
+    SystemSleep,
+    /// 锁定屏幕
+    #[serde(rename = "screen_lock")]
+    ScreenLock,
+    /// 切换用户
+    #[serde(rename = "user_switch")]
+    UserSwitch,
+}
+
+impl LockReason {
+    fn description(&self) -> &'static str {
+        match self {
+            LockReason::SystemSleep => "系统睡眠，密码库已自动锁定",
+            LockReason::ScreenLock => "屏幕锁定，密码库已自动锁定",
+            LockReason::UserSwitch => "切换用户，密码库已自动锁定",
+        }
+    }
+}
+
+/**
+ * locked 事件内容
+ */
+#[derive(Debug, Serialize, Clone)]
+pub struct LockedPayload {
+    /// 锁定原因
+    pub reason: LockReason,
+}
+
+/// 是否启用系统事件锁定，默认启用
+async fn lock_on_system_event_enabled() -> bool {
+    match get_auth_setting("lockOnSystemSleep").await {
+        Ok(value) => value.as_deref() != Some("false"),
+        Err(e) => {
+            log::warn!("读取锁定设置失败，按启用处理: {}", e);
+            true
+        }
+    }
+}
+
+/**
+ * 因系统事件锁定密码库
+ *
+ * @param app Tauri应用句柄
+ * @param reason 锁定原因
+ */
+async fn lock_for(app: &AppHandle, reason: LockReason) {
+    if !lock_on_system_event_enabled().await {
+        return;
+    }
+
+    // 先清除密钥，再处理会话和审计等需要访问数据库的操作
+    let was_unlocked = vault::lock();
+    if let Err(e) = end_session().await {
+        log::warn!("结束会话失败: {}", e);
+    }
+    if !was_unlocked {
+        return;
+    }
+
+    log::info!("{}", reason.description());
+    record_quietly(
+        AuditEvent::VaultLocked,
+        reason.description(),
+        Some(serde_json::json!({ "reason": reason })),
+    )
+    .await;
+    if let Err(e) = app.emit(LOCKED_EVENT, LockedPayload { reason }) {
+        log::warn!("发送锁定事件失败: {}", e);
+    }
+}
+
+/**
+ * 通过系统时间跳跃检测睡眠
+ *
+ * 进程在睡眠期间不会运行，唤醒后系统时间与上次检查的差值会明显超过检查间隔
+ *
+ * @param app Tauri应用句柄
+ */
+async fn watch_clock_jump(app: AppHandle) {
+    let mut last = chrono::Utc::now().timestamp();
+    loop {
+        tokio::time::sleep(Duration::from_secs(CLOCK_CHECK_INTERVAL_SECS as u64)).await;
+        let now = chrono::Utc::now().timestamp();
+        if now - last > CLOCK_CHECK_INTERVAL_SECS + CLOCK_JUMP_THRESHOLD_SECS {
+            lock_for(&app, LockReason::SystemSleep).await;
+        }
+        last = now;
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod logind {
+    use super::{lock_for, LockReason};
+    use futures_util::StreamExt;
+    use tauri::AppHandle;
+    use zbus::zvariant::OwnedObjectPath;
+    use zbus::{Connection, Proxy};
+
+    const LOGIND_SERVICE: &str = "org.freedesktop.login1";
+    const LOGIND_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+    const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+    /**
+     * 监听 logind 事件
+     *
+     * 连接系统总线失败时立即返回错误，连接成功后持续运行
+     *
+     * @param app Tauri应用句柄
+     * @return 监听结果
+     */
+    pub async fn watch(app: AppHandle) -> zbus::Result<()> {
+        let conn = Connection::system().await?;
+        let manager = Proxy::new(&conn, LOGIND_SERVICE, LOGIND_PATH, MANAGER_INTERFACE).await?;
+        let mut sleep = manager.receive_signal("PrepareForSleep").await?;
+
+        // 当前进程可能不属于任何 logind 会话（例如通过服务启动），此时只监听睡眠
+        let session = match manager
+            .call::<_, _, OwnedObjectPath>("GetSessionByPID", &(std::process::id(),))
+            .await
+        {
+            Ok(path) => Some(Proxy::new(&conn, LOGIND_SERVICE, path, SESSION_INTERFACE).await?),
+            Err(e) => {
+                log::warn!("无法获取当前 logind 会话，将仅监听系统睡眠: {}", e);
+                None
+            }
+        };
+        let (mut lock, mut locked_hint, mut active) = match &session {
+            Some(session) => (
+                Some(session.receive_signal("Lock").await?),
+                Some(session.receive_property_changed::<bool>("LockedHint").await),
+                Some(session.receive_property_changed::<bool>("Active").await),
+            ),
+            None => (None, None, None),
+        };
+        log::info!("已开始监听系统睡眠与锁屏事件");
+
+        loop {
+            tokio::select! {
+                Some(message) = sleep.next() => {
+                    // PrepareForSleep(true) 在睡眠前发送，false 在唤醒后发送
+                    if message.body().deserialize::<bool>().unwrap_or(false) {
+                        lock_for(&app, LockReason::SystemSleep).await;
+                    }
+                }
+                Some(_) = async { lock.as_mut()?.next().await } => {
+                    lock_for(&app, LockReason::ScreenLock).await;
+                }
+                Some(changed) = async { locked_hint.as_mut()?.next().await } => {
+                    if changed.get().await.unwrap_or(false) {
+                        lock_for(&app, LockReason::ScreenLock).await;
+                    }
+                }
+                Some(changed) = async { active.as_mut()?.next().await } => {
+                    if !changed.get().await.unwrap_or(true) {
+                        lock_for(&app, LockReason::UserSwitch).await;
+                    }
+                }
+                else => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod wts {
+    use super::{lock_for, LockReason};
+    use std::sync::OnceLock;
+    use tauri::AppHandle;
+    use tokio::sync::mpsc::UnboundedSender;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, MSG, PBT_APMSUSPEND, WM_POWERBROADCAST, WM_WTSSESSION_CHANGE, WNDCLASSW,
+        WS_OVERLAPPED, WTS_CONSOLE_DISCONNECT, WTS_REMOTE_DISCONNECT, WTS_SESSION_LOCK,
+    };
+
+    /// 窗口过程向异步任务转发事件的通道
+    static EVENTS: OnceLock<UnboundedSender<LockReason>> = OnceLock::new();
+
+    /// 将窗口消息转换为锁定原因
+    fn reason_for(message: u32, wparam: WPARAM) -> Option<LockReason> {
+        match (message, wparam as u32) {
+            (WM_POWERBROADCAST, PBT_APMSUSPEND) => Some(LockReason::SystemSleep),
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK) => Some(LockReason::ScreenLock),
+            (WM_WTSSESSION_CHANGE, WTS_CONSOLE_DISCONNECT | WTS_REMOTE_DISCONNECT) => {
+                Some(LockReason::UserSwitch)
+            }
+            _ => None,
+        }
+    }
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        message: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if let (Some(reason), Some(events)) = (reason_for(message, wparam), EVENTS.get()) {
+            let _ = events.send(reason);
+        }
+        DefWindowProcW(hwnd, message, wparam, lparam)
+    }
+
+    /**
+     * 创建接收通知的隐藏窗口
+     *
+     * 电源广播只发送给顶层窗口，不能使用 HWND_MESSAGE 消息窗口
+     */
+    fn create_window() -> Result<HWND, String> {
+        let class_name: Vec<u16> = "TaiASSTLockMonitor\0".encode_utf16().collect();
+        unsafe {
+            let instance = GetModuleHandleW(std::ptr::null());
+            let class = WNDCLASSW {
+                style: 0,
+                lpfnWndProc: Some(window_proc),
+                cbClsExtra: 0,
+                cbWndExtra: 0,
+                hInstance: instance,
+                hIcon: std::ptr::null_mut(),
+                hCursor: std::ptr::null_mut(),
+                hbrBackground: std::ptr::null_mut(),
+                lpszMenuName: std::ptr::null(),
+                lpszClassName: class_name.as_ptr(),
+            };
+            if RegisterClassW(&class) == 0 {
+                return Err(format!(
+                    "注册窗口类失败: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                WS_OVERLAPPED,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                return Err(format!("创建窗口失败: {}", std::io::Error::last_os_error()));
+            }
+            if WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) == 0 {
+                return Err(format!(
+                    "注册会话通知失败: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            Ok(hwnd)
+        }
+    }
+
+    /// 运行消息循环，直到窗口线程退出
+    fn pump_messages() {
+        unsafe {
+            let mut message: MSG = std::mem::zeroed();
+            while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        }
+    }
+
+    /**
+     * 监听会话与电源事件
+     *
+     * 在专用线程上创建窗口并运行消息循环，窗口创建失败时返回错误
+     *
+     * @param app Tauri应用句柄
+     * @return 监听结果
+     */
+    pub async fn watch(app: AppHandle) -> Result<(), String> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        EVENTS
+            .set(tx)
+            .map_err(|_| "会话事件监听已启动".to_string())?;
+
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        std::thread::Builder::new()
+            .name("lock-monitor".to_string())
+            .spawn(move || match create_window() {
+                Ok(_) => {
+                    let _ = ready_tx.send(Ok(()));
+                    pump_messages();
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            })
+            .map_err(|e| format!("启动监听线程失败: {}", e))?;
+        ready_rx.await.map_err(|_| "监听线程已退出".to_string())??;
+        log::info!("已开始监听系统睡眠与锁屏事件");
+
+        while let Some(reason) = rx.recv().await {
+            lock_for(&app, reason).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod workspace {
+    use super::{lock_for, LockReason};
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceSessionDidResignActiveNotification,
+        NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::{
+        NSDistributedNotificationCenter, NSNotification, NSNotificationCenter, NSNotificationName,
+        NSString,
+    };
+    use std::ptr::NonNull;
+    use tauri::AppHandle;
+    use tokio::sync::mpsc::UnboundedSender;
+
+    /// 屏幕锁定时系统发出的分布式通知
+    const SCREEN_LOCKED_NOTIFICATION: &str = "com.apple.screenIsLocked";
+
+    /**
+     * 注册通知观察者
+     *
+     * 观察者在进程退出前一直有效
+     */
+    fn observe(
+        center: &NSNotificationCenter,
+        name: &NSNotificationName,
+        reason: LockReason,
+        events: UnboundedSender<LockReason>,
+    ) {
+        let block = RcBlock::new(move |_: NonNull<NSNotification>| {
+            let _ = events.send(reason);
+        });
+        let observer = unsafe {
+            center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block)
+        };
+        std::mem::forget(observer);
+    }
+
+    /**
+     * 监听 NSWorkspace 与屏幕锁定通知
+     *
+     * 通知由主线程的运行循环派发，因此在主线程上注册观察者
+     *
+     * @param app Tauri应用句柄
+     * @return 监听结果
+     */
+    pub async fn watch(app: AppHandle) -> Result<(), String> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        app.run_on_main_thread(move || unsafe {
+            let workspace = NSWorkspace::sharedWorkspace().notificationCenter();
+            observe(
+                &workspace,
+                NSWorkspaceWillSleepNotification,
+                LockReason::SystemSleep,
+                tx.clone(),
+            );
+            observe(
+                &workspace,
+                NSWorkspaceSessionDidResignActiveNotification,
+                LockReason::UserSwitch,
+                tx.clone(),
+            );
+            observe(
+                &NSDistributedNotificationCenter::defaultCenter(),
+                &NSString::from_str(SCREEN_LOCKED_NOTIFICATION),
+                LockReason::ScreenLock,
+                tx,
+            );
+        })
+        .map_err(|e| format!("注册系统通知失败: {}", e))?;
+        log::info!("已开始监听系统睡眠与锁屏事件");
+
+        while let Some(reason) = rx.recv().await {
+            lock_for(&app, reason).await;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * 启动系统事件监听
+ *
+ * 应在数据库初始化完成后调用
+ *
+ * @param app Tauri应用句柄
+ */
+pub fn start_lock_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        #[cfg(target_os = "linux")]
+        match logind::watch(app.clone()).await {
+            Ok(()) => {
+                log::warn!("logind 事件流已结束，改用时间跳跃检测");
+            }
+            Err(e) => {
+                log::warn!("无法监听 logind 事件，改用时间跳跃检测: {}", e);
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        if let Err(e) = wts::watch(app.clone()).await {
+            log::warn!("无法监听会话通知，改用时间跳跃检测: {}", e);
+        }
+
+        #[cfg(target_os = "macos")]
+        if let Err(e) = workspace::watch(app.clone()).await {
+            log::warn!("无法监听系统通知，改用时间跳跃检测: {}", e);
+        }
+
+        watch_clock_jump(app).await;
+    });
+}
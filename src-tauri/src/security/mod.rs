@@ -0,0 +1,19 @@
+/**
+ * 安全模块
+ *
+ * 该模块提供加密与认证相关的基础功能：
+ * - crypto: 密钥派生与对称加解密
+ * - auth: 主密码校验
+ * - vault: 密码库密钥的解锁与锁定
+ * - storage: 通用加密键值存储
+ * - audit: 防篡改的安全审计日志
+ * - session: 解锁会话的签发与校验
+ * - lock_monitor: 系统睡眠、锁屏时自动锁定
+ */
+pub mod audit;
+pub mod auth;
+pub mod crypto;
+pub mod lock_monitor;
+pub mod session;
+pub mod storage;
+pub mod vault;
@@ -0,0 +1,331 @@
+/**
+ * 会话管理
+ *
+ * 密码库解锁时签发会话ID并写入 security_sessions 表，查看密码、导出、安装插件等
+ * 敏感命令需要携带有效的会话ID。每次通过校验都会刷新最后活动时间，会话在以下
+ * 情况下失效：
+ * - 闲置超过自动锁定时间（auth_settings 中的 autoLockTime，关闭自动锁定时不限制）
+ * - 自创建起超过绝对有效期
+ * - 密码库被锁定
+ *
+ * 后台任务定期清理过期会话，当前会话过期时同时锁定密码库
+ */
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::auth::get_auth_setting;
+use crate::security::crypto::random_bytes;
+use crate::security::vault;
+use serde::Serialize;
+use sqlx::Row;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 会话绝对有效期（秒）
+const SESSION_MAX_LIFETIME_SECS: i64 = 12 * 60 * 60;
+/// 默认闲置超时时间（分钟），与前端自动锁定默认值一致
+const DEFAULT_IDLE_MINUTES: i64 = 30;
+/// 会话ID的随机字节数
+const SESSION_ID_BYTES: usize = 32;
+/// 过期会话检查间隔（秒）
+const EXPIRE_CHECK_INTERVAL_SECS: u64 = 30;
+
+// 使用lazy_static保存当前会话ID
+lazy_static::lazy_static! {
+    static ref CURRENT_SESSION: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 会话信息
+ */
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    /// 会话ID
+    pub session_id: String,
+    /// 创建时间
+    pub created_at: i64,
+    /// 最后活动时间
+    pub last_activity: i64,
+    /// 绝对过期时间
+    pub expires_at: i64,
+    /// 闲置超时时间（秒），为空表示不限制
+    pub idle_timeout_secs: Option<i64>,
+}
+
+/**
+ * 获取当前会话ID
+ *
+ * @return 当前会话ID，未解锁时为空
+ */
+pub fn current_session_id() -> Option<String> {
+    CURRENT_SESSION
+        .lock()
+        .map(|session| session.clone())
+        .unwrap_or(None)
+}
+
+/// 设置或清除当前会话ID
+fn set_current_session(session_id: Option<String>) {
+    match CURRENT_SESSION.lock() {
+        Ok(mut current) => *current = session_id,
+        Err(e) => *e.into_inner() = session_id,
+    }
+}
+
+/**
+ * 读取闲置超时时间
+ *
+ * @return 闲置超时时间（秒），关闭自动锁定时为空
+ */
+async fn idle_timeout_secs() -> Result<Option<i64>, String> {
+    if get_auth_setting("autoLockEnabled").await?.as_deref() == Some("false") {
+        return Ok(None);
+    }
+    let minutes = get_auth_setting("autoLockTime")
+        .await?
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(DEFAULT_IDLE_MINUTES);
+
+    Ok(Some(minutes * 60))
+}
+
+/**
+ * 签发新会话
+ *
+ * 同一时间只保留一个有效会话，已有的会话会被结束
+ *
+ * @return 会话信息
+ */
+pub(crate) async fn start_session() -> Result<SessionInfo, String> {
+    let idle_timeout_secs = idle_timeout_secs().await?;
+    let session_id = hex::encode(random_bytes(SESSION_ID_BYTES));
+    let now = chrono::Utc::now().timestamp();
+    let expires_at = now + SESSION_MAX_LIFETIME_SECS;
+
+    {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        sqlx::query("UPDATE security_sessions SET is_active = 0 WHERE is_active = 1")
+            .execute(db.get_pool())
+            .await
+            .map_err(|e| format!("结束旧会话失败: {}", e))?;
+        crate::execute_with_params!(
+            db,
+            "INSERT INTO security_sessions (session_id, created_at, last_activity, expires_at, is_active)
+             VALUES (?, ?, ?, ?, 1)",
+            &session_id,
+            now,
+            now,
+            expires_at
+        )?;
+    }
+
+    set_current_session(Some(session_id.clone()));
+    log::info!("已创建新的会话");
+
+    Ok(SessionInfo {
+        session_id,
+        created_at: now,
+        last_activity: now,
+        expires_at,
+        idle_timeout_secs,
+    })
+}
+
+/**
+ * 结束当前会话
+ *
+ * @return 操作结果
+ */
+pub(crate) async fn end_session() -> Result<(), String> {
+    let Some(session_id) = current_session_id() else {
+        return Ok(());
+    };
+    set_current_session(None);
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "UPDATE security_sessions SET is_active = 0 WHERE session_id = ?",
+        &session_id
+    )
+}
+
+/**
+ * 校验会话并刷新最后活动时间
+ *
+ * 会话无效时写入权限拒绝的审计事件
+ *
+ * @param session_id 会话ID
+ * @return 会话信息
+ */
+pub async fn require_session(session_id: &str) -> Result<SessionInfo, String> {
+    match check_session(session_id).await {
+        Ok(info) => Ok(info),
+        Err(reason) => {
+            record_quietly(
+                AuditEvent::PermissionDenied,
+                "敏感操作缺少有效会话",
+                Some(serde_json::json!({ "reason": reason })),
+            )
+            .await;
+            Err(format!("会话无效或已过期，请重新解锁: {}", reason))
+        }
+    }
+}
+
+/// 校验会话，返回失败原因
+async fn check_session(session_id: &str) -> Result<SessionInfo, String> {
+    if session_id.is_empty() {
+        return Err("未提供会话ID".to_string());
+    }
+    if current_session_id().as_deref() != Some(session_id) {
+        return Err("会话不是当前会话".to_string());
+    }
+    if !vault::is_unlocked() && get_auth_setting("passwordHash").await?.is_some() {
+        return Err("密码库未解锁".to_string());
+    }
+    let idle_timeout_secs = idle_timeout_secs().await?;
+    let now = chrono::Utc::now().timestamp();
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let row = crate::query_one_with_params!(
+        db,
+        "SELECT created_at, last_activity, expires_at, is_active FROM security_sessions
+         WHERE session_id = ?",
+        session_id
+    )?
+    .ok_or_else(|| "会话不存在".to_string())?;
+
+    let created_at: i64 = row.get("created_at");
+    let last_activity: i64 = row.get("last_activity");
+    let expires_at: i64 = row.get("expires_at");
+    if !row.get::<bool, _>("is_active") {
+        return Err("会话已结束".to_string());
+    }
+    let idle_expired = idle_timeout_secs.is_some_and(|idle| now - last_activity > idle);
+    if now >= expires_at || idle_expired {
+        crate::execute_with_params!(
+            db,
+            "UPDATE security_sessions SET is_active = 0 WHERE session_id = ?",
+            session_id
+        )?;
+        return Err("会话已过期".to_string());
+    }
+
+    crate::execute_with_params!(
+        db,
+        "UPDATE security_sessions SET last_activity = ? WHERE session_id = ?",
+        now,
+        session_id
+    )?;
+
+    Ok(SessionInfo {
+        session_id: session_id.to_string(),
+        created_at,
+        last_activity: now,
+        expires_at,
+        idle_timeout_secs,
+    })
+}
+
+/**
+ * 结束所有已过期的会话
+ *
+ * 当前会话过期时锁定密码库
+ *
+ * @return 操作结果
+ */
+async fn expire_sessions() -> Result<(), String> {
+    let idle_timeout_secs = idle_timeout_secs().await?;
+    let now = chrono::Utc::now().timestamp();
+    let idle_cutoff = idle_timeout_secs.map(|idle| now - idle).unwrap_or(i64::MIN);
+
+    let expired = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let rows = crate::query_with_params!(
+            db,
+            "UPDATE security_sessions SET is_active = 0
+             WHERE is_active = 1 AND (expires_at <= ? OR last_activity < ?)
+             RETURNING session_id",
+            now,
+            idle_cutoff
+        )?;
+        rows.iter()
+            .map(|row| row.get::<String, _>("session_id"))
+            .collect::<Vec<_>>()
+    };
+
+    let current = current_session_id();
+    if current.is_some_and(|id| expired.contains(&id)) {
+        set_current_session(None);
+        if vault::lock() {
+            log::info!("会话已过期，密码库已锁定");
+            record_quietly(AuditEvent::VaultLocked, "会话过期，密码库已自动锁定", None).await;
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 结束上次运行遗留的会话
+ *
+ * 应用重启后内存中的密码库密钥已不存在，遗留的会话不再有效
+ */
+async fn end_stale_sessions() -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    sqlx::query("UPDATE security_sessions SET is_active = 0 WHERE is_active = 1")
+        .execute(db.get_pool())
+        .await
+        .map_err(|e| format!("结束遗留会话失败: {}", e))?;
+    Ok(())
+}
+
+/**
+ * 启动过期会话检查任务
+ *
+ * 应在数据库初始化完成后调用
+ */
+pub fn start_session_monitor() {
+    tauri::async_runtime::spawn(async {
+        if let Err(e) = end_stale_sessions().await {
+            log::warn!("{}", e);
+        }
+        let mut interval = tokio::time::interval(Duration::from_secs(EXPIRE_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = expire_sessions().await {
+                log::warn!("检查过期会话失败: {}", e);
+            }
+        }
+    });
+}
+
+/**
+ * 未设置主密码时签发会话
+ *
+ * 已设置主密码时必须通过 unlock_vault 解锁获取会话
+ *
+ * @return 会话信息
+ */
+#[tauri::command]
+pub async fn open_session() -> Result<SessionInfo, String> {
+    if get_auth_setting("passwordHash").await?.is_some() {
+        return Err("已设置主密码，请先解锁密码库".to_string());
+    }
+    start_session().await
+}
+
+/**
+ * 刷新会话的最后活动时间
+ *
+ * @param session_id 会话ID
+ * @return 会话信息
+ */
+#[tauri::command]
+pub async fn touch_session(session_id: String) -> Result<SessionInfo, String> {
+    check_session(&session_id).await
+}
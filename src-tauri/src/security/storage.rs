@@ -0,0 +1,436 @@
+/**
+ * 加密键值存储
+ *
+ * 基于 encrypted_storage 表保存各模块的机密数据（AI 接口密钥、插件令牌等），
+ * 每条记录按 encryption_level 选择加密密钥：
+ * - device: 使用本机设备密钥，应用启动后即可读取
+ * - master: 使用密码库密钥，需要先用主密码解锁
+ *
+ * 键名约定为 "<命名空间>.<名称>"，例如 "ai.api_key.openai"、
+ * "plugin.<插件ID>.token"。键名与加密级别作为附加认证数据，
+ * 密文无法被移动到其他记录下解密
+ *
+ * 前端命令只能访问 "app." 与 "plugin." 命名空间，其余命名空间（AI 接口密钥、
+ * 同步密钥、浏览器配对等）仅供后端模块通过 get_secret / set_secret 使用
+ */
+use crate::file::paths::get_data_dir;
+use crate::security::crypto::{decrypt_from_base64, encrypt_to_base64, random_bytes, KEY_LEN};
+use crate::security::vault::with_vault_key;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use zeroize::Zeroizing;
+
+/// 设备密钥文件名
+const DEVICE_KEY_FILE: &str = "device.key";
+/// 键名最大长度
+const MAX_KEY_LEN: usize = 256;
+/// 前端命令可访问的命名空间
+const WEBVIEW_NAMESPACES: [&str; 2] = ["app.", "plugin."];
+
+// 使用lazy_static缓存设备密钥，避免每次读取文件
+lazy_static::lazy_static! {
+    static ref DEVICE_KEY: Arc<Mutex<Option<Zeroizing<Vec<u8>>>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 加密级别
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum EncryptionLevel {
+    /// 设备密钥
+    #[default]
+    #[serde(rename = "device")]
+    Device,
+    /// 密码库密钥（需主密码解锁）
+    #[serde(rename = "master")]
+    Master,
+}
+
+impl EncryptionLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EncryptionLevel::Device => "device",
+            EncryptionLevel::Master => "master",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "device" => Ok(EncryptionLevel::Device),
+            "master" => Ok(EncryptionLevel::Master),
+            other => Err(format!("未知的加密级别: {}", other)),
+        }
+    }
+}
+
+/**
+ * 存储项信息，不包含内容
+ */
+#[derive(Debug, Serialize)]
+pub struct SecureItemInfo {
+    /// 键名
+    pub key: String,
+    /// 加密级别
+    pub encryption_level: EncryptionLevel,
+    /// 附加元数据
+    pub metadata: Option<serde_json::Value>,
+    /// 创建时间
+    pub created_at: i64,
+    /// 更新时间
+    pub updated_at: i64,
+}
+
+/// 校验键名
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!("键名长度需在1到{}之间", MAX_KEY_LEN));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | ':'))
+    {
+        return Err(format!("键名包含非法字符: {}", key));
+    }
+    Ok(())
+}
+
+/// 校验前端命令访问的键名或前缀是否属于开放的命名空间
+fn check_webview_key(key: &str) -> Result<(), String> {
+    validate_key(key)?;
+    if !WEBVIEW_NAMESPACES.iter().any(|ns| key.starts_with(ns)) {
+        return Err(format!("无权访问存储项: {}", key));
+    }
+    Ok(())
+}
+
+/// 附加认证数据：键名与加密级别
+fn storage_aad(key: &str, level: EncryptionLevel) -> Vec<u8> {
+    format!("encrypted_storage:{}:{}", level.as_str(), key).into_bytes()
+}
+
+/**
+ * 读取或生成设备密钥
+ *
+ * 设备密钥保存在应用数据目录中，首次使用时生成
+ *
+ * @param app Tauri应用句柄
+ * @return 设备密钥
+ */
+pub(crate) fn device_key(app: AppHandle) -> Result<Zeroizing<Vec<u8>>, String> {
+    let mut cached = DEVICE_KEY
+        .lock()
+        .map_err(|e| format!("获取设备密钥锁失败: {}", e))?;
+    if let Some(key) = cached.as_ref() {
+        return Ok(key.clone());
+    }
+
+    let dir = get_data_dir(app)?;
+    let path = dir.join(DEVICE_KEY_FILE);
+    let key = if path.exists() {
+        let key = Zeroizing::new(fs::read(&path).map_err(|e| format!("读取设备密钥失败: {}", e))?);
+        if key.len() != KEY_LEN {
+            return Err("设备密钥文件已损坏".to_string());
+        }
+        key
+    } else {
+        fs::create_dir_all(&dir).map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+        let key = Zeroizing::new(random_bytes(KEY_LEN));
+        fs::write(&path, key.as_slice()).map_err(|e| format!("保存设备密钥失败: {}", e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("设置设备密钥权限失败: {}", e))?;
+        }
+
+        log::info!("已生成设备密钥");
+        key
+    };
+
+    *cached = Some(key.clone());
+    Ok(key)
+}
+
+/**
+ * 使用指定级别的密钥加密
+ */
+fn seal(
+    app: AppHandle,
+    key: &str,
+    level: EncryptionLevel,
+    plaintext: &[u8],
+) -> Result<String, String> {
+    let aad = storage_aad(key, level);
+    match level {
+        EncryptionLevel::Device => encrypt_to_base64(&device_key(app)?, plaintext, &aad),
+        EncryptionLevel::Master => with_vault_key(|k| encrypt_to_base64(k, plaintext, &aad)),
+    }
+}
+
+/**
+ * 使用指定级别的密钥解密
+ */
+fn open(app: AppHandle, key: &str, level: EncryptionLevel, data: &str) -> Result<Vec<u8>, String> {
+    let aad = storage_aad(key, level);
+    match level {
+        EncryptionLevel::Device => decrypt_from_base64(&device_key(app)?, data, &aad),
+        EncryptionLevel::Master => with_vault_key(|k| decrypt_from_base64(k, data, &aad)),
+    }
+    .map_err(|_| format!("无法解密存储项: {}", key))
+}
+
+/**
+ * 写入机密数据
+ *
+ * 供其他模块在后端直接调用
+ *
+ * @param app Tauri应用句柄
+ * @param key 键名
+ * @param value 内容
+ * @param level 加密级别
+ * @param metadata 附加元数据（明文保存）
+ * @return 操作结果
+ */
+pub async fn set_secret(
+    app: AppHandle,
+    key: &str,
+    value: &str,
+    level: EncryptionLevel,
+    metadata: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    validate_key(key)?;
+    let encrypted = seal(app, key, level, value.as_bytes())?;
+    let metadata = metadata
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("序列化元数据失败: {}", e))?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let now = chrono::Utc::now().timestamp();
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO encrypted_storage
+         (id, encryption_level, encrypted_content, metadata, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET encryption_level = excluded.encryption_level,
+                                       encrypted_content = excluded.encrypted_content,
+                                       metadata = excluded.metadata,
+                                       updated_at = excluded.updated_at",
+        key,
+        level.as_str(),
+        encrypted,
+        metadata,
+        now,
+        now
+    )
+}
+
+/**
+ * 读取机密数据
+ *
+ * @param app Tauri应用句柄
+ * @param key 键名
+ * @return 内容，不存在时为空
+ */
+pub async fn get_secret(app: AppHandle, key: &str) -> Result<Option<String>, String> {
+    validate_key(key)?;
+    let row = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        crate::query_one_with_params!(
+            db,
+            "SELECT encryption_level, encrypted_content FROM encrypted_storage WHERE id = ?",
+            key
+        )?
+    };
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let level = EncryptionLevel::parse(&row.get::<String, _>("encryption_level"))?;
+    let plaintext = open(app, key, level, &row.get::<String, _>("encrypted_content"))?;
+
+    String::from_utf8(plaintext)
+        .map(Some)
+        .map_err(|_| format!("存储项内容不是有效的文本: {}", key))
+}
+
+/**
+ * 删除机密数据
+ *
+ * @param key 键名
+ * @return 是否存在并被删除
+ */
+pub async fn delete_secret(key: &str) -> Result<bool, String> {
+    validate_key(key)?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let result = sqlx::query("DELETE FROM encrypted_storage WHERE id = ?")
+        .bind(key)
+        .execute(db.get_pool())
+        .await
+        .map_err(|e| format!("删除存储项失败: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/**
+ * 删除指定前缀下的全部机密数据
+ *
+ * 用于卸载插件等场景清理命名空间
+ *
+ * @param prefix 键名前缀
+ * @return 删除的数量
+ */
+pub async fn delete_secrets_with_prefix(prefix: &str) -> Result<u64, String> {
+    validate_key(prefix)?;
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let result = sqlx::query("DELETE FROM encrypted_storage WHERE substr(id, 1, length(?)) = ?")
+        .bind(prefix)
+        .bind(prefix)
+        .execute(db.get_pool())
+        .await
+        .map_err(|e| format!("删除存储项失败: {}", e))?;
+
+    Ok(result.rows_affected())
+}
+
+/**
+ * 写入机密数据
+ *
+ * @param app Tauri应用句柄
+ * @param key 键名
+ * @param value 内容
+ * @param encryption_level 加密级别，默认使用设备密钥
+ * @param metadata 附加元数据（明文保存）
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn secure_set(
+    app: AppHandle,
+    key: String,
+    value: String,
+    encryption_level: Option<EncryptionLevel>,
+    metadata: Option<serde_json::Value>,
+) -> Result<(), String> {
+    check_webview_key(&key)?;
+    let value = Zeroizing::new(value);
+    set_secret(
+        app,
+        &key,
+        &value,
+        encryption_level.unwrap_or_default(),
+        metadata.as_ref(),
+    )
+    .await
+}
+
+/**
+ * 读取机密数据
+ *
+ * @param app Tauri应用句柄
+ * @param key 键名
+ * @return 内容，不存在时为空
+ */
+#[tauri::command]
+pub async fn secure_get(app: AppHandle, key: String) -> Result<Option<String>, String> {
+    check_webview_key(&key)?;
+    get_secret(app, &key).await
+}
+
+/**
+ * 删除机密数据
+ *
+ * @param key 键名
+ * @return 是否存在并被删除
+ */
+#[tauri::command]
+pub async fn secure_delete(key: String) -> Result<bool, String> {
+    check_webview_key(&key)?;
+    delete_secret(&key).await
+}
+
+/**
+ * 列出存储项
+ *
+ * 仅返回键名、加密级别和元数据，不解密内容
+ *
+ * @param prefix 键名前缀，未提供时列出前端可访问的全部命名空间
+ * @return 存储项列表
+ */
+#[tauri::command]
+pub async fn secure_list(prefix: Option<String>) -> Result<Vec<SecureItemInfo>, String> {
+    match prefix {
+        Some(prefix) => {
+            check_webview_key(&prefix)?;
+            list_secrets(&prefix).await
+        }
+        None => {
+            let mut items = Vec::new();
+            for namespace in WEBVIEW_NAMESPACES {
+                items.extend(list_secrets(namespace).await?);
+            }
+            Ok(items)
+        }
+    }
+}
+
+/**
+ * 列出指定前缀下的存储项
+ *
+ * 供其他模块在后端直接调用，仅返回键名、加密级别和元数据
+ *
+ * @param prefix 键名前缀
+ * @return 存储项列表
+ */
+pub async fn list_secrets(prefix: &str) -> Result<Vec<SecureItemInfo>, String> {
+    validate_key(prefix)?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = crate::query_with_params!(
+        db,
+        "SELECT id, encryption_level, metadata, created_at, updated_at FROM encrypted_storage
+         WHERE substr(id, 1, length(?)) = ? ORDER BY id",
+        prefix,
+        prefix
+    )?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(SecureItemInfo {
+                key: row.get("id"),
+                encryption_level: EncryptionLevel::parse(
+                    &row.get::<String, _>("encryption_level"),
+                )?,
+                metadata: row
+                    .get::<Option<String>, _>("metadata")
+                    .and_then(|m| serde_json::from_str(&m).ok()),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webview_keys_are_limited_to_open_namespaces() {
+        assert!(check_webview_key("app.theme_token").is_ok());
+        assert!(check_webview_key("plugin.demo.token").is_ok());
+        assert!(check_webview_key("ai.api_key.openai").is_err());
+        assert!(check_webview_key("sync.key").is_err());
+        assert!(check_webview_key("browser.paired.abc").is_err());
+        assert!(check_webview_key("app").is_err());
+        assert!(check_webview_key("app./../x").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+/**
+ * 密码库密钥管理
+ *
+ * 密码库使用随机生成的数据密钥加密敏感内容，数据密钥由主密码经 Argon2id
+ * 派生的密钥加密后保存在 auth_settings 中。解锁后数据密钥仅保存在内存中，
+ * 锁定时立即清零
+ */
+use crate::password::stop_agent;
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::auth::{get_auth_setting, set_auth_setting, verify_master_password};
+use crate::security::crypto::{
+    decode_base64, decrypt, derive_key, encode_base64, encrypt, random_bytes, KdfParams, KEY_LEN,
+    SALT_LEN,
+};
+use crate::security::session::{end_session, start_session, SessionInfo};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroize;
+
+/// 数据密钥盐值的设置项名称
+const VAULT_KEY_SALT: &str = "vaultKeySalt";
+/// 加密后的数据密钥的设置项名称
+const VAULT_KEY_WRAPPED: &str = "vaultKeyWrapped";
+/// 数据密钥派生参数的设置项名称
+const VAULT_KEY_PARAMS: &str = "vaultKeyParams";
+/// 加密数据密钥时使用的附加认证数据
+const VAULT_KEY_AAD: &[u8] = b"taiasst-vault-key";
+
+/**
+ * 内存中的数据密钥，释放时自动清零
+ */
+pub struct VaultKey([u8; KEY_LEN]);
+
+impl Drop for VaultKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// 使用lazy_static管理全局密码库密钥
+lazy_static::lazy_static! {
+    static ref VAULT_KEY: Arc<Mutex<Option<VaultKey>>> = Arc::new(Mutex::new(None));
+}
+
+/**
+ * 密码库状态
+ */
+#[derive(Debug, Serialize)]
+pub struct VaultStatus {
+    /// 是否已解锁
+    pub unlocked: bool,
+    /// 是否已设置主密码
+    pub has_master_password: bool,
+}
+
+/**
+ * 判断密码库是否已解锁
+ */
+pub fn is_unlocked() -> bool {
+    VAULT_KEY.lock().map(|key| key.is_some()).unwrap_or(false)
+}
+
+/**
+ * 使用数据密钥执行操作
+ *
+ * 密钥不会被复制到调用方，密码库未解锁时返回错误
+ *
+ * @param f 使用密钥的操作
+ * @return 操作结果
+ */
+pub fn with_vault_key<R>(f: impl FnOnce(&[u8]) -> Result<R, String>) -> Result<R, String> {
+    let guard = VAULT_KEY
+        .lock()
+        .map_err(|e| format!("获取密码库密钥锁失败: {}", e))?;
+    let key = guard.as_ref().ok_or_else(|| "密码库未解锁".to_string())?;
+    f(&key.0)
+}
+
+/**
+ * 清除内存中的数据密钥
+ *
+ * 同时停止依赖密码库密钥的 SSH 代理
+ *
+ * @return 锁定前是否处于解锁状态
+ */
+pub fn lock() -> bool {
+    stop_agent();
+    match VAULT_KEY.lock() {
+        Ok(mut key) => key.take().is_some(),
+        Err(e) => {
+            // 即使锁被污染也要确保密钥被清除
+            e.into_inner().take().is_some()
+        }
+    }
+}
+
+/**
+ * 使用主密码加密数据密钥并保存
+ *
+ * 每次保存都会生成新的盐值
+ *
+ * @param master_password 主密码
+ * @param key 数据密钥
+ * @return 操作结果
+ */
+async fn store_wrapped_key(master_password: &str, key: [u8; KEY_LEN]) -> Result<(), String> {
+    let params = KdfParams::default();
+    let salt = random_bytes(SALT_LEN);
+    let password = master_password.to_string();
+    let params_for_task = params.clone();
+    let salt_for_task = salt.clone();
+
+    let wrapped = tokio::task::spawn_blocking(move || {
+        let mut key = key;
+        let mut kek = derive_key(password.as_bytes(), &salt_for_task, &params_for_task)?;
+        let wrapped = encrypt(&kek, &key, VAULT_KEY_AAD);
+        kek.zeroize();
+        key.zeroize();
+        wrapped
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let params =
+        serde_json::to_string(&params).map_err(|e| format!("序列化密钥参数失败: {}", e))?;
+    set_auth_setting(VAULT_KEY_PARAMS, &params).await?;
+    set_auth_setting(VAULT_KEY_SALT, &encode_base64(&salt)).await?;
+    set_auth_setting(VAULT_KEY_WRAPPED, &encode_base64(&wrapped)).await
+}
+
+/**
+ * 从主密码获取数据密钥
+ *
+ * 首次解锁时生成新的数据密钥并保存加密后的副本。主密码已通过校验但无法解密
+ * 已保存的数据密钥时返回错误并保留原有的密钥副本，避免已加密的数据无法读取
+ *
+ * @param master_password 主密码
+ * @return 数据密钥
+ */
+async fn load_vault_key(master_password: &str) -> Result<VaultKey, String> {
+    let salt = get_auth_setting(VAULT_KEY_SALT).await?;
+    let wrapped = get_auth_setting(VAULT_KEY_WRAPPED).await?;
+    let params = get_auth_setting(VAULT_KEY_PARAMS).await?;
+
+    if let (Some(salt), Some(wrapped)) = (salt, wrapped) {
+        let params: KdfParams = params
+            .and_then(|p| serde_json::from_str(&p).ok())
+            .unwrap_or_default();
+        let salt = decode_base64(&salt)?;
+        let wrapped = decode_base64(&wrapped)?;
+        let password = master_password.to_string();
+
+        let unwrapped = tokio::task::spawn_blocking(move || {
+            let mut kek = derive_key(password.as_bytes(), &salt, &params)?;
+            let result = decrypt(&kek, &wrapped, VAULT_KEY_AAD);
+            kek.zeroize();
+            Ok::<_, String>(result.ok())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        match unwrapped {
+            Some(mut plain) if plain.len() == KEY_LEN => {
+                let mut key = [0u8; KEY_LEN];
+                key.copy_from_slice(&plain);
+                plain.zeroize();
+                return Ok(VaultKey(key));
+            }
+            Some(mut plain) => {
+                plain.zeroize();
+                return Err("密码库密钥长度无效".to_string());
+            }
+            None => {
+                log::warn!("无法使用当前主密码解密密码库密钥");
+                return Err(
+                    "无法使用当前主密码解密密码库密钥，请使用设置该密钥时的主密码解锁".to_string(),
+                );
+            }
+        }
+    }
+
+    let mut key = [0u8; KEY_LEN];
+    key.copy_from_slice(&random_bytes(KEY_LEN));
+    store_wrapped_key(master_password, key).await?;
+    log::info!("已生成新的密码库密钥");
+
+    Ok(VaultKey(key))
+}
+
+/**
+ * 使用主密码解密数据密钥并保存在内存中
+ *
+ * @param master_password 主密码
+ * @return 操作结果
+ */
+async fn load_into_memory(master_password: &str) -> Result<(), String> {
+    let key = load_vault_key(master_password).await?;
+
+    VAULT_KEY
+        .lock()
+        .map_err(|e| format!("获取密码库密钥锁失败: {}", e))?
+        .replace(key);
+    Ok(())
+}
+
+/**
+ * 解锁密码库
+ *
+ * 解锁成功后签发新的会话
+ *
+ * @param master_password 主密码
+ * @return 会话信息
+ */
+#[tauri::command]
+pub async fn unlock_vault(master_password: String) -> Result<SessionInfo, String> {
+    if let Err(e) = verify_master_password(&master_password).await {
+        record_quietly(AuditEvent::UnlockFailure, "密码库解锁失败", None).await;
+        return Err(e);
+    }
+    load_into_memory(&master_password).await?;
+    let session = start_session().await?;
+
+    log::info!("密码库已解锁");
+    record_quietly(AuditEvent::UnlockSuccess, "密码库已解锁", None).await;
+    Ok(session)
+}
+
+/**
+ * 主密码重新设置后重新加密数据密钥
+ *
+ * 密码库已解锁时使用新的主密码重新加密当前数据密钥，否则按新主密码解锁，
+ * 两种情况均保留当前会话
+ *
+ * @param master_password 新的主密码
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn rewrap_vault_key(master_password: String) -> Result<(), String> {
+    verify_master_password(&master_password).await?;
+
+    let key = if is_unlocked() {
+        Some(with_vault_key(|key| {
+            let mut copy = [0u8; KEY_LEN];
+            copy.copy_from_slice(key);
+            Ok(copy)
+        })?)
+    } else {
+        None
+    };
+
+    match key {
+        Some(key) => {
+            store_wrapped_key(&master_password, key).await?;
+            log::info!("密码库密钥已使用新的主密码重新加密");
+            Ok(())
+        }
+        None => load_into_memory(&master_password).await,
+    }
+}
+
+/**
+ * 锁定密码库，清除内存中的密钥并结束当前会话
+ */
+#[tauri::command]
+pub async fn lock_vault() {
+    if let Err(e) = end_session().await {
+        log::warn!("结束会话失败: {}", e);
+    }
+    if lock() {
+        log::info!("密码库已锁定");
+        record_quietly(AuditEvent::VaultLocked, "密码库已锁定", None).await;
+    }
+}
+
+/**
+ * 获取密码库状态
+ *
+ * @return 密码库状态
+ */
+#[tauri::command]
+pub async fn get_vault_status() -> Result<VaultStatus, String> {
+    let has_master_password = get_auth_setting("passwordHash").await?.is_some();
+    Ok(VaultStatus {
+        unlocked: is_unlocked(),
+        has_master_password,
+    })
+}
@@ -0,0 +1,137 @@
+/**
+ * 向量时钟
+ *
+ * 每台设备维护自己的计数器，本地修改一条记录时递增本设备的计数。比较两个时钟
+ * 即可判断两个版本是先后关系还是并发修改
+ */
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/**
+ * 两个时钟的先后关系
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockOrdering {
+    /// 完全相同
+    Equal,
+    /// 早于对方，对方包含了本版本的全部修改
+    Before,
+    /// 晚于对方
+    After,
+    /// 并发修改，互不包含
+    Concurrent,
+}
+
+/**
+ * 向量时钟，设备ID到计数的映射
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct VectorClock(BTreeMap<String, u64>);
+
+impl VectorClock {
+    /**
+     * 递增指定设备的计数
+     *
+     * @param device_id 设备ID
+     */
+    pub fn increment(&mut self, device_id: &str) {
+        *self.0.entry(device_id.to_string()).or_insert(0) += 1;
+    }
+
+    /**
+     * 合并另一个时钟，每台设备取较大的计数
+     *
+     * @param other 另一个时钟
+     */
+    pub fn merge(&mut self, other: &VectorClock) {
+        for (device, &count) in &other.0 {
+            let current = self.0.entry(device.clone()).or_insert(0);
+            *current = (*current).max(count);
+        }
+    }
+
+    /**
+     * 比较两个时钟
+     *
+     * @param other 另一个时钟
+     * @return 本时钟相对于另一个时钟的先后关系
+     */
+    pub fn compare(&self, other: &VectorClock) -> ClockOrdering {
+        let mut less = false;
+        let mut greater = false;
+        for device in self.0.keys().chain(other.0.keys()) {
+            let mine = self.0.get(device).copied().unwrap_or(0);
+            let theirs = other.0.get(device).copied().unwrap_or(0);
+            less |= mine < theirs;
+            greater |= mine > theirs;
+        }
+
+        match (less, greater) {
+            (false, false) => ClockOrdering::Equal,
+            (true, false) => ClockOrdering::Before,
+            (false, true) => ClockOrdering::After,
+            (true, true) => ClockOrdering::Concurrent,
+        }
+    }
+
+    /**
+     * 序列化为 JSON 文本，用于保存到数据库
+     */
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /**
+     * 从 JSON 文本解析，格式错误时视为空时钟
+     *
+     * @param json JSON 文本
+     */
+    pub fn from_json(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(entries: &[(&str, u64)]) -> VectorClock {
+        VectorClock(entries.iter().map(|(d, c)| (d.to_string(), *c)).collect())
+    }
+
+    #[test]
+    fn compare_orders_clocks() {
+        let a = clock(&[("a", 1)]);
+        let ab = clock(&[("a", 1), ("b", 1)]);
+        assert_eq!(a.compare(&a), ClockOrdering::Equal);
+        assert_eq!(a.compare(&ab), ClockOrdering::Before);
+        assert_eq!(ab.compare(&a), ClockOrdering::After);
+        assert_eq!(
+            clock(&[("a", 2)]).compare(&clock(&[("b", 1)])),
+            ClockOrdering::Concurrent
+        );
+        assert_eq!(
+            VectorClock::default().compare(&clock(&[("a", 0)])),
+            ClockOrdering::Equal
+        );
+    }
+
+    #[test]
+    fn increment_and_merge() {
+        let mut a = VectorClock::default();
+        a.increment("a");
+        a.increment("a");
+        let mut b = clock(&[("a", 1), ("b", 3)]);
+        b.merge(&a);
+        assert_eq!(b, clock(&[("a", 2), ("b", 3)]));
+        assert_eq!(b.compare(&a), ClockOrdering::After);
+    }
+
+    #[test]
+    fn json_round_trip_and_invalid_input() {
+        let a = clock(&[("a", 1), ("b", 2)]);
+        assert_eq!(VectorClock::from_json(&a.to_json()), a);
+        assert_eq!(VectorClock::from_json("not json"), VectorClock::default());
+    }
+}
@@ -0,0 +1,371 @@
+/**
+ * 同步配置
+ *
+ * 所有设备使用同一个同步口令，通过 Argon2id 派生同步密钥。派生参数、盐值和
+ * 校验数据保存在同步目标的 meta.json 中；本机以主密码级别加密保存派生出的
+ * 同步密钥和 WebDAV 密码，目标配置保存在 system_settings 中
+ */
+use super::target::{SyncTarget, SyncTargetConfig, META_FILE};
+use crate::security::crypto::{
+    decode_base64, decrypt, derive_key, encode_base64, encrypt, random_bytes, KdfParams, KEY_LEN,
+    SALT_LEN,
+};
+use crate::security::session::require_session;
+use crate::security::storage::{delete_secret, get_secret, set_secret, EncryptionLevel};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tauri::AppHandle;
+use zeroize::Zeroizing;
+
+/// 同步目标配置的系统设置项
+const SYNC_CONFIG_SETTING: &str = "syncConfig";
+/// 本设备ID的系统设置项
+const SYNC_DEVICE_SETTING: &str = "syncDeviceId";
+/// 上次同步时间的系统设置项
+pub const SYNC_LAST_RUN_SETTING: &str = "syncLastRunAt";
+/// 同步密钥在加密存储中的键名
+const SYNC_KEY_SECRET: &str = "sync.key";
+/// WebDAV 密码在加密存储中的键名
+const WEBDAV_PASSWORD_SECRET: &str = "sync.webdav_password";
+/// 元数据格式标识
+const META_FORMAT: &str = "taiasst-sync";
+/// 当前元数据版本
+const META_VERSION: u32 = 1;
+/// 同步口令最小长度
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// 校验数据的附加认证数据
+const VERIFIER_AAD: &[u8] = b"taiasst-sync-meta";
+/// 校验数据明文
+const VERIFIER_PLAINTEXT: &[u8] = b"taiasst-sync";
+
+/**
+ * 同步目标的元数据
+ */
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncMeta {
+    /// 格式标识
+    format: String,
+    /// 版本
+    version: u32,
+    /// 密钥派生参数
+    kdf_params: KdfParams,
+    /// 盐值（Base64）
+    salt: String,
+    /// 用同步密钥加密的校验数据（Base64）
+    verifier: String,
+}
+
+/**
+ * 同步状态
+ */
+#[derive(Debug, Serialize)]
+pub struct SyncStatus {
+    /// 是否已配置
+    pub configured: bool,
+    /// 同步目标
+    pub target: Option<SyncTargetConfig>,
+    /// 本设备ID
+    pub device_id: String,
+    /// 上次同步时间（毫秒）
+    pub last_sync_at: Option<i64>,
+    /// 待上传的记录数
+    pub pending_changes: i64,
+    /// 未解决的冲突数
+    pub conflicts: i64,
+}
+
+/**
+ * 读取系统设置
+ */
+pub(crate) async fn get_setting(key: &str) -> Result<Option<String>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    Ok(
+        crate::query_one_with_params!(db, "SELECT value FROM system_settings WHERE key = ?", key)?
+            .and_then(|row| row.get::<Option<String>, _>("value")),
+    )
+}
+
+/**
+ * 写入系统设置
+ */
+pub(crate) async fn set_setting(key: &str, value: &str) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "INSERT INTO system_settings (key, value, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        key,
+        value,
+        chrono::Utc::now().timestamp()
+    )
+}
+
+/**
+ * 获取本设备ID，首次使用时生成
+ *
+ * @return 设备ID
+ */
+pub(crate) async fn device_id() -> Result<String, String> {
+    if let Some(id) = get_setting(SYNC_DEVICE_SETTING).await? {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    set_setting(SYNC_DEVICE_SETTING, &id).await?;
+    Ok(id)
+}
+
+/**
+ * 读取同步目标配置
+ */
+async fn load_target_config() -> Result<Option<SyncTargetConfig>, String> {
+    get_setting(SYNC_CONFIG_SETTING)
+        .await?
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("同步配置无效: {}", e)))
+        .transpose()
+}
+
+/**
+ * 打开已配置的同步目标并读取同步密钥
+ *
+ * @param app Tauri应用句柄
+ * @return 同步目标与同步密钥
+ */
+pub(crate) async fn open_target(
+    app: &AppHandle,
+) -> Result<(SyncTarget, Zeroizing<Vec<u8>>), String> {
+    let config = load_target_config()
+        .await?
+        .ok_or_else(|| "尚未配置同步".to_string())?;
+    let password = get_secret(app.clone(), WEBDAV_PASSWORD_SECRET).await?;
+    let key = get_secret(app.clone(), SYNC_KEY_SECRET)
+        .await?
+        .ok_or_else(|| "同步密钥缺失，请重新配置同步".to_string())?;
+    let key = Zeroizing::new(decode_base64(&key)?);
+
+    let target = SyncTarget::open(&config, password)?;
+    verify_key(&target, &key).await?;
+    Ok((target, key))
+}
+
+/**
+ * 读取同步目标的元数据
+ */
+async fn read_meta(target: &SyncTarget) -> Result<Option<SyncMeta>, String> {
+    let Some(data) = target.read(META_FILE).await? else {
+        return Ok(None);
+    };
+    let meta: SyncMeta =
+        serde_json::from_slice(&data).map_err(|e| format!("同步元数据无效: {}", e))?;
+    if meta.format != META_FORMAT {
+        return Err("同步目标中不是有效的TaiASST同步数据".to_string());
+    }
+    if meta.version > META_VERSION {
+        return Err(format!("不支持的同步数据版本: {}", meta.version));
+    }
+    Ok(Some(meta))
+}
+
+/**
+ * 校验同步密钥与目标中的元数据一致
+ */
+async fn verify_key(target: &SyncTarget, key: &[u8]) -> Result<(), String> {
+    let meta = read_meta(target)
+        .await?
+        .ok_or_else(|| "同步目标中的元数据缺失，请重新配置同步".to_string())?;
+    decrypt(key, &decode_base64(&meta.verifier)?, VERIFIER_AAD)
+        .map(|_| ())
+        .map_err(|_| "同步口令已在其他设备上更改，请重新配置同步".to_string())
+}
+
+/**
+ * 从同步口令派生同步密钥
+ *
+ * 目标中已有元数据时校验口令，否则创建新的元数据
+ *
+ * @param target 同步目标
+ * @param passphrase 同步口令
+ * @return 同步密钥
+ */
+async fn unlock_target(target: &SyncTarget, passphrase: String) -> Result<[u8; KEY_LEN], String> {
+    let passphrase = Zeroizing::new(passphrase);
+    if let Some(meta) = read_meta(target).await? {
+        meta.kdf_params.check_untrusted()?;
+        let salt = decode_base64(&meta.salt)?;
+        let params = meta.kdf_params.clone();
+        let key =
+            tokio::task::spawn_blocking(move || derive_key(passphrase.as_bytes(), &salt, &params))
+                .await
+                .map_err(|e| e.to_string())??;
+        decrypt(&key, &decode_base64(&meta.verifier)?, VERIFIER_AAD)
+            .map_err(|_| "同步口令错误".to_string())?;
+        return Ok(key);
+    }
+
+    let salt = random_bytes(SALT_LEN);
+    let params = KdfParams::default();
+    let (key, salt) = {
+        let params = params.clone();
+        tokio::task::spawn_blocking(move || {
+            derive_key(passphrase.as_bytes(), &salt, &params).map(|key| (key, salt))
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+    let meta = SyncMeta {
+        format: META_FORMAT.to_string(),
+        version: META_VERSION,
+        kdf_params: params,
+        salt: encode_base64(&salt),
+        verifier: encode_base64(&encrypt(&key, VERIFIER_PLAINTEXT, VERIFIER_AAD)?),
+    };
+    let data =
+        serde_json::to_vec_pretty(&meta).map_err(|e| format!("序列化同步元数据失败: {}", e))?;
+    target.write(META_FILE, &data).await?;
+    Ok(key)
+}
+
+/**
+ * 读取同步状态
+ */
+async fn current_status() -> Result<SyncStatus, String> {
+    let target = load_target_config().await?;
+    let device_id = device_id().await?;
+    let last_sync_at = get_setting(SYNC_LAST_RUN_SETTING)
+        .await?
+        .and_then(|v| v.parse().ok());
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let pending_changes: i64 =
+        sqlx::query("SELECT COUNT(*) AS count FROM sync_records WHERE pending = 1")
+            .fetch_one(db.get_pool())
+            .await
+            .map_err(|e| format!("查询同步状态失败: {}", e))?
+            .get("count");
+    let conflicts: i64 = sqlx::query("SELECT COUNT(*) AS count FROM sync_conflicts")
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(|e| format!("查询同步状态失败: {}", e))?
+        .get("count");
+
+    Ok(SyncStatus {
+        configured: target.is_some(),
+        target,
+        device_id,
+        last_sync_at,
+        pending_changes,
+        conflicts,
+    })
+}
+
+/**
+ * 配置同步目标
+ *
+ * 目标中已有同步数据时需输入相同的同步口令；更换目标后全部记录会重新上传
+ *
+ * @param app Tauri应用句柄
+ * @param target 同步目标
+ * @param passphrase 同步口令
+ * @param webdav_password WebDAV 密码
+ * @param session_id 会话ID
+ * @return 同步状态
+ */
+#[tauri::command]
+pub async fn configure_sync(
+    app: AppHandle,
+    target: SyncTargetConfig,
+    passphrase: String,
+    webdav_password: Option<String>,
+    session_id: String,
+) -> Result<SyncStatus, String> {
+    require_session(&session_id).await?;
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("同步口令至少需要{}个字符", MIN_PASSPHRASE_LEN));
+    }
+
+    let opened = SyncTarget::open(&target, webdav_password.clone())?;
+    opened.prepare().await?;
+    let key = Zeroizing::new(unlock_target(&opened, passphrase).await?);
+
+    set_secret(
+        app.clone(),
+        SYNC_KEY_SECRET,
+        &encode_base64(key.as_slice()),
+        EncryptionLevel::Master,
+        None,
+    )
+    .await?;
+    match webdav_password.filter(|p| !p.is_empty()) {
+        Some(password) => {
+            set_secret(
+                app.clone(),
+                WEBDAV_PASSWORD_SECRET,
+                &password,
+                EncryptionLevel::Master,
+                None,
+            )
+            .await?
+        }
+        None => {
+            delete_secret(WEBDAV_PASSWORD_SECRET).await?;
+        }
+    }
+
+    let previous = load_target_config().await?;
+    if previous.as_ref() != Some(&target) {
+        // 新目标中没有本机的变更集，需要重新上传全部记录
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        for sql in [
+            "DELETE FROM sync_peers",
+            "DELETE FROM sync_conflicts",
+            "UPDATE sync_records SET pending = 1",
+        ] {
+            sqlx::query(sql)
+                .execute(db.get_pool())
+                .await
+                .map_err(|e| format!("重置同步状态失败: {}", e))?;
+        }
+    }
+    let config =
+        serde_json::to_string(&target).map_err(|e| format!("序列化同步配置失败: {}", e))?;
+    set_setting(SYNC_CONFIG_SETTING, &config).await?;
+
+    log::info!("已配置密码库同步");
+    current_status().await
+}
+
+/**
+ * 停用同步
+ *
+ * 删除本机保存的目标配置和密钥，不影响同步目标中的数据
+ *
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn disable_sync(session_id: String) -> Result<(), String> {
+    require_session(&session_id).await?;
+    delete_secret(SYNC_KEY_SECRET).await?;
+    delete_secret(WEBDAV_PASSWORD_SECRET).await?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    crate::execute_with_params!(
+        db,
+        "DELETE FROM system_settings WHERE key = ?",
+        SYNC_CONFIG_SETTING
+    )
+}
+
+/**
+ * 获取同步状态
+ *
+ * @return 同步状态
+ */
+#[tauri::command]
+pub async fn get_sync_status() -> Result<SyncStatus, String> {
+    current_status().await
+}
@@ -0,0 +1,1122 @@
+/**
+ * 同步引擎
+ *
+ * 每次同步依次执行：
+ * 1. 扫描本地：记录内容摘要与上次同步时不同（包括删除）时，递增本设备的时钟计数
+ *    并标记为待上传
+ * 2. 拉取：按序读取其他设备新写入的变更集并用向量时钟合并。远端版本较新时覆盖
+ *    本地，本地较新时忽略；并发修改且内容不同时保留更新时间较晚的版本，时间相同、
+ *    没有更新时间或一方已删除时记录冲突、暂时保留本地版本
+ * 3. 推送：把待上传的记录写成本设备的下一个变更集
+ *
+ * 变更集以同步密钥加密，设备ID和序号作为附加认证数据
+ */
+use super::clock::{ClockOrdering, VectorClock};
+use super::config::{device_id, open_target, set_setting, SYNC_LAST_RUN_SETTING};
+use super::target::CHANGES_DIR;
+use crate::password::fields::{open_fields, seal_fields};
+use crate::password::store::{get_all_categories, get_all_entries, insert_entry, update_entry};
+use crate::password::types::{EntryFields, PasswordCategory, PasswordEntry};
+use crate::security::audit::{record_quietly, AuditEvent};
+use crate::security::crypto::{decode_base64, decrypt, encode_base64, encrypt};
+use crate::security::crypto::{decrypt_from_base64, encrypt_to_base64};
+use crate::security::session::require_session;
+use crate::security::vault::{self, with_vault_key};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+/// 变更集文件格式标识
+const CHANGE_SET_FORMAT: &str = "taiasst-sync-changes";
+
+/// 是否正在同步
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+/**
+ * 同步的记录类型
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordType {
+    /// 密码条目
+    #[serde(rename = "entry")]
+    Entry,
+    /// 密码分类
+    #[serde(rename = "category")]
+    Category,
+}
+
+impl RecordType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordType::Entry => "entry",
+            RecordType::Category => "category",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "entry" => Ok(RecordType::Entry),
+            "category" => Ok(RecordType::Category),
+            _ => Err(format!("未知的同步记录类型: {}", value)),
+        }
+    }
+}
+
+/**
+ * 同步的记录内容
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncRecord {
+    /// 密码条目，包含解密后的专有字段
+    Entry {
+        entry: Box<PasswordEntry>,
+        fields: Option<EntryFields>,
+    },
+    /// 密码分类
+    Category { category: PasswordCategory },
+}
+
+impl SyncRecord {
+    fn key(&self) -> (RecordType, String) {
+        match self {
+            SyncRecord::Entry { entry, .. } => (RecordType::Entry, entry.id.clone()),
+            SyncRecord::Category { category } => (RecordType::Category, category.id.clone()),
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            SyncRecord::Entry { entry, .. } => &entry.title,
+            SyncRecord::Category { category } => &category.name,
+        }
+    }
+
+    fn updated_at(&self) -> Option<i64> {
+        match self {
+            SyncRecord::Entry { entry, .. } => Some(entry.updated_at),
+            SyncRecord::Category { .. } => None,
+        }
+    }
+
+    /// 内容摘要，用于判断记录是否有变化
+    fn content_hash(&self) -> Result<String, String> {
+        let data = serde_json::to_vec(self).map_err(|e| format!("序列化同步记录失败: {}", e))?;
+        Ok(hex::encode(Sha256::digest(&data)))
+    }
+}
+
+/**
+ * 一条记录的变更
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SyncChange {
+    record_type: RecordType,
+    record_id: String,
+    clock: VectorClock,
+    /// 记录内容，为空表示已删除
+    data: Option<SyncRecord>,
+}
+
+/**
+ * 变更集文件
+ */
+#[derive(Debug, Serialize, Deserialize)]
+struct ChangeSetFile {
+    format: String,
+    device_id: String,
+    seq: u64,
+    /// 创建时间（毫秒）
+    created_at: i64,
+    /// 加密的变更列表（Base64）
+    payload: String,
+}
+
+/**
+ * 本地记录的同步状态
+ */
+#[derive(Debug, Default)]
+struct RecordState {
+    clock: VectorClock,
+    /// 上次同步时的内容摘要，为空表示已删除
+    content_hash: Option<String>,
+    pending: bool,
+}
+
+/**
+ * 同步结果
+ */
+#[derive(Debug, Serialize, Default)]
+pub struct SyncReport {
+    /// 读取的变更集数量
+    pub change_sets_pulled: usize,
+    /// 应用到本地的远端变更数
+    pub changes_applied: usize,
+    /// 新发现的冲突数
+    pub conflicts: usize,
+    /// 上传的记录数
+    pub changes_pushed: usize,
+}
+
+/**
+ * 同步冲突概要
+ */
+#[derive(Debug, Serialize)]
+pub struct SyncConflictInfo {
+    /// 冲突ID
+    pub id: String,
+    /// 记录类型
+    pub record_type: RecordType,
+    /// 记录ID
+    pub record_id: String,
+    /// 本地标题，本地已删除时为空
+    pub local_title: Option<String>,
+    /// 远端标题，远端已删除时为空
+    pub remote_title: Option<String>,
+    /// 本地更新时间（毫秒）
+    pub local_updated_at: Option<i64>,
+    /// 远端更新时间（毫秒）
+    pub remote_updated_at: Option<i64>,
+    /// 远端设备ID
+    pub remote_device: String,
+    /// 发现冲突的时间（毫秒）
+    pub created_at: i64,
+}
+
+/**
+ * 冲突双方的完整内容
+ */
+#[derive(Debug, Serialize)]
+pub struct SyncConflictDetail {
+    /// 本地版本，已删除时为空
+    pub local: Option<SyncRecord>,
+    /// 远端版本，已删除时为空
+    pub remote: Option<SyncRecord>,
+}
+
+/**
+ * 冲突处理方式
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ConflictResolution {
+    /// 保留本地版本
+    #[serde(rename = "local")]
+    Local,
+    /// 采用远端版本
+    #[serde(rename = "remote")]
+    Remote,
+}
+
+/// 同步期间持有，结束时清除同步标记
+struct SyncGuard;
+
+impl SyncGuard {
+    fn acquire() -> Result<Self, String> {
+        if SYNCING.swap(true, Ordering::SeqCst) {
+            return Err("正在同步，请稍后再试".to_string());
+        }
+        Ok(SyncGuard)
+    }
+}
+
+impl Drop for SyncGuard {
+    fn drop(&mut self) {
+        SYNCING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 变更集文件名
+fn change_set_name(device_id: &str, seq: u64) -> String {
+    format!("{}-{:010}.json", device_id, seq)
+}
+
+/// 从变更集文件名解析设备ID和序号
+fn parse_change_set_name(name: &str) -> Option<(String, u64)> {
+    let (device, seq) = name.strip_suffix(".json")?.rsplit_once('-')?;
+    if device.is_empty() || !device.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((device.to_string(), seq.parse().ok()?))
+}
+
+/// 变更集的附加认证数据
+fn change_set_aad(device_id: &str, seq: u64) -> Vec<u8> {
+    format!("taiasst-sync:{}:{}", device_id, seq).into_bytes()
+}
+
+/// 冲突中远端版本的附加认证数据
+fn conflict_aad(conflict_id: &str) -> Vec<u8> {
+    format!("sync_conflict:{}", conflict_id).into_bytes()
+}
+
+/// 冲突ID
+fn conflict_id(record_type: RecordType, record_id: &str) -> String {
+    format!("{}:{}", record_type.as_str(), record_id)
+}
+
+/**
+ * 读取本地全部可同步的记录
+ *
+ * 条目的最后使用时间只在本机有意义，不参与同步
+ *
+ * @param pool 数据库连接池
+ * @return 记录键到记录内容的映射
+ */
+async fn load_local_records(
+    pool: &SqlitePool,
+) -> Result<HashMap<(RecordType, String), SyncRecord>, String> {
+    let rows = sqlx::query(
+        "SELECT id, encrypted_fields FROM password_entries WHERE encrypted_fields IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("查询条目字段失败: {}", e))?;
+    let mut fields = HashMap::new();
+    for row in &rows {
+        let id: String = row.get("id");
+        let value = open_fields(&id, &row.get::<String, _>("encrypted_fields"))?;
+        fields.insert(id, value);
+    }
+
+    let mut records = HashMap::new();
+    for mut entry in get_all_entries(pool).await? {
+        entry.last_used = None;
+        let record = SyncRecord::Entry {
+            fields: fields.remove(&entry.id),
+            entry: Box::new(entry),
+        };
+        records.insert(record.key(), record);
+    }
+    for category in get_all_categories(pool).await? {
+        let record = SyncRecord::Category { category };
+        records.insert(record.key(), record);
+    }
+    Ok(records)
+}
+
+/**
+ * 读取全部记录的同步状态
+ */
+async fn load_states(
+    conn: &mut SqliteConnection,
+) -> Result<HashMap<(RecordType, String), RecordState>, String> {
+    let rows = sqlx::query(
+        "SELECT record_type, record_id, clock, content_hash, pending FROM sync_records",
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(|e| format!("查询同步状态失败: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok((
+                (
+                    RecordType::parse(&row.get::<String, _>("record_type"))?,
+                    row.get("record_id"),
+                ),
+                RecordState {
+                    clock: VectorClock::from_json(&row.get::<String, _>("clock")),
+                    content_hash: row.get("content_hash"),
+                    pending: row.get::<i64, _>("pending") != 0,
+                },
+            ))
+        })
+        .collect()
+}
+
+/**
+ * 读取单条记录的同步状态
+ */
+async fn load_state(
+    conn: &mut SqliteConnection,
+    record_type: RecordType,
+    record_id: &str,
+) -> Result<RecordState, String> {
+    let row = sqlx::query(
+        "SELECT clock, content_hash, pending FROM sync_records
+         WHERE record_type = ? AND record_id = ?",
+    )
+    .bind(record_type.as_str())
+    .bind(record_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| format!("查询同步状态失败: {}", e))?;
+
+    Ok(row
+        .map(|row| RecordState {
+            clock: VectorClock::from_json(&row.get::<String, _>("clock")),
+            content_hash: row.get("content_hash"),
+            pending: row.get::<i64, _>("pending") != 0,
+        })
+        .unwrap_or_default())
+}
+
+/**
+ * 保存记录的同步状态
+ */
+async fn save_state(
+    conn: &mut SqliteConnection,
+    record_type: RecordType,
+    record_id: &str,
+    state: &RecordState,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO sync_records (record_type, record_id, clock, content_hash, pending, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(record_type, record_id) DO UPDATE SET clock = excluded.clock,
+                                                           content_hash = excluded.content_hash,
+                                                           pending = excluded.pending,
+                                                           updated_at = excluded.updated_at",
+    )
+    .bind(record_type.as_str())
+    .bind(record_id)
+    .bind(state.clock.to_json())
+    .bind(&state.content_hash)
+    .bind(state.pending as i64)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(conn)
+    .await
+    .map_err(|e| format!("保存同步状态失败: {}", e))?;
+    Ok(())
+}
+
+/**
+ * 扫描本地修改
+ *
+ * @param device_id 本设备ID
+ * @return 有变化的记录数
+ */
+async fn scan_local(device_id: &str) -> Result<usize, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let records = load_local_records(db.get_pool()).await?;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    let mut states = load_states(&mut tx).await?;
+
+    let mut changed = 0;
+    for (key, record) in &records {
+        let hash = record.content_hash()?;
+        let mut state = states.remove(key).unwrap_or_default();
+        if state.content_hash.as_deref() == Some(hash.as_str()) {
+            continue;
+        }
+        state.clock.increment(device_id);
+        state.content_hash = Some(hash);
+        state.pending = true;
+        save_state(&mut tx, key.0, &key.1, &state).await?;
+        changed += 1;
+    }
+
+    // 剩余的状态对应本地已不存在的记录，未标记删除的需要生成删除记录
+    for ((record_type, record_id), mut state) in states {
+        if state.content_hash.is_none() {
+            continue;
+        }
+        state.clock.increment(device_id);
+        state.content_hash = None;
+        state.pending = true;
+        save_state(&mut tx, record_type, &record_id, &state).await?;
+        changed += 1;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+    Ok(changed)
+}
+
+/**
+ * 将远端记录写入本地
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param change 远端变更
+ * @return 操作结果
+ */
+async fn apply_record(conn: &mut SqliteConnection, change: &SyncChange) -> Result<(), String> {
+    let Some(record) = &change.data else {
+        let sql = match change.record_type {
+            RecordType::Entry => "DELETE FROM password_entries WHERE id = ?",
+            RecordType::Category => "DELETE FROM password_categories WHERE id = ?",
+        };
+        sqlx::query(sql)
+            .bind(&change.record_id)
+            .execute(conn)
+            .await
+            .map_err(|e| format!("删除同步记录失败: {}", e))?;
+        return Ok(());
+    };
+    if record.key() != (change.record_type, change.record_id.clone()) {
+        return Err("变更集中的记录与记录ID不一致".to_string());
+    }
+
+    match record {
+        SyncRecord::Entry { entry, fields } => {
+            let mut entry = (**entry).clone();
+            let existing = sqlx::query("SELECT last_used FROM password_entries WHERE id = ?")
+                .bind(&entry.id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| format!("查询密码条目失败: {}", e))?;
+            match existing {
+                Some(row) => {
+                    entry.last_used = row.get("last_used");
+                    update_entry(conn, &entry).await?;
+                }
+                None => {
+                    entry.last_used = None;
+                    insert_entry(conn, &entry).await?;
+                }
+            }
+
+            let encrypted = match fields {
+                Some(fields) => seal_fields(&entry.id, fields)?,
+                None => None,
+            };
+            sqlx::query("UPDATE password_entries SET encrypted_fields = ? WHERE id = ?")
+                .bind(encrypted)
+                .bind(&entry.id)
+                .execute(conn)
+                .await
+                .map_err(|e| format!("保存条目字段失败: {}", e))?;
+        }
+        SyncRecord::Category { category } => {
+            sqlx::query(
+                "INSERT INTO password_categories (id, name, icon, color) VALUES (?, ?, ?, ?)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, icon = excluded.icon,
+                                               color = excluded.color",
+            )
+            .bind(&category.id)
+            .bind(&category.name)
+            .bind(&category.icon)
+            .bind(&category.color)
+            .execute(conn)
+            .await
+            .map_err(|e| format!("写入密码分类失败 ({}): {}", category.name, e))?;
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 记录并发修改冲突
+ *
+ * 同一记录已有冲突时合并远端时钟并以最新的远端版本为准
+ *
+ * @param conn 数据库连接（可为事务）
+ * @param change 远端变更
+ * @param remote_device 远端设备ID
+ * @return 操作结果
+ */
+async fn save_conflict(
+    conn: &mut SqliteConnection,
+    change: &SyncChange,
+    remote_device: &str,
+) -> Result<(), String> {
+    let id = conflict_id(change.record_type, &change.record_id);
+    let aad = conflict_aad(&id);
+    let existing = sqlx::query("SELECT remote_change FROM sync_conflicts WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| format!("查询同步冲突失败: {}", e))?;
+
+    let mut change = change.clone();
+    if let Some(row) = existing {
+        let previous = with_vault_key(|key| {
+            decrypt_from_base64(key, &row.get::<String, _>("remote_change"), &aad)
+        })?;
+        if let Ok(previous) = serde_json::from_slice::<SyncChange>(&previous) {
+            change.clock.merge(&previous.clock);
+        }
+    }
+    let plaintext =
+        serde_json::to_vec(&change).map_err(|e| format!("序列化同步冲突失败: {}", e))?;
+    let encrypted = with_vault_key(|key| encrypt_to_base64(key, &plaintext, &aad))?;
+
+    sqlx::query(
+        "INSERT INTO sync_conflicts
+         (id, record_type, record_id, remote_device, remote_title, remote_updated_at,
+          remote_change, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET remote_device = excluded.remote_device,
+                                       remote_title = excluded.remote_title,
+                                       remote_updated_at = excluded.remote_updated_at,
+                                       remote_change = excluded.remote_change",
+    )
+    .bind(&id)
+    .bind(change.record_type.as_str())
+    .bind(&change.record_id)
+    .bind(remote_device)
+    .bind(change.data.as_ref().map(|r| r.title().to_string()))
+    .bind(change.data.as_ref().and_then(SyncRecord::updated_at))
+    .bind(encrypted)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(conn)
+    .await
+    .map_err(|e| format!("保存同步冲突失败: {}", e))?;
+    Ok(())
+}
+
+/**
+ * 读取本地记录的更新时间
+ *
+ * 分类没有更新时间，已删除的记录返回空
+ */
+async fn local_updated_at(
+    conn: &mut SqliteConnection,
+    record_type: RecordType,
+    record_id: &str,
+) -> Result<Option<i64>, String> {
+    if record_type != RecordType::Entry {
+        return Ok(None);
+    }
+    let row = sqlx::query("SELECT updated_at FROM password_entries WHERE id = ?")
+        .bind(record_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询密码条目失败: {}", e))?;
+    Ok(row.map(|row| row.get("updated_at")))
+}
+
+/**
+ * 并发修改时按更新时间选择保留的版本
+ *
+ * @param local 本地更新时间
+ * @param remote 远端更新时间
+ * @return 较新的一方，时间相同或缺少时间时为空
+ */
+fn resolve_by_time(local: Option<i64>, remote: Option<i64>) -> Option<ConflictResolution> {
+    match local?.cmp(&remote?) {
+        std::cmp::Ordering::Less => Some(ConflictResolution::Remote),
+        std::cmp::Ordering::Greater => Some(ConflictResolution::Local),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/**
+ * 采用远端版本并清除该记录的冲突
+ */
+async fn accept_remote(
+    conn: &mut SqliteConnection,
+    change: &SyncChange,
+    state: &RecordState,
+) -> Result<(), String> {
+    apply_record(conn, change).await?;
+    save_state(conn, change.record_type, &change.record_id, state).await?;
+    sqlx::query("DELETE FROM sync_conflicts WHERE id = ?")
+        .bind(conflict_id(change.record_type, &change.record_id))
+        .execute(conn)
+        .await
+        .map_err(|e| format!("删除同步冲突失败: {}", e))?;
+    Ok(())
+}
+
+/**
+ * 合并一个远端变更集
+ *
+ * 变更和读取进度在同一事务中提交
+ *
+ * @param remote_device 远端设备ID
+ * @param seq 变更集序号
+ * @param changes 变更列表
+ * @param report 同步结果
+ * @return 操作结果
+ */
+async fn apply_change_set(
+    remote_device: &str,
+    seq: u64,
+    changes: &[SyncChange],
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    for change in changes {
+        let mut state = load_state(&mut tx, change.record_type, &change.record_id).await?;
+        let remote_hash = change
+            .data
+            .as_ref()
+            .map(SyncRecord::content_hash)
+            .transpose()?;
+
+        match change.clock.compare(&state.clock) {
+            ClockOrdering::Before | ClockOrdering::Equal => {}
+            ClockOrdering::After => {
+                let new_state = RecordState {
+                    clock: change.clock.clone(),
+                    content_hash: remote_hash,
+                    pending: false,
+                };
+                accept_remote(&mut tx, change, &new_state).await?;
+                report.changes_applied += 1;
+            }
+            ClockOrdering::Concurrent if remote_hash == state.content_hash => {
+                // 两端修改成了相同的内容，合并时钟即可
+                state.clock.merge(&change.clock);
+                save_state(&mut tx, change.record_type, &change.record_id, &state).await?;
+            }
+            ClockOrdering::Concurrent => {
+                let local_time =
+                    local_updated_at(&mut tx, change.record_type, &change.record_id).await?;
+                let remote_time = change.data.as_ref().and_then(SyncRecord::updated_at);
+                let resolution = resolve_by_time(local_time, remote_time);
+                if resolution.is_some() {
+                    // 合并后的时钟包含双方的修改，重新上传使其他设备得到相同结果
+                    state.clock.merge(&change.clock);
+                    state.pending = true;
+                }
+                match resolution {
+                    Some(ConflictResolution::Remote) => {
+                        state.content_hash = remote_hash;
+                        accept_remote(&mut tx, change, &state).await?;
+                        report.changes_applied += 1;
+                    }
+                    Some(ConflictResolution::Local) => {
+                        save_state(&mut tx, change.record_type, &change.record_id, &state).await?;
+                    }
+                    None => {
+                        save_conflict(&mut tx, change, remote_device).await?;
+                        report.conflicts += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    sqlx::query(
+        "INSERT INTO sync_peers (device_id, last_seq, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(device_id) DO UPDATE SET last_seq = excluded.last_seq,
+                                              updated_at = excluded.updated_at",
+    )
+    .bind(remote_device)
+    .bind(seq as i64)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("保存同步进度失败: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))?;
+    report.change_sets_pulled += 1;
+    Ok(())
+}
+
+/**
+ * 读取各设备的已读序号
+ */
+async fn load_peers() -> Result<HashMap<String, u64>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = sqlx::query("SELECT device_id, last_seq FROM sync_peers")
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(|e| format!("查询同步进度失败: {}", e))?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("device_id"), row.get::<i64, _>("last_seq") as u64))
+        .collect())
+}
+
+/**
+ * 解密变更集
+ *
+ * @param key 同步密钥
+ * @param device_id 文件名中的设备ID
+ * @param seq 文件名中的序号
+ * @param data 文件内容
+ * @return 变更列表
+ */
+fn open_change_set(
+    key: &[u8],
+    device_id: &str,
+    seq: u64,
+    data: &[u8],
+) -> Result<Vec<SyncChange>, String> {
+    let file: ChangeSetFile =
+        serde_json::from_slice(data).map_err(|e| format!("变更集格式无效: {}", e))?;
+    if file.format != CHANGE_SET_FORMAT || file.device_id != device_id || file.seq != seq {
+        return Err(format!("变更集无效: {}", change_set_name(device_id, seq)));
+    }
+    let plaintext = decrypt(
+        key,
+        &decode_base64(&file.payload)?,
+        &change_set_aad(device_id, seq),
+    )
+    .map_err(|_| format!("无法解密变更集: {}", change_set_name(device_id, seq)))?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("解析变更集失败: {}", e))
+}
+
+/**
+ * 收集待上传的变更
+ *
+ * @return 变更列表
+ */
+async fn collect_pending() -> Result<Vec<SyncChange>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut records = load_local_records(db.get_pool()).await?;
+    let mut conn = db
+        .get_pool()
+        .acquire()
+        .await
+        .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+    Ok(load_states(&mut conn)
+        .await?
+        .into_iter()
+        .filter(|(_, state)| state.pending)
+        .filter_map(|(key, state)| {
+            let data = match state.content_hash {
+                Some(_) => Some(records.remove(&key)?),
+                None => None,
+            };
+            Some(SyncChange {
+                record_type: key.0,
+                record_id: key.1,
+                clock: state.clock,
+                data,
+            })
+        })
+        .collect())
+}
+
+/**
+ * 标记变更已上传
+ *
+ * 只清除时钟未再变化的记录
+ */
+async fn mark_pushed(device_id: &str, seq: u64, changes: &[SyncChange]) -> Result<(), String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    for change in changes {
+        sqlx::query(
+            "UPDATE sync_records SET pending = 0
+             WHERE record_type = ? AND record_id = ? AND clock = ?",
+        )
+        .bind(change.record_type.as_str())
+        .bind(&change.record_id)
+        .bind(change.clock.to_json())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("更新同步状态失败: {}", e))?;
+    }
+    sqlx::query(
+        "INSERT INTO sync_peers (device_id, last_seq, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(device_id) DO UPDATE SET last_seq = excluded.last_seq,
+                                              updated_at = excluded.updated_at",
+    )
+    .bind(device_id)
+    .bind(seq as i64)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("保存同步进度失败: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))
+}
+
+/**
+ * 立即同步
+ *
+ * @param app Tauri应用句柄
+ * @param session_id 会话ID
+ * @return 同步结果
+ */
+#[tauri::command]
+pub async fn run_sync(app: AppHandle, session_id: String) -> Result<SyncReport, String> {
+    require_session(&session_id).await?;
+    if !vault::is_unlocked() {
+        return Err("密码库未解锁".to_string());
+    }
+    let _guard = SyncGuard::acquire()?;
+
+    let device_id = device_id().await?;
+    let (target, key) = open_target(&app).await?;
+    target.prepare().await?;
+
+    let mut change_sets: Vec<(String, u64, String)> = target
+        .list_changes()
+        .await?
+        .into_iter()
+        .filter_map(|name| parse_change_set_name(&name).map(|(device, seq)| (device, seq, name)))
+        .collect();
+    change_sets.sort();
+
+    scan_local(&device_id).await?;
+
+    let peers = load_peers().await?;
+    let mut report = SyncReport::default();
+    for (device, seq, name) in &change_sets {
+        if *device == device_id || *seq <= peers.get(device).copied().unwrap_or(0) {
+            continue;
+        }
+        let data = target
+            .read(&format!("{}/{}", CHANGES_DIR, name))
+            .await?
+            .ok_or_else(|| format!("变更集不存在: {}", name))?;
+        let changes = open_change_set(&key, device, *seq, &data)?;
+        apply_change_set(device, *seq, &changes, &mut report).await?;
+    }
+
+    let pending = collect_pending().await?;
+    if !pending.is_empty() {
+        // 本机数据可能从备份恢复过，以目标中已有的最大序号为准
+        let seq = change_sets
+            .iter()
+            .filter(|(device, _, _)| *device == device_id)
+            .map(|(_, seq, _)| *seq)
+            .chain(peers.get(&device_id).copied())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let plaintext =
+            serde_json::to_vec(&pending).map_err(|e| format!("序列化变更集失败: {}", e))?;
+        let file = ChangeSetFile {
+            format: CHANGE_SET_FORMAT.to_string(),
+            device_id: device_id.clone(),
+            seq,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            payload: encode_base64(&encrypt(
+                &key,
+                &plaintext,
+                &change_set_aad(&device_id, seq),
+            )?),
+        };
+        let data = serde_json::to_vec(&file).map_err(|e| format!("序列化变更集失败: {}", e))?;
+        target
+            .write(
+                &format!("{}/{}", CHANGES_DIR, change_set_name(&device_id, seq)),
+                &data,
+            )
+            .await?;
+        mark_pushed(&device_id, seq, &pending).await?;
+        report.changes_pushed = pending.len();
+    }
+
+    set_setting(
+        SYNC_LAST_RUN_SETTING,
+        &chrono::Utc::now().timestamp_millis().to_string(),
+    )
+    .await?;
+    record_quietly(
+        AuditEvent::Export,
+        "同步密码库",
+        Some(serde_json::json!({
+            "pulled": report.change_sets_pulled,
+            "applied": report.changes_applied,
+            "pushed": report.changes_pushed,
+            "conflicts": report.conflicts,
+        })),
+    )
+    .await;
+    log::info!(
+        "密码库同步完成: 读取 {} 个变更集, 应用 {} 条, 上传 {} 条, 冲突 {} 条",
+        report.change_sets_pulled,
+        report.changes_applied,
+        report.changes_pushed,
+        report.conflicts
+    );
+    Ok(report)
+}
+
+/**
+ * 列出未解决的同步冲突
+ *
+ * @return 冲突列表
+ */
+#[tauri::command]
+pub async fn list_sync_conflicts() -> Result<Vec<SyncConflictInfo>, String> {
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let rows = sqlx::query(
+        "SELECT c.id, c.record_type, c.record_id, c.remote_device, c.remote_title,
+                c.remote_updated_at, c.created_at,
+                COALESCE(e.title, g.name) AS local_title, e.updated_at AS local_updated_at
+         FROM sync_conflicts c
+         LEFT JOIN password_entries e ON c.record_type = 'entry' AND e.id = c.record_id
+         LEFT JOIN password_categories g ON c.record_type = 'category' AND g.id = c.record_id
+         ORDER BY c.created_at DESC",
+    )
+    .fetch_all(db.get_pool())
+    .await
+    .map_err(|e| format!("查询同步冲突失败: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(SyncConflictInfo {
+                id: row.get("id"),
+                record_type: RecordType::parse(&row.get::<String, _>("record_type"))?,
+                record_id: row.get("record_id"),
+                local_title: row.get("local_title"),
+                remote_title: row.get("remote_title"),
+                local_updated_at: row.get("local_updated_at"),
+                remote_updated_at: row.get("remote_updated_at"),
+                remote_device: row.get("remote_device"),
+                created_at: row.get("created_at"),
+            })
+        })
+        .collect()
+}
+
+/**
+ * 读取冲突中的远端变更
+ */
+async fn load_conflict_change(
+    conn: &mut SqliteConnection,
+    conflict_id: &str,
+) -> Result<SyncChange, String> {
+    let row = sqlx::query("SELECT remote_change FROM sync_conflicts WHERE id = ?")
+        .bind(conflict_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("查询同步冲突失败: {}", e))?
+        .ok_or_else(|| format!("同步冲突不存在: {}", conflict_id))?;
+    let aad = conflict_aad(conflict_id);
+    let plaintext = with_vault_key(|key| {
+        decrypt_from_base64(key, &row.get::<String, _>("remote_change"), &aad)
+    })?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("解析同步冲突失败: {}", e))
+}
+
+/**
+ * 获取冲突双方的完整内容
+ *
+ * @param conflict_id 冲突ID
+ * @param session_id 会话ID
+ * @return 本地与远端版本
+ */
+#[tauri::command]
+pub async fn get_sync_conflict(
+    conflict_id: String,
+    session_id: String,
+) -> Result<SyncConflictDetail, String> {
+    require_session(&session_id).await?;
+    let detail = {
+        let db = crate::get_db().await?;
+        let db = db.lock().await;
+        let mut conn = db
+            .get_pool()
+            .acquire()
+            .await
+            .map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let change = load_conflict_change(&mut conn, &conflict_id).await?;
+        let mut local = load_local_records(db.get_pool()).await?;
+        SyncConflictDetail {
+            local: local.remove(&(change.record_type, change.record_id.clone())),
+            remote: change.data,
+        }
+    };
+
+    record_quietly(
+        AuditEvent::VaultReveal,
+        "查看同步冲突",
+        Some(serde_json::json!({ "conflict_id": conflict_id })),
+    )
+    .await;
+    Ok(detail)
+}
+
+/**
+ * 解决同步冲突
+ *
+ * 合并双方时钟后递增本设备计数，使处理结果在下次同步时覆盖所有设备上的版本
+ *
+ * @param conflict_id 冲突ID
+ * @param resolution 处理方式
+ * @param session_id 会话ID
+ * @return 操作结果
+ */
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    conflict_id: String,
+    resolution: ConflictResolution,
+    session_id: String,
+) -> Result<(), String> {
+    require_session(&session_id).await?;
+    let device_id = device_id().await?;
+
+    let db = crate::get_db().await?;
+    let db = db.lock().await;
+    let mut tx = db
+        .get_pool()
+        .begin()
+        .await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+
+    let change = load_conflict_change(&mut tx, &conflict_id).await?;
+    let mut state = load_state(&mut tx, change.record_type, &change.record_id).await?;
+    state.clock.merge(&change.clock);
+    state.clock.increment(&device_id);
+    state.pending = true;
+    if resolution == ConflictResolution::Remote {
+        apply_record(&mut tx, &change).await?;
+        state.content_hash = change
+            .data
+            .as_ref()
+            .map(SyncRecord::content_hash)
+            .transpose()?;
+    }
+    save_state(&mut tx, change.record_type, &change.record_id, &state).await?;
+
+    sqlx::query("DELETE FROM sync_conflicts WHERE id = ?")
+        .bind(&conflict_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("删除同步冲突失败: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("提交事务失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_changes_keep_the_later_update() {
+        assert_eq!(
+            resolve_by_time(Some(1), Some(2)),
+            Some(ConflictResolution::Remote)
+        );
+        assert_eq!(
+            resolve_by_time(Some(3), Some(2)),
+            Some(ConflictResolution::Local)
+        );
+        assert_eq!(resolve_by_time(Some(2), Some(2)), None);
+        // 删除或分类没有更新时间
+        assert_eq!(resolve_by_time(None, Some(2)), None);
+        assert_eq!(resolve_by_time(Some(2), None), None);
+    }
+
+    #[test]
+    fn change_set_names_round_trip() {
+        let name = change_set_name("abc123", 42);
+        assert_eq!(name, "abc123-0000000042.json");
+        assert_eq!(
+            parse_change_set_name(&name),
+            Some(("abc123".to_string(), 42))
+        );
+        assert_eq!(parse_change_set_name("../x-1.json"), None);
+        assert_eq!(parse_change_set_name("abc-x.json"), None);
+        assert_eq!(parse_change_set_name("abc-1.txt"), None);
+    }
+}
@@ -0,0 +1,17 @@
+/**
+ * 同步模块
+ *
+ * 将密码条目和分类以加密变更集的形式同步到文件夹或 WebDAV：
+ * - clock: 向量时钟
+ * - target: 文件夹与 WebDAV 同步目标
+ * - config: 同步口令、目标配置与同步状态
+ * - engine: 本地扫描、变更合并、冲突处理
+ */
+mod clock;
+mod config;
+mod engine;
+mod target;
+
+// 导出公开API
+pub use config::*;
+pub use engine::*;
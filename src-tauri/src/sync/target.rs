@@ -0,0 +1,271 @@
+/**
+ * 同步目标
+ *
+ * 同步数据保存在目标下的 taiasst-sync 目录中：
+ * - meta.json: 同步口令的密钥派生参数与校验数据
+ * - changes/<设备ID>-<序号>.json: 各设备写入的加密变更集
+ *
+ * 目标可以是本地文件夹（可由网盘客户端同步）或 WebDAV 服务
+ */
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri_plugin_http::reqwest::{self, Method, StatusCode};
+use url::Url;
+
+/// 同步数据目录
+const SYNC_DIR: &str = "taiasst-sync";
+/// 元数据文件
+pub const META_FILE: &str = "meta.json";
+/// 变更集目录
+pub const CHANGES_DIR: &str = "changes";
+
+/**
+ * 同步目标配置，不包含 WebDAV 密码
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncTargetConfig {
+    /// 本地文件夹
+    Folder { path: String },
+    /// WebDAV 服务
+    Webdav {
+        url: String,
+        username: Option<String>,
+    },
+}
+
+/**
+ * WebDAV 连接
+ */
+pub struct WebDav {
+    client: reqwest::Client,
+    /// 同步数据目录的地址，以 / 结尾
+    base: Url,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+/**
+ * 已打开的同步目标
+ */
+pub enum SyncTarget {
+    Folder(PathBuf),
+    WebDav(WebDav),
+}
+
+impl WebDav {
+    fn request(&self, method: Method, path: &str) -> Result<reqwest::RequestBuilder, String> {
+        let url = self
+            .base
+            .join(path)
+            .map_err(|e| format!("WebDAV 地址无效: {}", e))?;
+        let request = self.client.request(method, url);
+        Ok(match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_deref()),
+            None => request,
+        })
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        request
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV 请求失败: {}", e))
+    }
+
+    /// 创建目录，目录已存在时忽略
+    async fn mkcol(&self, path: &str) -> Result<(), String> {
+        let method = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+        let response = self.send(self.request(method, path)?).await?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err("WebDAV 认证失败，请检查用户名和密码".to_string())
+            }
+            status => Err(format!("创建 WebDAV 目录失败: {}", status)),
+        }
+    }
+
+    async fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self.send(self.request(Method::GET, path)?).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response
+                .bytes()
+                .await
+                .map(|bytes| Some(bytes.to_vec()))
+                .map_err(|e| format!("读取 WebDAV 文件失败: {}", e)),
+            status => Err(format!("读取 WebDAV 文件失败 ({}): {}", path, status)),
+        }
+    }
+
+    async fn write(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        let request = self.request(Method::PUT, path)?.body(data.to_vec());
+        let status = self.send(request).await?.status();
+        if !status.is_success() {
+            return Err(format!("写入 WebDAV 文件失败 ({}): {}", path, status));
+        }
+        Ok(())
+    }
+
+    async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let method = Method::from_bytes(b"PROPFIND").map_err(|e| e.to_string())?;
+        let request = self
+            .request(method, dir)?
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(
+                r#"<?xml version="1.0" encoding="utf-8"?><propfind xmlns="DAV:"><prop><resourcetype/></prop></propfind>"#,
+            );
+        let response = self.send(request).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            return Err(format!("列出 WebDAV 目录失败: {}", response.status()));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("读取 WebDAV 响应失败: {}", e))?;
+        let doc = roxmltree::Document::parse(&body)
+            .map_err(|e| format!("解析 WebDAV 响应失败: {}", e))?;
+        Ok(doc
+            .descendants()
+            .filter(|node| node.tag_name().name() == "href")
+            .filter_map(|node| node.text())
+            .filter_map(|href| href.trim_end_matches('/').rsplit('/').next())
+            .filter(|name| name.ends_with(".json"))
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+impl SyncTarget {
+    /**
+     * 打开同步目标
+     *
+     * @param config 目标配置
+     * @param password WebDAV 密码
+     * @return 同步目标
+     */
+    pub fn open(config: &SyncTargetConfig, password: Option<String>) -> Result<Self, String> {
+        match config {
+            SyncTargetConfig::Folder { path } => {
+                let path = PathBuf::from(path);
+                if !path.is_absolute() {
+                    return Err("同步文件夹必须为绝对路径".to_string());
+                }
+                if !path.is_dir() {
+                    return Err(format!("同步文件夹不存在: {}", path.display()));
+                }
+                Ok(SyncTarget::Folder(path.join(SYNC_DIR)))
+            }
+            SyncTargetConfig::Webdav { url, username } => {
+                let mut base =
+                    Url::parse(url.trim()).map_err(|e| format!("WebDAV 地址无效: {}", e))?;
+                if !matches!(base.scheme(), "http" | "https") {
+                    return Err("WebDAV 地址必须以 http:// 或 https:// 开头".to_string());
+                }
+                if !base.path().ends_with('/') {
+                    base.set_path(&format!("{}/", base.path()));
+                }
+                let base = base
+                    .join(&format!("{}/", SYNC_DIR))
+                    .map_err(|e| format!("WebDAV 地址无效: {}", e))?;
+                Ok(SyncTarget::WebDav(WebDav {
+                    client: reqwest::Client::new(),
+                    base,
+                    username: username.clone().filter(|u| !u.is_empty()),
+                    password,
+                }))
+            }
+        }
+    }
+
+    /**
+     * 创建同步数据目录
+     */
+    pub async fn prepare(&self) -> Result<(), String> {
+        match self {
+            SyncTarget::Folder(root) => tokio::fs::create_dir_all(root.join(CHANGES_DIR))
+                .await
+                .map_err(|e| format!("创建同步目录失败: {}", e)),
+            SyncTarget::WebDav(dav) => {
+                dav.mkcol("").await?;
+                dav.mkcol(&format!("{}/", CHANGES_DIR)).await
+            }
+        }
+    }
+
+    /**
+     * 读取文件
+     *
+     * @param path 相对同步数据目录的路径
+     * @return 文件内容，不存在时为空
+     */
+    pub async fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        match self {
+            SyncTarget::Folder(root) => match tokio::fs::read(root.join(path)).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("读取同步文件失败 ({}): {}", path, e)),
+            },
+            SyncTarget::WebDav(dav) => dav.read(path).await,
+        }
+    }
+
+    /**
+     * 写入文件
+     *
+     * 文件夹目标先写入临时文件再重命名，避免其他设备读到不完整的文件
+     *
+     * @param path 相对同步数据目录的路径
+     * @param data 文件内容
+     */
+    pub async fn write(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        match self {
+            SyncTarget::Folder(root) => {
+                let target = root.join(path);
+                let temp = root.join(format!("{}.part", path));
+                tokio::fs::write(&temp, data)
+                    .await
+                    .map_err(|e| format!("写入同步文件失败 ({}): {}", path, e))?;
+                tokio::fs::rename(&temp, &target)
+                    .await
+                    .map_err(|e| format!("写入同步文件失败 ({}): {}", path, e))
+            }
+            SyncTarget::WebDav(dav) => dav.write(path, data).await,
+        }
+    }
+
+    /**
+     * 列出变更集文件名
+     */
+    pub async fn list_changes(&self) -> Result<Vec<String>, String> {
+        match self {
+            SyncTarget::Folder(root) => {
+                let mut dir = match tokio::fs::read_dir(root.join(CHANGES_DIR)).await {
+                    Ok(dir) => dir,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                    Err(e) => return Err(format!("读取同步目录失败: {}", e)),
+                };
+                let mut names = Vec::new();
+                while let Some(item) = dir
+                    .next_entry()
+                    .await
+                    .map_err(|e| format!("读取同步目录失败: {}", e))?
+                {
+                    let name = item.file_name().to_string_lossy().to_string();
+                    if name.ends_with(".json") {
+                        names.push(name);
+                    }
+                }
+                Ok(names)
+            }
+            SyncTarget::WebDav(dav) => dav.list(&format!("{}/", CHANGES_DIR)).await,
+        }
+    }
+}
@@ -20,6 +20,7 @@ import {
 } from "react-icons/ri";
 import { hashPassword, checkPasswordStrength } from "@/utils/crypto";
 import { setPasswordHash } from "@/services/db/auth";
+import { invoke } from "@tauri-apps/api/core";
 
 interface PasswordSetupProps {
   onComplete: () => void;
@@ -93,6 +94,7 @@ export const PasswordSetup: React.FC<PasswordSetupProps> = ({
     try {
       const { hash, salt } = await hashPassword(password);
       await setPasswordHash(hash, salt);
+      await invoke("rewrap_vault_key", { masterPassword: password });
       onComplete();
     } catch (err) {
       setError("设置密码失败，请重试");
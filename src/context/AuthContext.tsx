@@ -4,6 +4,7 @@ import React, {
   useReducer,
   useEffect,
   useCallback,
+  useRef,
 } from "react";
 import {
   getAuthSettings,
@@ -13,7 +14,15 @@ import {
   AuthSettings,
 } from "@/services/db/auth";
 import { verifyPassword } from "@/utils/crypto";
+import {
+  openSession,
+  setCurrentSessionId,
+  touchSession,
+  unlockVault,
+} from "@/services/tauri/session";
 import { error, info } from "@tauri-apps/plugin-log";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 interface AuthState {
   isAuthenticated: boolean;
@@ -24,6 +33,7 @@ interface AuthState {
   settings: AuthSettings | null;
   lockoutUntil?: number;
   failedAttempts: number;
+  sessionId?: string;
 }
 
 interface AuthContextType {
@@ -44,7 +54,8 @@ type AuthAction =
   | { type: "SET_SETTINGS"; payload: AuthSettings }
   | { type: "UPDATE_ACTIVITY" }
   | { type: "SET_FAILED_ATTEMPTS"; payload: number }
-  | { type: "SET_LOCKOUT"; payload: number | undefined };
+  | { type: "SET_LOCKOUT"; payload: number | undefined }
+  | { type: "SET_SESSION"; payload: string | undefined };
 
 const initialState: AuthState = {
   isAuthenticated: false,
@@ -83,6 +94,8 @@ function authReducer(state: AuthState, action: AuthAction): AuthState {
       return { ...state, failedAttempts: action.payload };
     case "SET_LOCKOUT":
       return { ...state, lockoutUntil: action.payload };
+    case "SET_SESSION":
+      return { ...state, sessionId: action.payload };
     default:
       return state;
   }
@@ -94,6 +107,14 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
   children,
 }) => {
   const [state, dispatch] = useReducer(authReducer, initialState);
+  const lastSessionTouch = useRef(0);
+
+  // 更新会话，同步给需要携带会话ID的服务
+  const setSession = useCallback((sessionId: string | undefined) => {
+    setCurrentSessionId(sessionId);
+    lastSessionTouch.current = Date.now();
+    dispatch({ type: "SET_SESSION", payload: sessionId });
+  }, []);
 
   // 刷新设置
   const refreshSettings = useCallback(async () => {
@@ -110,11 +131,13 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
       // 如果没有设置密码，则自动认证
       if (!settings.hasPassword) {
         dispatch({ type: "SET_AUTHENTICATED", payload: true });
+        const session = await openSession();
+        setSession(session.session_id);
       }
     } catch (err) {
       error(`刷新认证设置失败: ${String(err)}`);
     }
-  }, []);
+  }, [setSession]);
 
   // 登录
   const login = useCallback(async (password: string): Promise<boolean> => {
@@ -148,6 +171,8 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
 
       if (isValid) {
         await updateLastAuthTime();
+        const session = await unlockVault(password);
+        setSession(session.session_id);
         dispatch({ type: "SET_AUTHENTICATED", payload: true });
         dispatch({ type: "SET_LOCKED", payload: false });
         dispatch({ type: "SET_FAILED_ATTEMPTS", payload: 0 });
@@ -158,11 +183,16 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
         const failedCount = await incrementFailedAttempts();
         dispatch({ type: "SET_FAILED_ATTEMPTS", payload: failedCount });
 
+        let lockoutTime: number | undefined;
         if (failedCount >= 5) {
           dispatch({ type: "SET_LOCKED", payload: true });
-          const lockoutTime = Date.now() + 15 * 60 * 1000;
+          lockoutTime = Date.now() + 15 * 60 * 1000;
           dispatch({ type: "SET_LOCKOUT", payload: lockoutTime });
         }
+        await invoke("record_auth_failure", {
+          failedAttempts: failedCount,
+          lockedUntil: lockoutTime,
+        }).catch((err) => error(`写入审计日志失败: ${String(err)}`));
 
         error(`登录失败，失败次数: ${failedCount}`);
         return false;
@@ -173,27 +203,45 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
     } finally {
       dispatch({ type: "SET_LOADING", payload: false });
     }
-  }, []);
+  }, [setSession]);
 
   // 登出
   const logout = useCallback(() => {
+    invoke("lock_vault").catch((err) =>
+      error(`锁定密码库失败: ${String(err)}`),
+    );
+    setSession(undefined);
     dispatch({ type: "SET_AUTHENTICATED", payload: false });
     info("用户已登出");
-  }, []);
+  }, [setSession]);
 
   // 锁定
   const lock = useCallback(() => {
+    invoke("lock_vault").catch((err) =>
+      error(`锁定密码库失败: ${String(err)}`),
+    );
+    setSession(undefined);
     dispatch({ type: "SET_LOCKED", payload: true });
     dispatch({ type: "SET_AUTHENTICATED", payload: false });
     info("应用已锁定");
-  }, []);
+  }, [setSession]);
 
   // 更新活动时间
   const updateActivity = useCallback(() => {
     if (state.isAuthenticated && !state.isLocked) {
       dispatch({ type: "UPDATE_ACTIVITY" });
+
+      // 每分钟最多刷新一次后端会话，会话失效时锁定应用
+      const sessionId = state.sessionId;
+      if (sessionId && Date.now() - lastSessionTouch.current >= 60000) {
+        lastSessionTouch.current = Date.now();
+        touchSession(sessionId).catch((err) => {
+          error(`会话已失效: ${String(err)}`);
+          lock();
+        });
+      }
     }
-  }, [state.isAuthenticated, state.isLocked]);
+  }, [state.isAuthenticated, state.isLocked, state.sessionId, lock]);
 
   // 检查自动锁定
   const checkAutoLock = useCallback(() => {
@@ -246,30 +294,18 @@ export const AuthProvider: React.FC<{ children: React.ReactNode }> = ({
     return () => clearInterval(interval);
   }, [checkAutoLock, state.settings?.autoLockEnabled]);
 
-  // 系统睡眠检测（简单实现）
+  // 后端在系统睡眠、锁屏或切换用户时已清除密钥，这里同步界面状态
   useEffect(() => {
-    if (!state.settings?.lockOnSystemSleep) {
-      return;
-    }
-
-    let lastTime = Date.now();
-
-    const checkSystemSleep = () => {
-      const currentTime = Date.now();
-      const timeDiff = currentTime - lastTime;
-
-      // 如果时间差超过5分钟，可能是系统睡眠了
-      if (timeDiff > 5 * 60 * 1000 && state.isAuthenticated) {
-        lock();
-        info("检测到系统睡眠，应用已自动锁定");
-      }
-
-      lastTime = currentTime;
+    const unlisten = listen<{ reason: string }>("locked", (event) => {
+      setSession(undefined);
+      dispatch({ type: "SET_LOCKED", payload: true });
+      dispatch({ type: "SET_AUTHENTICATED", payload: false });
+      info(`应用已因系统事件锁定: ${event.payload.reason}`);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
     };
-
-    const interval = setInterval(checkSystemSleep, 10000); // 每10秒检查一次
-    return () => clearInterval(interval);
-  }, [state.settings?.lockOnSystemSleep, state.isAuthenticated, lock]);
+  }, [setSession]);
 
   const value: AuthContextType = {
     state,
@@ -171,7 +171,7 @@ function AiApp() {
     try {
       const configs = await aiManager.getAllProviderConfigs();
       const hasEnabled = configs.some(
-        (config) => config.enabled && config.apiKey,
+        (config) => config.enabled && config.hasApiKey,
       );
       setHasConfiguredProviders(hasEnabled);
       return hasEnabled;
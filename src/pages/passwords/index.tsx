@@ -43,10 +43,8 @@ import {
   getAllPasswordEntries,
   getAllPasswordCategories,
   deletePasswordEntry,
-  updatePasswordLastUsed,
 } from "@/services/db/password";
 import {
-  copyToClipboard,
   formatTimestamp,
   extractDomain,
   getFaviconUrl,
@@ -54,6 +52,7 @@ import {
   getPasswordStrengthColor,
   getPasswordStrengthText,
 } from "@/utils/password";
+import { copyEntryField, ClipboardField } from "@/services/tauri/password";
 import PasswordEntryModal from "./components/PasswordEntryModal";
 import PasswordGeneratorModal from "./components/PasswordGeneratorModal";
 
@@ -65,6 +64,7 @@ interface PasswordCardProps {
   onToggleVisibility: () => void;
   onCopyPassword: () => void;
   onCopyUsername: () => void;
+  onCopyTotp: () => void;
   onEdit: () => void;
   onDelete: () => void;
 }
@@ -76,6 +76,7 @@ const PasswordCard: React.FC<PasswordCardProps> = ({
   onToggleVisibility,
   onCopyPassword,
   onCopyUsername,
+  onCopyTotp,
   onEdit,
   onDelete,
 }) => {
@@ -249,6 +250,25 @@ const PasswordCard: React.FC<PasswordCardProps> = ({
             </div>
           </div>
 
+          {/* 两步验证码 */}
+          {password.hasTotp && (
+            <div className="flex items-center justify-between">
+              <span className="text-sm text-foreground/60">验证码</span>
+              <div className="flex items-center gap-2">
+                <span className="text-sm font-mono">••• •••</span>
+                <Button
+                  isIconOnly
+                  size="sm"
+                  variant="light"
+                  className="text-default-400 hover:text-primary"
+                  onPress={onCopyTotp}
+                >
+                  <RiFileCopyLine className="w-3 h-3" />
+                </Button>
+              </div>
+            </div>
+          )}
+
           {/* 最后使用时间 */}
           {password.lastUsed && (
             <div className="text-xs text-foreground/50 pt-2 border-t border-divider/20">
@@ -345,23 +365,21 @@ function PasswordManager() {
   });
 
   // 处理密码操作
-  const handleCopyPassword = async (password: PasswordEntry) => {
-    const success = await copyToClipboard(password.password);
-    if (success) {
-      toast.success("密码已复制到剪贴板");
-      await updatePasswordLastUsed(password.id);
+  const handleCopyField = async (
+    password: PasswordEntry,
+    field: ClipboardField,
+    label: string,
+  ) => {
+    try {
+      const result = await copyEntryField(password.id, field);
+      toast.success(
+        result.clear_after_secs > 0
+          ? `${label}已复制，${result.clear_after_secs}秒后自动清除`
+          : `${label}已复制到剪贴板`,
+      );
       await loadData(); // 刷新数据以更新最后使用时间
-    } else {
-      toast.error("复制失败");
-    }
-  };
-
-  const handleCopyUsername = async (username: string) => {
-    const success = await copyToClipboard(username);
-    if (success) {
-      toast.success("用户名已复制到剪贴板");
-    } else {
-      toast.error("复制失败");
+    } catch (err) {
+      toast.error(`复制失败: ${String(err)}`);
     }
   };
 
@@ -552,8 +570,13 @@ function PasswordManager() {
               onToggleVisibility={() =>
                 handleTogglePasswordVisibility(password.id)
               }
-              onCopyPassword={() => handleCopyPassword(password)}
-              onCopyUsername={() => handleCopyUsername(password.username)}
+              onCopyPassword={() =>
+                handleCopyField(password, "password", "密码")
+              }
+              onCopyUsername={() =>
+                handleCopyField(password, "username", "用户名")
+              }
+              onCopyTotp={() => handleCopyField(password, "totp", "验证码")}
               onEdit={() => handleEditPassword(password)}
               onDelete={() => handleDeletePassword(password)}
             />
@@ -22,8 +22,6 @@ import {
   RiRobot2Line,
   RiSettings4Line,
   RiTestTubeLine,
-  RiEyeLine,
-  RiEyeOffLine,
   RiRefreshLine,
 } from "react-icons/ri";
 import { toast } from "sonner";
@@ -121,9 +119,6 @@ const AISettingsTab: React.FC = () => {
   const [defaultModels, setDefaultModels] = useState<
     Record<AIProvider, string>
   >({} as any);
-  const [showApiKeys, setShowApiKeys] = useState<Record<AIProvider, boolean>>(
-    {} as any,
-  );
 
   const {
     isOpen: isConfigModalOpen,
@@ -195,9 +190,11 @@ const AISettingsTab: React.FC = () => {
     if (!editingConfig || !tempConfig) return;
 
     try {
+      // 未输入新密钥时保留已保存的密钥
       const updatedConfig: AIProviderConfig = {
         ...editingConfig,
         ...tempConfig,
+        apiKey: tempConfig.apiKey || undefined,
       };
 
       await aiManager.saveProviderConfig(updatedConfig);
@@ -243,21 +240,12 @@ const AISettingsTab: React.FC = () => {
   const openConfigModal = (config: AIProviderConfig) => {
     setEditingConfig(config);
     setTempConfig({
-      apiKey: config.apiKey,
       baseUrl: config.baseUrl,
       enabled: config.enabled,
     });
     onConfigModalOpen();
   };
 
-  // 切换API密钥显示
-  const toggleApiKeyVisibility = (provider: AIProvider) => {
-    setShowApiKeys((prev) => ({
-      ...prev,
-      [provider]: !prev[provider],
-    }));
-  };
-
   if (loading) {
     return (
       <div className="flex items-center justify-center py-20">
@@ -372,30 +360,14 @@ const AISettingsTab: React.FC = () => {
                       <p className="text-sm text-foreground/60 mt-1">
                         {config.description}
                       </p>
-                      {config.apiKey && (
+                      {config.hasApiKey && (
                         <div className="flex items-center gap-2 mt-2">
                           <span className="text-xs text-foreground/40">
                             API密钥:
                           </span>
                           <code className="text-xs bg-default-100 px-2 py-1 rounded">
-                            {showApiKeys[config.provider]
-                              ? config.apiKey
-                              : "••••••••••••••••"}
+                            {config.apiKeyPreview}
                           </code>
-                          <Button
-                            isIconOnly
-                            size="sm"
-                            variant="light"
-                            onPress={() =>
-                              toggleApiKeyVisibility(config.provider)
-                            }
-                          >
-                            {showApiKeys[config.provider] ? (
-                              <RiEyeOffLine className="w-3 h-3" />
-                            ) : (
-                              <RiEyeLine className="w-3 h-3" />
-                            )}
-                          </Button>
                         </div>
                       )}
                     </div>
@@ -408,7 +380,10 @@ const AISettingsTab: React.FC = () => {
                       startContent={<RiTestTubeLine />}
                       onPress={() => handleTestConnection(config.provider)}
                       isLoading={testingProvider === config.provider}
-                      isDisabled={!config.enabled || !config.apiKey}
+                      isDisabled={
+                        !config.enabled ||
+                        (!config.hasApiKey && config.provider !== "ollama")
+                      }
                     >
                       测试连接
                     </Button>
@@ -474,7 +449,11 @@ const AISettingsTab: React.FC = () => {
                 onValueChange={(value) =>
                   setTempConfig((prev) => ({ ...prev, apiKey: value }))
                 }
-                placeholder="输入API密钥"
+                placeholder={
+                  editingConfig?.hasApiKey
+                    ? `已保存 ${editingConfig.apiKeyPreview}，留空则保持不变`
+                    : "输入API密钥"
+                }
               />
             </div>
 
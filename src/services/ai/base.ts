@@ -11,6 +11,8 @@ import {
   AIResponseCallbacks,
   AIServiceError,
 } from "./types";
+import { gatewayChat, testProviderConnection } from "@/services/tauri/ai";
+import { v4 as uuidv4 } from "uuid";
 
 /**
  * AI服务基类
@@ -32,7 +34,7 @@ export abstract class BaseAIService implements IAIService {
   async validateConfig(config: AIProviderConfig): Promise<boolean> {
     try {
       // 基础验证
-      if (!config.apiKey && this.requiresApiKey()) {
+      if (!config.hasApiKey && this.requiresApiKey()) {
         return false;
       }
 
@@ -56,29 +58,18 @@ export abstract class BaseAIService implements IAIService {
   }
 
   /**
-   * 测试连接
+   * 测试连接，由Rust端使用已保存的密钥发起请求
    */
   async testConnection(): Promise<boolean> {
     try {
-      const testParams: AIRequestParams = {
-        messages: [{ role: "user", content: "Hello" }],
-        model: this.config.models[0]?.id || "",
-        maxTokens: 10,
-        temperature: 0.1,
-        stream: false,
-      };
-
-      let success = false;
-      await this.chat(testParams, {
-        onComplete: () => {
-          success = true;
-        },
-        onError: () => {
-          success = false;
-        },
-      });
-
-      return success;
+      const result = await testProviderConnection(
+        this.provider,
+        this.config.models[0]?.id,
+      );
+      if (!result.success) {
+        console.error(`${this.provider} 连接测试失败:`, result.message);
+      }
+      return result.success;
     } catch (error) {
       console.error(`${this.provider} 连接测试失败:`, error);
       return false;
@@ -101,138 +92,55 @@ export abstract class BaseAIService implements IAIService {
   }
 
   /**
-   * 构建请求头
-   */
-  protected buildHeaders(): Record<string, string> {
-    const headers: Record<string, string> = {
-      "Content-Type": "application/json",
-    };
-
-    if (this.config.apiKey) {
-      switch (this.provider) {
-        case "openai":
-        case "deepseek":
-          headers["Authorization"] = `Bearer ${this.config.apiKey}`;
-          break;
-        case "claude":
-          headers["x-api-key"] = this.config.apiKey;
-          headers["anthropic-version"] = "2023-06-01";
-          break;
-        case "qianwen":
-          headers["Authorization"] = `Bearer ${this.config.apiKey}`;
-          headers["X-DashScope-SSE"] = "enable";
-          break;
-      }
-    }
-
-    return headers;
-  }
-
-  /**
-   * 处理流式响应
-   */
-  protected async handleStreamResponse(
-    response: Response,
-    callbacks: AIResponseCallbacks,
-  ): Promise<void> {
-    if (!response.body) {
-      throw new AIServiceError("响应体为空", this.provider);
-    }
-
-    const reader = response.body.getReader();
-    const decoder = new TextDecoder();
-    let fullResponse = "";
-
-    callbacks.onStart?.();
-
-    try {
-      while (true) {
-        const { done, value } = await reader.read();
-        if (done) break;
-
-        const chunk = decoder.decode(value, { stream: true });
-        const lines = chunk.split("\n").filter((line) => line.trim());
-
-        for (const line of lines) {
-          const content = this.parseStreamChunk(line);
-          if (content) {
-            fullResponse += content;
-            callbacks.onChunk?.(content);
-          }
-        }
-      }
-
-      callbacks.onComplete?.(fullResponse);
-    } catch (error) {
-      callbacks.onError?.(error as Error);
-    } finally {
-      reader.releaseLock();
-    }
-  }
-
-  /**
-   * 解析流式响应块 - 抽象方法，由子类实现
-   */
-  protected abstract parseStreamChunk(chunk: string): string | null;
-
-  /**
-   * 构建请求体 - 抽象方法，由子类实现
-   */
-  protected abstract buildRequestBody(params: AIRequestParams): any;
-
-  /**
-   * 获取API端点
-   */
-  protected abstract getApiEndpoint(): string;
-
-  /**
-   * 处理错误响应
-   */
-  protected handleErrorResponse(response: Response): AIServiceError {
-    return new AIServiceError(
-      `API请求失败: ${response.status} ${response.statusText}`,
-      this.provider,
-      response.status.toString(),
-    );
-  }
-
-  /**
-   * 发送HTTP请求
+   * 通过Rust AI网关发送请求，API密钥不经过前端
    */
   protected async sendRequest(
     params: AIRequestParams,
     callbacks: AIResponseCallbacks,
   ): Promise<void> {
-    const url = `${this.config.baseUrl}${this.getApiEndpoint()}`;
-    const headers = this.buildHeaders();
-    const body = this.buildRequestBody(params);
-
     try {
-      const response = await fetch(url, {
-        method: "POST",
-        headers,
-        body: JSON.stringify(body),
-      });
-
-      if (!response.ok) {
-        throw this.handleErrorResponse(response);
-      }
-
-      if (params.stream) {
-        await this.handleStreamResponse(response, callbacks);
-      } else {
-        const data = await response.json();
-        const content = this.parseNonStreamResponse(data);
-        callbacks.onStart?.();
-        callbacks.onComplete?.(content);
-      }
+      const result = await gatewayChat(
+        uuidv4(),
+        {
+          provider: this.provider,
+          model: params.model,
+          messages: params.messages,
+          temperature: params.temperature,
+          max_tokens: params.maxTokens,
+          stream: params.stream,
+          session_id: params.sessionId,
+          context_strategy: params.contextStrategy,
+          use_tools: params.useTools,
+          knowledge_base_ids: params.knowledgeBaseIds,
+        },
+        (event) => {
+          if (event.event === "context") {
+            callbacks.onTokens?.(event.tokens);
+          } else if (event.event === "started") {
+            callbacks.onStart?.();
+          } else if (event.event === "citations") {
+            callbacks.onCitations?.(event.citations);
+          } else if (event.event === "delta" && params.stream) {
+            callbacks.onChunk?.(event.text);
+          } else if (event.event === "tool_call") {
+            callbacks.onToolCall?.(event.call);
+          } else if (event.event === "tool_result") {
+            callbacks.onToolResult?.({
+              id: event.id,
+              name: event.name,
+              status: event.status,
+              output: event.output,
+            });
+          }
+        },
+      );
+      callbacks.onComplete?.(result.content);
     } catch (error) {
-      callbacks.onError?.(error as Error);
+      callbacks.onError?.(
+        error instanceof Error
+          ? error
+          : new AIServiceError(String(error), this.provider),
+      );
     }
   }
-
-  /**
-   * 解析非流式响应 - 抽象方法，由子类实现
-   */
-  protected abstract parseNonStreamResponse(data: any): string;
 }
@@ -6,12 +6,48 @@ import { execute, select } from "@/services/db/index";
 import { error, info } from "@tauri-apps/plugin-log";
 import {
   AIConfigStore,
+  AIMessage,
   AIProvider,
   AIProviderConfig,
   ChatSession,
   DEFAULT_AI_PROVIDERS,
 } from "./types";
 import { v4 as uuidv4 } from "uuid";
+import {
+  appendChatMessage,
+  GatewayProviderSettings,
+  getChatMessages,
+  listChatSessions,
+  listProviderSettings,
+  saveProviderSettings,
+} from "@/services/tauri/ai";
+
+/**
+ * 合并默认配置与Rust端返回的提供商配置
+ */
+function toProviderConfig(
+  defaultConfig: (typeof DEFAULT_AI_PROVIDERS)[number],
+  settings: GatewayProviderSettings,
+): AIProviderConfig {
+  return {
+    ...defaultConfig,
+    hasApiKey: settings.has_api_key,
+    apiKeyPreview: settings.api_key_preview || undefined,
+    baseUrl: settings.base_url || defaultConfig.baseUrl,
+    enabled: settings.enabled,
+  };
+}
+
+/**
+ * 读取会话的全部消息
+ */
+async function loadSessionMessages(sessionId: string): Promise<AIMessage[]> {
+  const page = await getChatMessages(sessionId);
+  return page.messages.map((message) => ({
+    role: message.role,
+    content: message.content,
+  }));
+}
 
 /**
  * AI配置存储实现
@@ -29,30 +65,23 @@ export class AIConfigStoreImpl implements AIConfigStore {
   }
 
   // 提供商配置管理
+  // API密钥由Rust端加密保存，这里只能读取到掩码预览
   async getProviderConfig(
     provider: AIProvider,
   ): Promise<AIProviderConfig | null> {
     try {
-      const rows = await select<any>(
-        "SELECT * FROM ai_provider_configs WHERE provider = ?",
-        [provider],
+      const settings = (await listProviderSettings()).find(
+        (item) => item.provider === provider,
       );
-
-      if (rows.length === 0) {
+      if (!settings?.updated_at) {
         return null;
       }
 
-      const row = rows[0];
       const defaultConfig = DEFAULT_AI_PROVIDERS.find(
         (p) => p.provider === provider,
       );
 
-      return {
-        ...defaultConfig!,
-        apiKey: row.api_key,
-        baseUrl: row.base_url || defaultConfig?.baseUrl,
-        enabled: Boolean(row.enabled),
-      };
+      return toProviderConfig(defaultConfig!, settings);
     } catch (err) {
       error(`获取AI提供商配置失败: ${String(err)}`);
       return null;
@@ -61,17 +90,11 @@ export class AIConfigStoreImpl implements AIConfigStore {
 
   async setProviderConfig(config: AIProviderConfig): Promise<void> {
     try {
-      await execute(
-        `INSERT OR REPLACE INTO ai_provider_configs 
-         (provider, api_key, base_url, enabled, updated_at)
-         VALUES (?, ?, ?, ?, ?)`,
-        [
-          config.provider,
-          config.apiKey || null,
-          config.baseUrl || null,
-          config.enabled ? 1 : 0,
-          Date.now(),
-        ],
+      await saveProviderSettings(
+        config.provider,
+        config.apiKey,
+        config.baseUrl,
+        config.enabled,
       );
 
       info(`AI提供商配置已保存: ${config.provider}`);
@@ -83,18 +106,19 @@ export class AIConfigStoreImpl implements AIConfigStore {
 
   async getAllProviderConfigs(): Promise<AIProviderConfig[]> {
     try {
-      const rows = await select<any>("SELECT * FROM ai_provider_configs");
+      const settings = await listProviderSettings();
       const configs: AIProviderConfig[] = [];
 
       for (const defaultConfig of DEFAULT_AI_PROVIDERS) {
-        const row = rows.find((r) => r.provider === defaultConfig.provider);
+        const item = settings.find(
+          (s) => s.provider === defaultConfig.provider,
+        );
 
-        configs.push({
-          ...defaultConfig,
-          apiKey: row?.api_key || undefined,
-          baseUrl: row?.base_url || defaultConfig.baseUrl,
-          enabled: row ? Boolean(row.enabled) : false,
-        });
+        configs.push(
+          item
+            ? toProviderConfig(defaultConfig, item)
+            : { ...defaultConfig, enabled: false },
+        );
       }
 
       return configs;
@@ -161,7 +185,7 @@ export class AIConfigStoreImpl implements AIConfigStore {
   async getChatSession(id: string): Promise<ChatSession | null> {
     try {
       const rows = await select<any>(
-        "SELECT * FROM ai_chat_sessions WHERE id = ?",
+        "SELECT id, title, provider, model, created_at, updated_at FROM ai_chat_sessions WHERE id = ?",
         [id],
       );
 
@@ -173,7 +197,7 @@ export class AIConfigStoreImpl implements AIConfigStore {
       return {
         id: row.id,
         title: row.title,
-        messages: JSON.parse(row.messages || "[]"),
+        messages: await loadSessionMessages(row.id),
         provider: row.provider,
         model: row.model,
         createdAt: row.created_at,
@@ -187,14 +211,19 @@ export class AIConfigStoreImpl implements AIConfigStore {
 
   async saveChatSession(session: ChatSession): Promise<void> {
     try {
+      // 消息单独保存在 ai_chat_messages 中，这里只更新会话信息；
+      // 不能使用 INSERT OR REPLACE，否则会级联删除已保存的消息
       await execute(
-        `INSERT OR REPLACE INTO ai_chat_sessions 
+        `INSERT INTO ai_chat_sessions
          (id, title, messages, provider, model, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?)`,
+         VALUES (?, ?, '[]', ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET title = excluded.title,
+                                       provider = excluded.provider,
+                                       model = excluded.model,
+                                       updated_at = excluded.updated_at`,
         [
           session.id,
           session.title,
-          JSON.stringify(session.messages),
           session.provider,
           session.model,
           session.createdAt,
@@ -207,21 +236,38 @@ export class AIConfigStoreImpl implements AIConfigStore {
     }
   }
 
-  async getAllChatSessions(): Promise<ChatSession[]> {
+  async appendChatMessage(
+    sessionId: string,
+    message: AIMessage,
+  ): Promise<void> {
     try {
-      const rows = await select<any>(
-        "SELECT * FROM ai_chat_sessions ORDER BY updated_at DESC",
+      await appendChatMessage(
+        sessionId,
+        message.role,
+        message.content,
+        message.attachments,
       );
+    } catch (err) {
+      error(`保存聊天消息失败: ${String(err)}`);
+      throw err;
+    }
+  }
 
-      return rows.map((row) => ({
-        id: row.id,
-        title: row.title,
-        messages: JSON.parse(row.messages || "[]"),
-        provider: row.provider,
-        model: row.model,
-        createdAt: row.created_at,
-        updatedAt: row.updated_at,
-      }));
+  async getAllChatSessions(): Promise<ChatSession[]> {
+    try {
+      const sessions = await listChatSessions();
+
+      return await Promise.all(
+        sessions.map(async (session) => ({
+          id: session.id,
+          title: session.title,
+          messages: await loadSessionMessages(session.id),
+          provider: session.provider,
+          model: session.model,
+          createdAt: session.created_at,
+          updatedAt: session.updated_at,
+        })),
+      );
     } catch (err) {
       error(`获取所有聊天会话失败: ${String(err)}`);
       return [];
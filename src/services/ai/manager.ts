@@ -14,6 +14,7 @@ import {
 } from "./types";
 import { getAIServiceFactory } from "./factory";
 import { getAIConfigStore, createNewChatSession } from "./config";
+import { testProviderConnection } from "@/services/tauri/ai";
 
 /**
  * AI管理服务
@@ -59,7 +60,7 @@ export class AIManager {
       );
     }
 
-    if (!config.apiKey && targetProvider !== "ollama") {
+    if (!config.hasApiKey && targetProvider !== "ollama") {
       throw new AIServiceError(
         `AI提供商 ${targetProvider} 缺少API密钥，请前往系统设置 > AI助手 配置API密钥`,
         targetProvider,
@@ -135,6 +136,9 @@ export class AIManager {
       content: message,
     };
     session.messages.push(userMessage);
+    session.updatedAt = Date.now();
+    await this.configStore.saveChatSession(session);
+    await this.configStore.appendChatMessage(session.id, userMessage);
 
     // 准备AI请求
     const requestParams: AIRequestParams = {
@@ -143,13 +147,19 @@ export class AIManager {
       temperature: 0.7,
       maxTokens: 2000,
       stream: true,
+      sessionId: session.id,
+      contextStrategy: "summarize",
+      useTools: true,
     };
 
     let aiResponse = "";
 
     // 发送请求
     await service.chat(requestParams, {
+      onTokens: callbacks?.onTokens,
       onStart: callbacks?.onStart,
+      onToolCall: callbacks?.onToolCall,
+      onToolResult: callbacks?.onToolResult,
       onChunk: (chunk) => {
         aiResponse += chunk;
         callbacks?.onChunk?.(chunk);
@@ -168,7 +178,8 @@ export class AIManager {
           session.title = this.generateSessionTitle(message);
         }
 
-        // 保存会话
+        // 保存回复与会话信息
+        await this.configStore.appendChatMessage(session.id, aiMessage);
         await this.configStore.saveChatSession(session);
         callbacks?.onComplete?.(fullResponse || aiResponse);
       },
@@ -199,8 +210,16 @@ export class AIManager {
    */
   async testProviderConnection(provider: AIProvider): Promise<boolean> {
     try {
-      const service = await this.getAIService(provider);
-      return await service.testConnection();
+      // 优先使用默认模型测试，可同时验证模型是否可用
+      const model = await this.getDefaultModel(provider);
+      const result = await testProviderConnection(
+        provider,
+        model || undefined,
+      );
+      if (!result.success) {
+        console.error(`测试 ${provider} 连接失败:`, result.message);
+      }
+      return result.success;
     } catch (error) {
       console.error(`测试 ${provider} 连接失败:`, error);
       return false;
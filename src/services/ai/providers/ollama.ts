@@ -22,41 +22,6 @@ export class OllamaService extends BaseAIService {
     await this.sendRequest(params, callbacks);
   }
 
-  protected getApiEndpoint(): string {
-    return "/api/chat";
-  }
-
-  protected buildRequestBody(params: AIRequestParams): any {
-    return {
-      model: params.model,
-      messages: params.messages,
-      options: {
-        temperature: params.temperature || 0.7,
-        num_predict: params.maxTokens || 2000,
-      },
-      stream: params.stream || false,
-    };
-  }
-
-  protected parseStreamChunk(chunk: string): string | null {
-    try {
-      const parsed = JSON.parse(chunk);
-
-      if (parsed.message?.content) {
-        return parsed.message.content;
-      }
-
-      return null;
-    } catch (error) {
-      console.error("解析Ollama流式响应失败:", error);
-      return null;
-    }
-  }
-
-  protected parseNonStreamResponse(data: any): string {
-    return data.message?.content || "";
-  }
-
   protected requiresApiKey(): boolean {
     return false; // Ollama通常不需要API密钥
   }
@@ -93,15 +58,4 @@ export class OllamaService extends BaseAIService {
     const i = Math.floor(Math.log(bytes) / Math.log(1024));
     return Math.round((bytes / Math.pow(1024, i)) * 100) / 100 + " " + sizes[i];
   }
-
-  // 重写测试连接方法
-  async testConnection(): Promise<boolean> {
-    try {
-      const response = await fetch(`${this.config.baseUrl}/api/tags`);
-      return response.ok;
-    } catch (error) {
-      console.error("Ollama连接测试失败:", error);
-      return false;
-    }
-  }
 }
@@ -2,10 +2,20 @@
  * AI服务类型定义
  */
 
+import type {
+  ContextStrategy,
+  GatewayTokenBreakdown,
+  GatewayToolCall,
+  GatewayToolResult,
+  KnowledgeCitation,
+} from "@/services/tauri/ai";
+
 // AI消息类型
 export interface AIMessage {
   role: "system" | "user" | "assistant";
   content: string;
+  /** 附件ID，由 addChatAttachment 返回 */
+  attachments?: string[];
 }
 
 // AI提供商类型
@@ -31,7 +41,12 @@ export interface AIProviderConfig {
   provider: AIProvider;
   name: string;
   description: string;
+  // 新的API密钥，仅在保存时传入，读取配置时始终为空
   apiKey?: string;
+  // 是否已保存API密钥
+  hasApiKey?: boolean;
+  // 掩码后的密钥预览
+  apiKeyPreview?: string;
   baseUrl?: string;
   models: AIModel[];
   enabled: boolean;
@@ -46,12 +61,24 @@ export interface AIRequestParams {
   temperature?: number;
   maxTokens?: number;
   stream?: boolean;
+  // 所属会话，用于保存和复用历史摘要
+  sessionId?: string;
+  // 超出上下文长度时的处理方式
+  contextStrategy?: ContextStrategy;
+  // 是否允许模型调用已启用的工具
+  useTools?: boolean;
+  // 检索的知识库，回复中按编号引用检索到的资料
+  knowledgeBaseIds?: string[];
 }
 
 // AI响应回调
 export interface AIResponseCallbacks {
+  onTokens?: (tokens: GatewayTokenBreakdown) => void;
   onStart?: () => void;
+  onCitations?: (citations: KnowledgeCitation[]) => void;
   onChunk?: (chunk: string) => void;
+  onToolCall?: (call: GatewayToolCall) => void;
+  onToolResult?: (result: GatewayToolResult) => void;
   onComplete?: (fullResponse: string) => void;
   onError?: (error: Error) => void;
 }
@@ -107,6 +134,7 @@ export interface AIConfigStore {
   // 聊天会话
   getChatSession(id: string): Promise<ChatSession | null>;
   saveChatSession(session: ChatSession): Promise<void>;
+  appendChatMessage(sessionId: string, message: AIMessage): Promise<void>;
   getAllChatSessions(): Promise<ChatSession[]>;
   deleteChatSession(id: string): Promise<void>;
 }
@@ -114,7 +142,7 @@ export interface AIConfigStore {
 // 默认AI提供商配置
 export const DEFAULT_AI_PROVIDERS: Omit<
   AIProviderConfig,
-  "apiKey" | "enabled"
+  "apiKey" | "hasApiKey" | "apiKeyPreview" | "enabled"
 >[] = [
   {
     provider: "openai",
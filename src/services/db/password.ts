@@ -6,6 +6,7 @@ import {
   PasswordFilter,
 } from "@/types/password";
 import { v4 as uuidv4 } from "uuid";
+import { invoke } from "@tauri-apps/api/core";
 
 // 注意：密码管理表的创建和默认分类的初始化现在由Rust端的迁移系统处理
 // 参见：src-tauri/src/db/migrations.rs 中的 get_password_management_migrations()
@@ -19,7 +20,8 @@ export async function getAllPasswordEntries(
   try {
     let sql = `
       SELECT id, title, username, password, url, notes, category, tags, 
-             is_favorite, created_at, updated_at, last_used
+             is_favorite, entry_type, created_at, updated_at, last_used,
+             (entry_type = 'login' AND encrypted_fields IS NOT NULL) AS has_totp
       FROM password_entries
     `;
     const params: any[] = [];
@@ -60,9 +62,11 @@ export async function getAllPasswordEntries(
       password: row.password,
       url: row.url,
       notes: row.notes,
+      hasTotp: Boolean(row.has_totp),
       category: row.category,
       tags: JSON.parse(row.tags || "[]"),
       isFavorite: Boolean(row.is_favorite),
+      entryType: row.entry_type || "login",
       createdAt: row.created_at,
       updatedAt: row.updated_at,
       lastUsed: row.last_used,
@@ -82,7 +86,8 @@ export async function getPasswordEntry(
   try {
     const rows = await select<any>(
       `SELECT id, title, username, password, url, notes, category, tags, 
-              is_favorite, created_at, updated_at, last_used
+              is_favorite, entry_type, created_at, updated_at, last_used,
+              (entry_type = 'login' AND encrypted_fields IS NOT NULL) AS has_totp
        FROM password_entries WHERE id = ?`,
       [id],
     );
@@ -97,9 +102,11 @@ export async function getPasswordEntry(
       password: row.password,
       url: row.url,
       notes: row.notes,
+      hasTotp: Boolean(row.has_totp),
       category: row.category,
       tags: JSON.parse(row.tags || "[]"),
       isFavorite: Boolean(row.is_favorite),
+      entryType: row.entry_type || "login",
       createdAt: row.created_at,
       updatedAt: row.updated_at,
       lastUsed: row.last_used,
@@ -122,8 +129,8 @@ export async function createPasswordEntry(
 
     await execute(
       `INSERT INTO password_entries 
-       (id, title, username, password, url, notes, category, tags, is_favorite, created_at, updated_at, last_used)
-       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+       (id, title, username, password, url, notes, category, tags, is_favorite, entry_type, created_at, updated_at, last_used)
+       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
       [
         id,
         entry.title,
@@ -134,6 +141,7 @@ export async function createPasswordEntry(
         entry.category,
         JSON.stringify(entry.tags),
         entry.isFavorite ? 1 : 0,
+        entry.entryType || "login",
         now,
         now,
         entry.lastUsed || null,
@@ -156,35 +164,21 @@ export async function updatePasswordEntry(
   entry: Partial<PasswordEntry>,
 ): Promise<void> {
   try {
-    const now = Date.now();
-
-    await execute(
-      `UPDATE password_entries 
-       SET title = COALESCE(?, title),
-           username = COALESCE(?, username),
-           password = COALESCE(?, password),
-           url = COALESCE(?, url),
-           notes = COALESCE(?, notes),
-           category = COALESCE(?, category),
-           tags = COALESCE(?, tags),
-           is_favorite = COALESCE(?, is_favorite),
-           updated_at = ?,
-           last_used = COALESCE(?, last_used)
-       WHERE id = ?`,
-      [
-        entry.title,
-        entry.username,
-        entry.password,
-        entry.url,
-        entry.notes,
-        entry.category,
-        entry.tags ? JSON.stringify(entry.tags) : null,
-        entry.isFavorite !== undefined ? (entry.isFavorite ? 1 : 0) : null,
-        now,
-        entry.lastUsed,
-        id,
-      ],
-    );
+    // 由Rust端更新，以便在修改敏感字段前保存加密的历史版本
+    await invoke("update_password_entry", {
+      id,
+      update: {
+        title: entry.title,
+        username: entry.username,
+        password: entry.password,
+        url: entry.url,
+        notes: entry.notes,
+        category: entry.category,
+        tags: entry.tags,
+        is_favorite: entry.isFavorite,
+        last_used: entry.lastUsed,
+      },
+    });
 
     info(`密码条目更新成功: ${id}`);
   } catch (err) {
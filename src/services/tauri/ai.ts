@@ -0,0 +1,1268 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+import type { AIMessage, AIProvider } from "@/services/ai/types";
+import { getCurrentSessionId } from "@/services/tauri/session";
+
+/**
+ * Rust AI 网关的对话请求
+ * 对应 src-tauri/src/ai/types.rs -> ChatRequest
+ */
+export interface GatewayChatRequest {
+  provider: AIProvider;
+  model: string;
+  messages: AIMessage[];
+  temperature?: number;
+  max_tokens?: number;
+  /** 是否流式返回，默认是 */
+  stream?: boolean;
+  /** 所属会话ID，非系统消息须与会话消息按顺序一一对应 */
+  session_id?: string;
+  /** 超出上下文长度时的处理方式，默认裁剪 */
+  context_strategy?: ContextStrategy;
+  /** 是否允许模型调用已启用的工具，默认否 */
+  use_tools?: boolean;
+  /** 检索的知识库，检索结果作为资料附在系统消息之后 */
+  knowledge_base_ids?: string[];
+  /** 检索的片段数，默认 5 */
+  knowledge_top_k?: number;
+}
+
+/**
+ * 超出上下文长度时的处理方式
+ * 对应 src-tauri/src/ai/types.rs -> ContextStrategy
+ */
+export type ContextStrategy = "trim" | "summarize";
+
+/**
+ * 单次请求的 token 估算明细
+ * 对应 src-tauri/src/ai/types.rs -> TokenBreakdown
+ */
+export interface GatewayTokenBreakdown {
+  /** 模型上下文长度 */
+  context_window: number;
+  /** 为回复预留的 token 数 */
+  reserved_output: number;
+  system_tokens: number;
+  summary_tokens: number;
+  history_tokens: number;
+  /** 实际发送的输入 token 总数 */
+  prompt_tokens: number;
+  /** 被丢弃的消息数 */
+  dropped_messages: number;
+  /** 由摘要替代的消息数 */
+  summarized_messages: number;
+}
+
+/**
+ * token 用量
+ * 对应 src-tauri/src/ai/types.rs -> ChatUsage
+ */
+export interface GatewayChatUsage {
+  prompt_tokens: number;
+  completion_tokens: number;
+}
+
+/**
+ * 模型发起的工具调用
+ * 对应 src-tauri/src/ai/types.rs -> ToolCall
+ */
+export interface GatewayToolCall {
+  id: string;
+  name: string;
+  /** 调用参数，模型返回的参数不是有效 JSON 时为原始字符串 */
+  arguments: unknown;
+}
+
+/**
+ * 工具调用结果
+ * 对应 src-tauri/src/ai/types.rs -> ToolInvocationStatus
+ */
+export type ToolInvocationStatus = "success" | "error" | "denied";
+
+/**
+ * 工具调用结束事件的内容
+ */
+export interface GatewayToolResult {
+  id: string;
+  name: string;
+  status: ToolInvocationStatus;
+  /** 返回给模型的内容 */
+  output: string;
+}
+
+/**
+ * 检索到的知识库片段
+ * 对应 src-tauri/src/ai/types.rs -> KnowledgeCitation
+ */
+export interface KnowledgeCitation {
+  /** 引用编号，从 1 开始，与回复中的 [编号] 一致 */
+  index: number;
+  kb_id: string;
+  document_id: string;
+  title: string;
+  path: string;
+  /** 所在小节的标题 */
+  heading: string | null;
+  /** 片段在文档中的序号 */
+  chunk_seq: number;
+  /** 余弦相似度 */
+  score: number;
+  content: string;
+}
+
+/**
+ * 流式事件
+ * 对应 src-tauri/src/ai/types.rs -> ChatStreamEvent
+ */
+export type GatewayStreamEvent =
+  | { event: "context"; tokens: GatewayTokenBreakdown }
+  | { event: "started"; request_id: string }
+  | { event: "delta"; text: string }
+  | { event: "tool_call"; call: GatewayToolCall }
+  | ({ event: "tool_result" } & GatewayToolResult)
+  | { event: "citations"; citations: KnowledgeCitation[] }
+  | { event: "finished"; usage: GatewayChatUsage | null };
+
+/**
+ * 对话结果
+ * 对应 src-tauri/src/ai/types.rs -> ChatResult
+ */
+export interface GatewayChatResult {
+  request_id: string;
+  /** 完整回复，取消时为已生成的部分 */
+  content: string;
+  usage: GatewayChatUsage | null;
+  /** 发送前的 token 估算明细 */
+  tokens: GatewayTokenBreakdown | null;
+  /** 尚未执行的工具调用，仅在达到工具调用轮数上限时存在 */
+  tool_calls?: GatewayToolCall[];
+  /** 引用的知识库片段 */
+  citations?: KnowledgeCitation[];
+  cancelled: boolean;
+}
+
+/**
+ * 通过 Rust 网关发起对话
+ * @param requestId 请求ID，用于取消
+ * @param request 对话请求
+ * @param onEvent 流式事件回调
+ * @returns 对话结果
+ */
+export async function gatewayChat(
+  requestId: string,
+  request: GatewayChatRequest,
+  onEvent: (event: GatewayStreamEvent) => void,
+): Promise<GatewayChatResult> {
+  const channel = new Channel<GatewayStreamEvent>();
+  channel.onmessage = onEvent;
+  return invoke<GatewayChatResult>("ai_chat", {
+    requestId,
+    request,
+    onEvent: channel,
+  });
+}
+
+/**
+ * 取消进行中的对话
+ * @param requestId 请求ID
+ * @returns 请求是否存在
+ */
+export async function cancelGatewayChat(requestId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_ai_request", { requestId });
+}
+
+/**
+ * 估算请求的 token 数，不会生成新的摘要
+ * @param request 对话请求
+ * @returns token 估算明细
+ */
+export async function estimateChatTokens(
+  request: GatewayChatRequest,
+): Promise<GatewayTokenBreakdown> {
+  return invoke<GatewayTokenBreakdown>("estimate_chat_tokens", { request });
+}
+
+/**
+ * 设置模型的上下文长度
+ * @param provider 提供商
+ * @param model 模型ID
+ * @param contextWindow 上下文长度，不传时恢复按模型名推断
+ */
+export async function setModelContextWindow(
+  provider: AIProvider,
+  model: string,
+  contextWindow?: number,
+): Promise<void> {
+  return invoke<void>("set_ai_model_context_window", {
+    provider,
+    model,
+    contextWindow: contextWindow ?? null,
+  });
+}
+
+/**
+ * 查询模型是否支持图片输入
+ * @param provider 提供商
+ * @param model 模型ID
+ * @returns 是否支持
+ */
+export async function getModelImageSupport(
+  provider: AIProvider,
+  model: string,
+): Promise<boolean> {
+  return invoke<boolean>("get_ai_model_image_support", { provider, model });
+}
+
+/**
+ * 设置模型是否支持图片输入
+ * @param provider 提供商
+ * @param model 模型ID
+ * @param supported 是否支持，不传时恢复按模型名推断
+ */
+export async function setModelImageSupport(
+  provider: AIProvider,
+  model: string,
+  supported?: boolean,
+): Promise<void> {
+  return invoke<void>("set_ai_model_image_support", {
+    provider,
+    model,
+    supported: supported ?? null,
+  });
+}
+
+/**
+ * 提供商配置，API 密钥只返回掩码预览
+ * 对应 src-tauri/src/ai/config.rs -> ProviderSettings
+ */
+export interface GatewayProviderSettings {
+  provider: AIProvider;
+  /** 自定义接口地址，为空时使用默认地址 */
+  base_url: string | null;
+  enabled: boolean;
+  has_api_key: boolean;
+  api_key_preview: string | null;
+  /** 更新时间（毫秒） */
+  updated_at: number | null;
+}
+
+/**
+ * 连接测试结果
+ * 对应 src-tauri/src/ai/gateway.rs -> ProviderTestResult
+ */
+export interface ProviderTestResult {
+  success: boolean;
+  latency_ms: number;
+  message: string;
+}
+
+/**
+ * 获取全部提供商配置
+ */
+export async function listProviderSettings(): Promise<
+  GatewayProviderSettings[]
+> {
+  return invoke<GatewayProviderSettings[]>("list_ai_provider_configs");
+}
+
+/**
+ * 保存提供商配置
+ * @param provider 提供商
+ * @param apiKey 新的 API 密钥，不传时保留原密钥，空字符串表示清除
+ * @param baseUrl 接口地址
+ * @param enabled 是否启用
+ * @returns 保存后的配置
+ */
+export async function saveProviderSettings(
+  provider: AIProvider,
+  apiKey: string | undefined,
+  baseUrl: string | undefined,
+  enabled: boolean,
+): Promise<GatewayProviderSettings> {
+  return invoke<GatewayProviderSettings>("save_ai_provider_config", {
+    provider,
+    apiKey: apiKey ?? null,
+    baseUrl: baseUrl ?? null,
+    enabled,
+  });
+}
+
+/**
+ * 使用已保存的密钥测试提供商连接
+ * @param provider 提供商
+ * @param model 用于测试的模型，不传时请求模型列表接口
+ * @returns 测试结果
+ */
+export async function testProviderConnection(
+  provider: AIProvider,
+  model?: string,
+): Promise<ProviderTestResult> {
+  return invoke<ProviderTestResult>("test_ai_provider", {
+    provider,
+    model: model ?? null,
+  });
+}
+
+/**
+ * 已保存的会话
+ * 对应 src-tauri/src/ai/history.rs -> ChatSessionInfo
+ */
+export interface ChatSessionInfo {
+  id: string;
+  title: string;
+  provider: AIProvider;
+  model: string;
+  /** 分支来源消息ID */
+  branched_from: string | null;
+  message_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+/**
+ * 已保存的消息
+ * 对应 src-tauri/src/ai/history.rs -> StoredChatMessage
+ */
+export interface StoredChatMessage {
+  id: string;
+  session_id: string;
+  /** 会话内序号，从0开始 */
+  seq: number;
+  role: AIMessage["role"];
+  content: string;
+  /** 附件，没有附件时省略 */
+  attachments?: ChatAttachment[];
+  created_at: number;
+}
+
+/**
+ * 附件类型
+ */
+export type AttachmentKind = "image" | "document";
+
+/**
+ * 对话附件，图片统一重新编码为 PNG，文档保存提取的文本
+ * 对应 src-tauri/src/ai/attachments/store.rs -> ChatAttachment
+ */
+export interface ChatAttachment {
+  id: string;
+  /** 所属消息ID，尚未发送时为空 */
+  message_id: string | null;
+  kind: AttachmentKind;
+  /** 原始文件名 */
+  file_name: string;
+  mime_type: string;
+  /** 处理后的大小（字节） */
+  size: number;
+  /** 图片宽度 */
+  width: number | null;
+  /** 图片高度 */
+  height: number | null;
+  created_at: number;
+}
+
+/**
+ * 一页消息
+ * 对应 src-tauri/src/ai/history.rs -> ChatMessagePage
+ */
+export interface ChatMessagePage {
+  /** 按序号升序排列的消息 */
+  messages: StoredChatMessage[];
+  /** 是否还有更早的消息 */
+  has_more: boolean;
+}
+
+/**
+ * 消息检索结果
+ * 对应 src-tauri/src/ai/history.rs -> ChatSearchHit
+ */
+export interface ChatSearchHit {
+  message_id: string;
+  session_id: string;
+  session_title: string;
+  role: AIMessage["role"];
+  /** 包含关键词的摘录 */
+  snippet: string;
+  created_at: number;
+}
+
+/**
+ * 创建会话
+ * @param title 标题
+ * @param provider 提供商
+ * @param model 模型ID
+ * @returns 新会话
+ */
+export async function createChatSession(
+  title: string,
+  provider: AIProvider,
+  model: string,
+): Promise<ChatSessionInfo> {
+  return invoke<ChatSessionInfo>("create_chat_session", {
+    title,
+    provider,
+    model,
+  });
+}
+
+/**
+ * 获取全部会话，按更新时间倒序
+ */
+export async function listChatSessions(): Promise<ChatSessionInfo[]> {
+  return invoke<ChatSessionInfo[]>("list_chat_sessions");
+}
+
+/**
+ * 追加一条消息
+ * @param sessionId 会话ID
+ * @param role 角色
+ * @param content 内容
+ * @param attachmentIds 附件ID
+ * @returns 保存后的消息
+ */
+export async function appendChatMessage(
+  sessionId: string,
+  role: AIMessage["role"],
+  content: string,
+  attachmentIds?: string[],
+): Promise<StoredChatMessage> {
+  return invoke<StoredChatMessage>("append_chat_message", {
+    sessionId,
+    role,
+    content,
+    attachmentIds: attachmentIds ?? null,
+  });
+}
+
+/**
+ * 添加对话附件，支持 PNG、JPEG 图片与文本、PDF 文档
+ * @param filePath 文件路径
+ * @returns 附件，发送消息前可通过 deleteChatAttachment 移除
+ */
+export async function addChatAttachment(
+  filePath: string,
+): Promise<ChatAttachment> {
+  return invoke<ChatAttachment>("add_chat_attachment", { filePath });
+}
+
+/**
+ * 删除对话附件
+ * @param id 附件ID
+ * @returns 附件是否存在
+ */
+export async function deleteChatAttachment(id: string): Promise<boolean> {
+  return invoke<boolean>("delete_chat_attachment", { id });
+}
+
+/**
+ * 读取对话附件内容
+ * @param id 附件ID
+ * @returns 图片为 data URL，文档为提取的文本
+ */
+export async function getChatAttachmentContent(id: string): Promise<string> {
+  return invoke<string>("get_chat_attachment_content", { id });
+}
+
+/**
+ * 分页获取会话消息，从最新的消息向前翻页
+ * @param sessionId 会话ID
+ * @param beforeSeq 只返回序号小于该值的消息，不传时从最新的消息开始
+ * @param limit 每页数量，不传时返回全部
+ * @returns 一页消息
+ */
+export async function getChatMessages(
+  sessionId: string,
+  beforeSeq?: number,
+  limit?: number,
+): Promise<ChatMessagePage> {
+  return invoke<ChatMessagePage>("get_chat_messages", {
+    sessionId,
+    beforeSeq: beforeSeq ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/**
+ * 从指定消息处创建分支会话
+ * @param messageId 分支点消息ID，该消息及之前的消息会复制到新会话
+ * @param title 新会话标题，不传时沿用原标题
+ * @returns 新会话
+ */
+export async function branchChatSession(
+  messageId: string,
+  title?: string,
+): Promise<ChatSessionInfo> {
+  return invoke<ChatSessionInfo>("branch_chat_session", {
+    messageId,
+    title: title ?? null,
+  });
+}
+
+/**
+ * 全文检索历史消息
+ * @param query 关键词，多个关键词以空格分隔
+ * @param limit 最大返回数量
+ * @returns 检索结果
+ */
+export async function searchChatMessages(
+  query: string,
+  limit?: number,
+): Promise<ChatSearchHit[]> {
+  return invoke<ChatSearchHit[]>("search_chat_messages", {
+    query,
+    limit: limit ?? null,
+  });
+}
+
+/**
+ * 模型单价，每百万 token，统一使用记账货币
+ * 对应 src-tauri/src/ai/usage.rs -> ModelPrice
+ */
+export interface ModelPrice {
+  provider: AIProvider;
+  /** 模型ID，"*" 表示该提供商的全部模型 */
+  model: string;
+  prompt_price: number;
+  completion_price: number;
+  updated_at: number;
+}
+
+/**
+ * 用量统计的分组方式
+ */
+export type UsageGroupBy = "day" | "provider" | "model" | "session";
+
+/**
+ * 一组用量合计
+ * 对应 src-tauri/src/ai/usage.rs -> UsageTotal
+ */
+export interface UsageTotal {
+  /** 日期（YYYY-MM-DD）、提供商、模型ID或会话ID */
+  key: string;
+  /** 按模型分组时为提供商，按会话分组时为会话标题 */
+  label: string | null;
+  requests: number;
+  errors: number;
+  prompt_tokens: number;
+  completion_tokens: number;
+  cost: number;
+  avg_latency_ms: number;
+}
+
+/**
+ * 月度预算设置
+ * 对应 src-tauri/src/ai/usage.rs -> UsageBudget
+ */
+export interface UsageBudget {
+  monthly_limit: number;
+  /** 费用达到预算的该百分比时提醒 */
+  alert_percent: number;
+}
+
+/**
+ * 本月预算使用情况
+ * 对应 src-tauri/src/ai/usage.rs -> BudgetStatus
+ */
+export interface BudgetStatus {
+  budget: UsageBudget | null;
+  /** 月份（YYYY-MM） */
+  month: string;
+  month_cost: number;
+}
+
+/**
+ * 获取全部模型单价
+ */
+export async function listModelPrices(): Promise<ModelPrice[]> {
+  return invoke<ModelPrice[]>("list_ai_model_prices");
+}
+
+/**
+ * 设置模型单价
+ * @param provider 提供商
+ * @param model 模型ID，"*" 表示该提供商的全部模型
+ * @param promptPrice 输入单价（每百万 token）
+ * @param completionPrice 输出单价（每百万 token）
+ */
+export async function setModelPrice(
+  provider: AIProvider,
+  model: string,
+  promptPrice: number,
+  completionPrice: number,
+): Promise<void> {
+  return invoke<void>("set_ai_model_price", {
+    provider,
+    model,
+    promptPrice,
+    completionPrice,
+  });
+}
+
+/**
+ * 删除模型单价
+ * @param provider 提供商
+ * @param model 模型ID
+ */
+export async function deleteModelPrice(
+  provider: AIProvider,
+  model: string,
+): Promise<void> {
+  return invoke<void>("delete_ai_model_price", { provider, model });
+}
+
+/**
+ * 按分组统计用量
+ * @param groupBy 分组方式
+ * @param start 起始时间（毫秒，含）
+ * @param end 结束时间（毫秒，不含）
+ * @returns 用量合计
+ */
+export async function getUsageSummary(
+  groupBy: UsageGroupBy,
+  start?: number,
+  end?: number,
+): Promise<UsageTotal[]> {
+  return invoke<UsageTotal[]>("get_ai_usage_summary", {
+    groupBy,
+    start: start ?? null,
+    end: end ?? null,
+  });
+}
+
+/**
+ * 获取本月预算使用情况
+ */
+export async function getUsageBudget(): Promise<BudgetStatus> {
+  return invoke<BudgetStatus>("get_ai_usage_budget");
+}
+
+/**
+ * 设置月度预算
+ * @param monthlyLimit 每月预算，不传时关闭预算提醒
+ * @param alertPercent 提醒比例（百分比），默认 80
+ */
+export async function setUsageBudget(
+  monthlyLimit?: number,
+  alertPercent?: number,
+): Promise<void> {
+  return invoke<void>("set_ai_usage_budget", {
+    monthlyLimit: monthlyLimit ?? null,
+    alertPercent: alertPercent ?? null,
+  });
+}
+
+/**
+ * 工具的确认策略
+ * 对应 src-tauri/src/ai/tools.rs -> ToolPolicy
+ */
+export type ToolPolicy = "allow" | "confirm" | "disabled";
+
+/**
+ * 工具来源
+ * 对应 src-tauri/src/ai/tools.rs -> ToolSource
+ */
+export type ToolSource =
+  | { type: "builtin" }
+  | { type: "plugin"; plugin_id: string }
+  | { type: "mcp"; server_id: string; tool: string };
+
+/**
+ * 工具信息
+ * 对应 src-tauri/src/ai/tools.rs -> ToolInfo
+ */
+export interface ToolInfo {
+  name: string;
+  description: string;
+  /** 参数的 JSON Schema */
+  parameters: Record<string, unknown>;
+  source: ToolSource;
+  policy: ToolPolicy;
+  /** 设置确认策略时使用的键 */
+  policy_key: string;
+}
+
+/**
+ * 工具调用记录
+ * 对应 src-tauri/src/ai/tools.rs -> ToolInvocation
+ */
+export interface ToolInvocation {
+  id: string;
+  request_id: string | null;
+  session_id: string | null;
+  tool: string;
+  arguments: unknown;
+  /** 返回给模型的内容 */
+  output: string;
+  status: ToolInvocationStatus;
+  duration_ms: number;
+  /** 调用时间（毫秒） */
+  created_at: number;
+}
+
+/**
+ * 获取全部工具及其确认策略
+ */
+export async function listTools(): Promise<ToolInfo[]> {
+  return invoke<ToolInfo[]>("list_ai_tools");
+}
+
+/**
+ * 设置工具的确认策略，密码库查询不能设为直接执行
+ * @param key 工具信息中的 policy_key
+ * @param policy 确认策略
+ */
+export async function setToolPolicy(
+  key: string,
+  policy: ToolPolicy,
+): Promise<void> {
+  return invoke<void>("set_ai_tool_policy", {
+    sessionId: getCurrentSessionId(),
+    key,
+    policy,
+  });
+}
+
+/**
+ * 获取允许AI读取的目录
+ */
+export async function getToolAllowedDirs(): Promise<string[]> {
+  return invoke<string[]>("get_ai_tool_allowed_dirs");
+}
+
+/**
+ * 设置允许AI读取的目录
+ * @param dirs 目录列表
+ */
+export async function setToolAllowedDirs(dirs: string[]): Promise<void> {
+  return invoke<void>("set_ai_tool_allowed_dirs", {
+    sessionId: getCurrentSessionId(),
+    dirs,
+  });
+}
+
+/**
+ * 查询工具调用记录
+ * @param chatSessionId 只返回该对话会话的记录
+ * @param requestId 只返回该请求的记录
+ * @param limit 最大返回数量
+ * @returns 调用记录，按时间倒序
+ */
+export async function listToolInvocations(
+  chatSessionId?: string,
+  requestId?: string,
+  limit?: number,
+): Promise<ToolInvocation[]> {
+  return invoke<ToolInvocation[]>("list_ai_tool_invocations", {
+    sessionId: getCurrentSessionId(),
+    chatSessionId: chatSessionId ?? null,
+    requestId: requestId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/**
+ * MCP 传输方式
+ * 对应 src-tauri/src/ai/mcp/servers.rs -> McpTransportKind
+ */
+export type McpTransportKind = "stdio" | "http";
+
+/**
+ * MCP 服务器，不包含环境变量与请求头的值
+ * 对应 src-tauri/src/ai/mcp/servers.rs -> McpServer
+ */
+export interface McpServer {
+  id: string;
+  name: string;
+  transport: McpTransportKind;
+  /** 可执行文件，仅 stdio */
+  command: string | null;
+  args: string[];
+  /** 端点地址，仅 HTTP */
+  url: string | null;
+  /** 已设置的环境变量名 */
+  env_keys: string[];
+  /** 已设置的请求头名 */
+  header_keys: string[];
+  enabled: boolean;
+  connected: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+/**
+ * 保存 MCP 服务器的参数
+ * 对应 src-tauri/src/ai/mcp/servers.rs -> McpServerInput
+ */
+export interface McpServerInput {
+  /** 为空时新建 */
+  id?: string;
+  name: string;
+  transport: McpTransportKind;
+  command?: string;
+  args?: string[];
+  url?: string;
+  /** 环境变量，不传时保留原值 */
+  env?: Record<string, string>;
+  /** 请求头，不传时保留原值 */
+  headers?: Record<string, string>;
+  enabled: boolean;
+}
+
+/**
+ * MCP 服务器能力发现结果
+ * 对应 src-tauri/src/ai/mcp/servers.rs -> McpServerDiscovery
+ */
+export interface McpServerDiscovery {
+  info: {
+    name: string;
+    version: string;
+    protocol_version: string;
+    instructions: string | null;
+  };
+  tools: {
+    name: string;
+    description: string | null;
+    input_schema: Record<string, unknown>;
+  }[];
+  resources: {
+    uri: string;
+    name: string;
+    description: string | null;
+    mime_type: string | null;
+  }[];
+  prompts: {
+    name: string;
+    description: string | null;
+    arguments: {
+      name: string;
+      description: string | null;
+      required: boolean;
+    }[];
+  }[];
+}
+
+/**
+ * MCP 提示词展开后的消息
+ * 对应 src-tauri/src/ai/mcp/client.rs -> McpPromptMessage
+ */
+export interface McpPromptMessage {
+  role: "user" | "assistant";
+  content: string;
+}
+
+/**
+ * 获取全部 MCP 服务器
+ */
+export async function listMcpServers(): Promise<McpServer[]> {
+  return invoke<McpServer[]>("list_mcp_servers");
+}
+
+/**
+ * 保存 MCP 服务器
+ * @param server 服务器定义
+ * @returns 保存后的服务器
+ */
+export async function saveMcpServer(
+  server: McpServerInput,
+): Promise<McpServer> {
+  return invoke<McpServer>("save_mcp_server", {
+    sessionId: getCurrentSessionId(),
+    server,
+  });
+}
+
+/**
+ * 删除 MCP 服务器及其工具的确认策略
+ * @param id 服务器ID
+ */
+export async function deleteMcpServer(id: string): Promise<void> {
+  return invoke<void>("delete_mcp_server", {
+    sessionId: getCurrentSessionId(),
+    id,
+  });
+}
+
+/**
+ * 连接 MCP 服务器并发现其工具、资源与提示词，已连接时重新连接
+ * @param id 服务器ID
+ * @returns 发现结果
+ */
+export async function connectMcpServer(
+  id: string,
+): Promise<McpServerDiscovery> {
+  return invoke<McpServerDiscovery>("connect_mcp_server", {
+    sessionId: getCurrentSessionId(),
+    id,
+  });
+}
+
+/**
+ * 断开 MCP 服务器
+ * @param id 服务器ID
+ */
+export async function disconnectMcpServer(id: string): Promise<void> {
+  return invoke<void>("disconnect_mcp_server", { id });
+}
+
+/**
+ * 读取 MCP 资源
+ * @param serverId 服务器ID
+ * @param uri 资源地址
+ * @returns 资源的文本内容
+ */
+export async function readMcpResource(
+  serverId: string,
+  uri: string,
+): Promise<string> {
+  return invoke<string>("read_mcp_resource", {
+    sessionId: getCurrentSessionId(),
+    serverId,
+    uri,
+  });
+}
+
+/**
+ * 获取 MCP 提示词
+ * @param serverId 服务器ID
+ * @param name 提示词名称
+ * @param args 参数
+ * @returns 展开后的消息
+ */
+export async function getMcpPrompt(
+  serverId: string,
+  name: string,
+  args?: Record<string, string>,
+): Promise<McpPromptMessage[]> {
+  return invoke<McpPromptMessage[]>("get_mcp_prompt", {
+    sessionId: getCurrentSessionId(),
+    serverId,
+    name,
+    arguments: args ?? null,
+  });
+}
+
+/**
+ * 知识库
+ * 对应 src-tauri/src/ai/knowledge/index.rs -> KnowledgeBase
+ */
+export interface KnowledgeBase {
+  id: string;
+  name: string;
+  /** 索引的目录 */
+  folders: string[];
+  /** 向量化提供商 */
+  embedding_provider: AIProvider;
+  embedding_model: string;
+  /** 向量维度，尚未索引时为空 */
+  dimensions: number | null;
+  document_count: number;
+  chunk_count: number;
+  /** 最近一次索引完成的时间 */
+  indexed_at: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
+/**
+ * 知识库中的文档
+ * 对应 src-tauri/src/ai/knowledge/index.rs -> KnowledgeDocument
+ */
+export interface KnowledgeDocument {
+  id: string;
+  path: string;
+  title: string;
+  size: number;
+  chunk_count: number;
+  /** 提取或分块失败的原因 */
+  error: string | null;
+  indexed_at: number;
+}
+
+/**
+ * 单个文档的索引结果
+ * 对应 src-tauri/src/ai/knowledge/index.rs -> IndexStatus
+ */
+export type IndexStatus = "indexed" | "unchanged" | "failed";
+
+/**
+ * 索引进度
+ * 对应 src-tauri/src/ai/knowledge/index.rs -> IndexProgress
+ */
+export interface IndexProgress {
+  /** 已处理的文档数 */
+  processed: number;
+  total: number;
+  path: string;
+  status: IndexStatus;
+  error: string | null;
+}
+
+/**
+ * 索引结果统计
+ * 对应 src-tauri/src/ai/knowledge/index.rs -> IndexSummary
+ */
+export interface IndexSummary {
+  indexed: number;
+  unchanged: number;
+  failed: number;
+  /** 已从目录中删除的文档数 */
+  removed: number;
+}
+
+/**
+ * 获取全部知识库
+ */
+export async function listKnowledgeBases(): Promise<KnowledgeBase[]> {
+  return invoke<KnowledgeBase[]>("list_knowledge_bases");
+}
+
+/**
+ * 保存知识库，更换向量模型时清除已有的索引
+ * @param id 知识库ID，为空时新建
+ * @param name 名称
+ * @param folders 索引的目录
+ * @param embeddingProvider 向量化提供商
+ * @param embeddingModel 向量模型
+ * @returns 保存后的知识库
+ */
+export async function saveKnowledgeBase(
+  id: string | null,
+  name: string,
+  folders: string[],
+  embeddingProvider: AIProvider,
+  embeddingModel: string,
+): Promise<KnowledgeBase> {
+  return invoke<KnowledgeBase>("save_knowledge_base", {
+    sessionId: getCurrentSessionId(),
+    id,
+    name,
+    folders,
+    embeddingProvider,
+    embeddingModel,
+  });
+}
+
+/**
+ * 删除知识库及其索引
+ * @param id 知识库ID
+ */
+export async function deleteKnowledgeBase(id: string): Promise<void> {
+  return invoke<void>("delete_knowledge_base", { id });
+}
+
+/**
+ * 获取知识库中的文档
+ * @param kbId 知识库ID
+ */
+export async function listKnowledgeDocuments(
+  kbId: string,
+): Promise<KnowledgeDocument[]> {
+  return invoke<KnowledgeDocument[]>("list_knowledge_documents", { kbId });
+}
+
+/**
+ * 增量索引知识库
+ * @param id 知识库ID
+ * @param onProgress 索引进度回调
+ * @returns 索引结果统计
+ */
+export async function indexKnowledgeBase(
+  id: string,
+  onProgress: (progress: IndexProgress) => void,
+): Promise<IndexSummary> {
+  const channel = new Channel<IndexProgress>();
+  channel.onmessage = onProgress;
+  return invoke<IndexSummary>("index_knowledge_base", {
+    id,
+    onProgress: channel,
+  });
+}
+
+/**
+ * 检索知识库
+ * @param kbIds 知识库ID
+ * @param query 查询文本
+ * @param topK 返回的片段数，默认 5
+ * @returns 按相似度降序排列的片段
+ */
+export async function searchKnowledgeBase(
+  kbIds: string[],
+  query: string,
+  topK?: number,
+): Promise<KnowledgeCitation[]> {
+  return invoke<KnowledgeCitation[]>("search_knowledge_base", {
+    kbIds,
+    query,
+    topK: topK ?? null,
+  });
+}
+
+/**
+ * 提示词模板变量类型
+ * 对应 src-tauri/src/ai/prompts/template.rs -> VariableKind
+ */
+export type PromptVariableKind =
+  | "text"
+  | "multiline"
+  | "number"
+  | "boolean"
+  | "enum"
+  | "file"
+  | "clipboard";
+
+/**
+ * 提示词模板变量定义
+ * 对应 src-tauri/src/ai/prompts/template.rs -> PromptVariable
+ */
+export interface PromptVariable {
+  /** 变量名，与模板中的 {{name}} 一致 */
+  name: string;
+  label?: string;
+  description?: string;
+  kind?: PromptVariableKind;
+  default?: string;
+  /** 是否必填，默认是 */
+  required?: boolean;
+  /** 可选值，仅枚举类型 */
+  options?: string[];
+}
+
+/**
+ * 提示词模板
+ * 对应 src-tauri/src/ai/prompts/store.rs -> PromptTemplate
+ */
+export interface PromptTemplate {
+  id: number;
+  title: string;
+  description: string | null;
+  content: string;
+  category: string | null;
+  tags: string[];
+  /** 变量定义，按模板中首次出现的顺序 */
+  variables: PromptVariable[];
+  is_favorite: boolean;
+  use_count: number;
+  last_used_at: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
+/**
+ * 保存提示词模板的参数
+ * 对应 src-tauri/src/ai/prompts/store.rs -> PromptTemplateInput
+ */
+export interface PromptTemplateInput {
+  /** 为空时新建 */
+  id?: number;
+  title: string;
+  description?: string;
+  content: string;
+  category?: string;
+  tags?: string[];
+  /** 未声明的变量按必填文本处理 */
+  variables?: PromptVariable[];
+}
+
+/**
+ * 提示词模板渲染结果
+ * 对应 src-tauri/src/ai/prompts/template.rs -> RenderedPrompt
+ */
+export interface RenderedPrompt {
+  /** 渲染后的内容，存在校验错误时为空 */
+  content: string | null;
+  errors: { name: string; message: string }[];
+}
+
+/**
+ * 模板包导入结果
+ * 对应 src-tauri/src/ai/prompts/pack.rs -> PromptImportSummary
+ */
+export interface PromptImportSummary {
+  imported: number;
+  /** 与已有模板标题和内容相同而跳过的模板数 */
+  skipped: number;
+  /** 校验失败的模板及原因 */
+  errors: string[];
+}
+
+/**
+ * 获取提示词模板，收藏的在前，其余按使用次数排列
+ * @param category 分类，为空时返回全部
+ */
+export async function listPromptTemplates(
+  category?: string,
+): Promise<PromptTemplate[]> {
+  return invoke<PromptTemplate[]>("list_prompt_templates", {
+    category: category ?? null,
+  });
+}
+
+/**
+ * 保存提示词模板
+ * @param template 模板参数
+ * @returns 保存后的模板
+ */
+export async function savePromptTemplate(
+  template: PromptTemplateInput,
+): Promise<PromptTemplate> {
+  return invoke<PromptTemplate>("save_prompt_template", { template });
+}
+
+/**
+ * 删除提示词模板
+ * @param id 模板ID
+ */
+export async function deletePromptTemplate(id: number): Promise<void> {
+  return invoke<void>("delete_prompt_template", { id });
+}
+
+/**
+ * 收藏或取消收藏提示词模板
+ * @param id 模板ID
+ * @param favorite 是否收藏
+ */
+export async function setPromptTemplateFavorite(
+  id: number,
+  favorite: boolean,
+): Promise<void> {
+  return invoke<void>("set_prompt_template_favorite", { id, favorite });
+}
+
+/**
+ * 渲染提示词模板，成功时累计使用次数
+ * @param id 模板ID
+ * @param values 变量的值，文件变量传文件路径
+ * @returns 渲染结果
+ */
+export async function renderPromptTemplate(
+  id: number,
+  values: Record<string, string>,
+): Promise<RenderedPrompt> {
+  return invoke<RenderedPrompt>("render_prompt_template", { id, values });
+}
+
+/**
+ * 导出提示词模板包
+ * @param filePath 导出路径，扩展名为 .yaml 或 .yml 时导出为 YAML
+ * @param ids 导出的模板ID，为空时导出全部
+ * @returns 导出的模板数
+ */
+export async function exportPromptTemplates(
+  filePath: string,
+  ids?: number[],
+): Promise<number> {
+  return invoke<number>("export_prompt_templates", {
+    filePath,
+    ids: ids ?? null,
+  });
+}
+
+/**
+ * 导入提示词模板包
+ * @param filePath 模板包路径，扩展名为 .yaml 或 .yml 时按 YAML 解析
+ * @returns 导入结果
+ */
+export async function importPromptTemplates(
+  filePath: string,
+): Promise<PromptImportSummary> {
+  return invoke<PromptImportSummary>("import_prompt_templates", { filePath });
+}
@@ -0,0 +1,52 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentSessionId } from "@/services/tauri/session";
+
+/**
+ * 已配对的浏览器扩展
+ * 对应 src-tauri/src/bridge/server.rs -> BrowserPairing
+ */
+export interface BrowserPairing {
+  /** 宿主公钥指纹 */
+  fingerprint: string;
+  /** 配对时间（秒） */
+  paired_at: number;
+}
+
+/**
+ * 支持的浏览器
+ */
+export type NativeHostBrowser = "chrome" | "firefox";
+
+/**
+ * 列出已配对的浏览器扩展
+ */
+export async function listBrowserPairings(): Promise<BrowserPairing[]> {
+  return invoke<BrowserPairing[]>("list_browser_pairings");
+}
+
+/**
+ * 取消浏览器扩展配对
+ * @param fingerprint 宿主公钥指纹
+ * @returns 是否存在并被删除
+ */
+export async function removeBrowserPairing(
+  fingerprint: string,
+): Promise<boolean> {
+  return invoke<boolean>("remove_browser_pairing", {
+    fingerprint,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 生成原生消息宿主清单
+ * @param browser 浏览器类型
+ * @param extensionId 扩展ID
+ * @returns 清单 JSON，需保存到浏览器规定的目录
+ */
+export async function getNativeHostManifest(
+  browser: NativeHostBrowser,
+  extensionId: string,
+): Promise<string> {
+  return invoke<string>("get_native_host_manifest", { browser, extensionId });
+}
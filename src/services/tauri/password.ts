@@ -0,0 +1,285 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentSessionId } from "@/services/tauri/session";
+
+/**
+ * 可复制的条目字段
+ * 对应 src-tauri/src/password/clipboard.rs -> ClipboardField
+ */
+export type ClipboardField = "username" | "password" | "totp";
+
+/**
+ * 复制结果
+ * 对应 src-tauri/src/password/clipboard.rs -> ClipboardCopyResult
+ */
+export interface ClipboardCopyResult {
+  /** 复制的字段 */
+  field: ClipboardField;
+  /** 自动清空时间（秒），0表示不清空 */
+  clear_after_secs: number;
+  /** TOTP 验证码的剩余有效秒数 */
+  totp_remaining_secs?: number;
+}
+
+/**
+ * 复制条目字段到剪贴板，明文不经过前端
+ * @param entryId 条目ID
+ * @param field 要复制的字段
+ * @param clearAfter 自动清空时间（秒），不传时使用系统设置
+ */
+export async function copyEntryField(
+  entryId: string,
+  field: ClipboardField,
+  clearAfter?: number,
+): Promise<ClipboardCopyResult> {
+  return invoke<ClipboardCopyResult>("copy_entry_field", {
+    entryId,
+    field,
+    clearAfter,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 条目专有字段，按 entry_type 区分
+ * 对应 src-tauri/src/password/types.rs -> EntryFields
+ */
+export type EntryFields =
+  | { entry_type: "login"; totp?: string }
+  | { entry_type: "secure_note"; content: string }
+  | {
+      entry_type: "credit_card";
+      cardholder_name: string;
+      number: string;
+      expiry_month?: number;
+      expiry_year?: number;
+      cvv?: string;
+      pin?: string;
+      brand?: string;
+    }
+  | {
+      entry_type: "identity";
+      full_name: string;
+      email?: string;
+      phone?: string;
+      address?: string;
+      id_type?: string;
+      id_number?: string;
+      birth_date?: string;
+    }
+  | {
+      entry_type: "ssh_key";
+      private_key: string;
+      public_key?: string;
+      passphrase?: string;
+      comment?: string;
+    }
+  | {
+      entry_type: "api_token";
+      token: string;
+      endpoint?: string;
+      expires_at?: number;
+    };
+
+/**
+ * 附件信息
+ * 对应 src-tauri/src/password/types.rs -> AttachmentInfo
+ */
+export interface AttachmentInfo {
+  /** 附件ID */
+  id: string;
+  /** 所属条目ID */
+  entry_id: string;
+  /** 文件名 */
+  file_name: string;
+  /** 文件大小（字节） */
+  size: number;
+  /** 明文的 SHA-256 摘要 */
+  sha256: string;
+  /** 创建时间（毫秒） */
+  created_at: number;
+}
+
+/**
+ * 设置条目类型与专有字段
+ * @param entryId 条目ID
+ * @param fields 专有字段
+ */
+export async function setEntryFields(
+  entryId: string,
+  fields: EntryFields,
+): Promise<void> {
+  return invoke("set_entry_fields", {
+    entryId,
+    fields,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 获取解密后的条目专有字段
+ * @param entryId 条目ID
+ */
+export async function getEntryFields(entryId: string): Promise<EntryFields> {
+  return invoke<EntryFields>("get_entry_fields", {
+    entryId,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 为条目添加附件
+ * @param entryId 条目ID
+ * @param filePath 要添加的文件路径
+ */
+export async function addEntryAttachment(
+  entryId: string,
+  filePath: string,
+): Promise<AttachmentInfo> {
+  return invoke<AttachmentInfo>("add_entry_attachment", {
+    entryId,
+    filePath,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 列出条目的附件
+ * @param entryId 条目ID
+ */
+export async function listEntryAttachments(
+  entryId: string,
+): Promise<AttachmentInfo[]> {
+  return invoke<AttachmentInfo[]>("list_entry_attachments", { entryId });
+}
+
+/**
+ * 解密附件并保存到指定路径
+ * @param attachmentId 附件ID
+ * @param targetPath 保存路径
+ */
+export async function saveEntryAttachment(
+  attachmentId: string,
+  targetPath: string,
+): Promise<AttachmentInfo> {
+  return invoke<AttachmentInfo>("save_entry_attachment", {
+    attachmentId,
+    targetPath,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 删除附件
+ * @param attachmentId 附件ID
+ */
+export async function deleteEntryAttachment(
+  attachmentId: string,
+): Promise<boolean> {
+  return invoke<boolean>("delete_entry_attachment", {
+    attachmentId,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * SSH 代理状态
+ * 对应 src-tauri/src/password/ssh_agent.rs -> SshAgentStatus
+ */
+export interface SshAgentStatus {
+  /** 是否正在运行 */
+  running: boolean;
+  /** 套接字路径，用于设置 SSH_AUTH_SOCK */
+  socket_path?: string;
+  /** 可用的密钥数量 */
+  key_count: number;
+}
+
+/**
+ * 启动 SSH 代理，密码库锁定时自动停止
+ */
+export async function startSshAgent(): Promise<SshAgentStatus> {
+  return invoke<SshAgentStatus>("start_ssh_agent", {
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 停止 SSH 代理
+ * @returns 停止前是否正在运行
+ */
+export async function stopSshAgent(): Promise<boolean> {
+  return invoke<boolean>("stop_ssh_agent");
+}
+
+/**
+ * 获取 SSH 代理状态
+ */
+export async function getSshAgentStatus(): Promise<SshAgentStatus> {
+  return invoke<SshAgentStatus>("get_ssh_agent_status");
+}
+
+/**
+ * 条目的自动输入配置
+ * 对应 src-tauri/src/password/auto_type.rs -> AutoTypeConfig
+ */
+export interface AutoTypeConfig {
+  /** 条目ID */
+  entry_id: string;
+  /** 是否允许自动输入 */
+  enabled: boolean;
+  /** 窗口标题规则（* 匹配任意字符），为空时不参与快捷键自动输入 */
+  window_patterns: string[];
+  /** 输入序列，为空时使用 {USERNAME}{TAB}{PASSWORD}{ENTER} */
+  sequence?: string;
+}
+
+/**
+ * 获取条目的自动输入配置
+ * @param entryId 条目ID
+ */
+export async function getAutoTypeConfig(
+  entryId: string,
+): Promise<AutoTypeConfig> {
+  return invoke<AutoTypeConfig>("get_auto_type_config", { entryId });
+}
+
+/**
+ * 设置条目的自动输入配置
+ * @param config 自动输入配置
+ */
+export async function setAutoTypeConfig(config: AutoTypeConfig): Promise<void> {
+  return invoke<void>("set_auto_type_config", {
+    config,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 隐藏应用窗口后向之前的焦点窗口自动输入条目
+ * @param entryId 条目ID
+ */
+export async function autoTypeEntry(entryId: string): Promise<void> {
+  return invoke<void>("auto_type_entry", {
+    entryId,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 获取自动输入快捷键
+ * @returns 快捷键，空字符串表示已禁用
+ */
+export async function getAutoTypeHotkey(): Promise<string> {
+  return invoke<string>("get_auto_type_hotkey");
+}
+
+/**
+ * 设置自动输入快捷键
+ * @param hotkey 快捷键，如 CommandOrControl+Alt+A，空字符串表示禁用
+ */
+export async function setAutoTypeHotkey(hotkey: string): Promise<void> {
+  return invoke<void>("set_auto_type_hotkey", {
+    hotkey,
+    sessionId: getCurrentSessionId(),
+  });
+}
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { PluginMetadata } from "@/types/plugin";
+import { getCurrentSessionId } from "@/services/tauri/session";
 
 /**
  * 初始化插件系统
@@ -135,7 +136,10 @@ export async function installPluginFromZip(
   zipPath: string,
 ): Promise<PluginMetadata> {
   // 注意：此命令需要 AppHandle，invoke 会自动注入
-  return await invoke<PluginMetadata>("install_plugin_from_zip", { zipPath });
+  return await invoke<PluginMetadata>("install_plugin_from_zip", {
+    zipPath,
+    sessionId: getCurrentSessionId(),
+  });
 }
 
 /**
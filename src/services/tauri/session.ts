@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * 会话信息
+ * 对应 src-tauri/src/security/session.rs -> SessionInfo
+ */
+export interface SessionInfo {
+  /** 会话ID */
+  session_id: string;
+  /** 创建时间（秒） */
+  created_at: number;
+  /** 最后活动时间（秒） */
+  last_activity: number;
+  /** 绝对过期时间（秒） */
+  expires_at: number;
+  /** 闲置超时时间（秒），为空表示不限制 */
+  idle_timeout_secs?: number;
+}
+
+// 当前会话ID，由 AuthContext 在解锁和锁定时维护
+let currentSessionId: string | undefined;
+
+/**
+ * 设置当前会话ID
+ * @param sessionId 会话ID，锁定时传入 undefined
+ */
+export function setCurrentSessionId(sessionId: string | undefined): void {
+  currentSessionId = sessionId;
+}
+
+/**
+ * 获取当前会话ID，调用敏感命令时需要携带
+ */
+export function getCurrentSessionId(): string {
+  return currentSessionId ?? "";
+}
+
+/**
+ * 解锁密码库并获取会话
+ * @param masterPassword 主密码
+ */
+export async function unlockVault(masterPassword: string): Promise<SessionInfo> {
+  return invoke<SessionInfo>("unlock_vault", { masterPassword });
+}
+
+/**
+ * 未设置主密码时获取会话
+ */
+export async function openSession(): Promise<SessionInfo> {
+  return invoke<SessionInfo>("open_session");
+}
+
+/**
+ * 刷新会话的最后活动时间
+ * @param sessionId 会话ID
+ */
+export async function touchSession(sessionId: string): Promise<SessionInfo> {
+  return invoke<SessionInfo>("touch_session", { sessionId });
+}
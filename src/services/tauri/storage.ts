@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * 加密级别
+ * - device: 本机设备密钥，启动后即可读取
+ * - master: 密码库密钥，需主密码解锁
+ */
+export type EncryptionLevel = "device" | "master";
+
+/**
+ * 存储项信息
+ * 对应 src-tauri/src/security/storage.rs -> SecureItemInfo
+ */
+export interface SecureItemInfo {
+  /** 键名 */
+  key: string;
+  /** 加密级别 */
+  encryption_level: EncryptionLevel;
+  /** 附加元数据 */
+  metadata?: Record<string, any>;
+  /** 创建时间（秒） */
+  created_at: number;
+  /** 更新时间（秒） */
+  updated_at: number;
+}
+
+/**
+ * 写入机密数据
+ * @param key 键名，须以 "app." 或 "plugin." 开头，其余命名空间仅供后端使用
+ * @param value 内容
+ * @param encryptionLevel 加密级别，默认 device
+ * @param metadata 附加元数据（明文保存）
+ */
+export async function secureSet(
+  key: string,
+  value: string,
+  encryptionLevel?: EncryptionLevel,
+  metadata?: Record<string, any>,
+): Promise<void> {
+  return invoke("secure_set", { key, value, encryptionLevel, metadata });
+}
+
+/**
+ * 读取机密数据
+ * @param key 键名
+ * @returns 内容，不存在时返回 null
+ */
+export async function secureGet(key: string): Promise<string | null> {
+  return invoke<string | null>("secure_get", { key });
+}
+
+/**
+ * 删除机密数据
+ * @param key 键名
+ * @returns 是否存在并被删除
+ */
+export async function secureDelete(key: string): Promise<boolean> {
+  return invoke<boolean>("secure_delete", { key });
+}
+
+/**
+ * 列出存储项（不包含内容）
+ * @param prefix 键名前缀，不传时列出 "app." 与 "plugin." 下的全部存储项
+ */
+export async function secureList(prefix?: string): Promise<SecureItemInfo[]> {
+  return invoke<SecureItemInfo[]>("secure_list", { prefix });
+}
@@ -0,0 +1,168 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentSessionId } from "@/services/tauri/session";
+
+/**
+ * 同步目标
+ * 对应 src-tauri/src/sync/target.rs -> SyncTargetConfig
+ */
+export type SyncTargetConfig =
+  | { type: "folder"; path: string }
+  | { type: "webdav"; url: string; username?: string | null };
+
+/**
+ * 同步状态
+ * 对应 src-tauri/src/sync/config.rs -> SyncStatus
+ */
+export interface SyncStatus {
+  /** 是否已配置 */
+  configured: boolean;
+  /** 同步目标 */
+  target: SyncTargetConfig | null;
+  /** 本设备ID */
+  device_id: string;
+  /** 上次同步时间（毫秒） */
+  last_sync_at: number | null;
+  /** 待上传的记录数 */
+  pending_changes: number;
+  /** 未解决的冲突数 */
+  conflicts: number;
+}
+
+/**
+ * 同步结果
+ * 对应 src-tauri/src/sync/engine.rs -> SyncReport
+ */
+export interface SyncReport {
+  /** 读取的变更集数量 */
+  change_sets_pulled: number;
+  /** 应用到本地的远端变更数 */
+  changes_applied: number;
+  /** 新发现的冲突数 */
+  conflicts: number;
+  /** 上传的记录数 */
+  changes_pushed: number;
+}
+
+/**
+ * 同步的记录类型
+ */
+export type SyncRecordType = "entry" | "category";
+
+/**
+ * 同步冲突概要
+ * 对应 src-tauri/src/sync/engine.rs -> SyncConflictInfo
+ */
+export interface SyncConflictInfo {
+  /** 冲突ID */
+  id: string;
+  /** 记录类型 */
+  record_type: SyncRecordType;
+  /** 记录ID */
+  record_id: string;
+  /** 本地标题，本地已删除时为空 */
+  local_title: string | null;
+  /** 远端标题，远端已删除时为空 */
+  remote_title: string | null;
+  /** 本地更新时间（毫秒） */
+  local_updated_at: number | null;
+  /** 远端更新时间（毫秒） */
+  remote_updated_at: number | null;
+  /** 远端设备ID */
+  remote_device: string;
+  /** 发现冲突的时间（毫秒） */
+  created_at: number;
+}
+
+/**
+ * 冲突双方的完整内容，记录结构与密码条目、分类一致
+ * 对应 src-tauri/src/sync/engine.rs -> SyncConflictDetail
+ */
+export interface SyncConflictDetail {
+  /** 本地版本，已删除时为空 */
+  local: Record<string, unknown> | null;
+  /** 远端版本，已删除时为空 */
+  remote: Record<string, unknown> | null;
+}
+
+/**
+ * 冲突处理方式
+ */
+export type SyncConflictResolution = "local" | "remote";
+
+/**
+ * 配置同步目标
+ * @param target 同步目标
+ * @param passphrase 同步口令，各设备需一致
+ * @param webdavPassword WebDAV 密码
+ * @returns 同步状态
+ */
+export async function configureSync(
+  target: SyncTargetConfig,
+  passphrase: string,
+  webdavPassword?: string,
+): Promise<SyncStatus> {
+  return invoke<SyncStatus>("configure_sync", {
+    target,
+    passphrase,
+    webdavPassword: webdavPassword ?? null,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 停用同步
+ */
+export async function disableSync(): Promise<void> {
+  return invoke("disable_sync", { sessionId: getCurrentSessionId() });
+}
+
+/**
+ * 获取同步状态
+ */
+export async function getSyncStatus(): Promise<SyncStatus> {
+  return invoke<SyncStatus>("get_sync_status");
+}
+
+/**
+ * 立即同步
+ * @returns 同步结果
+ */
+export async function runSync(): Promise<SyncReport> {
+  return invoke<SyncReport>("run_sync", { sessionId: getCurrentSessionId() });
+}
+
+/**
+ * 列出未解决的同步冲突
+ */
+export async function listSyncConflicts(): Promise<SyncConflictInfo[]> {
+  return invoke<SyncConflictInfo[]>("list_sync_conflicts");
+}
+
+/**
+ * 获取冲突双方的完整内容
+ * @param conflictId 冲突ID
+ */
+export async function getSyncConflict(
+  conflictId: string,
+): Promise<SyncConflictDetail> {
+  return invoke<SyncConflictDetail>("get_sync_conflict", {
+    conflictId,
+    sessionId: getCurrentSessionId(),
+  });
+}
+
+/**
+ * 解决同步冲突
+ * @param conflictId 冲突ID
+ * @param resolution 保留本地版本或采用远端版本
+ */
+export async function resolveSyncConflict(
+  conflictId: string,
+  resolution: SyncConflictResolution,
+): Promise<void> {
+  return invoke("resolve_sync_conflict", {
+    conflictId,
+    resolution,
+    sessionId: getCurrentSessionId(),
+  });
+}
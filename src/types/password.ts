@@ -2,6 +2,18 @@
  * 密码管理系统类型定义
  */
 
+/**
+ * 条目类型
+ * 对应 src-tauri/src/password/types.rs -> EntryType
+ */
+export type EntryType =
+  | "login"
+  | "secure_note"
+  | "credit_card"
+  | "identity"
+  | "ssh_key"
+  | "api_token";
+
 /**
  * 密码条目接口
  */
@@ -12,9 +24,12 @@ export interface PasswordEntry {
   password: string;
   url?: string;
   notes?: string;
+  /** 是否设置了 TOTP 密钥，密钥加密保存在条目专有字段中 */
+  hasTotp?: boolean;
   category: string;
   tags: string[];
   isFavorite: boolean;
+  entryType?: EntryType;
   createdAt: number;
   updatedAt: number;
   lastUsed?: number;